@@ -0,0 +1,170 @@
+//! Per-execution caps on journal-producing host calls.
+//!
+//! Fuel bounds how long a guest computes, not how many durable writes it
+//! makes: a cheap loop of random draws can still fill storage. Each
+//! journal-producing host call is charged to a [`BudgetMeter`] before it is
+//! journaled, and once its [`HostBudgets`] cap is spent the call fails with
+//! [`ErrorKind::ResourceExhausted`] instead of writing.
+//!
+//! Calls answered from the replay cache were charged when first made, so
+//! replay does not charge them again: an execution that succeeded once
+//! always replays under the same budgets.
+
+use std::fmt;
+
+use invariant_types::{ErrorKind, ExecutionError};
+
+/// A journal-producing host call, as charged against [`HostBudgets`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum HostCall {
+    Invoke,
+    Timer,
+    Random,
+    SignalAwait,
+    JoinSet,
+}
+
+impl HostCall {
+    /// Every call kind, in declaration order.
+    pub const ALL: [HostCall; 5] = [
+        Self::Invoke,
+        Self::Timer,
+        Self::Random,
+        Self::SignalAwait,
+        Self::JoinSet,
+    ];
+}
+
+impl fmt::Display for HostCall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Invoke => "invoke",
+            Self::Timer => "timer",
+            Self::Random => "random",
+            Self::SignalAwait => "signal await",
+            Self::JoinSet => "join set",
+        })
+    }
+}
+
+/// Caps on journal-producing host calls for one execution. `None` is
+/// unlimited, the default.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct HostBudgets {
+    pub max_invokes: Option<u32>,
+    pub max_timers: Option<u32>,
+    pub max_random: Option<u32>,
+    pub max_signals_awaited: Option<u32>,
+    pub max_join_sets: Option<u32>,
+}
+
+impl HostBudgets {
+    /// The cap on `call`, if any.
+    pub fn limit(&self, call: HostCall) -> Option<u32> {
+        match call {
+            HostCall::Invoke => self.max_invokes,
+            HostCall::Timer => self.max_timers,
+            HostCall::Random => self.max_random,
+            HostCall::SignalAwait => self.max_signals_awaited,
+            HostCall::JoinSet => self.max_join_sets,
+        }
+    }
+}
+
+/// Host calls charged so far against a [`HostBudgets`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BudgetMeter {
+    budgets: HostBudgets,
+    /// Charged calls, indexed by `HostCall as usize`.
+    used: [u32; HostCall::ALL.len()],
+}
+
+impl BudgetMeter {
+    pub fn new(budgets: HostBudgets) -> Self {
+        Self {
+            budgets,
+            used: [0; HostCall::ALL.len()],
+        }
+    }
+
+    pub fn budgets(&self) -> &HostBudgets {
+        &self.budgets
+    }
+
+    /// Calls of kind `call` charged so far.
+    pub fn used(&self, call: HostCall) -> u32 {
+        self.used[call as usize]
+    }
+
+    /// Charge one `call`, unless `replayed` says it was answered from the
+    /// replay cache.
+    ///
+    /// # Errors
+    ///
+    /// [`ErrorKind::ResourceExhausted`] when the cap on `call` is already
+    /// spent; nothing is charged. The host returns it to the guest, which
+    /// journals it as the invoke's failure for [`HostCall::Invoke`] and as
+    /// the execution's failure otherwise.
+    pub fn charge(&mut self, call: HostCall, replayed: bool) -> Result<(), ExecutionError> {
+        if replayed {
+            return Ok(());
+        }
+        let used = &mut self.used[call as usize];
+        if let Some(max) = self.budgets.limit(call)
+            && *used >= max
+        {
+            return Err(ExecutionError::new(
+                ErrorKind::ResourceExhausted,
+                format!("{call} budget of {max} exhausted"),
+            ));
+        }
+        *used += 1;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exhausted_budget_fails_without_charging() {
+        let mut meter = BudgetMeter::new(HostBudgets {
+            max_random: Some(2),
+            ..HostBudgets::default()
+        });
+
+        meter.charge(HostCall::Random, false).unwrap();
+        meter.charge(HostCall::Random, false).unwrap();
+        let err = meter.charge(HostCall::Random, false).unwrap_err();
+        assert_eq!(err.kind, ErrorKind::ResourceExhausted);
+        assert_eq!(err.message, "random budget of 2 exhausted");
+        assert_eq!(meter.used(HostCall::Random), 2);
+
+        // Other calls have their own caps; unset ones are unlimited.
+        for _ in 0..10 {
+            meter.charge(HostCall::Invoke, false).unwrap();
+        }
+        assert_eq!(meter.used(HostCall::Invoke), 10);
+    }
+
+    #[test]
+    fn replayed_calls_are_not_charged() {
+        let budgets = HostBudgets {
+            max_invokes: Some(1),
+            ..HostBudgets::default()
+        };
+
+        // The original run spent the whole budget.
+        let mut original = BudgetMeter::new(budgets);
+        original.charge(HostCall::Invoke, false).unwrap();
+        assert!(original.charge(HostCall::Invoke, false).is_err());
+
+        // Replaying that call leaves the budget for the next live one.
+        let mut replay = BudgetMeter::new(budgets);
+        replay.charge(HostCall::Invoke, true).unwrap();
+        assert_eq!(replay.used(HostCall::Invoke), 0);
+        replay.charge(HostCall::Invoke, false).unwrap();
+        assert!(replay.charge(HostCall::Invoke, false).is_err());
+    }
+}
@@ -1,6 +1,8 @@
 use std::path::PathBuf;
 
+use sha2::{Digest, Sha256};
 use wasmtime::component::Component;
+use wasmtime::component::types::ComponentItem;
 
 use crate::engine::WasmEngine;
 use crate::error::RuntimeError;
@@ -16,6 +18,26 @@ pub enum ComponentSource {
     Registry(String),
 }
 
+/// What a component exposes, read from its type without instantiating it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ComponentMetadata {
+    /// Top-level export names.
+    pub exports: Vec<String>,
+    /// Top-level import names.
+    pub imports: Vec<String>,
+    /// Name of the first exported interface instance, if any.
+    pub wit_interface: Option<String>,
+    /// SHA-256 of the component bytes, for `PromiseId::promise_root`.
+    pub component_digest: Vec<u8>,
+}
+
+impl ComponentMetadata {
+    /// Hex-encoded [`component_digest`](Self::component_digest).
+    pub fn digest_hex(&self) -> String {
+        hex::encode(&self.component_digest)
+    }
+}
+
 impl ComponentLoader {
     pub fn new(engine: WasmEngine) -> Self {
         Self { engine }
@@ -27,7 +49,97 @@ impl ComponentLoader {
                 .map_err(RuntimeError::ComponentLoadError),
             ComponentSource::Bytes(bytes) => Component::new(self.engine.get_engine(), bytes)
                 .map_err(RuntimeError::ComponentLoadError),
-            ComponentSource::Registry(_) => unimplemented!(),
+            ComponentSource::Registry(name) => Err(registry_unsupported(&name)),
+        }
+    }
+
+    /// Load a component and describe its exports, imports and digest.
+    pub fn load_with_metadata(
+        self,
+        source: ComponentSource,
+    ) -> Result<(Component, ComponentMetadata), RuntimeError> {
+        let bytes = match source {
+            ComponentSource::FilePath(path) => {
+                std::fs::read(path).map_err(|e| RuntimeError::ComponentLoadError(e.into()))?
+            }
+            ComponentSource::Bytes(bytes) => bytes,
+            ComponentSource::Registry(name) => return Err(registry_unsupported(&name)),
+        };
+        let engine = self.engine.get_engine();
+        let component = Component::new(engine, &bytes).map_err(RuntimeError::ComponentLoadError)?;
+
+        let ty = component.component_type();
+        let mut exports = Vec::new();
+        let mut wit_interface = None;
+        for (name, item) in ty.exports(engine) {
+            if wit_interface.is_none() && matches!(item, ComponentItem::ComponentInstance(_)) {
+                wit_interface = Some(name.to_string());
+            }
+            exports.push(name.to_string());
         }
+        let imports = ty
+            .imports(engine)
+            .map(|(name, _)| name.to_string())
+            .collect();
+
+        let metadata = ComponentMetadata {
+            exports,
+            imports,
+            wit_interface,
+            component_digest: Sha256::digest(&bytes).to_vec(),
+        };
+        Ok((component, metadata))
+    }
+}
+
+fn registry_unsupported(name: &str) -> RuntimeError {
+    RuntimeError::ComponentLoadError(wasmtime::Error::msg(format!(
+        "registry sources unsupported: {name}"
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::EngineConfig;
+
+    const MINIMAL_COMPONENT: &str = r#"
+        (component
+            (core module $m (func (export "run")))
+            (core instance $i (instantiate $m))
+            (type $t (func))
+            (func $run (type $t) (canon lift (core func $i "run")))
+            (export "run" (func $run))
+        )
+    "#;
+
+    #[test]
+    fn load_with_metadata_detects_exports_and_digest() {
+        let engine = EngineConfig::default().build_engine().unwrap();
+        let bytes = MINIMAL_COMPONENT.as_bytes().to_vec();
+
+        let (_component, metadata) = ComponentLoader::new(engine)
+            .load_with_metadata(ComponentSource::Bytes(bytes.clone()))
+            .unwrap();
+
+        assert!(metadata.exports.iter().any(|e| e == "run"));
+        assert!(metadata.imports.is_empty());
+        assert_eq!(metadata.wit_interface, None);
+        assert_eq!(metadata.component_digest, Sha256::digest(&bytes).to_vec());
+        assert_eq!(metadata.digest_hex().len(), 64);
+    }
+
+    #[test]
+    fn registry_source_is_an_error() {
+        let engine = EngineConfig::default().build_engine().unwrap();
+
+        // `Component` is not `Debug`, so no `unwrap_err`.
+        let Err(err) = ComponentLoader::new(engine)
+            .load_with_metadata(ComponentSource::Registry("acme/billing".into()))
+        else {
+            panic!("registry source loaded");
+        };
+
+        assert!(err.to_string().contains("registry sources unsupported"));
     }
 }
@@ -1,28 +1,103 @@
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::thread;
 use std::time::Duration;
-use wasmtime::{Config, Engine};
+use wasmtime::{Cache, Config, Engine, Store, StoreLimits};
+
+use crate::host_context::AsHostContext;
+
+/// What a store does when the epoch passes its deadline.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DeadlineBehavior {
+    /// Trap with [`Trap::Interrupt`](wasmtime::Trap::Interrupt): a hard timeout.
+    #[default]
+    Trap,
+    /// Yield to the async executor, then extend the deadline by this many
+    /// ticks: cooperative time-slicing for long-running guests.
+    YieldAndUpdate(u64),
+}
 
 #[derive(Clone, Debug)]
 pub struct WasmEngine {
     engine: Arc<Engine>,
+    deterministic: bool,
+    on_deadline: DeadlineBehavior,
 }
 
 impl WasmEngine {
     pub fn get_engine(&self) -> &Arc<Engine> {
         &self.engine
     }
+
+    /// Whether NaN canonicalization and deterministic relaxed SIMD are enabled.
+    pub fn is_deterministic(&self) -> bool {
+        self.deterministic
+    }
+
+    /// What stores created by this engine do at their epoch deadline.
+    pub fn deadline_behavior(&self) -> DeadlineBehavior {
+        self.on_deadline
+    }
+
+    /// Create a store whose epoch deadline is `deadline_ticks` past the
+    /// current epoch, reacting to it as [`EngineConfig::on_deadline`] set.
+    pub fn new_store<T>(&self, data: T, deadline_ticks: u64) -> Store<T>
+    where
+        T: Send + 'static,
+    {
+        let mut store = Store::new(&self.engine, data);
+        self.apply_deadline(&mut store, deadline_ticks);
+        store
+    }
+
+    /// Create a store for an untrusted guest in one step.
+    ///
+    /// `limits` caps the guest's memories, tables and instances, and the
+    /// store's deadline is set as in [`new_store`](Self::new_store). The
+    /// limits live in the store's [`HostContext`](crate::HostContext) so the
+    /// limiter can reach them through [`AsHostContext`].
+    pub fn new_limited_store<T>(
+        &self,
+        mut data: T,
+        limits: StoreLimits,
+        deadline_ticks: u64,
+    ) -> Store<T>
+    where
+        T: AsHostContext + Send + 'static,
+    {
+        *data.host_context_mut().limits_mut() = limits;
+        let mut store = Store::new(&self.engine, data);
+        store.limiter(|data| data.host_context_mut().limits_mut());
+        self.apply_deadline(&mut store, deadline_ticks);
+        store
+    }
+
+    fn apply_deadline<T>(&self, store: &mut Store<T>, deadline_ticks: u64) {
+        store.set_epoch_deadline(deadline_ticks);
+        match self.on_deadline {
+            DeadlineBehavior::Trap => store.epoch_deadline_trap(),
+            DeadlineBehavior::YieldAndUpdate(delta) => {
+                store.epoch_deadline_async_yield_and_update(delta)
+            }
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct EngineConfig {
     epoch_interval_ms: u64,
+    deterministic: bool,
+    cache_config_path: Option<PathBuf>,
+    on_deadline: DeadlineBehavior,
 }
 
 impl Default for EngineConfig {
     fn default() -> Self {
         Self {
             epoch_interval_ms: 1000,
+            deterministic: true,
+            cache_config_path: None,
+            on_deadline: DeadlineBehavior::Trap,
         }
     }
 }
@@ -33,14 +108,43 @@ impl EngineConfig {
         self
     }
 
+    /// Toggle NaN canonicalization and deterministic relaxed SIMD together.
+    ///
+    /// On by default; replay depends on it. Non-replay workloads may turn it
+    /// off for speed.
+    pub fn deterministic(mut self, enabled: bool) -> Self {
+        self.deterministic = enabled;
+        self
+    }
+
+    /// Enable wasmtime's on-disk compilation cache from a cache config file.
+    ///
+    /// This caches compiled artifacts on disk across process restarts; it is
+    /// separate from any in-memory reuse of loaded components. `None` (the
+    /// default) leaves the cache disabled.
+    pub fn with_cache(mut self, cache_config_path: Option<PathBuf>) -> Self {
+        self.cache_config_path = cache_config_path;
+        self
+    }
+
+    /// How stores react to their epoch deadline. [`DeadlineBehavior::Trap`]
+    /// by default.
+    pub fn on_deadline(mut self, behavior: DeadlineBehavior) -> Self {
+        self.on_deadline = behavior;
+        self
+    }
+
     pub fn build_engine(&self) -> Result<WasmEngine, wasmtime::Error> {
         let mut engine_config = Config::default();
         engine_config
             .wasm_component_model(true)
             .async_support(true)
-            .cranelift_nan_canonicalization(true)
-            .relaxed_simd_deterministic(true)
+            .cranelift_nan_canonicalization(self.deterministic)
+            .relaxed_simd_deterministic(self.deterministic)
             .epoch_interruption(true);
+        if let Some(path) = &self.cache_config_path {
+            engine_config.cache(Some(Cache::from_file(Some(path))?));
+        }
 
         let engine = Engine::new(&engine_config)?;
         let engine_wrapper = Arc::new(engine);
@@ -57,6 +161,132 @@ impl EngineConfig {
         });
         Ok(WasmEngine {
             engine: engine_wrapper,
+            deterministic: self.deterministic,
+            on_deadline: self.on_deadline,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use invariant_types::ExecutionId;
+    use wasmtime::{Instance, Module, StoreLimitsBuilder, Trap};
+
+    use super::*;
+    use crate::HostContext;
+
+    #[test]
+    fn deterministic_defaults_on_and_can_be_disabled() {
+        let engine = EngineConfig::default().build_engine().unwrap();
+        assert!(engine.is_deterministic());
+
+        let engine = EngineConfig::default()
+            .deterministic(false)
+            .build_engine()
+            .unwrap();
+        assert!(!engine.is_deterministic());
+    }
+
+    #[test]
+    fn builds_with_cache_config_file() {
+        let dir = std::env::temp_dir().join(format!("invariant-cache-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let config_path = dir.join("cache.toml");
+        std::fs::write(
+            &config_path,
+            format!("[cache]\ndirectory = {:?}\n", dir.join("store")),
+        )
+        .unwrap();
+
+        let result = EngineConfig::default()
+            .with_cache(Some(config_path))
+            .build_engine();
+        std::fs::remove_dir_all(&dir).unwrap();
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn limited_store_enforces_memory_limit_and_epoch_deadline() {
+        let engine = EngineConfig::default()
+            .epoch_interval_ms(10)
+            .build_engine()
+            .unwrap();
+        let module = Module::new(
+            engine.get_engine(),
+            r#"(module
+                (memory 1)
+                (func (export "grow") (param i32) (result i32)
+                    local.get 0
+                    memory.grow)
+                (func (export "spin")
+                    (loop br 0)))"#,
+        )
+        .unwrap();
+        let limits = StoreLimitsBuilder::new().memory_size(2 * 65536).build();
+        let context = HostContext::new(ExecutionId::derive(&[1], "k", None));
+        // A generous deadline for setup and the grow calls, so a loaded
+        // machine cannot interrupt them before the spin loop.
+        let mut store = engine.new_limited_store(context, limits, u64::MAX / 2);
+        let instance = Instance::new_async(&mut store, &module, &[]).await.unwrap();
+
+        let grow = instance
+            .get_typed_func::<i32, i32>(&mut store, "grow")
+            .unwrap();
+        assert_eq!(grow.call_async(&mut store, 1).await.unwrap(), 1);
+        // A third page would exceed the 2-page limit; memory.grow reports -1.
+        assert_eq!(grow.call_async(&mut store, 1).await.unwrap(), -1);
+
+        let spin = instance
+            .get_typed_func::<(), ()>(&mut store, "spin")
+            .unwrap();
+        store.set_epoch_deadline(1);
+        let err = spin.call_async(&mut store, ()).await.unwrap_err();
+        assert_eq!(err.downcast_ref::<Trap>(), Some(&Trap::Interrupt));
+    }
+
+    #[tokio::test]
+    async fn deadline_behavior_selects_trap_or_yield() {
+        let spin = |engine: &WasmEngine| {
+            Module::new(
+                engine.get_engine(),
+                r#"(module (func (export "spin") (loop br 0)))"#,
+            )
+            .unwrap()
+        };
+
+        let engine = EngineConfig::default()
+            .epoch_interval_ms(10)
+            .build_engine()
+            .unwrap();
+        assert_eq!(engine.deadline_behavior(), DeadlineBehavior::Trap);
+        let module = spin(&engine);
+        let mut store = engine.new_store((), 1);
+        let instance = Instance::new_async(&mut store, &module, &[]).await.unwrap();
+        let func = instance
+            .get_typed_func::<(), ()>(&mut store, "spin")
+            .unwrap();
+        let err = func.call_async(&mut store, ()).await.unwrap_err();
+        assert_eq!(err.downcast_ref::<Trap>(), Some(&Trap::Interrupt));
+
+        let engine = EngineConfig::default()
+            .epoch_interval_ms(10)
+            .on_deadline(DeadlineBehavior::YieldAndUpdate(1))
+            .build_engine()
+            .unwrap();
+        assert_eq!(
+            engine.deadline_behavior(),
+            DeadlineBehavior::YieldAndUpdate(1)
+        );
+        let module = spin(&engine);
+        let mut store = engine.new_store((), 1);
+        let instance = Instance::new_async(&mut store, &module, &[]).await.unwrap();
+        let func = instance
+            .get_typed_func::<(), ()>(&mut store, "spin")
+            .unwrap();
+        // The guest keeps yielding instead of trapping, so only the outer
+        // timeout ends the call.
+        let call =
+            tokio::time::timeout(Duration::from_millis(100), func.call_async(&mut store, ()));
+        assert!(call.await.is_err());
+    }
+}
@@ -1,3 +1,4 @@
+use invariant_types::ExecutionError;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -5,5 +6,89 @@ pub enum RuntimeError {
     #[error("Failed to load component: {0}")]
     ComponentLoadError(#[from] wasmtime::Error),
     #[error("Failed to instantiate component: {0}")]
-    ComponentInstantiateError(String),
+    ComponentInstantiateError(ExecutionError),
+}
+
+/// The structured form of a runtime error, for reporting it across an RPC
+/// boundary (`invariant_journal::error::ErrorResponse::Runtime`).
+impl From<&RuntimeError> for ExecutionError {
+    fn from(err: &RuntimeError) -> Self {
+        match err {
+            RuntimeError::ComponentLoadError(err) => ExecutionError::from(err),
+            RuntimeError::ComponentInstantiateError(err) => err.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use invariant_types::ErrorKind;
+
+    use super::*;
+
+    #[test]
+    fn trap_converts_to_trap_kind() {
+        let err = wasmtime::Error::new(wasmtime::Trap::UnreachableCodeReached);
+        assert_eq!(ExecutionError::from(err).kind, ErrorKind::Trap);
+    }
+
+    #[test]
+    fn fuel_exhaustion_converts_to_timeout() {
+        let err = wasmtime::Error::new(wasmtime::Trap::OutOfFuel);
+        assert_eq!(ExecutionError::from(err).kind, ErrorKind::Timeout);
+    }
+
+    #[test]
+    fn non_trap_converts_to_uncategorized() {
+        let err = wasmtime::Error::msg("linker failed");
+        let converted = ExecutionError::from(err);
+        assert_eq!(converted.kind, ErrorKind::Uncategorized);
+        assert_eq!(converted.message, "linker failed");
+    }
+
+    #[test]
+    fn context_is_preserved_as_cause_chain() {
+        let err = wasmtime::Error::new(wasmtime::Trap::OutOfFuel).context("calling run");
+        let converted = ExecutionError::from(&err);
+        assert_eq!(converted.kind, ErrorKind::Timeout);
+        assert_eq!(converted.message, "calling run");
+
+        let root = converted.root_cause();
+        assert_eq!(root.kind, ErrorKind::Timeout);
+        assert_eq!(root.detail.as_deref(), Some("OutOfFuel"));
+        assert_eq!(converted.chain().count(), 2);
+    }
+
+    #[test]
+    fn trap_wrapping_timeout_classifies_by_root() {
+        let err = ExecutionError::new(ErrorKind::Trap, "host import failed").with_cause(
+            ExecutionError::from(wasmtime::Error::new(wasmtime::Trap::Interrupt)),
+        );
+        assert_eq!(err.kind, ErrorKind::Trap);
+        assert_eq!(err.root_kind(), &ErrorKind::Timeout);
+    }
+
+    #[test]
+    fn instantiate_error_carries_structured_error() {
+        let err = RuntimeError::ComponentInstantiateError(ExecutionError::from(
+            wasmtime::Error::new(wasmtime::Trap::StackOverflow),
+        ));
+        assert!(matches!(
+            err,
+            RuntimeError::ComponentInstantiateError(ExecutionError {
+                kind: ErrorKind::Trap,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn runtime_error_converts_to_its_structured_form() {
+        let load = RuntimeError::from(wasmtime::Error::new(wasmtime::Trap::OutOfFuel));
+        assert_eq!(ExecutionError::from(&load).kind, ErrorKind::Timeout);
+
+        let inner = ExecutionError::new(ErrorKind::Trap, "bad start");
+        let instantiate = RuntimeError::ComponentInstantiateError(inner.clone());
+        assert_eq!(ExecutionError::from(&instantiate), inner);
+    }
 }
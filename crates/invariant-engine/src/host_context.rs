@@ -0,0 +1,292 @@
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::time::Instant;
+
+use invariant_types::{ErrorKind, ExecutionError, ExecutionId};
+use wasmtime::StoreLimits;
+
+use crate::budget::{BudgetMeter, HostBudgets, HostCall};
+
+/// Most guest stderr bytes kept per execution; the rest are dropped.
+pub const STDERR_CAPTURE_LIMIT: usize = 64 * 1024;
+
+/// Store data for one execution: `Store<HostContext>`.
+///
+/// Host callbacks reach it through `Caller<'_, HostContext>` or
+/// `StoreContextMut<'_, HostContext>`. It owns the execution's host-call
+/// budgets, its wall-clock deadline and its captured stderr. Embedders
+/// attach their own per-execution state as typed extensions, one value per
+/// type.
+///
+/// The deterministic host that journals host calls is not part of this
+/// crate yet; until it is, callbacks journal through an extension and
+/// call [`charge`](Self::charge) themselves.
+///
+/// Host calls are not reentrant into the same store: a callback holds the
+/// only `&mut HostContext` for its duration and must not call back into the
+/// guest. Nothing here locks; a store is driven by one thread at a time.
+#[derive(Debug)]
+pub struct HostContext {
+    execution_id: ExecutionId,
+    extensions: Extensions,
+    /// Resource limits consulted by the store's limiter, if one is installed
+    /// (see [`WasmEngine::new_limited_store`](crate::WasmEngine::new_limited_store)).
+    limits: StoreLimits,
+    budget: BudgetMeter,
+    deadline: Option<Instant>,
+    stderr: Vec<u8>,
+    stderr_truncated: bool,
+}
+
+impl HostContext {
+    pub fn new(execution_id: ExecutionId) -> Self {
+        Self {
+            execution_id,
+            extensions: Extensions::default(),
+            limits: StoreLimits::default(),
+            budget: BudgetMeter::default(),
+            deadline: None,
+            stderr: Vec::new(),
+            stderr_truncated: false,
+        }
+    }
+
+    /// Cap journal-producing host calls at `budgets`.
+    pub fn with_budgets(mut self, budgets: HostBudgets) -> Self {
+        self.budget = BudgetMeter::new(budgets);
+        self
+    }
+
+    /// Fail host calls made after `deadline`.
+    ///
+    /// This bounds the whole execution across suspensions; the store's
+    /// epoch deadline only bounds uninterrupted guest code.
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    pub fn execution_id(&self) -> &ExecutionId {
+        &self.execution_id
+    }
+
+    pub fn budget(&self) -> &BudgetMeter {
+        &self.budget
+    }
+
+    /// Charge one host call to the budget, as [`BudgetMeter::charge`].
+    ///
+    /// # Errors
+    ///
+    /// [`ErrorKind::ResourceExhausted`] when the call's budget is spent.
+    pub fn charge(&mut self, call: HostCall, replayed: bool) -> Result<(), ExecutionError> {
+        self.budget.charge(call, replayed)
+    }
+
+    pub fn deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+
+    /// Check the execution deadline at `now`.
+    ///
+    /// # Errors
+    ///
+    /// [`ErrorKind::Timeout`] once `now` reaches the deadline.
+    pub fn check_deadline(&self, now: Instant) -> Result<(), ExecutionError> {
+        match self.deadline {
+            Some(deadline) if now >= deadline => Err(ExecutionError::new(
+                ErrorKind::Timeout,
+                "execution deadline passed",
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Append guest stderr output, keeping at most
+    /// [`STDERR_CAPTURE_LIMIT`] bytes.
+    pub fn capture_stderr(&mut self, bytes: &[u8]) {
+        let room = STDERR_CAPTURE_LIMIT - self.stderr.len();
+        if bytes.len() > room {
+            self.stderr_truncated = true;
+        }
+        self.stderr
+            .extend_from_slice(&bytes[..bytes.len().min(room)]);
+    }
+
+    /// Captured stderr so far.
+    pub fn stderr(&self) -> &[u8] {
+        &self.stderr
+    }
+
+    /// Whether stderr output was dropped at [`STDERR_CAPTURE_LIMIT`].
+    pub fn stderr_truncated(&self) -> bool {
+        self.stderr_truncated
+    }
+
+    pub fn extensions(&self) -> &Extensions {
+        &self.extensions
+    }
+
+    pub fn extensions_mut(&mut self) -> &mut Extensions {
+        &mut self.extensions
+    }
+
+    pub(crate) fn limits_mut(&mut self) -> &mut StoreLimits {
+        &mut self.limits
+    }
+}
+
+/// Access to the [`HostContext`] inside a store's data.
+///
+/// Host bindings are written against `T: AsHostContext` so an embedder can
+/// wrap `HostContext` in its own store type without the bindings and the
+/// embedder's code competing for the whole `&mut T`.
+pub trait AsHostContext {
+    fn host_context(&self) -> &HostContext;
+    fn host_context_mut(&mut self) -> &mut HostContext;
+}
+
+impl AsHostContext for HostContext {
+    fn host_context(&self) -> &HostContext {
+        self
+    }
+
+    fn host_context_mut(&mut self) -> &mut HostContext {
+        self
+    }
+}
+
+/// Typed slots for embedder state, keyed by type.
+#[derive(Default)]
+pub struct Extensions {
+    slots: HashMap<TypeId, Box<dyn Any + Send>>,
+}
+
+impl Extensions {
+    /// Store `value`, returning the previous value of the same type.
+    pub fn insert<T: Any + Send>(&mut self, value: T) -> Option<T> {
+        self.slots
+            .insert(TypeId::of::<T>(), Box::new(value))
+            .and_then(|old| old.downcast::<T>().ok().map(|old| *old))
+    }
+
+    pub fn get<T: Any + Send>(&self) -> Option<&T> {
+        self.slots.get(&TypeId::of::<T>())?.downcast_ref::<T>()
+    }
+
+    pub fn get_mut<T: Any + Send>(&mut self) -> Option<&mut T> {
+        self.slots.get_mut(&TypeId::of::<T>())?.downcast_mut::<T>()
+    }
+
+    pub fn remove<T: Any + Send>(&mut self) -> Option<T> {
+        self.slots
+            .remove(&TypeId::of::<T>())
+            .and_then(|old| old.downcast::<T>().ok().map(|old| *old))
+    }
+}
+
+impl std::fmt::Debug for Extensions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Extensions")
+            .field("len", &self.slots.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use wasmtime::{Caller, Func, Store};
+
+    use super::*;
+    use crate::EngineConfig;
+
+    /// Embedder extension recording every host invoke.
+    #[derive(Debug, Default, PartialEq)]
+    struct InvokeLog(Vec<i32>);
+
+    fn context() -> HostContext {
+        HostContext::new(ExecutionId::derive(&[1], "k", None))
+    }
+
+    #[test]
+    fn extensions_are_typed_slots() {
+        let mut ext = Extensions::default();
+        assert_eq!(ext.insert(InvokeLog(vec![1])), None);
+        assert_eq!(ext.insert(7u32), None);
+        ext.get_mut::<InvokeLog>().unwrap().0.push(2);
+        assert_eq!(ext.get::<InvokeLog>(), Some(&InvokeLog(vec![1, 2])));
+        assert_eq!(ext.insert(8u32), Some(7));
+        assert_eq!(ext.remove::<u32>(), Some(8));
+        assert_eq!(ext.get::<u32>(), None);
+    }
+
+    #[test]
+    fn charge_draws_on_the_configured_budgets() {
+        let mut ctx = context().with_budgets(HostBudgets {
+            max_timers: Some(1),
+            ..HostBudgets::default()
+        });
+        ctx.charge(HostCall::Timer, false).unwrap();
+        ctx.charge(HostCall::Timer, true).unwrap();
+        assert_eq!(
+            ctx.charge(HostCall::Timer, false).unwrap_err().kind,
+            ErrorKind::ResourceExhausted
+        );
+        assert_eq!(ctx.budget().used(HostCall::Timer), 1);
+    }
+
+    #[test]
+    fn deadline_fails_host_calls_with_timeout() {
+        let start = Instant::now();
+        let ctx = context().with_deadline(start + Duration::from_secs(5));
+        assert!(ctx.check_deadline(start).is_ok());
+        assert_eq!(
+            ctx.check_deadline(start + Duration::from_secs(5))
+                .unwrap_err()
+                .kind,
+            ErrorKind::Timeout
+        );
+        assert!(context().check_deadline(start).is_ok());
+    }
+
+    #[test]
+    fn stderr_capture_is_bounded() {
+        let mut ctx = context();
+        ctx.capture_stderr(b"warn: ");
+        ctx.capture_stderr(&vec![b'x'; STDERR_CAPTURE_LIMIT]);
+        assert_eq!(ctx.stderr().len(), STDERR_CAPTURE_LIMIT);
+        assert!(ctx.stderr().starts_with(b"warn: x"));
+        assert!(ctx.stderr_truncated());
+    }
+
+    #[tokio::test]
+    async fn host_callback_reaches_extension_through_store() {
+        let engine = EngineConfig::default().build_engine().unwrap();
+        let mut store = Store::new(engine.get_engine(), context());
+        store
+            .data_mut()
+            .extensions_mut()
+            .insert(InvokeLog::default());
+
+        let invoke = Func::wrap(
+            &mut store,
+            |mut caller: Caller<'_, HostContext>, arg: i32| {
+                let ctx = caller.data_mut().host_context_mut();
+                if let Some(log) = ctx.extensions_mut().get_mut::<InvokeLog>() {
+                    log.0.push(arg);
+                }
+            },
+        );
+        let invoke = invoke.typed::<i32, ()>(&store).unwrap();
+        // The engine enables async support, so calls must go through `call_async`.
+        invoke.call_async(&mut store, 3).await.unwrap();
+        invoke.call_async(&mut store, 5).await.unwrap();
+
+        assert_eq!(
+            store.data().extensions().get::<InvokeLog>(),
+            Some(&InvokeLog(vec![3, 5]))
+        );
+    }
+}
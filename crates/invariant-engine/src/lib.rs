@@ -1,7 +1,11 @@
+mod budget;
 mod component_loader;
 mod engine;
 mod error;
+mod host_context;
 
-pub use component_loader::{ComponentLoader, ComponentSource};
-pub use engine::{EngineConfig, WasmEngine};
+pub use budget::{BudgetMeter, HostBudgets, HostCall};
+pub use component_loader::{ComponentLoader, ComponentMetadata, ComponentSource};
+pub use engine::{DeadlineBehavior, EngineConfig, WasmEngine};
 pub use error::RuntimeError;
+pub use host_context::{AsHostContext, Extensions, HostContext, STDERR_CAPTURE_LIMIT};
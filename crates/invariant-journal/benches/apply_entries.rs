@@ -0,0 +1,91 @@
+//! Building `InvariantState` for a large validated journal: per-entry
+//! `check_append` versus bulk `apply_entries_from_journal`.
+
+use std::hint::black_box;
+
+use chrono::DateTime;
+use criterion::{BatchSize, Criterion, criterion_group, criterion_main};
+use invariant_journal::invariants::InvariantState;
+use invariant_types::{
+    Codec, EventType, ExecutionId, ExecutionJournal, InvokeKind, JournalEntry, Payload,
+};
+
+const ENTRIES: usize = 10_000;
+
+fn journal() -> ExecutionJournal {
+    let execution_id = ExecutionId::derive(&[1], "bench", None);
+    let payload = || Payload::new(vec![], Codec::Json);
+    let mut events = vec![EventType::ExecutionStarted {
+        component_digest: vec![1],
+        input: payload(),
+        parent_id: None,
+        idempotency_key: "bench".into(),
+    }];
+    let mut seq = 0;
+    while events.len() + 3 <= ENTRIES {
+        let promise_id = execution_id.child(seq).unwrap();
+        seq += 1;
+        events.push(EventType::InvokeScheduled {
+            promise_id: promise_id.clone(),
+            kind: InvokeKind::Function,
+            function_name: "step".into(),
+            input: payload(),
+            retry_policy: None,
+            sla: None,
+        });
+        events.push(EventType::InvokeStarted {
+            promise_id: promise_id.clone(),
+            attempt: 1,
+        });
+        events.push(EventType::InvokeCompleted {
+            promise_id,
+            result: payload(),
+            attempt: 1,
+        });
+    }
+    let entries = events
+        .into_iter()
+        .enumerate()
+        .map(|(i, event)| JournalEntry {
+            sequence: i as u64,
+            timestamp: DateTime::UNIX_EPOCH,
+            event,
+        })
+        .collect();
+    ExecutionJournal {
+        execution_id,
+        entries,
+        provenance: None,
+    }
+}
+
+fn apply_entries(c: &mut Criterion) {
+    let journal = journal();
+    let mut group = c.benchmark_group("invariant_state_10k");
+    group.bench_function("check_append_loop", |b| {
+        b.iter_batched(
+            InvariantState::new,
+            |mut state| {
+                for entry in &journal.entries {
+                    state.check_append(entry).unwrap();
+                }
+                black_box(state)
+            },
+            BatchSize::SmallInput,
+        )
+    });
+    group.bench_function("apply_entries_from_journal", |b| {
+        b.iter_batched(
+            InvariantState::new,
+            |mut state| {
+                state.apply_entries_from_journal(&journal);
+                black_box(state)
+            },
+            BatchSize::SmallInput,
+        )
+    });
+    group.finish();
+}
+
+criterion_group!(benches, apply_entries);
+criterion_main!(benches);
@@ -0,0 +1,77 @@
+//! Probing a large imported journal: `validate_quick` versus full
+//! `validate_journal`.
+
+use std::hint::black_box;
+
+use chrono::DateTime;
+use criterion::{Criterion, criterion_group, criterion_main};
+use invariant_journal::invariants::{QuickVerdict, validate_journal, validate_quick};
+use invariant_types::{
+    Codec, EventType, ExecutionId, ExecutionJournal, InvokeKind, JournalEntry, Payload,
+};
+
+const ENTRIES: usize = 50_000;
+
+fn journal() -> ExecutionJournal {
+    let execution_id = ExecutionId::derive(&[1], "bench", None);
+    let payload = || Payload::new(vec![], Codec::Json);
+    let mut events = vec![EventType::ExecutionStarted {
+        component_digest: vec![1],
+        input: payload(),
+        parent_id: None,
+        idempotency_key: "bench".into(),
+    }];
+    let mut seq = 0;
+    while events.len() + 4 <= ENTRIES {
+        let promise_id = execution_id.child(seq).unwrap();
+        seq += 1;
+        events.push(EventType::InvokeScheduled {
+            promise_id: promise_id.clone(),
+            kind: InvokeKind::Function,
+            function_name: "step".into(),
+            input: payload(),
+            retry_policy: None,
+            sla: None,
+        });
+        events.push(EventType::InvokeStarted {
+            promise_id: promise_id.clone(),
+            attempt: 1,
+        });
+        events.push(EventType::InvokeCompleted {
+            promise_id,
+            result: payload(),
+            attempt: 1,
+        });
+    }
+    events.push(EventType::ExecutionCompleted { result: payload() });
+    let entries = events
+        .into_iter()
+        .enumerate()
+        .map(|(i, event)| JournalEntry {
+            sequence: i as u64,
+            timestamp: DateTime::UNIX_EPOCH,
+            event,
+        })
+        .collect();
+    ExecutionJournal {
+        execution_id,
+        entries,
+        provenance: None,
+    }
+}
+
+fn quick_probe(c: &mut Criterion) {
+    let journal = journal();
+    assert_eq!(validate_quick(&journal), QuickVerdict::LooksValid);
+    let mut group = c.benchmark_group("probe_50k");
+    group.bench_function("validate_quick", |b| {
+        b.iter(|| black_box(validate_quick(black_box(&journal))))
+    });
+    group.bench_function("validate_journal", |b| {
+        b.iter(|| black_box(validate_journal(black_box(&journal))))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, quick_probe);
+criterion_main!(benches);
@@ -0,0 +1,316 @@
+//! Fluent assertions over an execution's history, enabled by the `testing`
+//! feature.
+//!
+//! Workflow authors use [`JournalAssert`] in tests to pin down properties of
+//! their own journals, e.g. "`charge_card` was invoked at most once". Each
+//! failed assertion panics with the journal entries that broke it.
+
+use std::collections::HashSet;
+use std::fmt::Write as _;
+
+use invariant_types::{
+    EventType, ExecutionJournal, ExecutionStatusKind, FunctionName, JournalEntry, PromiseId,
+};
+
+use crate::causality::{EventSelector, HappenedBefore};
+use crate::status::derive_status;
+
+/// Chainable assertions over one [`ExecutionJournal`].
+///
+/// ```ignore
+/// JournalAssert::new(&journal)
+///     .assert_invoked_at_most(&FunctionName::new("charge_card")?, 1)
+///     .assert_no_retries_for(&FunctionName::new("send_email")?)
+///     .assert_terminal(ExecutionStatusKind::Completed);
+/// ```
+pub struct JournalAssert<'a> {
+    journal: &'a ExecutionJournal,
+    order: HappenedBefore,
+}
+
+impl<'a> JournalAssert<'a> {
+    pub fn new(journal: &'a ExecutionJournal) -> Self {
+        Self {
+            journal,
+            order: HappenedBefore::from(journal),
+        }
+    }
+
+    /// Assert that `function_name` was scheduled at most `max` times.
+    ///
+    /// # Panics
+    ///
+    /// Panics listing every `InvokeScheduled` for the function.
+    #[track_caller]
+    pub fn assert_invoked_at_most(&self, function_name: &FunctionName, max: usize) -> &Self {
+        let scheduled: Vec<_> = self
+            .entries()
+            .iter()
+            .filter(|e| scheduled_function(&e.event) == Some(function_name.as_str()))
+            .collect();
+        assert!(
+            scheduled.len() <= max,
+            "expected `{function_name}` to be invoked at most {max} time(s), found {}:\n{}",
+            scheduled.len(),
+            render(scheduled)
+        );
+        self
+    }
+
+    /// Assert that the event `a` names happened strictly before `b`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either event is missing, or with the entries from `b`
+    /// through `a` if they are out of order.
+    #[track_caller]
+    pub fn assert_order(&self, a: EventSelector<'_>, b: EventSelector<'_>) -> &Self {
+        let (Some(a_seq), Some(b_seq)) = (self.order.seq_of(a), self.order.seq_of(b)) else {
+            panic!(
+                "cannot order {a:?} before {b:?}: {} not in the journal",
+                match (self.order.seq_of(a), self.order.seq_of(b)) {
+                    (None, None) => "neither event is",
+                    (None, Some(_)) => "the first event is",
+                    _ => "the second event is",
+                }
+            );
+        };
+        assert!(
+            a_seq < b_seq,
+            "expected {a:?} (seq {a_seq}) before {b:?} (seq {b_seq}):\n{}",
+            render(self.journal.entries_in_range(b_seq, a_seq))
+        );
+        self
+    }
+
+    /// Assert that no invoke of `function_name` was ever retried.
+    ///
+    /// # Panics
+    ///
+    /// Panics listing every `InvokeRetrying` for the function's promises.
+    #[track_caller]
+    pub fn assert_no_retries_for(&self, function_name: &FunctionName) -> &Self {
+        let pids: HashSet<&PromiseId> = self
+            .entries()
+            .iter()
+            .filter(|e| scheduled_function(&e.event) == Some(function_name.as_str()))
+            .filter_map(|e| e.event.promise_id())
+            .collect();
+        let retries: Vec<_> = self
+            .entries()
+            .iter()
+            .filter(|e| match &e.event {
+                EventType::InvokeRetrying { promise_id, .. } => pids.contains(promise_id),
+                _ => false,
+            })
+            .collect();
+        assert!(
+            retries.is_empty(),
+            "expected no retries for `{function_name}`, found {}:\n{}",
+            retries.len(),
+            render(retries)
+        );
+        self
+    }
+
+    /// Assert that the journal ended in the terminal status `expected`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `expected` is not a terminal kind (`Completed`, `Failed` or
+    /// `Cancelled`), on an empty journal, or with the journal's last entries
+    /// if the status differs.
+    #[track_caller]
+    pub fn assert_terminal(&self, expected: ExecutionStatusKind) -> &Self {
+        assert!(
+            expected.is_terminal(),
+            "assert_terminal takes a terminal status kind, got {expected:?}"
+        );
+        let entries = self.entries();
+        assert!(
+            !entries.is_empty(),
+            "expected {expected:?}, journal is empty"
+        );
+        let actual = derive_status(entries).kind();
+        assert!(
+            actual == expected,
+            "expected status {expected:?}, journal ends {actual:?}:\n{}",
+            render(&entries[entries.len().saturating_sub(TAIL_LEN)..])
+        );
+        self
+    }
+
+    fn entries(&self) -> &'a [JournalEntry] {
+        &self.journal.entries
+    }
+}
+
+/// Entries shown when a status assertion fails.
+const TAIL_LEN: usize = 5;
+
+fn scheduled_function(event: &EventType) -> Option<&str> {
+    match event {
+        EventType::InvokeScheduled { function_name, .. } => Some(function_name),
+        _ => None,
+    }
+}
+
+/// One line per entry: sequence, event name and promise, if any.
+fn render<'e>(entries: impl IntoIterator<Item = &'e JournalEntry>) -> String {
+    let mut out = String::new();
+    for e in entries {
+        let _ = write!(out, "  {:>4}  {}", e.sequence, e.event.name());
+        if let Some(pid) = e.event.promise_id() {
+            let _ = write!(out, " {pid}");
+        }
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::DateTime;
+    use invariant_types::{
+        Codec, ErrorKind, ExecutionError, ExecutionId, InvokeKind, Payload, PromiseId,
+    };
+
+    use super::*;
+
+    fn payload() -> Payload {
+        Payload::new(vec![], Codec::Json)
+    }
+
+    fn pid(n: u8) -> PromiseId {
+        PromiseId::new([n; 32])
+    }
+
+    fn function(name: &str) -> FunctionName {
+        FunctionName::new(name).unwrap()
+    }
+
+    fn journal(events: Vec<EventType>) -> ExecutionJournal {
+        let mut all = vec![EventType::ExecutionStarted {
+            component_digest: vec![1],
+            input: payload(),
+            parent_id: None,
+            idempotency_key: "k".into(),
+        }];
+        all.extend(events);
+        ExecutionJournal {
+            execution_id: ExecutionId::derive(&[1], "k", None),
+            entries: all
+                .into_iter()
+                .enumerate()
+                .map(|(seq, event)| JournalEntry {
+                    sequence: seq as u64,
+                    timestamp: DateTime::UNIX_EPOCH,
+                    event,
+                })
+                .collect(),
+            provenance: None,
+        }
+    }
+
+    fn scheduled(p: PromiseId, name: &str) -> EventType {
+        EventType::InvokeScheduled {
+            promise_id: p,
+            kind: InvokeKind::Function,
+            function_name: name.into(),
+            input: payload(),
+            retry_policy: None,
+            sla: None,
+        }
+    }
+
+    fn started(p: PromiseId, attempt: u32) -> EventType {
+        EventType::InvokeStarted {
+            promise_id: p,
+            attempt,
+        }
+    }
+
+    fn completed(p: PromiseId, attempt: u32) -> EventType {
+        EventType::InvokeCompleted {
+            promise_id: p,
+            result: payload(),
+            attempt,
+        }
+    }
+
+    /// `charge_card` once, `send_email` retried once, then completed.
+    fn checkout() -> ExecutionJournal {
+        journal(vec![
+            scheduled(pid(1), "charge_card"),
+            started(pid(1), 1),
+            completed(pid(1), 1),
+            scheduled(pid(2), "send_email"),
+            started(pid(2), 1),
+            EventType::InvokeRetrying {
+                promise_id: pid(2),
+                failed_attempt: 1,
+                error: ExecutionError::new(ErrorKind::Timeout, "smtp"),
+                retry_at: DateTime::UNIX_EPOCH,
+            },
+            started(pid(2), 2),
+            completed(pid(2), 2),
+            EventType::ExecutionCompleted { result: payload() },
+        ])
+    }
+
+    #[test]
+    fn passing_assertions_chain() {
+        let j = checkout();
+        JournalAssert::new(&j)
+            .assert_invoked_at_most(&function("charge_card"), 1)
+            .assert_invoked_at_most(&function("refund"), 0)
+            .assert_no_retries_for(&function("charge_card"))
+            .assert_order(
+                EventSelector::InvokeCompletedOf(&pid(1)),
+                EventSelector::InvokeScheduledOf(&pid(2)),
+            )
+            .assert_terminal(ExecutionStatusKind::Completed);
+    }
+
+    #[test]
+    #[should_panic(expected = "`charge_card` to be invoked at most 0 time(s), found 1")]
+    fn invoked_at_most_reports_count() {
+        JournalAssert::new(&checkout()).assert_invoked_at_most(&function("charge_card"), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "6  InvokeRetrying")]
+    fn retries_panic_shows_retry_entry() {
+        JournalAssert::new(&checkout()).assert_no_retries_for(&function("send_email"));
+    }
+
+    #[test]
+    #[should_panic(expected = "(seq 4) before")]
+    fn order_panic_reports_sequences() {
+        JournalAssert::new(&checkout()).assert_order(
+            EventSelector::InvokeScheduledOf(&pid(2)),
+            EventSelector::InvokeScheduledOf(&pid(1)),
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "the second event is not in the journal")]
+    fn order_panic_names_missing_event() {
+        JournalAssert::new(&checkout()).assert_order(
+            EventSelector::InvokeScheduledOf(&pid(1)),
+            EventSelector::CancelRequested,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "expected status Failed, journal ends Completed")]
+    fn terminal_panic_reports_actual_status() {
+        JournalAssert::new(&checkout()).assert_terminal(ExecutionStatusKind::Failed);
+    }
+
+    #[test]
+    #[should_panic(expected = "assert_terminal takes a terminal status kind, got Running")]
+    fn terminal_rejects_non_terminal_kind() {
+        JournalAssert::new(&checkout()).assert_terminal(ExecutionStatusKind::Running);
+    }
+}
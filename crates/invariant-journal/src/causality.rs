@@ -0,0 +1,387 @@
+//! Happened-before queries over journal sequences.
+//!
+//! A journal is a total order, so "did X happen before Y" reduces to
+//! comparing the sequence numbers of two events. [`EventSelector`] names an
+//! event declaratively (for example "the first await on join set J") and
+//! [`HappenedBefore`] answers ordering questions over those names after a
+//! single indexing pass.
+//!
+//! Selectors always resolve to the *first* matching entry in journal order.
+
+use std::collections::HashMap;
+use std::ops::Range;
+
+use invariant_types::{
+    EventType, ExecutionJournal, JoinSetId, JournalEntry, PromiseId, SignalDeliveryId,
+};
+
+/// Names one event in a journal by what it refers to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EventSelector<'a> {
+    InvokeScheduledOf(&'a PromiseId),
+    /// First `InvokeStarted` for the promise, whatever the attempt.
+    InvokeStartedOf(&'a PromiseId),
+    InvokeCompletedOf(&'a PromiseId),
+    TimerScheduledOf(&'a PromiseId),
+    TimerFiredOf(&'a PromiseId),
+    SignalDeliveredOf(&'a str, SignalDeliveryId),
+    SignalReceivedOf(&'a str, SignalDeliveryId),
+    JoinSetCreatedOf(&'a JoinSetId),
+    FirstSubmitOf(&'a JoinSetId),
+    FirstAwaitOf(&'a JoinSetId),
+    CancelRequested,
+    /// The first terminal event (see [`EventType::is_terminal`]).
+    Terminal,
+}
+
+impl EventSelector<'_> {
+    /// Whether `event` is one this selector names.
+    pub fn matches(&self, event: &EventType) -> bool {
+        match (*self, event) {
+            (Self::InvokeScheduledOf(pid), EventType::InvokeScheduled { promise_id, .. })
+            | (Self::InvokeStartedOf(pid), EventType::InvokeStarted { promise_id, .. })
+            | (Self::InvokeCompletedOf(pid), EventType::InvokeCompleted { promise_id, .. })
+            | (Self::TimerScheduledOf(pid), EventType::TimerScheduled { promise_id, .. })
+            | (Self::TimerFiredOf(pid), EventType::TimerFired { promise_id }) => promise_id == pid,
+            (
+                Self::SignalDeliveredOf(name, id),
+                EventType::SignalDelivered {
+                    signal_name,
+                    delivery_id,
+                    ..
+                },
+            )
+            | (
+                Self::SignalReceivedOf(name, id),
+                EventType::SignalReceived {
+                    signal_name,
+                    delivery_id,
+                    ..
+                },
+            ) => signal_name == name && *delivery_id == id,
+            (Self::JoinSetCreatedOf(js), EventType::JoinSetCreated { join_set_id })
+            | (Self::FirstSubmitOf(js), EventType::JoinSetSubmitted { join_set_id, .. })
+            | (Self::FirstAwaitOf(js), EventType::JoinSetAwaited { join_set_id, .. }) => {
+                join_set_id == js
+            }
+            (Self::CancelRequested, EventType::CancelRequested { .. }) => true,
+            (Self::Terminal, event) => event.is_terminal(),
+            _ => false,
+        }
+    }
+}
+
+/// Returns the sequence of the first entry `selector` matches.
+///
+/// Use this for one-off questions; build a [`HappenedBefore`] when asking
+/// several.
+/// Scan complexity: O(n).
+pub fn first_seq(entries: &[JournalEntry], selector: EventSelector<'_>) -> Option<u64> {
+    entries
+        .iter()
+        .find(|e| selector.matches(&e.event))
+        .map(|e| e.sequence)
+}
+
+/// Index of first-occurrence sequences for every selectable event.
+///
+/// Built in one O(n) pass; each query is O(1).
+#[derive(Clone, Debug, Default)]
+pub struct HappenedBefore {
+    invoke_scheduled: HashMap<PromiseId, u64>,
+    invoke_started: HashMap<PromiseId, u64>,
+    invoke_completed: HashMap<PromiseId, u64>,
+    timer_scheduled: HashMap<PromiseId, u64>,
+    timer_fired: HashMap<PromiseId, u64>,
+    signal_delivered: HashMap<String, HashMap<SignalDeliveryId, u64>>,
+    signal_received: HashMap<String, HashMap<SignalDeliveryId, u64>>,
+    join_set_created: HashMap<JoinSetId, u64>,
+    first_submit: HashMap<JoinSetId, u64>,
+    first_await: HashMap<JoinSetId, u64>,
+    cancel_requested: Option<u64>,
+    terminal: Option<u64>,
+}
+
+impl HappenedBefore {
+    /// Index `entries`, keeping the first sequence seen for each selector.
+    pub fn from_entries(entries: &[JournalEntry]) -> Self {
+        let mut index = Self::default();
+        for e in entries {
+            let seq = e.sequence;
+            match &e.event {
+                EventType::InvokeScheduled { promise_id, .. } => {
+                    index
+                        .invoke_scheduled
+                        .entry(promise_id.clone())
+                        .or_insert(seq);
+                }
+                EventType::InvokeStarted { promise_id, .. } => {
+                    index
+                        .invoke_started
+                        .entry(promise_id.clone())
+                        .or_insert(seq);
+                }
+                EventType::InvokeCompleted { promise_id, .. } => {
+                    index
+                        .invoke_completed
+                        .entry(promise_id.clone())
+                        .or_insert(seq);
+                }
+                EventType::TimerScheduled { promise_id, .. } => {
+                    index
+                        .timer_scheduled
+                        .entry(promise_id.clone())
+                        .or_insert(seq);
+                }
+                EventType::TimerFired { promise_id } => {
+                    index.timer_fired.entry(promise_id.clone()).or_insert(seq);
+                }
+                EventType::SignalDelivered {
+                    signal_name,
+                    delivery_id,
+                    ..
+                } => {
+                    index
+                        .signal_delivered
+                        .entry(signal_name.clone())
+                        .or_default()
+                        .entry(*delivery_id)
+                        .or_insert(seq);
+                }
+                EventType::SignalReceived {
+                    signal_name,
+                    delivery_id,
+                    ..
+                } => {
+                    index
+                        .signal_received
+                        .entry(signal_name.clone())
+                        .or_default()
+                        .entry(*delivery_id)
+                        .or_insert(seq);
+                }
+                EventType::JoinSetCreated { join_set_id } => {
+                    index
+                        .join_set_created
+                        .entry(join_set_id.clone())
+                        .or_insert(seq);
+                }
+                EventType::JoinSetSubmitted { join_set_id, .. } => {
+                    index.first_submit.entry(join_set_id.clone()).or_insert(seq);
+                }
+                EventType::JoinSetAwaited { join_set_id, .. } => {
+                    index.first_await.entry(join_set_id.clone()).or_insert(seq);
+                }
+                EventType::CancelRequested { .. } => {
+                    index.cancel_requested.get_or_insert(seq);
+                }
+                _ => {}
+            }
+            if e.event.is_terminal() {
+                index.terminal.get_or_insert(seq);
+            }
+        }
+        index
+    }
+
+    /// Sequence of the event `selector` names, if it occurred.
+    pub fn seq_of(&self, selector: EventSelector<'_>) -> Option<u64> {
+        match selector {
+            EventSelector::InvokeScheduledOf(pid) => self.invoke_scheduled.get(pid),
+            EventSelector::InvokeStartedOf(pid) => self.invoke_started.get(pid),
+            EventSelector::InvokeCompletedOf(pid) => self.invoke_completed.get(pid),
+            EventSelector::TimerScheduledOf(pid) => self.timer_scheduled.get(pid),
+            EventSelector::TimerFiredOf(pid) => self.timer_fired.get(pid),
+            EventSelector::SignalDeliveredOf(name, id) => {
+                self.signal_delivered.get(name).and_then(|ids| ids.get(&id))
+            }
+            EventSelector::SignalReceivedOf(name, id) => {
+                self.signal_received.get(name).and_then(|ids| ids.get(&id))
+            }
+            EventSelector::JoinSetCreatedOf(js) => self.join_set_created.get(js),
+            EventSelector::FirstSubmitOf(js) => self.first_submit.get(js),
+            EventSelector::FirstAwaitOf(js) => self.first_await.get(js),
+            EventSelector::CancelRequested => self.cancel_requested.as_ref(),
+            EventSelector::Terminal => self.terminal.as_ref(),
+        }
+        .copied()
+    }
+
+    /// Whether `a` happened strictly before `b`.
+    ///
+    /// `None` if either event is absent from the journal.
+    pub fn before(&self, a: EventSelector<'_>, b: EventSelector<'_>) -> Option<bool> {
+        Some(self.seq_of(a)? < self.seq_of(b)?)
+    }
+
+    /// Sequences of the entries strictly between `a` and `b`.
+    ///
+    /// `None` if either event is absent or `a` does not precede `b`. Adjacent
+    /// events yield an empty range.
+    pub fn interval(&self, a: EventSelector<'_>, b: EventSelector<'_>) -> Option<Range<u64>> {
+        let (a, b) = (self.seq_of(a)?, self.seq_of(b)?);
+        (a < b).then(|| a + 1..b)
+    }
+}
+
+impl From<&ExecutionJournal> for HappenedBefore {
+    fn from(journal: &ExecutionJournal) -> Self {
+        Self::from_entries(&journal.entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::DateTime;
+    use invariant_types::{Codec, ExecutionId, InvokeKind, Payload};
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::testing::arb_valid_journal;
+
+    fn pid(tag: u8) -> PromiseId {
+        PromiseId::new([tag; 32])
+    }
+
+    fn payload() -> Payload {
+        Payload::new(vec![], Codec::Json)
+    }
+
+    fn journal(events: Vec<EventType>) -> ExecutionJournal {
+        ExecutionJournal {
+            execution_id: ExecutionId::derive(&[1], "k", None),
+            entries: events
+                .into_iter()
+                .enumerate()
+                .map(|(i, event)| JournalEntry {
+                    sequence: i as u64,
+                    timestamp: DateTime::UNIX_EPOCH,
+                    event,
+                })
+                .collect(),
+            provenance: None,
+        }
+    }
+
+    /// The selector naming `event` itself, if it is selectable.
+    fn selector_for(event: &EventType) -> Option<EventSelector<'_>> {
+        Some(match event {
+            EventType::InvokeScheduled { promise_id, .. } => {
+                EventSelector::InvokeScheduledOf(promise_id)
+            }
+            EventType::InvokeStarted { promise_id, .. } => {
+                EventSelector::InvokeStartedOf(promise_id)
+            }
+            EventType::InvokeCompleted { promise_id, .. } => {
+                EventSelector::InvokeCompletedOf(promise_id)
+            }
+            EventType::TimerScheduled { promise_id, .. } => {
+                EventSelector::TimerScheduledOf(promise_id)
+            }
+            EventType::TimerFired { promise_id } => EventSelector::TimerFiredOf(promise_id),
+            EventType::SignalDelivered {
+                signal_name,
+                delivery_id,
+                ..
+            } => EventSelector::SignalDeliveredOf(signal_name, *delivery_id),
+            EventType::SignalReceived {
+                signal_name,
+                delivery_id,
+                ..
+            } => EventSelector::SignalReceivedOf(signal_name, *delivery_id),
+            EventType::JoinSetCreated { join_set_id } => {
+                EventSelector::JoinSetCreatedOf(join_set_id)
+            }
+            EventType::JoinSetSubmitted { join_set_id, .. } => {
+                EventSelector::FirstSubmitOf(join_set_id)
+            }
+            EventType::JoinSetAwaited { join_set_id, .. } => {
+                EventSelector::FirstAwaitOf(join_set_id)
+            }
+            EventType::CancelRequested { .. } => EventSelector::CancelRequested,
+            e if e.is_terminal() => EventSelector::Terminal,
+            _ => return None,
+        })
+    }
+
+    #[test]
+    fn orders_invoke_lifecycle() {
+        let p = pid(1);
+        let j = journal(vec![
+            EventType::ExecutionStarted {
+                component_digest: vec![1],
+                input: payload(),
+                parent_id: None,
+                idempotency_key: "k".into(),
+            },
+            EventType::InvokeScheduled {
+                promise_id: p.clone(),
+                kind: InvokeKind::Function,
+                function_name: "f".into(),
+                input: payload(),
+                retry_policy: None,
+                sla: None,
+            },
+            EventType::InvokeStarted {
+                promise_id: p.clone(),
+                attempt: 1,
+            },
+            EventType::InvokeCompleted {
+                promise_id: p.clone(),
+                result: payload(),
+                attempt: 1,
+            },
+            EventType::ExecutionCompleted { result: payload() },
+        ]);
+        let hb = HappenedBefore::from(&j);
+
+        let scheduled = EventSelector::InvokeScheduledOf(&p);
+        let completed = EventSelector::InvokeCompletedOf(&p);
+        assert_eq!(hb.seq_of(completed), Some(3));
+        assert_eq!(hb.before(scheduled, completed), Some(true));
+        assert_eq!(hb.before(completed, scheduled), Some(false));
+        assert_eq!(hb.interval(scheduled, completed), Some(2..3));
+        assert_eq!(hb.interval(completed, EventSelector::Terminal), Some(4..4));
+        assert_eq!(hb.interval(completed, scheduled), None);
+
+        let other = pid(2);
+        assert_eq!(
+            hb.before(scheduled, EventSelector::TimerFiredOf(&other)),
+            None
+        );
+        assert_eq!(hb.seq_of(EventSelector::CancelRequested), None);
+    }
+
+    proptest! {
+        #[test]
+        fn index_matches_brute_force_scan(j in arb_valid_journal(64)) {
+            let hb = HappenedBefore::from(&j);
+            let missing = pid(0);
+            let mut selectors: Vec<_> =
+                j.entries.iter().filter_map(|e| selector_for(&e.event)).collect();
+            selectors.push(EventSelector::InvokeCompletedOf(&missing));
+            selectors.push(EventSelector::CancelRequested);
+
+            for &a in &selectors {
+                let seq_a = first_seq(&j.entries, a);
+                prop_assert_eq!(hb.seq_of(a), seq_a, "{:?}", a);
+                for &b in &selectors {
+                    let seq_b = first_seq(&j.entries, b);
+                    let expected = seq_a.zip(seq_b).map(|(a, b)| a < b);
+                    prop_assert_eq!(hb.before(a, b), expected);
+
+                    let between: Option<Vec<u64>> = seq_a.zip(seq_b).filter(|(a, b)| a < b).map(
+                        |(a, b)| {
+                            j.entries
+                                .iter()
+                                .map(|e| e.sequence)
+                                .filter(|s| *s > a && *s < b)
+                                .collect()
+                        },
+                    );
+                    prop_assert_eq!(hb.interval(a, b).map(|r| r.collect()), between);
+                }
+            }
+        }
+    }
+}
@@ -12,7 +12,7 @@ use invariant_types::{
 /// non-allocating form for type-safe event conversion.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum Command {
-    // Lifecycle (4)
+    // Lifecycle (5)
     Complete {
         result: Payload,
     },
@@ -25,12 +25,19 @@ pub enum Command {
     Cancel {
         reason: String,
     },
-    // Side Effects (4)
+    /// Record an approved switch to a new component build.
+    UpgradeComponent {
+        old_digest: Vec<u8>,
+        new_digest: Vec<u8>,
+        approved_by: String,
+    },
+    // Side Effects (5)
     ScheduleInvoke {
         kind: InvokeKind,
         function_name: String,
         input: Payload,
         retry_policy: Option<RetryPolicy>,
+        sla: Option<Duration>,
     },
     StartInvoke {
         promise_id: PromiseId,
@@ -47,6 +54,13 @@ pub enum Command {
         error: ExecutionError,
         retry_at: DateTime<Utc>,
     },
+    /// Report that a running attempt is alive.
+    RecordHeartbeat {
+        promise_id: PromiseId,
+        attempt: u32,
+        at: DateTime<Utc>,
+        progress: Option<Payload>,
+    },
     // Nondeterminism (2)
     CaptureRandom {
         value: Vec<u8>,
@@ -54,7 +68,7 @@ pub enum Command {
     CaptureTime {
         time: DateTime<Utc>,
     },
-    // Control Flow (6)
+    // Control Flow (8)
     ScheduleTimer {
         duration: Duration,
         fire_at: DateTime<Utc>,
@@ -66,6 +80,12 @@ pub enum Command {
         signal_name: String,
         payload: Payload,
         delivery_id: SignalDeliveryId,
+        external_id: Option<String>,
+    },
+    /// Drop a buffered delivery that was never consumed.
+    ExpireSignal {
+        signal_name: String,
+        delivery_id: SignalDeliveryId,
     },
     ConsumeSignal {
         signal_name: String,
@@ -77,6 +97,10 @@ pub enum Command {
         kind: AwaitKind,
     },
     Resume,
+    ReportAwaitProgress {
+        waiting_on_remaining: u32,
+        resolved_since_await: Vec<PromiseId>,
+    },
     // Concurrency (3)
     CreateJoinSet,
     SubmitToJoinSet {
@@ -101,11 +125,13 @@ impl Command {
                 function_name,
                 input,
                 retry_policy,
+                sla,
             } => CommandKind::Allocating(AllocatingCommand::ScheduleInvoke {
                 kind,
                 function_name,
                 input,
                 retry_policy,
+                sla,
             }),
             Command::CaptureRandom { value } => {
                 CommandKind::Allocating(AllocatingCommand::CaptureRandom { value })
@@ -126,7 +152,7 @@ impl Command {
                 delivery_id,
             }),
             Command::CreateJoinSet => CommandKind::Allocating(AllocatingCommand::CreateJoinSet),
-            // ── Non-allocating (13) ──
+            // ── Non-allocating (17) ──
             Command::Complete { result } => {
                 CommandKind::NonAllocating(NonAllocatingCommand::Complete { result })
             }
@@ -139,6 +165,15 @@ impl Command {
             Command::Cancel { reason } => {
                 CommandKind::NonAllocating(NonAllocatingCommand::Cancel { reason })
             }
+            Command::UpgradeComponent {
+                old_digest,
+                new_digest,
+                approved_by,
+            } => CommandKind::NonAllocating(NonAllocatingCommand::UpgradeComponent {
+                old_digest,
+                new_digest,
+                approved_by,
+            }),
             Command::StartInvoke {
                 promise_id,
                 attempt,
@@ -166,6 +201,17 @@ impl Command {
                 error,
                 retry_at,
             }),
+            Command::RecordHeartbeat {
+                promise_id,
+                attempt,
+                at,
+                progress,
+            } => CommandKind::NonAllocating(NonAllocatingCommand::RecordHeartbeat {
+                promise_id,
+                attempt,
+                at,
+                progress,
+            }),
             Command::FireTimer { promise_id } => {
                 CommandKind::NonAllocating(NonAllocatingCommand::FireTimer { promise_id })
             }
@@ -173,15 +219,31 @@ impl Command {
                 signal_name,
                 payload,
                 delivery_id,
+                external_id,
             } => CommandKind::NonAllocating(NonAllocatingCommand::DeliverSignal {
                 signal_name,
                 payload,
                 delivery_id,
+                external_id,
+            }),
+            Command::ExpireSignal {
+                signal_name,
+                delivery_id,
+            } => CommandKind::NonAllocating(NonAllocatingCommand::ExpireSignal {
+                signal_name,
+                delivery_id,
             }),
             Command::Await { waiting_on, kind } => {
                 CommandKind::NonAllocating(NonAllocatingCommand::Await { waiting_on, kind })
             }
             Command::Resume => CommandKind::NonAllocating(NonAllocatingCommand::Resume),
+            Command::ReportAwaitProgress {
+                waiting_on_remaining,
+                resolved_since_await,
+            } => CommandKind::NonAllocating(NonAllocatingCommand::ReportAwaitProgress {
+                waiting_on_remaining,
+                resolved_since_await,
+            }),
             Command::SubmitToJoinSet {
                 join_set_id,
                 promise_id,
@@ -216,6 +278,7 @@ pub(crate) enum AllocatingCommand {
         function_name: String,
         input: Payload,
         retry_policy: Option<RetryPolicy>,
+        sla: Option<Duration>,
     },
     CaptureRandom {
         value: Vec<u8>,
@@ -237,7 +300,7 @@ pub(crate) enum AllocatingCommand {
 
 /// Commands that carry their own [`PromiseId`] or need none.
 pub(crate) enum NonAllocatingCommand {
-    // Lifecycle (4)
+    // Lifecycle (5)
     Complete {
         result: Payload,
     },
@@ -250,7 +313,12 @@ pub(crate) enum NonAllocatingCommand {
     Cancel {
         reason: String,
     },
-    // Side Effects — referencing (3)
+    UpgradeComponent {
+        old_digest: Vec<u8>,
+        new_digest: Vec<u8>,
+        approved_by: String,
+    },
+    // Side Effects — referencing (4)
     StartInvoke {
         promise_id: PromiseId,
         attempt: u32,
@@ -266,7 +334,13 @@ pub(crate) enum NonAllocatingCommand {
         error: ExecutionError,
         retry_at: DateTime<Utc>,
     },
-    // Control Flow — referencing (3)
+    RecordHeartbeat {
+        promise_id: PromiseId,
+        attempt: u32,
+        at: DateTime<Utc>,
+        progress: Option<Payload>,
+    },
+    // Control Flow — referencing (6)
     FireTimer {
         promise_id: PromiseId,
     },
@@ -274,12 +348,21 @@ pub(crate) enum NonAllocatingCommand {
         signal_name: String,
         payload: Payload,
         delivery_id: SignalDeliveryId,
+        external_id: Option<String>,
+    },
+    ExpireSignal {
+        signal_name: String,
+        delivery_id: SignalDeliveryId,
     },
     Await {
         waiting_on: Vec<PromiseId>,
         kind: AwaitKind,
     },
     Resume,
+    ReportAwaitProgress {
+        waiting_on_remaining: u32,
+        resolved_since_await: Vec<PromiseId>,
+    },
     // Concurrency — referencing (2)
     SubmitToJoinSet {
         join_set_id: JoinSetId,
@@ -309,12 +392,14 @@ pub(crate) fn allocating_to_event(cmd: AllocatingCommand, allocated_id: PromiseI
             function_name,
             input,
             retry_policy,
+            sla,
         } => EventType::InvokeScheduled {
             promise_id: allocated_id,
             kind,
             function_name,
             input,
             retry_policy,
+            sla,
         },
         AllocatingCommand::CaptureRandom { value } => EventType::RandomGenerated {
             promise_id: allocated_id,
@@ -353,6 +438,15 @@ pub(crate) fn non_allocating_to_event(cmd: NonAllocatingCommand) -> EventType {
         NonAllocatingCommand::Fail { error } => EventType::ExecutionFailed { error },
         NonAllocatingCommand::RequestCancel { reason } => EventType::CancelRequested { reason },
         NonAllocatingCommand::Cancel { reason } => EventType::ExecutionCancelled { reason },
+        NonAllocatingCommand::UpgradeComponent {
+            old_digest,
+            new_digest,
+            approved_by,
+        } => EventType::ComponentUpgraded {
+            old_digest,
+            new_digest,
+            approved_by,
+        },
         // ── Side Effects ──
         NonAllocatingCommand::StartInvoke {
             promise_id,
@@ -381,21 +475,48 @@ pub(crate) fn non_allocating_to_event(cmd: NonAllocatingCommand) -> EventType {
             error,
             retry_at,
         },
+        NonAllocatingCommand::RecordHeartbeat {
+            promise_id,
+            attempt,
+            at,
+            progress,
+        } => EventType::InvokeHeartbeat {
+            promise_id,
+            attempt,
+            at,
+            progress,
+        },
         // ── Control Flow ──
         NonAllocatingCommand::FireTimer { promise_id } => EventType::TimerFired { promise_id },
         NonAllocatingCommand::DeliverSignal {
             signal_name,
             payload,
             delivery_id,
+            external_id,
         } => EventType::SignalDelivered {
             signal_name,
             payload,
             delivery_id,
+            external_id,
+        },
+        NonAllocatingCommand::ExpireSignal {
+            signal_name,
+            delivery_id,
+        } => EventType::SignalDeliveryExpired {
+            signal_name,
+            delivery_id,
         },
         NonAllocatingCommand::Await { waiting_on, kind } => {
             EventType::ExecutionAwaiting { waiting_on, kind }
         }
         NonAllocatingCommand::Resume => EventType::ExecutionResumed,
+        NonAllocatingCommand::ReportAwaitProgress {
+            waiting_on_remaining,
+            resolved_since_await,
+        } => EventType::AwaitProgress {
+            waiting_on_remaining,
+            resolved_since_await,
+        },
         // ── Concurrency ──
         NonAllocatingCommand::SubmitToJoinSet {
             join_set_id,
@@ -0,0 +1,795 @@
+//! Tolerant matching of a run against an approved "golden" journal.
+//!
+//! Contract tests pin the shape of a workflow, not its data: a
+//! [`JournalTemplate`] wraps a recorded journal together with the variance
+//! the test accepts, and [`match_journal`] reports every place an actual run
+//! steps outside it. Entry timestamps, recorded clock values and timer
+//! deadlines never count as deviations.
+//!
+//! Templates are serde types so goldens can be committed next to the test.
+//! When the workflow changes on purpose, [`JournalTemplate::regenerate`]
+//! swaps in a fresh run and keeps the tolerances.
+
+use std::collections::HashMap;
+
+use chrono::DateTime;
+use invariant_types::{EventType, ExecutionJournal, JoinSetId, PromiseId};
+use serde::{Deserialize, Serialize};
+
+/// Variance a [`JournalTemplate`] accepts.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Tolerance {
+    /// Compare payload codecs but not their bytes, nor `RandomGenerated`
+    /// values.
+    #[serde(default)]
+    pub ignore_payloads: bool,
+    /// `InvokeRetrying` entries allowed per promise. Retries up to this bound
+    /// and their extra `InvokeStarted` attempts are invisible to the match.
+    #[serde(default)]
+    pub max_retries_per_promise: u32,
+    /// Join sets whose entries may appear in any order, from `JoinSetCreated`
+    /// through the set's last submit or await.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub concurrent_regions: Vec<JoinSetId>,
+}
+
+/// A golden journal plus the [`Tolerance`] a run is matched with.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct JournalTemplate {
+    pub golden: ExecutionJournal,
+    #[serde(default)]
+    pub tolerance: Tolerance,
+}
+
+impl JournalTemplate {
+    /// Template that only accepts runs with the same events as `golden`,
+    /// ignoring timestamps.
+    pub fn from_golden(golden: ExecutionJournal) -> Self {
+        Self {
+            golden,
+            tolerance: Tolerance::default(),
+        }
+    }
+
+    pub fn ignore_payloads(mut self) -> Self {
+        self.tolerance.ignore_payloads = true;
+        self
+    }
+
+    pub fn allow_retries(mut self, max_per_promise: u32) -> Self {
+        self.tolerance.max_retries_per_promise = max_per_promise;
+        self
+    }
+
+    pub fn concurrent_region(mut self, join_set_id: JoinSetId) -> Self {
+        self.tolerance.concurrent_regions.push(join_set_id);
+        self
+    }
+
+    /// The update-goldens step: the same tolerances over a fresh run.
+    ///
+    /// Use it when a deviation is intended, then commit the result in place
+    /// of the old template.
+    pub fn regenerate(&self, fresh: &ExecutionJournal) -> Self {
+        Self {
+            golden: fresh.clone(),
+            tolerance: self.tolerance.clone(),
+        }
+    }
+}
+
+/// Outcome of [`match_journal`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MatchResult {
+    /// Every deviation, in actual-journal order, followed by retry overruns.
+    pub deviations: Vec<Deviation>,
+}
+
+impl MatchResult {
+    pub fn is_match(&self) -> bool {
+        self.deviations.is_empty()
+    }
+}
+
+/// One place the actual run left the template.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Deviation {
+    /// Sequence number in the actual journal, except for
+    /// [`Rule::Missing`], where it is the golden entry's.
+    pub seq: u64,
+    pub rule: Rule,
+}
+
+/// The rule a [`Deviation`] broke.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Rule {
+    /// The actual entry differs from the golden entry at this position.
+    Mismatch {
+        expected: &'static str,
+        actual: &'static str,
+    },
+    /// A golden entry has no counterpart in the actual run.
+    Missing { expected: &'static str },
+    /// An actual entry has no counterpart in the golden.
+    Unexpected { actual: &'static str },
+    /// A promise was retried more often than the template allows. `seq` is
+    /// the first retry over the bound.
+    TooManyRetries {
+        promise_id: PromiseId,
+        retries: u32,
+        max: u32,
+    },
+}
+
+/// Match `actual` against `template`.
+///
+/// Both journals are normalized first (retries dropped, attempts and clock
+/// values cleared, payloads emptied if ignored), then aligned on their
+/// longest common subsequence so one missing entry is reported once
+/// rather than shifting everything after it. Concurrent regions align as a
+/// unit and are compared as multisets.
+///
+/// Complexity: O(n·m) time in the lengths of the two journals after their
+/// common prefix, but only O(n + m) memory.
+pub fn match_journal(actual: &ExecutionJournal, template: &JournalTemplate) -> MatchResult {
+    let tol = &template.tolerance;
+    let (golden, actual) = if tol.ignore_payloads {
+        (template.golden.redacted(), actual.redacted())
+    } else {
+        (template.golden.clone(), actual.clone())
+    };
+
+    let (golden_steps, _) = normalize(&golden, tol);
+    let (actual_steps, retries) = normalize(&actual, tol);
+    let golden_units = units(golden_steps, tol);
+    let actual_units = units(actual_steps, tol);
+
+    let mut deviations = Vec::new();
+    let mut gap_golden: Vec<&Unit> = Vec::new();
+    let mut gap_actual: Vec<&Unit> = Vec::new();
+    for op in align(&golden_units, &actual_units) {
+        match op {
+            Op::Both(g, a) => {
+                flush_gap(&mut gap_golden, &mut gap_actual, &mut deviations);
+                if let (Unit::Region(_, g), Unit::Region(_, a)) = (g, a) {
+                    region_diff(g, a, &mut deviations);
+                }
+            }
+            Op::Golden(g) => gap_golden.push(g),
+            Op::Actual(a) => gap_actual.push(a),
+        }
+    }
+    flush_gap(&mut gap_golden, &mut gap_actual, &mut deviations);
+
+    let mut overruns: Vec<_> = retries
+        .into_iter()
+        .filter_map(|(promise_id, r)| Some((r.first_over?, promise_id, r.count)))
+        .collect();
+    overruns.sort_by_key(|(seq, ..)| *seq);
+    deviations.extend(
+        overruns
+            .into_iter()
+            .map(|(seq, promise_id, retries)| Deviation {
+                seq,
+                rule: Rule::TooManyRetries {
+                    promise_id,
+                    retries,
+                    max: tol.max_retries_per_promise,
+                },
+            }),
+    );
+
+    MatchResult { deviations }
+}
+
+/// A normalized entry and the sequence it came from.
+#[derive(Debug)]
+struct Step {
+    seq: u64,
+    event: EventType,
+}
+
+#[derive(Default)]
+struct Retries {
+    count: u32,
+    /// Sequence of the first retry over the bound, once there is one.
+    first_over: Option<u64>,
+}
+
+/// Drop retry noise and clear fields expected to vary between runs.
+fn normalize(
+    journal: &ExecutionJournal,
+    tol: &Tolerance,
+) -> (Vec<Step>, HashMap<PromiseId, Retries>) {
+    let mut retries: HashMap<PromiseId, Retries> = HashMap::new();
+    let mut steps = Vec::with_capacity(journal.entries.len());
+    for entry in &journal.entries {
+        let mut event = entry.event.clone();
+        match &mut event {
+            EventType::InvokeRetrying { promise_id, .. } => {
+                let r = retries.entry(promise_id.clone()).or_default();
+                r.count = r.count.saturating_add(1);
+                if r.count > tol.max_retries_per_promise && r.first_over.is_none() {
+                    r.first_over = Some(entry.sequence);
+                }
+                continue;
+            }
+            EventType::InvokeStarted { attempt, .. } if *attempt > 1 => continue,
+            EventType::InvokeCompleted { attempt, .. } => *attempt = 1,
+            EventType::TimeRecorded { time, .. } => *time = DateTime::UNIX_EPOCH,
+            EventType::TimerScheduled { fire_at, .. } => *fire_at = DateTime::UNIX_EPOCH,
+            EventType::RandomGenerated { value, .. } if tol.ignore_payloads => value.clear(),
+            _ => {}
+        }
+        steps.push(Step {
+            seq: entry.sequence,
+            event,
+        });
+    }
+    (steps, retries)
+}
+
+/// An alignment unit: one entry, or a whole concurrent region.
+#[derive(Debug)]
+enum Unit {
+    Single(Step),
+    Region(JoinSetId, Vec<Step>),
+}
+
+impl Unit {
+    fn first(&self) -> &Step {
+        match self {
+            Self::Single(step) => step,
+            Self::Region(_, steps) => &steps[0],
+        }
+    }
+
+    fn aligns_with(&self, other: &Unit) -> bool {
+        match (self, other) {
+            (Self::Single(a), Self::Single(b)) => a.event == b.event,
+            (Self::Region(a, _), Self::Region(b, _)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+fn units(steps: Vec<Step>, tol: &Tolerance) -> Vec<Unit> {
+    // Start index of each concurrent region, with its join set and last index.
+    let regions: HashMap<usize, (JoinSetId, usize)> = steps
+        .iter()
+        .enumerate()
+        .filter_map(|(i, step)| match &step.event {
+            EventType::JoinSetCreated { join_set_id }
+                if tol.concurrent_regions.contains(join_set_id) =>
+            {
+                let end = steps
+                    .iter()
+                    .rposition(|s| touches_join_set(&s.event, join_set_id))
+                    .map_or(i, |end| end.max(i));
+                Some((i, (join_set_id.clone(), end)))
+            }
+            _ => None,
+        })
+        .collect();
+
+    let mut out = Vec::with_capacity(steps.len());
+    let mut open: Option<(JoinSetId, usize, Vec<Step>)> = None;
+    for (i, step) in steps.into_iter().enumerate() {
+        if open.is_none() {
+            if let Some((id, end)) = regions.get(&i) {
+                open = Some((id.clone(), *end, Vec::new()));
+            } else {
+                out.push(Unit::Single(step));
+                continue;
+            }
+        }
+        if let Some((id, end, mut region)) = open.take() {
+            region.push(step);
+            if i == end {
+                out.push(Unit::Region(id, region));
+            } else {
+                open = Some((id, end, region));
+            }
+        }
+    }
+    out
+}
+
+fn touches_join_set(event: &EventType, id: &JoinSetId) -> bool {
+    match event {
+        EventType::JoinSetSubmitted { join_set_id, .. }
+        | EventType::JoinSetAwaited { join_set_id, .. } => join_set_id == id,
+        _ => false,
+    }
+}
+
+enum Op<'a> {
+    Both(&'a Unit, &'a Unit),
+    Golden(&'a Unit),
+    Actual(&'a Unit),
+}
+
+/// Longest-common-subsequence alignment of the two unit lists.
+///
+/// The common prefix is paired off first; the rest is aligned with
+/// Hirschberg's divide step, which keeps two rows of the LCS table at a
+/// time instead of the whole table. Ties go to the earliest match, with
+/// golden units reported before actual ones.
+fn align<'a>(golden: &'a [Unit], actual: &'a [Unit]) -> Vec<Op<'a>> {
+    let prefix = golden
+        .iter()
+        .zip(actual)
+        .take_while(|(g, a)| g.aligns_with(a))
+        .count();
+    let (head_golden, golden) = golden.split_at(prefix);
+    let (head_actual, actual) = actual.split_at(prefix);
+
+    let mut ops: Vec<_> = head_golden
+        .iter()
+        .zip(head_actual)
+        .map(|(g, a)| Op::Both(g, a))
+        .collect();
+    ops.reserve(golden.len().max(actual.len()));
+    align_rest(golden, actual, &mut ops);
+    ops
+}
+
+fn align_rest<'a>(golden: &'a [Unit], actual: &'a [Unit], ops: &mut Vec<Op<'a>>) {
+    match golden {
+        [] => {
+            ops.extend(actual.iter().map(Op::Actual));
+            return;
+        }
+        _ if actual.is_empty() => {
+            ops.extend(golden.iter().map(Op::Golden));
+            return;
+        }
+        [g] => {
+            match actual.iter().position(|a| g.aligns_with(a)) {
+                Some(j) => {
+                    ops.extend(actual[..j].iter().map(Op::Actual));
+                    ops.push(Op::Both(g, &actual[j]));
+                    ops.extend(actual[j + 1..].iter().map(Op::Actual));
+                }
+                None => {
+                    ops.push(Op::Golden(g));
+                    ops.extend(actual.iter().map(Op::Actual));
+                }
+            }
+            return;
+        }
+        _ => {}
+    }
+
+    let (top, bottom) = golden.split_at(golden.len() / 2);
+    let m = actual.len();
+    // forward[k]: LCS length of top and actual[..k];
+    // backward[k]: LCS length of bottom and actual[m - k..].
+    let forward = lcs_lengths(top.iter(), actual.iter());
+    let backward = lcs_lengths(bottom.iter().rev(), actual.iter().rev());
+    let mut split = 0;
+    let mut best = 0;
+    for k in 0..=m {
+        let len = forward[k] + backward[m - k];
+        if k == 0 || len > best {
+            split = k;
+            best = len;
+        }
+    }
+
+    align_rest(top, &actual[..split], ops);
+    align_rest(bottom, &actual[split..], ops);
+}
+
+/// Last row of the LCS table of `golden` against each prefix of `actual`.
+fn lcs_lengths<'a>(
+    golden: impl Iterator<Item = &'a Unit>,
+    actual: impl Iterator<Item = &'a Unit> + Clone,
+) -> Vec<u32> {
+    let mut row = vec![0u32; actual.clone().count() + 1];
+    for g in golden {
+        let mut diagonal = 0;
+        for (j, a) in actual.clone().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = if g.aligns_with(a) {
+                diagonal + 1
+            } else {
+                above.max(row[j])
+            };
+            diagonal = above;
+        }
+    }
+    row
+}
+
+/// Report a run of unaligned units: pairs as mismatches, the rest as
+/// missing or unexpected.
+fn flush_gap(golden: &mut Vec<&Unit>, actual: &mut Vec<&Unit>, out: &mut Vec<Deviation>) {
+    let paired = golden.len().min(actual.len());
+    for (g, a) in golden.iter().zip(actual.iter()) {
+        out.push(Deviation {
+            seq: a.first().seq,
+            rule: Rule::Mismatch {
+                expected: g.first().event.name(),
+                actual: a.first().event.name(),
+            },
+        });
+    }
+    out.extend(golden[paired..].iter().map(|g| Deviation {
+        seq: g.first().seq,
+        rule: Rule::Missing {
+            expected: g.first().event.name(),
+        },
+    }));
+    out.extend(actual[paired..].iter().map(|a| Deviation {
+        seq: a.first().seq,
+        rule: Rule::Unexpected {
+            actual: a.first().event.name(),
+        },
+    }));
+    golden.clear();
+    actual.clear();
+}
+
+/// Multiset difference of two aligned concurrent regions.
+fn region_diff(golden: &[Step], actual: &[Step], out: &mut Vec<Deviation>) {
+    let mut unmatched: Vec<&Step> = actual.iter().collect();
+    for g in golden {
+        match unmatched.iter().position(|a| a.event == g.event) {
+            Some(pos) => {
+                unmatched.remove(pos);
+            }
+            None => out.push(Deviation {
+                seq: g.seq,
+                rule: Rule::Missing {
+                    expected: g.event.name(),
+                },
+            }),
+        }
+    }
+    out.extend(unmatched.into_iter().map(|a| Deviation {
+        seq: a.seq,
+        rule: Rule::Unexpected {
+            actual: a.event.name(),
+        },
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeDelta, Utc};
+    use invariant_types::{
+        Codec, ErrorKind, ExecutionError, ExecutionId, InvokeKind, JournalEntry, Payload,
+    };
+
+    use super::*;
+
+    fn payload(byte: u8) -> Payload {
+        Payload::new(vec![byte], Codec::Json)
+    }
+
+    fn pid(n: u8) -> PromiseId {
+        PromiseId::new([n; 32])
+    }
+
+    /// Journal with entries `offset_ms` apart, starting at `ExecutionStarted`.
+    fn journal(offset_ms: i64, events: Vec<EventType>) -> ExecutionJournal {
+        let mut all = vec![EventType::ExecutionStarted {
+            component_digest: vec![1],
+            input: payload(0),
+            parent_id: None,
+            idempotency_key: "k".into(),
+        }];
+        all.extend(events);
+        ExecutionJournal {
+            execution_id: ExecutionId::derive(&[1], "k", None),
+            entries: all
+                .into_iter()
+                .enumerate()
+                .map(|(seq, event)| JournalEntry {
+                    sequence: seq as u64,
+                    timestamp: DateTime::UNIX_EPOCH
+                        + TimeDelta::milliseconds(seq as i64 * offset_ms),
+                    event,
+                })
+                .collect(),
+            provenance: None,
+        }
+    }
+
+    fn invoke(p: PromiseId, result: u8) -> Vec<EventType> {
+        vec![
+            EventType::InvokeScheduled {
+                promise_id: p.clone(),
+                kind: InvokeKind::Function,
+                function_name: "f".into(),
+                input: payload(0),
+                retry_policy: None,
+                sla: None,
+            },
+            EventType::InvokeStarted {
+                promise_id: p.clone(),
+                attempt: 1,
+            },
+            EventType::InvokeCompleted {
+                promise_id: p,
+                result: payload(result),
+                attempt: 1,
+            },
+        ]
+    }
+
+    fn retried_invoke(p: PromiseId, retries: u32) -> Vec<EventType> {
+        let mut events = invoke(p.clone(), 1);
+        events.truncate(2);
+        for failed_attempt in 1..=retries {
+            events.push(EventType::InvokeRetrying {
+                promise_id: p.clone(),
+                failed_attempt,
+                error: ExecutionError::new(ErrorKind::Timeout, "slow"),
+                retry_at: DateTime::UNIX_EPOCH,
+            });
+            events.push(EventType::InvokeStarted {
+                promise_id: p.clone(),
+                attempt: failed_attempt + 1,
+            });
+        }
+        events.push(EventType::InvokeCompleted {
+            promise_id: p,
+            result: payload(1),
+            attempt: retries + 1,
+        });
+        events
+    }
+
+    fn completed() -> EventType {
+        EventType::ExecutionCompleted { result: payload(9) }
+    }
+
+    /// Two invokes joined through set `js`, submitted in `order`.
+    fn fan_out(order: [u8; 2]) -> Vec<EventType> {
+        let js = JoinSetId(pid(10));
+        let mut events = vec![EventType::JoinSetCreated {
+            join_set_id: js.clone(),
+        }];
+        for n in order {
+            events.extend(invoke(pid(n), n));
+            events.push(EventType::JoinSetSubmitted {
+                join_set_id: js.clone(),
+                promise_id: pid(n),
+            });
+        }
+        for n in order {
+            events.push(EventType::JoinSetAwaited {
+                join_set_id: js.clone(),
+                promise_id: pid(n),
+                result: payload(n),
+            });
+        }
+        events
+    }
+
+    fn golden() -> ExecutionJournal {
+        let mut events = invoke(pid(1), 1);
+        events.push(EventType::TimeRecorded {
+            promise_id: pid(2),
+            time: DateTime::UNIX_EPOCH,
+        });
+        events.push(completed());
+        journal(1, events)
+    }
+
+    #[test]
+    fn timestamps_and_recorded_clock_never_deviate() {
+        let mut events = invoke(pid(1), 1);
+        events.push(EventType::TimeRecorded {
+            promise_id: pid(2),
+            time: Utc::now(),
+        });
+        events.push(completed());
+
+        let result = match_journal(
+            &journal(250, events),
+            &JournalTemplate::from_golden(golden()),
+        );
+        assert!(result.is_match(), "{:?}", result.deviations);
+    }
+
+    #[test]
+    fn payload_contents_deviate_unless_ignored() {
+        let mut events = invoke(pid(1), 7);
+        events.push(EventType::TimeRecorded {
+            promise_id: pid(2),
+            time: DateTime::UNIX_EPOCH,
+        });
+        events.push(completed());
+        let actual = journal(1, events);
+
+        let strict = match_journal(&actual, &JournalTemplate::from_golden(golden()));
+        assert_eq!(
+            strict.deviations,
+            vec![Deviation {
+                seq: 3,
+                rule: Rule::Mismatch {
+                    expected: "InvokeCompleted",
+                    actual: "InvokeCompleted",
+                },
+            }]
+        );
+
+        let tolerant = JournalTemplate::from_golden(golden()).ignore_payloads();
+        assert!(match_journal(&actual, &tolerant).is_match());
+    }
+
+    #[test]
+    fn ignored_payloads_may_differ_in_length() {
+        let mut events = invoke(pid(1), 1);
+        events.push(EventType::TimeRecorded {
+            promise_id: pid(2),
+            time: DateTime::UNIX_EPOCH,
+        });
+        events.push(EventType::ExecutionCompleted {
+            result: Payload::new(b"a much longer result".to_vec(), Codec::Json),
+        });
+        let actual = journal(1, events);
+
+        let tolerant = JournalTemplate::from_golden(golden()).ignore_payloads();
+        let result = match_journal(&actual, &tolerant);
+        assert!(result.is_match(), "{:?}", result.deviations);
+    }
+
+    #[test]
+    fn retries_within_bound_match_and_overruns_are_reported() {
+        let golden = journal(1, [invoke(pid(1), 1), vec![completed()]].concat());
+        let template = JournalTemplate::from_golden(golden).allow_retries(2);
+
+        let twice = journal(1, [retried_invoke(pid(1), 2), vec![completed()]].concat());
+        assert!(match_journal(&twice, &template).is_match());
+
+        // Entries: started, scheduled, started, then retry/start pairs at 3..=8.
+        let thrice = journal(1, [retried_invoke(pid(1), 3), vec![completed()]].concat());
+        assert_eq!(
+            match_journal(&thrice, &template).deviations,
+            vec![Deviation {
+                seq: 7,
+                rule: Rule::TooManyRetries {
+                    promise_id: pid(1),
+                    retries: 3,
+                    max: 2,
+                },
+            }]
+        );
+    }
+
+    #[test]
+    fn unbounded_retry_tolerance_does_not_overflow() {
+        let golden = journal(1, [invoke(pid(1), 1), vec![completed()]].concat());
+        let template = JournalTemplate::from_golden(golden).allow_retries(u32::MAX);
+        let retried = journal(1, [retried_invoke(pid(1), 3), vec![completed()]].concat());
+        assert!(match_journal(&retried, &template).is_match());
+    }
+
+    #[test]
+    fn concurrent_region_tolerates_reordering() {
+        let golden = journal(1, [fan_out([1, 2]), vec![completed()]].concat());
+        let actual = journal(1, [fan_out([2, 1]), vec![completed()]].concat());
+
+        let strict = match_journal(&actual, &JournalTemplate::from_golden(golden.clone()));
+        assert!(!strict.is_match());
+
+        let template = JournalTemplate::from_golden(golden).concurrent_region(JoinSetId(pid(10)));
+        let result = match_journal(&actual, &template);
+        assert!(result.is_match(), "{:?}", result.deviations);
+    }
+
+    #[test]
+    fn reordering_region_still_reports_missing_members() {
+        let golden = journal(1, [fan_out([1, 2]), vec![completed()]].concat());
+        let mut events = fan_out([2, 1]);
+        // Drop the await of promise 1.
+        events.pop();
+        let actual = journal(1, [events, vec![completed()]].concat());
+
+        let template = JournalTemplate::from_golden(golden).concurrent_region(JoinSetId(pid(10)));
+        assert_eq!(
+            match_journal(&actual, &template).deviations,
+            vec![Deviation {
+                seq: 10,
+                rule: Rule::Missing {
+                    expected: "JoinSetAwaited",
+                },
+            }]
+        );
+    }
+
+    #[test]
+    fn scattered_differences_in_long_journals_are_reported_in_place() {
+        let run = |changed: &[u8]| {
+            let events = (0..200u8)
+                .flat_map(|n| invoke(pid(n), u8::from(changed.contains(&n))))
+                .collect();
+            journal(1, events)
+        };
+        let golden = JournalTemplate::from_golden(run(&[]));
+        let changed = [0, 57, 123, 199];
+
+        let result = match_journal(&run(&changed), &golden);
+        assert_eq!(
+            result.deviations,
+            changed
+                .iter()
+                .map(|&n| Deviation {
+                    // Each invoke is three entries after ExecutionStarted.
+                    seq: u64::from(n) * 3 + 3,
+                    rule: Rule::Mismatch {
+                        expected: "InvokeCompleted",
+                        actual: "InvokeCompleted",
+                    },
+                })
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn missing_entries_do_not_shift_the_rest() {
+        let actual = journal(
+            1,
+            [invoke(pid(1), 1), vec![completed(), completed()]].concat(),
+        );
+        assert_eq!(
+            match_journal(&actual, &JournalTemplate::from_golden(golden())).deviations,
+            vec![
+                Deviation {
+                    seq: 4,
+                    rule: Rule::Missing {
+                        expected: "TimeRecorded",
+                    },
+                },
+                Deviation {
+                    seq: 5,
+                    rule: Rule::Unexpected {
+                        actual: "ExecutionCompleted",
+                    },
+                },
+            ]
+        );
+
+        let short = journal(1, invoke(pid(1), 1));
+        assert_eq!(
+            match_journal(&short, &JournalTemplate::from_golden(golden())).deviations,
+            vec![
+                Deviation {
+                    seq: 4,
+                    rule: Rule::Missing {
+                        expected: "TimeRecorded",
+                    },
+                },
+                Deviation {
+                    seq: 5,
+                    rule: Rule::Missing {
+                        expected: "ExecutionCompleted",
+                    },
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn templates_round_trip_and_regenerate_keeps_tolerance() {
+        let template = JournalTemplate::from_golden(golden())
+            .ignore_payloads()
+            .allow_retries(2)
+            .concurrent_region(JoinSetId(pid(10)));
+        let json = serde_json::to_string(&template).unwrap();
+        assert_eq!(
+            serde_json::from_str::<JournalTemplate>(&json).unwrap(),
+            template
+        );
+
+        let fresh = journal(1, [invoke(pid(3), 3), vec![completed()]].concat());
+        let updated = template.regenerate(&fresh);
+        assert_eq!(updated.golden, fresh);
+        assert_eq!(updated.tolerance, template.tolerance);
+        assert!(match_journal(&fresh, &updated).is_match());
+    }
+}
@@ -0,0 +1,295 @@
+//! Continue-as-new: sealing one execution and continuing in a fresh one.
+//!
+//! A long-running workflow ends its journal with `ContinuedAsNew` and starts a
+//! new execution whose input is the carried state. The next execution's
+//! identity is derived from the previous `ExecutionStarted` with the
+//! generation counter folded into the idempotency key, so the id recorded in
+//! `ContinuedAsNew` is exactly what [`ExecutionId::derive`] produces for the
+//! next journal's own `ExecutionStarted`.
+
+use invariant_types::{EventType, ExecutionId, ExecutionJournal, Payload, PromiseId};
+
+use crate::error::ChainError;
+
+/// Separates the workflow's own idempotency key from the generation counter.
+const GENERATION_MARKER: &str = "/continue-as-new/";
+
+/// The idempotency key of generation `generation` of the execution keyed `base_key`.
+///
+/// Generation 0 is the original execution and keeps `base_key` unchanged.
+pub fn continuation_key(base_key: &str, generation: u32) -> String {
+    if generation == 0 {
+        base_key.to_string()
+    } else {
+        format!("{base_key}{GENERATION_MARKER}{generation}")
+    }
+}
+
+/// Split an idempotency key into its base key and generation.
+fn split_key(key: &str) -> (&str, u32) {
+    key.rsplit_once(GENERATION_MARKER)
+        .and_then(|(base, generation)| Some((base, generation.parse().ok()?)))
+        .unwrap_or((key, 0))
+}
+
+/// The `ExecutionStarted` fields that define an execution's identity.
+struct Start<'a> {
+    component_digest: &'a [u8],
+    input: &'a Payload,
+    parent_id: Option<&'a PromiseId>,
+    idempotency_key: &'a str,
+}
+
+fn start_of(journal: &ExecutionJournal) -> Option<Start<'_>> {
+    match &journal.entries.first()?.event {
+        EventType::ExecutionStarted {
+            component_digest,
+            input,
+            parent_id,
+            idempotency_key,
+        } => Some(Start {
+            component_digest,
+            input,
+            parent_id: parent_id.as_ref(),
+            idempotency_key,
+        }),
+        _ => None,
+    }
+}
+
+/// Build the events that hand `prev` off to its continuation.
+///
+/// Returns the terminal `ContinuedAsNew` to append to `prev` and the
+/// `ExecutionStarted` that opens the next journal. The next execution keeps
+/// `prev`'s component digest and parent, takes `carried_state` as input, and
+/// is keyed one generation after `prev`.
+///
+/// # Errors
+///
+/// - [`ChainError::MissingStart`] — `prev` does not start with `ExecutionStarted`.
+/// - [`ChainError::GenerationOverflow`] — `prev` is the last representable generation.
+pub fn begin_continuation(
+    prev: &ExecutionJournal,
+    carried_state: Payload,
+) -> Result<(EventType, EventType), ChainError> {
+    let start = start_of(prev).ok_or(ChainError::MissingStart { index: 0 })?;
+    let (base_key, generation) = split_key(start.idempotency_key);
+    let next_generation = generation
+        .checked_add(1)
+        .ok_or(ChainError::GenerationOverflow)?;
+    let idempotency_key = continuation_key(base_key, next_generation);
+    let next_execution_id =
+        ExecutionId::derive(start.component_digest, &idempotency_key, start.parent_id);
+
+    let terminal = EventType::ContinuedAsNew {
+        next_execution_id,
+        carried_state: carried_state.clone(),
+    };
+    let started = EventType::ExecutionStarted {
+        component_digest: start.component_digest.to_vec(),
+        input: carried_state,
+        parent_id: start.parent_id.cloned(),
+        idempotency_key,
+    };
+    Ok((terminal, started))
+}
+
+/// A validated sequence of executions linked by continue-as-new.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ContinuationChain {
+    /// Execution ids in chain order, oldest first.
+    pub execution_ids: Vec<ExecutionId>,
+}
+
+/// Validate that `journals` form one continue-as-new chain, oldest first.
+///
+/// Checks linkage only; each journal should also pass
+/// [`validate_journal`](crate::invariants::validate_journal). For every
+/// adjacent pair, the earlier journal must end with `ContinuedAsNew` naming
+/// the later journal's execution id, and the later journal's input must equal
+/// the carried state. The last journal may still be running.
+///
+/// # Errors
+///
+/// Returns the first broken link as a [`ChainError`].
+pub fn chain(journals: &[ExecutionJournal]) -> Result<ContinuationChain, ChainError> {
+    if journals.is_empty() {
+        return Err(ChainError::EmptyChain);
+    }
+    let mut execution_ids = Vec::with_capacity(journals.len());
+    for (index, journal) in journals.iter().enumerate() {
+        let start = start_of(journal).ok_or(ChainError::MissingStart { index })?;
+        let derived = ExecutionId::derive(
+            start.component_digest,
+            start.idempotency_key,
+            start.parent_id,
+        );
+        if journal.execution_id != derived {
+            return Err(ChainError::ExecutionIdMismatch {
+                index,
+                expected: derived,
+                actual: journal.execution_id.clone(),
+            });
+        }
+
+        if let Some(prev) = index.checked_sub(1).map(|i| &journals[i]) {
+            let Some(EventType::ContinuedAsNew {
+                next_execution_id,
+                carried_state,
+            }) = prev.entries.last().map(|e| &e.event)
+            else {
+                return Err(ChainError::NotContinued { index: index - 1 });
+            };
+            if *next_execution_id != journal.execution_id {
+                return Err(ChainError::ExecutionIdMismatch {
+                    index,
+                    expected: next_execution_id.clone(),
+                    actual: journal.execution_id.clone(),
+                });
+            }
+            if carried_state != start.input {
+                return Err(ChainError::CarriedStateMismatch { index });
+            }
+        }
+        execution_ids.push(journal.execution_id.clone());
+    }
+    Ok(ContinuationChain { execution_ids })
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::DateTime;
+    use invariant_types::{Codec, JournalEntry};
+
+    use super::*;
+    use crate::invariants::validate_journal;
+
+    fn payload(bytes: &[u8]) -> Payload {
+        Payload::new(bytes.to_vec(), Codec::Json)
+    }
+
+    fn journal_from(events: Vec<EventType>) -> ExecutionJournal {
+        let EventType::ExecutionStarted {
+            component_digest,
+            parent_id,
+            idempotency_key,
+            ..
+        } = &events[0]
+        else {
+            panic!("journal must start with ExecutionStarted");
+        };
+        ExecutionJournal {
+            execution_id: ExecutionId::derive(
+                component_digest,
+                idempotency_key,
+                parent_id.as_ref(),
+            ),
+            entries: events
+                .into_iter()
+                .enumerate()
+                .map(|(i, event)| JournalEntry {
+                    sequence: i as u64,
+                    timestamp: DateTime::UNIX_EPOCH,
+                    event,
+                })
+                .collect(),
+            provenance: None,
+        }
+    }
+
+    fn first() -> ExecutionJournal {
+        journal_from(vec![EventType::ExecutionStarted {
+            component_digest: vec![1],
+            input: payload(b"0"),
+            parent_id: None,
+            idempotency_key: "order-42".into(),
+        }])
+    }
+
+    /// Seal `prev` and open its continuation, returning both journals.
+    fn continue_from(
+        mut prev: ExecutionJournal,
+        state: &[u8],
+    ) -> (ExecutionJournal, ExecutionJournal) {
+        let (terminal, started) = begin_continuation(&prev, payload(state)).unwrap();
+        prev.entries.push(JournalEntry {
+            sequence: prev.entries.len() as u64,
+            timestamp: DateTime::UNIX_EPOCH,
+            event: terminal,
+        });
+        (prev, journal_from(vec![started]))
+    }
+
+    #[test]
+    fn begin_continuation_increments_generation_in_key() {
+        let (sealed, next) = continue_from(first(), b"1");
+        let (_, after) = continue_from(next.clone(), b"2");
+
+        assert!(matches!(
+            &next.entries[0].event,
+            EventType::ExecutionStarted { idempotency_key, input, .. }
+                if idempotency_key == "order-42/continue-as-new/1" && *input == payload(b"1")
+        ));
+        assert!(matches!(
+            &after.entries[0].event,
+            EventType::ExecutionStarted { idempotency_key, .. }
+                if idempotency_key == "order-42/continue-as-new/2"
+        ));
+        assert!(validate_journal(&sealed).is_empty());
+        assert!(sealed.entries.last().unwrap().event.is_terminal());
+    }
+
+    #[test]
+    fn chain_accepts_linked_journals() {
+        let (j0, j1) = continue_from(first(), b"1");
+        let (j1, j2) = continue_from(j1, b"2");
+
+        let chain = chain(&[j0.clone(), j1.clone(), j2.clone()]).unwrap();
+
+        assert_eq!(
+            chain.execution_ids,
+            vec![j0.execution_id, j1.execution_id, j2.execution_id]
+        );
+    }
+
+    #[test]
+    fn chain_rejects_unsealed_predecessor() {
+        let (_, j1) = continue_from(first(), b"1");
+        assert_eq!(
+            chain(&[first(), j1]),
+            Err(ChainError::NotContinued { index: 0 })
+        );
+    }
+
+    #[test]
+    fn chain_rejects_wrong_successor() {
+        let (j0, _) = continue_from(first(), b"1");
+        let stranger = journal_from(vec![EventType::ExecutionStarted {
+            component_digest: vec![1],
+            input: payload(b"1"),
+            parent_id: None,
+            idempotency_key: "other".into(),
+        }]);
+        assert!(matches!(
+            chain(&[j0, stranger]),
+            Err(ChainError::ExecutionIdMismatch { index: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn chain_rejects_carried_state_mismatch() {
+        let (j0, mut j1) = continue_from(first(), b"1");
+        if let EventType::ExecutionStarted { input, .. } = &mut j1.entries[0].event {
+            *input = payload(b"tampered");
+        }
+        assert_eq!(
+            chain(&[j0, j1]),
+            Err(ChainError::CarriedStateMismatch { index: 1 })
+        );
+    }
+
+    #[test]
+    fn chain_rejects_empty() {
+        assert_eq!(chain(&[]), Err(ChainError::EmptyChain));
+    }
+}
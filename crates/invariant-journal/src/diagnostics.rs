@@ -0,0 +1,963 @@
+//! Heuristic journal diagnostics.
+//!
+//! Unlike the invariants in [`crate::invariants`], nothing reported here makes
+//! a journal invalid. These checks flag shapes that are legal but usually mean
+//! a workflow bug, such as a loop appending `TimeRecorded` until storage fills,
+//! or two signal names that differ only by case.
+
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use indexmap::IndexMap;
+use invariant_types::{
+    AwaitKind, Codec, EventType, JoinSetId, JournalEntry, MAX_CALL_DEPTH, PromiseId,
+};
+
+/// Thresholds for [`anomalies`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AnomalyConfig {
+    /// Report runs longer than this many consecutive entries of one event type.
+    pub max_consecutive_same_event: usize,
+    /// Sliding wall-clock window used for burst detection. A zero window
+    /// counts each entry on its own.
+    pub burst_window: Duration,
+    /// Report more than this many entries inside any `burst_window`.
+    pub max_entries_per_window: usize,
+    /// Report promises within this many levels of [`MAX_CALL_DEPTH`].
+    pub depth_margin: usize,
+    /// Report join sets with more than this many submitted members.
+    pub max_join_set_members: usize,
+    /// Report in-flight attempts that heartbeated, then stayed silent for
+    /// longer than this.
+    pub max_heartbeat_silence: Duration,
+}
+
+impl Default for AnomalyConfig {
+    fn default() -> Self {
+        Self {
+            max_consecutive_same_event: 1_000,
+            burst_window: Duration::from_secs(1),
+            max_entries_per_window: 1_000,
+            depth_margin: 8,
+            max_join_set_members: 10_000,
+            max_heartbeat_silence: Duration::from_secs(5 * 60),
+        }
+    }
+}
+
+/// How urgently an anomaly should be looked at.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// Unusual but may be intentional.
+    Warning,
+    /// Well past the threshold, or at a hard limit.
+    Critical,
+}
+
+/// What a detector found.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AnomalyKind {
+    /// `count` consecutive entries all had event type `event`.
+    ConsecutiveSameEvent { event: &'static str, count: usize },
+    /// `count` entries were appended within one `window`.
+    Burst { count: usize, window: Duration },
+    /// `promise_id` sits within the configured margin of [`MAX_CALL_DEPTH`].
+    DeepPromise { promise_id: PromiseId, depth: usize },
+    /// `join_set_id` received `members` submissions.
+    LargeJoinSet {
+        join_set_id: JoinSetId,
+        members: usize,
+    },
+    /// Signal names `first` and `second` differ only by ASCII case.
+    ///
+    /// Names are matched exactly, so a delivery under one never satisfies an
+    /// await on the other.
+    SignalNameCaseCollision { first: String, second: String },
+    /// Attempt `attempt` of `promise_id` has not heartbeated for `silent_for`
+    /// as of the last entry.
+    ///
+    /// Only attempts that heartbeated at least once are judged; the rest
+    /// never opted in to liveness reporting.
+    SilentInvoke {
+        promise_id: PromiseId,
+        attempt: u32,
+        silent_for: Duration,
+    },
+}
+
+/// A single finding, covering sequences `first_seq..=last_seq`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Anomaly {
+    pub kind: AnomalyKind,
+    pub first_seq: u64,
+    pub last_seq: u64,
+    pub severity: Severity,
+}
+
+/// Run every detector over `entries` and return findings in detector order.
+///
+/// Burst detection assumes entry timestamps are non-decreasing; timestamps are
+/// wall-clock and only used here as a heuristic.
+/// Scan complexity: O(n).
+pub fn anomalies(entries: &[JournalEntry], config: &AnomalyConfig) -> Vec<Anomaly> {
+    let mut found = Vec::new();
+    consecutive_same_event(entries, config, &mut found);
+    bursts(entries, config, &mut found);
+    deep_promises(entries, config, &mut found);
+    large_join_sets(entries, config, &mut found);
+    signal_name_case_collisions(entries, &mut found);
+    silent_invokes(entries, config, &mut found);
+    found
+}
+
+/// Severity for a count that exceeded `threshold`: critical at twice the limit.
+fn severity_for(count: usize, threshold: usize) -> Severity {
+    if count > threshold.saturating_mul(2) {
+        Severity::Critical
+    } else {
+        Severity::Warning
+    }
+}
+
+fn consecutive_same_event(
+    entries: &[JournalEntry],
+    config: &AnomalyConfig,
+    out: &mut Vec<Anomaly>,
+) {
+    let limit = config.max_consecutive_same_event;
+    for run in entries.chunk_by(|a, b| a.event.name() == b.event.name()) {
+        if run.len() > limit {
+            let (first, last) = (&run[0], &run[run.len() - 1]);
+            out.push(Anomaly {
+                kind: AnomalyKind::ConsecutiveSameEvent {
+                    event: first.event.name(),
+                    count: run.len(),
+                },
+                first_seq: first.sequence,
+                last_seq: last.sequence,
+                severity: severity_for(run.len(), limit),
+            });
+        }
+    }
+}
+
+fn bursts(entries: &[JournalEntry], config: &AnomalyConfig, out: &mut Vec<Anomaly>) {
+    let limit = config.max_entries_per_window;
+    let window = config.burst_window;
+    let mut burst: Option<Anomaly> = None;
+    let mut left = 0;
+    for (right, entry) in entries.iter().enumerate() {
+        // The window always keeps `entry` itself, even when it is zero.
+        while left < right
+            && (entry.timestamp - entries[left].timestamp)
+                .to_std()
+                .is_ok_and(|elapsed| elapsed >= window)
+        {
+            left += 1;
+        }
+        let count = right - left + 1;
+        if count <= limit {
+            continue;
+        }
+        // Overlapping windows collapse into one finding.
+        match &mut burst {
+            Some(b) if b.last_seq >= entries[left].sequence => {
+                b.last_seq = entry.sequence;
+                if let AnomalyKind::Burst { count: c, .. } = &mut b.kind {
+                    *c = (*c).max(count);
+                    b.severity = severity_for(*c, limit);
+                }
+            }
+            _ => {
+                out.extend(burst.take());
+                burst = Some(Anomaly {
+                    kind: AnomalyKind::Burst { count, window },
+                    first_seq: entries[left].sequence,
+                    last_seq: entry.sequence,
+                    severity: severity_for(count, limit),
+                });
+            }
+        }
+    }
+    out.extend(burst);
+}
+
+fn deep_promises(entries: &[JournalEntry], config: &AnomalyConfig, out: &mut Vec<Anomaly>) {
+    let threshold = MAX_CALL_DEPTH.saturating_sub(config.depth_margin);
+    let mut seen: HashSet<&PromiseId> = HashSet::new();
+    for entry in entries {
+        let Some(pid) = entry.event.promise_id() else {
+            continue;
+        };
+        if pid.depth() >= threshold && seen.insert(pid) {
+            out.push(Anomaly {
+                kind: AnomalyKind::DeepPromise {
+                    promise_id: pid.clone(),
+                    depth: pid.depth(),
+                },
+                first_seq: entry.sequence,
+                last_seq: entry.sequence,
+                severity: if pid.depth() >= MAX_CALL_DEPTH {
+                    Severity::Critical
+                } else {
+                    Severity::Warning
+                },
+            });
+        }
+    }
+}
+
+fn large_join_sets(entries: &[JournalEntry], config: &AnomalyConfig, out: &mut Vec<Anomaly>) {
+    // join set -> (members, first submit seq, last submit seq)
+    let mut sets: HashMap<&JoinSetId, (usize, u64, u64)> = HashMap::new();
+    let mut order: Vec<&JoinSetId> = Vec::new();
+    for entry in entries {
+        if let EventType::JoinSetSubmitted { join_set_id, .. } = &entry.event {
+            let stats = sets.entry(join_set_id).or_insert_with(|| {
+                order.push(join_set_id);
+                (0, entry.sequence, entry.sequence)
+            });
+            stats.0 += 1;
+            stats.2 = entry.sequence;
+        }
+    }
+    let limit = config.max_join_set_members;
+    for js in order {
+        let (members, first_seq, last_seq) = sets[js];
+        if members > limit {
+            out.push(Anomaly {
+                kind: AnomalyKind::LargeJoinSet {
+                    join_set_id: js.clone(),
+                    members,
+                },
+                first_seq,
+                last_seq,
+                severity: severity_for(members, limit),
+            });
+        }
+    }
+}
+
+fn signal_name_case_collisions(entries: &[JournalEntry], out: &mut Vec<Anomaly>) {
+    // lowercased name -> first spelling seen and where
+    let mut spellings: HashMap<String, (&str, u64)> = HashMap::new();
+    let mut reported: HashSet<(&str, &str)> = HashSet::new();
+    for entry in entries {
+        let names = match &entry.event {
+            EventType::SignalDelivered { signal_name, .. }
+            | EventType::SignalReceived { signal_name, .. }
+            | EventType::SignalDeliveryExpired { signal_name, .. } => {
+                std::slice::from_ref(signal_name)
+            }
+            EventType::ExecutionAwaiting {
+                kind: AwaitKind::Signal { name, .. },
+                ..
+            } => std::slice::from_ref(name),
+            EventType::ExecutionAwaiting {
+                kind: AwaitKind::AnySignal { names, .. },
+                ..
+            } => names.as_slice(),
+            _ => continue,
+        };
+        for name in names {
+            let name = name.as_str();
+            let (first, first_seq) = *spellings
+                .entry(name.to_ascii_lowercase())
+                .or_insert((name, entry.sequence));
+            if first != name && reported.insert((first, name)) {
+                out.push(Anomaly {
+                    kind: AnomalyKind::SignalNameCaseCollision {
+                        first: first.to_string(),
+                        second: name.to_string(),
+                    },
+                    first_seq,
+                    last_seq: entry.sequence,
+                    severity: Severity::Warning,
+                });
+            }
+        }
+    }
+}
+
+fn silent_invokes(entries: &[JournalEntry], config: &AnomalyConfig, out: &mut Vec<Anomaly>) {
+    let Some(last) = entries.last() else {
+        return;
+    };
+    let limit = config.max_heartbeat_silence;
+    for pending in pending_invokes(entries, last.timestamp) {
+        let Some(heartbeat_seq) = pending.last_heartbeat else {
+            continue;
+        };
+        if pending.silent_for > limit {
+            out.push(Anomaly {
+                kind: AnomalyKind::SilentInvoke {
+                    promise_id: pending.promise_id,
+                    attempt: pending.attempt,
+                    silent_for: pending.silent_for,
+                },
+                first_seq: heartbeat_seq,
+                last_seq: last.sequence,
+                severity: if pending.silent_for > limit.saturating_mul(2) {
+                    Severity::Critical
+                } else {
+                    Severity::Warning
+                },
+            });
+        }
+    }
+}
+
+/// An invoke whose result was encoded with a different codec than its input.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CodecMismatch {
+    pub promise_id: PromiseId,
+    pub input_codec: Codec,
+    pub result_codec: Codec,
+}
+
+/// Flag invokes whose `InvokeCompleted.result` codec differs from their
+/// `InvokeScheduled.input` codec, in completion order.
+///
+/// Advisory: mixed codecs are legal, but break decoders that assume one
+/// codec per call. Completions without a matching schedule are skipped.
+/// Scan complexity: O(n).
+pub fn codec_consistency(entries: &[JournalEntry]) -> Vec<CodecMismatch> {
+    let mut inputs: HashMap<&PromiseId, &Codec> = HashMap::new();
+    let mut mismatches = Vec::new();
+    for entry in entries {
+        match &entry.event {
+            EventType::InvokeScheduled {
+                promise_id, input, ..
+            } => {
+                inputs.insert(promise_id, &input.codec);
+            }
+            EventType::InvokeCompleted {
+                promise_id, result, ..
+            } => {
+                if let Some(input_codec) = inputs.remove(promise_id)
+                    && *input_codec != result.codec
+                {
+                    mismatches.push(CodecMismatch {
+                        promise_id: promise_id.clone(),
+                        input_codec: input_codec.clone(),
+                        result_codec: result.codec.clone(),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+    mismatches
+}
+
+/// An invoke that took, or has so far taken, longer than its declared SLA.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SlaBreach {
+    pub promise_id: PromiseId,
+    pub function_name: String,
+    /// `InvokeScheduled.sla`.
+    pub sla: Duration,
+    /// Scheduled-to-completed time, or scheduled-to-last-entry time while
+    /// the invoke is still open.
+    pub observed: Duration,
+    /// Whether the invoke had not completed by the last entry.
+    pub open: bool,
+}
+
+/// Flag invokes whose `InvokeScheduled.sla` was exceeded.
+///
+/// Completed invokes are measured from their `InvokeScheduled` to their
+/// `InvokeCompleted` timestamp and reported in completion order. Invokes
+/// still open are measured up to the last entry's timestamp and reported
+/// after them, in scheduling order. Timestamps are wall-clock, so this is
+/// advisory; negative spans from clock skew count as zero.
+/// Scan complexity: O(n).
+pub fn sla_breaches(entries: &[JournalEntry]) -> Vec<SlaBreach> {
+    let mut pending: IndexMap<&PromiseId, (&str, Duration, DateTime<Utc>)> = IndexMap::new();
+    let mut breaches = Vec::new();
+    let span = |from: DateTime<Utc>, to: DateTime<Utc>| (to - from).to_std().unwrap_or_default();
+    for entry in entries {
+        match &entry.event {
+            EventType::InvokeScheduled {
+                promise_id,
+                function_name,
+                sla: Some(sla),
+                ..
+            } => {
+                pending.insert(promise_id, (function_name, *sla, entry.timestamp));
+            }
+            EventType::InvokeCompleted { promise_id, .. } => {
+                if let Some((function_name, sla, scheduled)) = pending.shift_remove(promise_id) {
+                    let observed = span(scheduled, entry.timestamp);
+                    if observed > sla {
+                        breaches.push(SlaBreach {
+                            promise_id: promise_id.clone(),
+                            function_name: function_name.to_string(),
+                            sla,
+                            observed,
+                            open: false,
+                        });
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    if let Some(last) = entries.last() {
+        for (promise_id, (function_name, sla, scheduled)) in pending {
+            let observed = span(scheduled, last.timestamp);
+            if observed > sla {
+                breaches.push(SlaBreach {
+                    promise_id: promise_id.clone(),
+                    function_name: function_name.to_string(),
+                    sla,
+                    observed,
+                    open: true,
+                });
+            }
+        }
+    }
+    breaches
+}
+
+/// An invoke attempt that has started and not yet finished.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PendingInvoke {
+    pub promise_id: PromiseId,
+    pub attempt: u32,
+    pub started_seq: u64,
+    /// Sequence of the attempt's last `InvokeHeartbeat`, if it sent any.
+    pub last_heartbeat: Option<u64>,
+    /// Time from the last heartbeat, or from `InvokeStarted` if there was
+    /// none, to `now`.
+    pub silent_for: Duration,
+}
+
+struct OpenAttempt {
+    started_seq: u64,
+    last_heartbeat: Option<u64>,
+    /// Timestamp of the start or the last heartbeat.
+    alive_at: DateTime<Utc>,
+}
+
+/// Attempts started but neither retried nor completed, in start order.
+///
+/// An attempt ends with an `InvokeRetrying` for it or an `InvokeCompleted`
+/// for its promise. Silence is measured on entry timestamps, not on the
+/// worker-reported `InvokeHeartbeat.at`; spans from clock skew count as
+/// zero. A long `silent_for` on an attempt that usually heartbeats points
+/// at a dead worker.
+/// Scan complexity: O(n).
+pub fn pending_invokes(entries: &[JournalEntry], now: DateTime<Utc>) -> Vec<PendingInvoke> {
+    let mut open: IndexMap<(&PromiseId, u32), OpenAttempt> = IndexMap::new();
+    let mut attempts: HashMap<&PromiseId, Vec<u32>> = HashMap::new();
+    for entry in entries {
+        match &entry.event {
+            EventType::InvokeStarted {
+                promise_id,
+                attempt,
+            } => {
+                attempts.entry(promise_id).or_default().push(*attempt);
+                open.insert(
+                    (promise_id, *attempt),
+                    OpenAttempt {
+                        started_seq: entry.sequence,
+                        last_heartbeat: None,
+                        alive_at: entry.timestamp,
+                    },
+                );
+            }
+            EventType::InvokeHeartbeat {
+                promise_id,
+                attempt,
+                ..
+            } => {
+                if let Some(open) = open.get_mut(&(promise_id, *attempt)) {
+                    open.last_heartbeat = Some(entry.sequence);
+                    open.alive_at = entry.timestamp;
+                }
+            }
+            EventType::InvokeRetrying {
+                promise_id,
+                failed_attempt,
+                ..
+            } => {
+                open.shift_remove(&(promise_id, *failed_attempt));
+            }
+            EventType::InvokeCompleted { promise_id, .. } => {
+                for attempt in attempts.remove(promise_id).unwrap_or_default() {
+                    open.shift_remove(&(promise_id, attempt));
+                }
+            }
+            _ => {}
+        }
+    }
+    open.into_iter()
+        .map(|((promise_id, attempt), open)| PendingInvoke {
+            promise_id: promise_id.clone(),
+            attempt,
+            started_seq: open.started_seq,
+            last_heartbeat: open.last_heartbeat,
+            silent_for: (now - open.alive_at).to_std().unwrap_or_default(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeDelta;
+    use invariant_types::{InvokeKind, Payload};
+
+    use super::*;
+
+    fn pid(tag: u8) -> PromiseId {
+        PromiseId::new([tag; 32])
+    }
+
+    fn at(sequence: u64, millis: i64, event: EventType) -> JournalEntry {
+        JournalEntry {
+            sequence,
+            timestamp: DateTime::UNIX_EPOCH + TimeDelta::milliseconds(millis),
+            event,
+        }
+    }
+
+    fn time_recorded(tag: u8) -> EventType {
+        EventType::TimeRecorded {
+            promise_id: pid(tag),
+            time: Utc::now(),
+        }
+    }
+
+    fn started() -> EventType {
+        EventType::ExecutionStarted {
+            component_digest: vec![1],
+            input: Payload::new(vec![], Codec::Json),
+            parent_id: None,
+            idempotency_key: "k".into(),
+        }
+    }
+
+    fn config() -> AnomalyConfig {
+        AnomalyConfig {
+            max_consecutive_same_event: 3,
+            burst_window: Duration::from_secs(1),
+            max_entries_per_window: 4,
+            depth_margin: 2,
+            max_join_set_members: 2,
+            max_heartbeat_silence: Duration::from_secs(60),
+        }
+    }
+
+    #[test]
+    fn consecutive_same_event_reports_run_range() {
+        // Spread over 10s so the burst detector stays quiet.
+        let mut entries = vec![at(0, 0, started())];
+        for seq in 1..=5u64 {
+            entries.push(at(seq, seq as i64 * 2_000, time_recorded(seq as u8)));
+        }
+
+        let found = anomalies(&entries, &config());
+
+        assert_eq!(
+            found,
+            vec![Anomaly {
+                kind: AnomalyKind::ConsecutiveSameEvent {
+                    event: "TimeRecorded",
+                    count: 5,
+                },
+                first_seq: 1,
+                last_seq: 5,
+                severity: Severity::Warning,
+            }]
+        );
+    }
+
+    #[test]
+    fn consecutive_run_at_threshold_is_not_reported() {
+        let entries: Vec<_> = (0..3u64)
+            .map(|seq| at(seq, seq as i64 * 2_000, time_recorded(seq as u8)))
+            .collect();
+        assert!(anomalies(&entries, &config()).is_empty());
+    }
+
+    #[test]
+    fn burst_within_window_is_reported_once() {
+        // Alternate event types so only the burst detector fires.
+        let entries: Vec<_> = (0..8u64)
+            .map(|seq| {
+                let event = if seq % 2 == 0 {
+                    started()
+                } else {
+                    time_recorded(seq as u8)
+                };
+                at(seq, seq as i64 * 10, event)
+            })
+            .collect();
+
+        let found = anomalies(&entries, &config());
+
+        assert_eq!(found.len(), 1);
+        assert_eq!(
+            found[0].kind,
+            AnomalyKind::Burst {
+                count: 8,
+                window: Duration::from_secs(1),
+            }
+        );
+        assert_eq!((found[0].first_seq, found[0].last_seq), (0, 7));
+    }
+
+    #[test]
+    fn entries_spread_beyond_window_are_not_a_burst() {
+        let entries: Vec<_> = (0..8u64)
+            .map(|seq| {
+                let event = if seq % 2 == 0 {
+                    started()
+                } else {
+                    time_recorded(seq as u8)
+                };
+                at(seq, seq as i64 * 500, event)
+            })
+            .collect();
+        assert!(anomalies(&entries, &config()).is_empty());
+    }
+
+    #[test]
+    fn zero_burst_window_counts_each_entry_alone() {
+        let entries: Vec<_> = (0..8u64)
+            .map(|seq| {
+                let event = if seq % 2 == 0 {
+                    started()
+                } else {
+                    time_recorded(seq as u8)
+                };
+                at(seq, 0, event)
+            })
+            .collect();
+        let config = AnomalyConfig {
+            burst_window: Duration::ZERO,
+            ..config()
+        };
+        assert!(anomalies(&entries, &config).is_empty());
+    }
+
+    #[test]
+    fn deep_promise_reported_once_with_severity() {
+        let mut near = pid(1);
+        for _ in 0..MAX_CALL_DEPTH - 1 {
+            near = near.child(0).unwrap();
+        }
+        let at_max = near.child(0).unwrap();
+        let entries = vec![
+            at(
+                0,
+                0,
+                EventType::TimerFired {
+                    promise_id: near.clone(),
+                },
+            ),
+            at(1, 2_000, started()),
+            at(
+                2,
+                4_000,
+                EventType::TimerFired {
+                    promise_id: near.clone(),
+                },
+            ),
+            at(
+                3,
+                6_000,
+                EventType::TimerFired {
+                    promise_id: at_max.clone(),
+                },
+            ),
+        ];
+
+        let found = anomalies(&entries, &config());
+
+        assert_eq!(found.len(), 2);
+        assert_eq!(
+            found[0].kind,
+            AnomalyKind::DeepPromise {
+                promise_id: near,
+                depth: MAX_CALL_DEPTH - 1,
+            }
+        );
+        assert_eq!(found[0].severity, Severity::Warning);
+        assert_eq!(found[1].first_seq, 3);
+        assert_eq!(found[1].severity, Severity::Critical);
+    }
+
+    #[test]
+    fn large_join_set_reports_submit_range() {
+        let js = JoinSetId(pid(10));
+        let small = JoinSetId(pid(20));
+        let submit = |seq: u64, js: &JoinSetId, tag: u8| {
+            at(
+                seq,
+                seq as i64 * 2_000,
+                EventType::JoinSetSubmitted {
+                    join_set_id: js.clone(),
+                    promise_id: pid(tag),
+                },
+            )
+        };
+        let entries = vec![
+            submit(0, &js, 1),
+            at(1, 2_000, started()),
+            submit(2, &small, 2),
+            submit(3, &js, 3),
+            submit(4, &js, 4),
+        ];
+
+        let found = anomalies(&entries, &config());
+
+        assert_eq!(
+            found,
+            vec![Anomaly {
+                kind: AnomalyKind::LargeJoinSet {
+                    join_set_id: js,
+                    members: 3,
+                },
+                first_seq: 0,
+                last_seq: 4,
+                severity: Severity::Warning,
+            }]
+        );
+    }
+
+    #[test]
+    fn default_config_is_quiet_on_small_journal() {
+        let entries = vec![at(0, 0, started()), at(1, 1, time_recorded(1))];
+        assert!(anomalies(&entries, &AnomalyConfig::default()).is_empty());
+    }
+
+    #[test]
+    fn signal_names_differing_by_case_are_reported_once() {
+        let delivered = |seq: u64, name: &str| {
+            at(
+                seq,
+                seq as i64 * 2_000,
+                EventType::SignalDelivered {
+                    signal_name: name.into(),
+                    payload: Payload::new(vec![], Codec::Json),
+                    delivery_id: seq,
+                    external_id: None,
+                },
+            )
+        };
+        let entries = vec![
+            at(0, 0, started()),
+            delivered(1, "approve"),
+            at(
+                2,
+                4_000,
+                EventType::ExecutionAwaiting {
+                    waiting_on: vec![pid(1)],
+                    kind: AwaitKind::Signal {
+                        name: "Approve".into(),
+                        promise_id: pid(1),
+                    },
+                },
+            ),
+            delivered(3, "Approve"),
+            delivered(4, "approve"),
+        ];
+
+        let found = anomalies(&entries, &config());
+
+        assert_eq!(
+            found,
+            vec![Anomaly {
+                kind: AnomalyKind::SignalNameCaseCollision {
+                    first: "approve".into(),
+                    second: "Approve".into(),
+                },
+                first_seq: 1,
+                last_seq: 2,
+                severity: Severity::Warning,
+            }]
+        );
+    }
+
+    #[test]
+    fn codec_mismatch_reported_for_inconsistent_invoke_only() {
+        let scheduled = |seq, tag, codec| {
+            at(
+                seq,
+                0,
+                EventType::InvokeScheduled {
+                    promise_id: pid(tag),
+                    kind: InvokeKind::Function,
+                    function_name: "f".into(),
+                    input: Payload::new(vec![], codec),
+                    retry_policy: None,
+                    sla: None,
+                },
+            )
+        };
+        let completed = |seq, tag, codec| {
+            at(
+                seq,
+                0,
+                EventType::InvokeCompleted {
+                    promise_id: pid(tag),
+                    result: Payload::new(vec![], codec),
+                    attempt: 1,
+                },
+            )
+        };
+        let entries = vec![
+            at(0, 0, started()),
+            scheduled(1, 1, Codec::Json),
+            scheduled(2, 2, Codec::Json),
+            completed(3, 1, Codec::Json),
+            completed(4, 2, Codec::Borsh),
+        ];
+
+        assert_eq!(
+            codec_consistency(&entries),
+            vec![CodecMismatch {
+                promise_id: pid(2),
+                input_codec: Codec::Json,
+                result_codec: Codec::Borsh,
+            }]
+        );
+    }
+
+    #[test]
+    fn sla_breaches_reports_closed_and_open_breaches() {
+        let scheduled = |tag, name: &str, sla_secs: Option<u64>| EventType::InvokeScheduled {
+            promise_id: pid(tag),
+            kind: InvokeKind::Function,
+            function_name: name.into(),
+            input: Payload::new(vec![], Codec::Json),
+            retry_policy: None,
+            sla: sla_secs.map(Duration::from_secs),
+        };
+        let completed = |tag| EventType::InvokeCompleted {
+            promise_id: pid(tag),
+            result: Payload::new(vec![], Codec::Json),
+            attempt: 1,
+        };
+        let entries = vec![
+            at(0, 0, scheduled(1, "met", Some(30))),
+            at(1, 0, scheduled(2, "breached", Some(30))),
+            at(2, 0, scheduled(3, "open", Some(5))),
+            at(3, 0, scheduled(4, "undeclared", None)),
+            at(4, 10_000, completed(1)),
+            at(5, 45_000, completed(2)),
+            at(6, 90_000, completed(4)),
+        ];
+
+        assert_eq!(
+            sla_breaches(&entries),
+            vec![
+                SlaBreach {
+                    promise_id: pid(2),
+                    function_name: "breached".into(),
+                    sla: Duration::from_secs(30),
+                    observed: Duration::from_secs(45),
+                    open: false,
+                },
+                SlaBreach {
+                    promise_id: pid(3),
+                    function_name: "open".into(),
+                    sla: Duration::from_secs(5),
+                    observed: Duration::from_secs(90),
+                    open: true,
+                },
+            ]
+        );
+        // Not yet past its SLA as of the last entry.
+        assert!(sla_breaches(&entries[..3]).is_empty());
+    }
+
+    #[test]
+    fn pending_invokes_measure_silence_since_the_last_heartbeat() {
+        let (p, q) = (pid(1), pid(2));
+        let started = |p: &PromiseId, attempt| EventType::InvokeStarted {
+            promise_id: p.clone(),
+            attempt,
+        };
+        let heartbeat = |p: &PromiseId, attempt| EventType::InvokeHeartbeat {
+            promise_id: p.clone(),
+            attempt,
+            at: DateTime::UNIX_EPOCH,
+            progress: None,
+        };
+        let entries = vec![
+            at(0, 0, started(&p, 1)),
+            at(1, 1_000, started(&q, 1)),
+            at(2, 2_000, heartbeat(&p, 1)),
+            at(
+                3,
+                3_000,
+                EventType::InvokeRetrying {
+                    promise_id: q.clone(),
+                    failed_attempt: 1,
+                    error: invariant_types::ExecutionError::new(
+                        invariant_types::ErrorKind::Timeout,
+                        "slow",
+                    ),
+                    retry_at: DateTime::UNIX_EPOCH,
+                },
+            ),
+            at(4, 4_000, started(&q, 2)),
+            at(5, 200_000, EventType::ExecutionResumed),
+        ];
+        let now = DateTime::UNIX_EPOCH + TimeDelta::seconds(10);
+
+        assert_eq!(
+            pending_invokes(&entries, now),
+            vec![
+                PendingInvoke {
+                    promise_id: p.clone(),
+                    attempt: 1,
+                    started_seq: 0,
+                    last_heartbeat: Some(2),
+                    silent_for: Duration::from_secs(8),
+                },
+                PendingInvoke {
+                    promise_id: q.clone(),
+                    attempt: 2,
+                    started_seq: 4,
+                    last_heartbeat: None,
+                    silent_for: Duration::from_secs(6),
+                },
+            ]
+        );
+
+        // Only the attempt that heartbeated is judged silent: 198s > 60s,
+        // and past twice the limit.
+        let found = anomalies(&entries, &config());
+        assert_eq!(
+            found,
+            vec![Anomaly {
+                kind: AnomalyKind::SilentInvoke {
+                    promise_id: p.clone(),
+                    attempt: 1,
+                    silent_for: Duration::from_secs(198),
+                },
+                first_seq: 2,
+                last_seq: 5,
+                severity: Severity::Critical,
+            }]
+        );
+
+        let mut completed = entries.clone();
+        completed.push(at(
+            6,
+            200_000,
+            EventType::InvokeCompleted {
+                promise_id: p,
+                result: Payload::new(vec![], Codec::Json),
+                attempt: 1,
+            },
+        ));
+        assert_eq!(pending_invokes(&completed, now).len(), 1);
+        assert!(anomalies(&completed, &config()).is_empty());
+    }
+}
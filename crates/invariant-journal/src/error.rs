@@ -1,13 +1,29 @@
-use invariant_types::{DomainError, JoinSetId, PromiseId, SignalDeliveryId};
+use invariant_types::{
+    DomainError, ExecutionError, ExecutionId, JoinSetId, PromiseId, SignalDeliveryId,
+};
+use serde::{Deserialize, Serialize};
 
 /// Describes a specific journal invariant violation.
 ///
-/// Variants are grouped as Structural (S-1..S-6), Side Effects (SE-1..SE-4),
-/// Control Flow (CF-1..CF-4), and JoinSet (JS-1..JS-7).
+/// Variants are grouped as Structural (S-1..S-7), Side Effects (SE-1..SE-5),
+/// Control Flow (CF-1..CF-9), and JoinSet (JS-1..JS-8).
 ///
 /// `AllocatedChildMismatch` is a recovery-time integrity check
 /// that ensures recovered allocated child IDs match deterministic derivation.
-#[derive(Clone, Debug, PartialEq, Eq)]
+/// `ExecutionIdMismatch` is an opt-in whole-journal check, reported only when
+/// [`ValidationOptions::verify_execution_id`](crate::invariants::ValidationOptions::verify_execution_id)
+/// is set; the incremental path never sees the execution ID.
+/// `SequenceOverflow` guards S-1 against journals too long to number; it has
+/// no catalog row since no storable journal reaches it. These four are the
+/// [`UNCATALOGUED`](crate::invariants::UNCATALOGUED) violations.
+/// `PayloadTooLarge` is an opt-in ingestion limit, reported only when
+/// [`ValidationOptions::max_payload_bytes`](crate::invariants::ValidationOptions::max_payload_bytes)
+/// is set. `InvokeRejected` is likewise reported only by registered
+/// [`InvokeValidator`](crate::invariants::InvokeValidator)s.
+///
+/// Serializes externally tagged by [`name`](Self::name).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
 pub enum JournalViolation {
     /// S-1: Sequence numbers must equal their array index (0-indexed, strict equality).
     NonMonotonicSequence {
@@ -15,6 +31,15 @@ pub enum JournalViolation {
         expected: u64,
         actual: u64,
     },
+    /// Journal guard: the journal's execution ID is not the derivation of
+    /// its `ExecutionStarted` (digest, idempotency key, parent).
+    ExecutionIdMismatch {
+        expected: ExecutionId,
+        actual: ExecutionId,
+    },
+    /// S-1 guard: the journal already holds as many entries as a `u64`
+    /// sequence (or `usize` length) can number.
+    SequenceOverflow { len: usize },
     /// S-2: The first event in every journal must be `ExecutionStarted`.
     MissingExecutionStarted { first_event: String },
     /// S-3: At most one terminal event (`Completed`, `Failed`, `Cancelled`) per journal.
@@ -22,10 +47,18 @@ pub enum JournalViolation {
     /// S-4: A terminal event must be the last entry in the journal.
     TerminalNotLast {
         terminal_seq: u64,
-        journal_len: usize,
+        extra_entry_seq: u64,
+        extra_event_name: String,
     },
     /// S-5: `ExecutionCancelled` requires a preceding `CancelRequested`.
     CancelledWithoutRequest { cancelled_seq: u64 },
+    /// S-7: `ComponentUpgraded.old_digest` must equal the digest currently in
+    /// effect (the start digest or the previous upgrade's `new_digest`).
+    UpgradeDigestMismatch {
+        upgrade_seq: u64,
+        expected: Vec<u8>,
+        actual: Vec<u8>,
+    },
     /// S-6: Recovery check — allocated child promise ID must match deterministic derivation
     /// from execution root and allocation sequence.
     AllocatedChildMismatch {
@@ -58,6 +91,13 @@ pub enum JournalViolation {
         offending_seq: u64,
         offending_event: String,
     },
+    /// SE-5: `InvokeHeartbeat` names an attempt that is in flight: started,
+    /// not retried, and its promise not completed.
+    HeartbeatNotInFlight {
+        promise_id: PromiseId,
+        attempt: u32,
+        heartbeat_seq: u64,
+    },
 
     /// CF-1: `TimerFired` requires a preceding `TimerScheduled` for the same promise.
     TimerFiredWithoutScheduled {
@@ -76,18 +116,69 @@ pub enum JournalViolation {
         delivery_id: SignalDeliveryId,
         second_seq: u64,
     },
-    /// CF-4: `ExecutionAwaiting` with `Signal` kind must have exactly one promise in
-    /// `waiting_on`, and it must match `AwaitKind::Signal.promise_id`.
+    /// CF-4: `ExecutionAwaiting` with `Signal` or `AnySignal` kind must have exactly
+    /// one promise in `waiting_on`, and it must match the kind's `promise_id`.
     AwaitSignalInconsistent {
         awaiting_seq: u64,
         waiting_on_count: usize,
     },
+    /// CF-4 companion: `AwaitKind::AnySignal.names` must be non-empty and free of
+    /// duplicates. `duplicate` is `None` for an empty list.
+    AnySignalNamesInvalid {
+        awaiting_seq: u64,
+        duplicate: Option<String>,
+    },
     /// Model-shape alignment: `ExecutionAwaiting.waiting_on` is set-like.
     /// Duplicate promise IDs are invalid.
     AwaitWaitingOnDuplicate {
         awaiting_seq: u64,
         promise_id: PromiseId,
     },
+    /// CF-5: While a signal await is outstanding, `SignalReceived` must resolve the
+    /// awaited promise. Signals received while not blocked on a signal are allowed.
+    SignalReceivedForWrongPromise { promise_id: PromiseId, seq: u64 },
+    /// CF-5 companion: while an `AnySignal` await is outstanding, the
+    /// `SignalReceived` resolving it must take the lowest pending
+    /// `(name, delivery_id)` among the listed names.
+    AnySignalWrongDelivery {
+        signal_name: String,
+        delivery_id: SignalDeliveryId,
+        seq: u64,
+    },
+    /// CF-6: `AwaitProgress` is only recorded while blocked, and may only list
+    /// resolved members of the active await. `promise_id` is `None` when the
+    /// execution was not blocked.
+    ProgressInconsistent {
+        seq: u64,
+        promise_id: Option<PromiseId>,
+    },
+    /// CF-6 companion: `AwaitProgress.waiting_on_remaining` equals the number
+    /// of active await members that are still unresolved.
+    ProgressRemainingMismatch {
+        seq: u64,
+        reported: u32,
+        pending: usize,
+    },
+    /// CF-7: A producer `external_id` is delivered at most once per signal name.
+    DuplicateExternalDelivery {
+        signal_name: String,
+        external_id: String,
+        second_seq: u64,
+    },
+    /// CF-8: `SignalDeliveryExpired` names a delivery that was delivered and
+    /// is neither consumed nor already expired.
+    SignalExpiryInvalid {
+        signal_name: String,
+        delivery_id: SignalDeliveryId,
+        expired_seq: u64,
+    },
+    /// CF-9: `SignalDelivered` repeats a `(signal_name, delivery_id)` key that
+    /// was already delivered, whatever its payload.
+    ConflictingSignalDelivery {
+        signal_name: String,
+        delivery_id: SignalDeliveryId,
+        seq: u64,
+    },
 
     /// JS-1: `JoinSetSubmitted` requires a preceding `JoinSetCreated` for the same set.
     SubmitWithoutCreate {
@@ -128,6 +219,112 @@ pub enum JournalViolation {
         first_js: JoinSetId,
         second_js: JoinSetId,
     },
+    /// JS-8: A submitted promise must live in the call tree that created the
+    /// join set.
+    SubmittedOutsideJoinSetTree {
+        join_set_id: JoinSetId,
+        promise_id: PromiseId,
+        seq: u64,
+    },
+
+    /// An event embeds a payload larger than the configured limit.
+    PayloadTooLarge { seq: u64, size: usize, max: usize },
+    /// A registered [`InvokeValidator`](crate::invariants::InvokeValidator)
+    /// rejected an `InvokeScheduled`.
+    InvokeRejected {
+        promise_id: PromiseId,
+        seq: u64,
+        reason: String,
+    },
+}
+
+impl JournalViolation {
+    /// Every name [`name`](Self::name) can return.
+    pub const NAMES: [&'static str; 37] = [
+        "NonMonotonicSequence",
+        "SequenceOverflow",
+        "MissingExecutionStarted",
+        "MultipleTerminalEvents",
+        "TerminalNotLast",
+        "CancelledWithoutRequest",
+        "ExecutionIdMismatch",
+        "UpgradeDigestMismatch",
+        "AllocatedChildMismatch",
+        "StartedWithoutScheduled",
+        "CompletedWithoutStarted",
+        "RetryingWithoutStarted",
+        "EventAfterCompleted",
+        "HeartbeatNotInFlight",
+        "TimerFiredWithoutScheduled",
+        "SignalReceivedWithoutDelivery",
+        "SignalConsumedTwice",
+        "AwaitSignalInconsistent",
+        "AnySignalNamesInvalid",
+        "AwaitWaitingOnDuplicate",
+        "SignalReceivedForWrongPromise",
+        "AnySignalWrongDelivery",
+        "ProgressInconsistent",
+        "ProgressRemainingMismatch",
+        "DuplicateExternalDelivery",
+        "SignalExpiryInvalid",
+        "ConflictingSignalDelivery",
+        "SubmitWithoutCreate",
+        "SubmitAfterAwait",
+        "AwaitedNotMember",
+        "AwaitedNotCompleted",
+        "DoubleConsume",
+        "ConsumeExceedsSubmit",
+        "PromiseInMultipleJoinSets",
+        "SubmittedOutsideJoinSetTree",
+        "PayloadTooLarge",
+        "InvokeRejected",
+    ];
+
+    /// Returns the variant name as a static string.
+    ///
+    /// Matches the `violation` column of
+    /// [`invariant_catalog`](crate::invariants::invariant_catalog).
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::NonMonotonicSequence { .. } => "NonMonotonicSequence",
+            Self::SequenceOverflow { .. } => "SequenceOverflow",
+            Self::MissingExecutionStarted { .. } => "MissingExecutionStarted",
+            Self::MultipleTerminalEvents { .. } => "MultipleTerminalEvents",
+            Self::TerminalNotLast { .. } => "TerminalNotLast",
+            Self::CancelledWithoutRequest { .. } => "CancelledWithoutRequest",
+            Self::ExecutionIdMismatch { .. } => "ExecutionIdMismatch",
+            Self::UpgradeDigestMismatch { .. } => "UpgradeDigestMismatch",
+            Self::AllocatedChildMismatch { .. } => "AllocatedChildMismatch",
+            Self::StartedWithoutScheduled { .. } => "StartedWithoutScheduled",
+            Self::CompletedWithoutStarted { .. } => "CompletedWithoutStarted",
+            Self::RetryingWithoutStarted { .. } => "RetryingWithoutStarted",
+            Self::EventAfterCompleted { .. } => "EventAfterCompleted",
+            Self::HeartbeatNotInFlight { .. } => "HeartbeatNotInFlight",
+            Self::TimerFiredWithoutScheduled { .. } => "TimerFiredWithoutScheduled",
+            Self::SignalReceivedWithoutDelivery { .. } => "SignalReceivedWithoutDelivery",
+            Self::SignalConsumedTwice { .. } => "SignalConsumedTwice",
+            Self::AwaitSignalInconsistent { .. } => "AwaitSignalInconsistent",
+            Self::AnySignalNamesInvalid { .. } => "AnySignalNamesInvalid",
+            Self::AwaitWaitingOnDuplicate { .. } => "AwaitWaitingOnDuplicate",
+            Self::SignalReceivedForWrongPromise { .. } => "SignalReceivedForWrongPromise",
+            Self::AnySignalWrongDelivery { .. } => "AnySignalWrongDelivery",
+            Self::ProgressInconsistent { .. } => "ProgressInconsistent",
+            Self::ProgressRemainingMismatch { .. } => "ProgressRemainingMismatch",
+            Self::DuplicateExternalDelivery { .. } => "DuplicateExternalDelivery",
+            Self::SignalExpiryInvalid { .. } => "SignalExpiryInvalid",
+            Self::ConflictingSignalDelivery { .. } => "ConflictingSignalDelivery",
+            Self::SubmitWithoutCreate { .. } => "SubmitWithoutCreate",
+            Self::SubmitAfterAwait { .. } => "SubmitAfterAwait",
+            Self::AwaitedNotMember { .. } => "AwaitedNotMember",
+            Self::AwaitedNotCompleted { .. } => "AwaitedNotCompleted",
+            Self::DoubleConsume { .. } => "DoubleConsume",
+            Self::ConsumeExceedsSubmit { .. } => "ConsumeExceedsSubmit",
+            Self::PromiseInMultipleJoinSets { .. } => "PromiseInMultipleJoinSets",
+            Self::SubmittedOutsideJoinSetTree { .. } => "SubmittedOutsideJoinSetTree",
+            Self::PayloadTooLarge { .. } => "PayloadTooLarge",
+            Self::InvokeRejected { .. } => "InvokeRejected",
+        }
+    }
 }
 
 /// Errors produced by journal operations.
@@ -141,6 +338,71 @@ pub enum JournalError {
     DomainError(DomainError),
 }
 
+/// Wire form of journal and runtime errors, for returning them across an
+/// RPC boundary.
+///
+/// Serializes as an object whose `kind` field is a stable snake_case tag
+/// per variant. Runtime errors travel as the [`ExecutionError`] the engine
+/// converts them to; domain errors, which borrow static strings, travel as
+/// their message.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum ErrorResponse {
+    /// [`JournalError::EmptyJournal`].
+    EmptyJournal,
+    /// [`JournalError::InvariantViolation`].
+    InvariantViolation { violation: JournalViolation },
+    /// [`JournalError::DomainError`].
+    Domain { message: String },
+    /// A failure running a component.
+    Runtime { error: ExecutionError },
+}
+
+impl From<JournalError> for ErrorResponse {
+    fn from(err: JournalError) -> Self {
+        match err {
+            JournalError::EmptyJournal => Self::EmptyJournal,
+            JournalError::InvariantViolation(violation) => Self::InvariantViolation {
+                violation: *violation,
+            },
+            JournalError::DomainError(err) => Self::Domain {
+                message: err.to_string(),
+            },
+        }
+    }
+}
+
+impl From<ExecutionError> for ErrorResponse {
+    fn from(error: ExecutionError) -> Self {
+        Self::Runtime { error }
+    }
+}
+
+/// Errors produced when linking journals with continue-as-new.
+///
+/// `index` is the position in the journal slice passed to
+/// [`chain`](crate::continuation::chain).
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum ChainError {
+    #[error("continuation chain is empty")]
+    EmptyChain,
+    #[error("journal {index} does not start with ExecutionStarted")]
+    MissingStart { index: usize },
+    #[error("journal {index} is followed by another but does not end with ContinuedAsNew")]
+    NotContinued { index: usize },
+    #[error("journal {index} has execution id {actual}, expected {expected}")]
+    ExecutionIdMismatch {
+        index: usize,
+        expected: ExecutionId,
+        actual: ExecutionId,
+    },
+    #[error("journal {index} input does not match the carried state handed to it")]
+    CarriedStateMismatch { index: usize },
+    #[error("continuation generation overflowed")]
+    GenerationOverflow,
+}
+
 impl std::fmt::Display for JournalViolation {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -152,6 +414,9 @@ impl std::fmt::Display for JournalViolation {
                 f,
                 "S-1: non-monotonic sequence at index {entry_index}: expected {expected}, got {actual}"
             ),
+            Self::SequenceOverflow { len } => {
+                write!(f, "S-1: journal of {len} entries cannot number another")
+            }
             Self::MissingExecutionStarted { first_event } => write!(
                 f,
                 "S-2: first event must be ExecutionStarted, got {first_event}"
@@ -165,15 +430,28 @@ impl std::fmt::Display for JournalViolation {
             ),
             Self::TerminalNotLast {
                 terminal_seq,
-                journal_len,
+                extra_entry_seq,
+                extra_event_name,
             } => write!(
                 f,
-                "S-4: terminal event at seq {terminal_seq} is not last (journal len {journal_len})"
+                "S-4: terminal at seq {terminal_seq}, but entry at seq {extra_entry_seq} ({extra_event_name}) follows it"
             ),
             Self::CancelledWithoutRequest { cancelled_seq } => write!(
                 f,
                 "S-5: ExecutionCancelled at seq {cancelled_seq} without prior CancelRequested"
             ),
+            Self::ExecutionIdMismatch { expected, actual } => write!(
+                f,
+                "journal execution id {actual} does not match {expected} derived from ExecutionStarted"
+            ),
+            Self::UpgradeDigestMismatch {
+                upgrade_seq,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "S-7: ComponentUpgraded at seq {upgrade_seq} replaces digest {actual:02x?}, but {expected:02x?} is in effect"
+            ),
             Self::AllocatedChildMismatch {
                 event_seq,
                 event_name,
@@ -213,6 +491,14 @@ impl std::fmt::Display for JournalViolation {
                 f,
                 "SE-4: {offending_event} at seq {offending_seq} for {promise_id} after InvokeCompleted"
             ),
+            Self::HeartbeatNotInFlight {
+                promise_id,
+                attempt,
+                heartbeat_seq,
+            } => write!(
+                f,
+                "SE-5: InvokeHeartbeat at seq {heartbeat_seq} for {promise_id} attempt {attempt}, which is not in flight"
+            ),
             Self::TimerFiredWithoutScheduled {
                 promise_id,
                 fired_seq,
@@ -243,6 +529,20 @@ impl std::fmt::Display for JournalViolation {
                 f,
                 "CF-4: ExecutionAwaiting(Signal) at seq {awaiting_seq} is inconsistent (waiting_on_count={waiting_on_count}); expected exactly one waiting promise matching AwaitKind::Signal.promise_id"
             ),
+            Self::AnySignalNamesInvalid {
+                awaiting_seq,
+                duplicate: None,
+            } => write!(
+                f,
+                "CF-4: ExecutionAwaiting(AnySignal) at seq {awaiting_seq} lists no signal names"
+            ),
+            Self::AnySignalNamesInvalid {
+                awaiting_seq,
+                duplicate: Some(name),
+            } => write!(
+                f,
+                "CF-4: ExecutionAwaiting(AnySignal) at seq {awaiting_seq} lists signal {name} twice"
+            ),
             Self::AwaitWaitingOnDuplicate {
                 awaiting_seq,
                 promise_id,
@@ -250,6 +550,61 @@ impl std::fmt::Display for JournalViolation {
                 f,
                 "ExecutionAwaiting at seq {awaiting_seq} contains duplicate waiting_on promise {promise_id}"
             ),
+            Self::SignalReceivedForWrongPromise { promise_id, seq } => write!(
+                f,
+                "CF-5: SignalReceived at seq {seq} for {promise_id} does not match the outstanding signal await"
+            ),
+            Self::AnySignalWrongDelivery {
+                signal_name,
+                delivery_id,
+                seq,
+            } => write!(
+                f,
+                "CF-5: SignalReceived at seq {seq} takes signal {signal_name:?} delivery {delivery_id}, not the next pending delivery of the awaited names"
+            ),
+            Self::ProgressInconsistent {
+                seq,
+                promise_id: None,
+            } => write!(f, "CF-6: AwaitProgress at seq {seq} while not blocked"),
+            Self::ProgressInconsistent {
+                seq,
+                promise_id: Some(promise_id),
+            } => write!(
+                f,
+                "CF-6: AwaitProgress at seq {seq} lists {promise_id}, which is not a resolved member of the active await"
+            ),
+            Self::ProgressRemainingMismatch {
+                seq,
+                reported,
+                pending,
+            } => write!(
+                f,
+                "CF-6: AwaitProgress at seq {seq} reports {reported} remaining, but {pending} member(s) of the active await are unresolved"
+            ),
+            Self::DuplicateExternalDelivery {
+                signal_name,
+                external_id,
+                second_seq,
+            } => write!(
+                f,
+                "CF-7: SignalDelivered at seq {second_seq} repeats external_id {external_id:?} for signal {signal_name:?}"
+            ),
+            Self::SignalExpiryInvalid {
+                signal_name,
+                delivery_id,
+                expired_seq,
+            } => write!(
+                f,
+                "CF-8: SignalDeliveryExpired at seq {expired_seq} for signal {signal_name:?} delivery {delivery_id}, which is not buffered"
+            ),
+            Self::ConflictingSignalDelivery {
+                signal_name,
+                delivery_id,
+                seq,
+            } => write!(
+                f,
+                "CF-9: SignalDelivered at seq {seq} repeats signal {signal_name:?} delivery {delivery_id}"
+            ),
             Self::SubmitWithoutCreate {
                 join_set_id,
                 submitted_seq,
@@ -303,6 +658,149 @@ impl std::fmt::Display for JournalViolation {
                 f,
                 "JS-7: {promise_id} submitted to both {first_js} and {second_js}"
             ),
+            Self::SubmittedOutsideJoinSetTree {
+                join_set_id,
+                promise_id,
+                seq,
+            } => write!(
+                f,
+                "JS-8: {promise_id} submitted to {join_set_id} at seq {seq} is outside the set's call tree"
+            ),
+            Self::PayloadTooLarge { seq, size, max } => write!(
+                f,
+                "payload at seq {seq} is {size} bytes, exceeding the {max}-byte limit"
+            ),
+            Self::InvokeRejected {
+                promise_id,
+                seq,
+                reason,
+            } => write!(f, "invoke {promise_id} at seq {seq} rejected: {reason}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use invariant_types::ErrorKind;
+
+    fn round_trip(response: &ErrorResponse) -> serde_json::Value {
+        let json = serde_json::to_value(response).unwrap();
+        let back: ErrorResponse = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(&back, response);
+        json
+    }
+
+    /// The variant names serde's derive generated for `T`, captured from
+    /// the `deserialize_enum` call it makes.
+    fn serde_variants<T: for<'de> Deserialize<'de>>() -> &'static [&'static str] {
+        use serde::de::{self, Visitor};
+
+        struct Capture<'a>(&'a mut Option<&'static [&'static str]>);
+
+        impl<'de> serde::Deserializer<'de> for Capture<'_> {
+            type Error = de::value::Error;
+
+            fn deserialize_any<V: Visitor<'de>>(self, _: V) -> Result<V::Value, Self::Error> {
+                Err(de::Error::custom("not an enum"))
+            }
+
+            fn deserialize_enum<V: Visitor<'de>>(
+                self,
+                _name: &'static str,
+                variants: &'static [&'static str],
+                _: V,
+            ) -> Result<V::Value, Self::Error> {
+                *self.0 = Some(variants);
+                Err(de::Error::custom("variants captured"))
+            }
+
+            serde::forward_to_deserialize_any! {
+                bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+                bytes byte_buf option unit unit_struct newtype_struct seq tuple
+                tuple_struct map struct identifier ignored_any
+            }
+        }
+
+        let mut variants = None;
+        let _ = T::deserialize(Capture(&mut variants));
+        variants.expect("type deserializes as an enum")
+    }
+
+    #[test]
+    fn names_lists_every_variant_exactly_once() {
+        let mut names = JournalViolation::NAMES.to_vec();
+        names.sort_unstable();
+        let before = names.len();
+        names.dedup();
+        assert_eq!(names.len(), before, "NAMES has duplicates");
+
+        let mut variants = serde_variants::<JournalViolation>().to_vec();
+        variants.sort_unstable();
+        assert_eq!(names, variants);
+    }
+
+    /// Catalogued variants have their `name()` checked by the catalog's
+    /// reachability test; the uncatalogued ones are checked here.
+    #[test]
+    fn uncatalogued_names_match_their_serde_tags() {
+        let id = ExecutionId::derive(&[1], "k", None);
+        let pid = PromiseId::new([1; 32]);
+        for violation in [
+            JournalViolation::ExecutionIdMismatch {
+                expected: id.clone(),
+                actual: id,
+            },
+            JournalViolation::SequenceOverflow { len: 0 },
+            JournalViolation::PayloadTooLarge {
+                seq: 0,
+                size: 2,
+                max: 1,
+            },
+            JournalViolation::InvokeRejected {
+                promise_id: pid,
+                seq: 0,
+                reason: "no".into(),
+            },
+        ] {
+            let json = serde_json::to_value(&violation).unwrap();
+            assert!(
+                json.get(violation.name()).is_some(),
+                "{} serializes as {json}",
+                violation.name()
+            );
         }
     }
+
+    #[test]
+    fn violation_response_round_trips_with_stable_tags() {
+        let response = ErrorResponse::from(JournalError::InvariantViolation(Box::new(
+            JournalViolation::DoubleConsume {
+                join_set_id: JoinSetId(PromiseId::new([1; 32])),
+                promise_id: PromiseId::new([2; 32]),
+                second_seq: 7,
+            },
+        )));
+        let json = round_trip(&response);
+        assert_eq!(json["kind"], "invariant_violation");
+        assert_eq!(json["violation"]["DoubleConsume"]["second_seq"], 7);
+
+        assert_eq!(
+            round_trip(&ErrorResponse::from(JournalError::EmptyJournal))["kind"],
+            "empty_journal"
+        );
+    }
+
+    #[test]
+    fn runtime_and_domain_responses_round_trip() {
+        let runtime = ErrorResponse::from(ExecutionError::new(ErrorKind::Trap, "unreachable"));
+        assert_eq!(round_trip(&runtime)["kind"], "runtime");
+
+        let domain = ErrorResponse::from(JournalError::DomainError(
+            DomainError::MaxChildrenExceeded { max: 3 },
+        ));
+        let json = round_trip(&domain);
+        assert_eq!(json["kind"], "domain");
+        assert_eq!(json["message"], "max children of 3 exceeded");
+    }
 }
@@ -0,0 +1,7 @@
+//! Rendering journals for external observability backends.
+//!
+//! Each backend lives behind its own feature so the core crate stays free of
+//! telemetry dependencies.
+
+#[cfg(feature = "otel")]
+pub mod otel;
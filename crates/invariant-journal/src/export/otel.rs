@@ -0,0 +1,671 @@
+//! Journal export to OpenTelemetry spans.
+//!
+//! An execution becomes one trace. The execution is the root span; join
+//! sets, invokes, timers and signal waits are spans beneath it, and each
+//! invoke attempt is a child of its invoke:
+//!
+//! ```text
+//! execution
+//! ├── join_set
+//! │   └── invoke            (members of the set)
+//! │       └── attempt
+//! ├── invoke                (invokes no join set owns)
+//! │   └── attempt
+//! ├── timer
+//! └── signal_wait
+//! ```
+//!
+//! The trace ID is taken from the execution ID and every span ID is a hash
+//! of the promise it renders, so exporting the same journal twice produces
+//! the same spans and backends deduplicate rather than fork the trace.
+//! Spans still open when the journal ends close at its last entry.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::time::SystemTime;
+
+use chrono::{DateTime, Utc};
+use indexmap::IndexMap;
+use invariant_types::{AwaitKind, EventType, ExecutionJournal, JoinSetId, PromiseId};
+use opentelemetry::trace::{
+    Event, SpanContext, SpanId, SpanKind, Status, TraceContextExt, TraceFlags, TraceId, TraceState,
+    Tracer, TracerProvider,
+};
+use opentelemetry::{Context, InstrumentationScope, KeyValue};
+use opentelemetry_sdk::trace::{SpanData, SpanEvents, SpanLinks};
+
+use crate::invariants::ValidationReport;
+
+/// Instrumentation scope name reported on every exported span.
+pub const SCOPE_NAME: &str = "invariant-journal";
+
+/// Render `journal` as OpenTelemetry span data, parents before children.
+///
+/// Returns no spans for an empty journal.
+pub fn journal_to_spans(journal: &ExecutionJournal) -> Vec<SpanData> {
+    build(journal, None)
+}
+
+/// [`journal_to_spans`], annotating the root span with one event per
+/// violation and warning in `report`.
+///
+/// Events are named `invariant.violation` or `invariant.warning` and carry
+/// the violation's name and message.
+pub fn journal_to_spans_with(
+    journal: &ExecutionJournal,
+    report: &ValidationReport,
+) -> Vec<SpanData> {
+    build(journal, Some(report))
+}
+
+/// Record the spans of `journal` through `provider`, so they reach whatever
+/// exporters it is configured with.
+///
+/// Span IDs, timestamps and parentage are those of [`journal_to_spans`];
+/// sampling is up to the provider.
+pub fn export_to<P>(provider: &P, journal: &ExecutionJournal)
+where
+    P: TracerProvider,
+    <P::Tracer as Tracer>::Span: Send + Sync + 'static,
+{
+    let tracer = provider.tracer(SCOPE_NAME);
+    let mut contexts: HashMap<SpanId, Context> = HashMap::new();
+    let mut ends = Vec::new();
+    for span in journal_to_spans(journal) {
+        let span_id = span.span_context.span_id();
+        let parent = contexts
+            .get(&span.parent_span_id)
+            .cloned()
+            .unwrap_or_default();
+        let builder = tracer
+            .span_builder(span.name)
+            .with_trace_id(span.span_context.trace_id())
+            .with_span_id(span_id)
+            .with_kind(span.span_kind)
+            .with_start_time(span.start_time)
+            .with_attributes(span.attributes)
+            .with_events(span.events.events)
+            .with_status(span.status);
+        let recorded = tracer.build_with_context(builder, &parent);
+        contexts.insert(span_id, Context::new().with_span(recorded));
+        ends.push((span_id, span.end_time));
+    }
+    // Children end before their parents, as they would have live.
+    for (span_id, end_time) in ends.iter().rev() {
+        contexts[span_id].span().end_with_timestamp(*end_time);
+    }
+}
+
+/// Which span of a promise a span ID is for; part of the hashed input so a
+/// promise's invoke, attempt and timer spans get distinct IDs.
+#[derive(Clone, Copy)]
+enum Role {
+    Execution = 0,
+    Invoke = 1,
+    Attempt = 2,
+    JoinSet = 3,
+    Timer = 4,
+    SignalWait = 5,
+}
+
+/// Stable span ID for the `role` span of `promise_id` (FNV-1a, 64-bit).
+///
+/// Never returns the invalid all-zero ID.
+fn span_id(promise_id: &PromiseId, role: Role, n: u32) -> SpanId {
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+    let bytes = std::iter::once(role as u8)
+        .chain(promise_id.root_bytes().iter().copied())
+        .chain(promise_id.path().iter().flat_map(|seq| seq.to_le_bytes()))
+        .chain(n.to_le_bytes());
+    let hash = bytes.fold(OFFSET, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+    });
+    SpanId::from_bytes(hash.max(1).to_be_bytes())
+}
+
+enum Parent {
+    Root,
+    Invoke(PromiseId),
+    JoinSetOrRoot(PromiseId),
+}
+
+/// A span under construction; `end` stays `None` while it is open.
+struct Draft {
+    span_id: SpanId,
+    parent: Parent,
+    name: &'static str,
+    start: DateTime<Utc>,
+    end: Option<DateTime<Utc>>,
+    attributes: Vec<KeyValue>,
+    status: Status,
+}
+
+impl Draft {
+    fn new(span_id: SpanId, parent: Parent, name: &'static str, start: DateTime<Utc>) -> Self {
+        Self {
+            span_id,
+            parent,
+            name,
+            start,
+            end: None,
+            attributes: Vec::new(),
+            status: Status::Unset,
+        }
+    }
+
+    fn close(&mut self, at: DateTime<Utc>) {
+        self.end.get_or_insert(at);
+    }
+}
+
+fn build(journal: &ExecutionJournal, report: Option<&ValidationReport>) -> Vec<SpanData> {
+    let (Some(first), Some(last)) = (journal.entries.first(), journal.entries.last()) else {
+        return Vec::new();
+    };
+    let execution = journal.execution_id.as_promise_id();
+    let mut trace_bytes = [0; 16];
+    trace_bytes.copy_from_slice(&execution.root_bytes()[..16]);
+    let trace_id = TraceId::from_bytes(trace_bytes);
+
+    let mut root = Draft::new(
+        span_id(execution, Role::Execution, 0),
+        Parent::Root,
+        "execution",
+        first.timestamp,
+    );
+    root.attributes.push(KeyValue::new(
+        "invariant.execution_id",
+        journal.execution_id.to_string(),
+    ));
+    let mut join_sets: IndexMap<JoinSetId, Draft> = IndexMap::new();
+    let mut invokes: IndexMap<PromiseId, Draft> = IndexMap::new();
+    let mut attempts: IndexMap<(PromiseId, u32), Draft> = IndexMap::new();
+    let mut timers: IndexMap<PromiseId, Draft> = IndexMap::new();
+    let mut signal_waits: IndexMap<PromiseId, Draft> = IndexMap::new();
+    let mut owners: HashMap<PromiseId, JoinSetId> = HashMap::new();
+
+    for entry in &journal.entries {
+        let at = entry.timestamp;
+        match &entry.event {
+            EventType::ExecutionCompleted { .. } | EventType::ContinuedAsNew { .. } => {
+                root.close(at);
+            }
+            EventType::ExecutionFailed { error } => {
+                root.attributes.push(KeyValue::new(
+                    "invariant.error_kind",
+                    error.kind.as_str().to_string(),
+                ));
+                root.status = Status::error(error.message.clone());
+                root.close(at);
+            }
+            EventType::ExecutionCancelled { reason } => {
+                root.attributes
+                    .push(KeyValue::new("invariant.cancel_reason", reason.clone()));
+                root.close(at);
+            }
+            EventType::InvokeScheduled {
+                promise_id,
+                kind,
+                function_name,
+                ..
+            } => {
+                let mut draft = Draft::new(
+                    span_id(promise_id, Role::Invoke, 0),
+                    Parent::JoinSetOrRoot(promise_id.clone()),
+                    "invoke",
+                    at,
+                );
+                draft.attributes.extend([
+                    KeyValue::new("invariant.promise_id", promise_id.to_string()),
+                    KeyValue::new("invariant.function_name", function_name.clone()),
+                    KeyValue::new("invariant.invoke_kind", format!("{kind:?}")),
+                ]);
+                invokes.entry(promise_id.clone()).or_insert(draft);
+            }
+            EventType::InvokeStarted {
+                promise_id,
+                attempt,
+            } => {
+                let mut draft = Draft::new(
+                    span_id(promise_id, Role::Attempt, *attempt),
+                    Parent::Invoke(promise_id.clone()),
+                    "attempt",
+                    at,
+                );
+                draft
+                    .attributes
+                    .push(KeyValue::new("invariant.attempt", i64::from(*attempt)));
+                attempts
+                    .entry((promise_id.clone(), *attempt))
+                    .or_insert(draft);
+            }
+            EventType::InvokeRetrying {
+                promise_id,
+                failed_attempt,
+                error,
+                ..
+            } => {
+                if let Some(draft) = attempts.get_mut(&(promise_id.clone(), *failed_attempt)) {
+                    draft.attributes.push(KeyValue::new(
+                        "invariant.error_kind",
+                        error.kind.as_str().to_string(),
+                    ));
+                    draft.status = Status::error(error.message.clone());
+                    draft.close(at);
+                }
+            }
+            EventType::InvokeCompleted {
+                promise_id,
+                attempt,
+                ..
+            } => {
+                if let Some(draft) = attempts.get_mut(&(promise_id.clone(), *attempt)) {
+                    draft.close(at);
+                }
+                if let Some(draft) = invokes.get_mut(promise_id) {
+                    draft
+                        .attributes
+                        .push(KeyValue::new("invariant.attempts", i64::from(*attempt)));
+                    draft.close(at);
+                }
+            }
+            EventType::JoinSetCreated { join_set_id } => {
+                let mut draft = Draft::new(
+                    span_id(&join_set_id.0, Role::JoinSet, 0),
+                    Parent::Root,
+                    "join_set",
+                    at,
+                );
+                draft.attributes.push(KeyValue::new(
+                    "invariant.join_set_id",
+                    join_set_id.0.to_string(),
+                ));
+                join_sets.entry(join_set_id.clone()).or_insert(draft);
+            }
+            EventType::JoinSetSubmitted {
+                join_set_id,
+                promise_id,
+            } => {
+                owners
+                    .entry(promise_id.clone())
+                    .or_insert_with(|| join_set_id.clone());
+            }
+            // A set spans until its last member is awaited.
+            EventType::JoinSetAwaited { join_set_id, .. } => {
+                if let Some(draft) = join_sets.get_mut(join_set_id) {
+                    draft.end = Some(at);
+                }
+            }
+            EventType::TimerScheduled {
+                promise_id,
+                fire_at,
+                ..
+            } => {
+                let mut draft = Draft::new(
+                    span_id(promise_id, Role::Timer, 0),
+                    Parent::Root,
+                    "timer",
+                    at,
+                );
+                draft.attributes.extend([
+                    KeyValue::new("invariant.promise_id", promise_id.to_string()),
+                    KeyValue::new("invariant.fire_at", fire_at.to_rfc3339()),
+                ]);
+                timers.entry(promise_id.clone()).or_insert(draft);
+            }
+            EventType::TimerFired { promise_id } => {
+                if let Some(draft) = timers.get_mut(promise_id) {
+                    draft.close(at);
+                }
+            }
+            EventType::ExecutionAwaiting {
+                kind: AwaitKind::Signal {
+                    promise_id, name, ..
+                },
+                ..
+            } => {
+                let mut draft = Draft::new(
+                    span_id(promise_id, Role::SignalWait, 0),
+                    Parent::Root,
+                    "signal_wait",
+                    at,
+                );
+                draft
+                    .attributes
+                    .push(KeyValue::new("invariant.signal_names", name.clone()));
+                signal_waits.entry(promise_id.clone()).or_insert(draft);
+            }
+            EventType::ExecutionAwaiting {
+                kind: AwaitKind::AnySignal { promise_id, names },
+                ..
+            } => {
+                let mut draft = Draft::new(
+                    span_id(promise_id, Role::SignalWait, 0),
+                    Parent::Root,
+                    "signal_wait",
+                    at,
+                );
+                draft
+                    .attributes
+                    .push(KeyValue::new("invariant.signal_names", names.join(",")));
+                signal_waits.entry(promise_id.clone()).or_insert(draft);
+            }
+            EventType::SignalReceived {
+                promise_id,
+                signal_name,
+                ..
+            } => {
+                if let Some(draft) = signal_waits.get_mut(promise_id)
+                    && draft.end.is_none()
+                {
+                    draft
+                        .attributes
+                        .push(KeyValue::new("invariant.signal_name", signal_name.clone()));
+                    draft.close(at);
+                }
+            }
+            _ => {}
+        }
+    }
+    root.close(last.timestamp);
+    let end = root.end.unwrap_or(last.timestamp);
+
+    let mut events = Vec::new();
+    if let Some(report) = report {
+        let annotate = |name: &'static str, violation: &crate::error::JournalViolation| {
+            Event::new(
+                name,
+                SystemTime::from(end),
+                vec![
+                    KeyValue::new("invariant.violation", violation.name()),
+                    KeyValue::new("message", violation.to_string()),
+                ],
+                0,
+            )
+        };
+        events.extend(
+            report
+                .violations
+                .iter()
+                .map(|v| annotate("invariant.violation", v)),
+        );
+        events.extend(
+            report
+                .warnings
+                .iter()
+                .map(|v| annotate("invariant.warning", v)),
+        );
+    }
+
+    let root_id = root.span_id;
+    let join_set_ids: HashMap<JoinSetId, SpanId> = join_sets
+        .iter()
+        .map(|(js, draft)| (js.clone(), draft.span_id))
+        .collect();
+    let parent_id = |parent: &Parent| match parent {
+        Parent::Root => root_id,
+        Parent::Invoke(promise_id) => span_id(promise_id, Role::Invoke, 0),
+        Parent::JoinSetOrRoot(promise_id) => owners
+            .get(promise_id)
+            .and_then(|js| join_set_ids.get(js))
+            .copied()
+            .unwrap_or(root_id),
+    };
+    let scope = InstrumentationScope::builder(SCOPE_NAME).build();
+    let to_span = |draft: Draft, parent_span_id: SpanId, events: Vec<Event>| {
+        let mut span_events = SpanEvents::default();
+        span_events.events = events;
+        SpanData {
+            span_context: SpanContext::new(
+                trace_id,
+                draft.span_id,
+                TraceFlags::SAMPLED,
+                false,
+                TraceState::default(),
+            ),
+            parent_span_id,
+            parent_span_is_remote: false,
+            span_kind: SpanKind::Internal,
+            name: Cow::Borrowed(draft.name),
+            start_time: draft.start.into(),
+            end_time: draft.end.unwrap_or(end).into(),
+            attributes: draft.attributes,
+            dropped_attributes_count: 0,
+            events: span_events,
+            links: SpanLinks::default(),
+            status: draft.status,
+            instrumentation_scope: scope.clone(),
+        }
+    };
+
+    let mut spans = vec![to_span(root, SpanId::INVALID, events)];
+    let children = join_sets
+        .into_values()
+        .chain(invokes.into_values())
+        .chain(attempts.into_values())
+        .chain(timers.into_values())
+        .chain(signal_waits.into_values());
+    for draft in children {
+        let parent = parent_id(&draft.parent);
+        spans.push(to_span(draft, parent, Vec::new()));
+    }
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Write;
+    use std::sync::{Arc, Mutex};
+
+    use chrono::TimeDelta;
+    use invariant_types::{Codec, ErrorKind, ExecutionError, InvokeKind, JournalEntry, Payload};
+    use opentelemetry_sdk::error::OTelSdkResult;
+    use opentelemetry_sdk::trace::{SdkTracerProvider, SpanExporter};
+
+    use super::*;
+    use crate::error::JournalViolation;
+    use crate::invariants::validate_journal;
+
+    fn payload() -> Payload {
+        Payload::new(vec![], Codec::Json)
+    }
+
+    /// One invoke retried once inside a join set, plus a timer.
+    fn fixture() -> ExecutionJournal {
+        let execution_id = invariant_types::ExecutionId::derive(&[1], "k", None);
+        let js = JoinSetId(execution_id.child(0).unwrap());
+        let invoke = execution_id.child(1).unwrap();
+        let timer = execution_id.child(2).unwrap();
+        let events = vec![
+            EventType::ExecutionStarted {
+                component_digest: vec![1],
+                input: payload(),
+                parent_id: None,
+                idempotency_key: "k".into(),
+            },
+            EventType::JoinSetCreated {
+                join_set_id: js.clone(),
+            },
+            EventType::InvokeScheduled {
+                promise_id: invoke.clone(),
+                kind: InvokeKind::Function,
+                function_name: "charge".into(),
+                input: payload(),
+                retry_policy: None,
+                sla: None,
+            },
+            EventType::JoinSetSubmitted {
+                join_set_id: js.clone(),
+                promise_id: invoke.clone(),
+            },
+            EventType::InvokeStarted {
+                promise_id: invoke.clone(),
+                attempt: 1,
+            },
+            EventType::InvokeRetrying {
+                promise_id: invoke.clone(),
+                failed_attempt: 1,
+                error: ExecutionError::new(ErrorKind::Timeout, "slow"),
+                retry_at: DateTime::UNIX_EPOCH,
+            },
+            EventType::InvokeStarted {
+                promise_id: invoke.clone(),
+                attempt: 2,
+            },
+            EventType::InvokeCompleted {
+                promise_id: invoke.clone(),
+                result: payload(),
+                attempt: 2,
+            },
+            EventType::JoinSetAwaited {
+                join_set_id: js,
+                promise_id: invoke,
+                result: payload(),
+            },
+            EventType::TimerScheduled {
+                promise_id: timer.clone(),
+                duration: std::time::Duration::from_secs(1),
+                fire_at: DateTime::UNIX_EPOCH,
+            },
+            EventType::TimerFired { promise_id: timer },
+            EventType::ExecutionCompleted { result: payload() },
+        ];
+        let entries = events
+            .into_iter()
+            .enumerate()
+            .map(|(i, event)| JournalEntry {
+                sequence: i as u64,
+                timestamp: DateTime::UNIX_EPOCH + TimeDelta::seconds(i as i64),
+                event,
+            })
+            .collect();
+        let journal = ExecutionJournal {
+            execution_id,
+            entries,
+            provenance: None,
+        };
+        assert!(validate_journal(&journal).is_empty());
+        journal
+    }
+
+    /// Indented `name [start-end]` lines, children under their parent.
+    fn render(spans: &[SpanData]) -> String {
+        fn walk(spans: &[SpanData], parent: SpanId, depth: usize, out: &mut String) {
+            for span in spans.iter().filter(|s| s.parent_span_id == parent) {
+                let secs =
+                    |t: SystemTime| t.duration_since(SystemTime::UNIX_EPOCH).unwrap().as_secs();
+                writeln!(
+                    out,
+                    "{}{} [{}-{}]",
+                    "  ".repeat(depth),
+                    span.name,
+                    secs(span.start_time),
+                    secs(span.end_time)
+                )
+                .unwrap();
+                walk(spans, span.span_context.span_id(), depth + 1, out);
+            }
+        }
+        let mut out = String::new();
+        walk(spans, SpanId::INVALID, 0, &mut out);
+        out
+    }
+
+    #[test]
+    fn span_tree_nests_attempts_under_join_set_invokes() {
+        let spans = journal_to_spans(&fixture());
+        assert_eq!(
+            render(&spans),
+            "\
+execution [0-11]
+  join_set [1-8]
+    invoke [2-7]
+      attempt [4-5]
+      attempt [6-7]
+  timer [9-10]
+"
+        );
+
+        let failed = spans
+            .iter()
+            .find(|s| {
+                s.attributes
+                    .contains(&KeyValue::new("invariant.attempt", 1))
+            })
+            .unwrap();
+        assert!(matches!(failed.status, Status::Error { .. }));
+        assert!(
+            failed
+                .attributes
+                .contains(&KeyValue::new("invariant.error_kind", "timeout"))
+        );
+        let trace_id = spans[0].span_context.trace_id();
+        assert!(spans.iter().all(|s| s.span_context.trace_id() == trace_id));
+    }
+
+    #[test]
+    fn reexport_is_idempotent() {
+        let ids = |spans: Vec<SpanData>| -> Vec<SpanId> {
+            spans.iter().map(|s| s.span_context.span_id()).collect()
+        };
+        let first = ids(journal_to_spans(&fixture()));
+        assert_eq!(first, ids(journal_to_spans(&fixture())));
+
+        let mut unique = first.clone();
+        unique.sort_by_key(|id| id.to_bytes());
+        unique.dedup();
+        assert_eq!(unique.len(), first.len());
+    }
+
+    #[test]
+    fn report_violations_become_root_events() {
+        let report = ValidationReport {
+            violations: vec![JournalViolation::CancelledWithoutRequest { cancelled_seq: 3 }],
+            warnings: Vec::new(),
+        };
+        let spans = journal_to_spans_with(&fixture(), &report);
+        let events = &spans[0].events.events;
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].name, "invariant.violation");
+        assert!(events[0].attributes.contains(&KeyValue::new(
+            "invariant.violation",
+            "CancelledWithoutRequest"
+        )));
+    }
+
+    #[derive(Clone, Debug, Default)]
+    struct Collect(Arc<Mutex<Vec<SpanData>>>);
+
+    impl SpanExporter for Collect {
+        async fn export(&self, batch: Vec<SpanData>) -> OTelSdkResult {
+            self.0.lock().unwrap().extend(batch);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn export_to_preserves_ids_and_parentage() {
+        let collected = Collect::default();
+        let provider = SdkTracerProvider::builder()
+            .with_simple_exporter(collected.clone())
+            .build();
+        export_to(&provider, &fixture());
+
+        let expected = journal_to_spans(&fixture());
+        let exported = collected.0.lock().unwrap();
+        assert_eq!(exported.len(), expected.len());
+        for span in &expected {
+            let got = exported
+                .iter()
+                .find(|s| s.span_context.span_id() == span.span_context.span_id())
+                .unwrap();
+            assert_eq!(got.parent_span_id, span.parent_span_id);
+            assert_eq!(got.span_context.trace_id(), span.span_context.trace_id());
+            assert_eq!(
+                (got.start_time, got.end_time),
+                (span.start_time, span.end_time)
+            );
+        }
+    }
+}
@@ -0,0 +1,227 @@
+use std::collections::BTreeSet;
+
+use invariant_types::{EventCategory, JournalEntry, PromiseId};
+use serde::{Deserialize, Serialize};
+
+/// Declarative selection of journal entries for external consumers.
+///
+/// Every criterion that is set must match (logical AND); the default filter
+/// matches everything. Filters are serializable so consumers can register
+/// them declaratively.
+///
+/// Filtering never renumbers entries: matched entries keep their original
+/// journal sequence, so consumers should expect gaps.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventFilter {
+    /// Only entries whose event falls in one of these categories.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub categories: Option<BTreeSet<EventCategory>>,
+    /// Only entries whose [`EventType::name`](invariant_types::EventType::name)
+    /// is in this set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub names: Option<BTreeSet<String>>,
+    /// Only entries referring to this promise or a descendant of it.
+    /// Entries without a promise (lifecycle, `SignalDelivered`, awaits) never match.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub promise_subtree: Option<PromiseId>,
+    /// Only terminal entries.
+    #[serde(default)]
+    pub terminal_only: bool,
+}
+
+impl EventFilter {
+    /// Filter matching only terminal events.
+    pub fn terminal_only() -> Self {
+        Self {
+            terminal_only: true,
+            ..Self::default()
+        }
+    }
+
+    /// Filter matching events in any of `categories`.
+    pub fn categories(categories: impl IntoIterator<Item = EventCategory>) -> Self {
+        Self {
+            categories: Some(categories.into_iter().collect()),
+            ..Self::default()
+        }
+    }
+
+    /// Filter matching events whose name is in `names`.
+    pub fn names<S: Into<String>>(names: impl IntoIterator<Item = S>) -> Self {
+        Self {
+            names: Some(names.into_iter().map(Into::into).collect()),
+            ..Self::default()
+        }
+    }
+
+    /// Filter matching events referring to `root` or any promise below it.
+    pub fn promise_subtree(root: PromiseId) -> Self {
+        Self {
+            promise_subtree: Some(root),
+            ..Self::default()
+        }
+    }
+
+    /// Whether `entry` passes every criterion of this filter.
+    pub fn matches(&self, entry: &JournalEntry) -> bool {
+        let event = &entry.event;
+        if self.terminal_only && !event.is_terminal() {
+            return false;
+        }
+        if let Some(categories) = &self.categories
+            && !categories.contains(&event.category())
+        {
+            return false;
+        }
+        if let Some(names) = &self.names
+            && !names.contains(event.name())
+        {
+            return false;
+        }
+        if let Some(root) = &self.promise_subtree {
+            match event.promise_id() {
+                Some(pid) if pid == root || root.is_ancestor_of(pid) => {}
+                _ => return false,
+            }
+        }
+        true
+    }
+
+    /// Iterate the entries of `entries` that match, keeping original sequences.
+    pub fn apply<'a>(
+        &'a self,
+        entries: &'a [JournalEntry],
+    ) -> impl Iterator<Item = &'a JournalEntry> + 'a {
+        entries.iter().filter(|e| self.matches(e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use invariant_types::{Codec, EventType, Payload};
+
+    use super::*;
+
+    fn payload() -> Payload {
+        Payload::new(vec![], Codec::Json)
+    }
+
+    fn entry(sequence: u64, event: EventType) -> JournalEntry {
+        JournalEntry {
+            sequence,
+            timestamp: Utc::now(),
+            event,
+        }
+    }
+
+    fn journal(root: &PromiseId) -> Vec<JournalEntry> {
+        let p = root.child(0).unwrap();
+        vec![
+            entry(
+                0,
+                EventType::ExecutionStarted {
+                    component_digest: vec![1],
+                    input: payload(),
+                    parent_id: None,
+                    idempotency_key: "k".into(),
+                },
+            ),
+            entry(
+                1,
+                EventType::SignalDelivered {
+                    signal_name: "approval".into(),
+                    payload: payload(),
+                    delivery_id: 0,
+                    external_id: None,
+                },
+            ),
+            entry(
+                2,
+                EventType::TimeRecorded {
+                    promise_id: p.clone(),
+                    time: Utc::now(),
+                },
+            ),
+            entry(
+                3,
+                EventType::SignalReceived {
+                    promise_id: root.child(1).unwrap(),
+                    signal_name: "approval".into(),
+                    payload: payload(),
+                    delivery_id: 0,
+                },
+            ),
+            entry(4, EventType::ExecutionCompleted { result: payload() }),
+        ]
+    }
+
+    fn sequences<'a>(it: impl Iterator<Item = &'a JournalEntry>) -> Vec<u64> {
+        it.map(|e| e.sequence).collect()
+    }
+
+    #[test]
+    fn default_filter_matches_everything() {
+        let entries = journal(&PromiseId::new([1; 32]));
+        assert_eq!(
+            sequences(EventFilter::default().apply(&entries)),
+            vec![0, 1, 2, 3, 4]
+        );
+    }
+
+    #[test]
+    fn name_filter_keeps_original_sequences() {
+        let entries = journal(&PromiseId::new([1; 32]));
+        let filter = EventFilter::names(["SignalDelivered", "SignalReceived"]);
+        assert_eq!(sequences(filter.apply(&entries)), vec![1, 3]);
+    }
+
+    #[test]
+    fn category_filter_selects_by_schema_group() {
+        let entries = journal(&PromiseId::new([1; 32]));
+        let filter = EventFilter::categories([EventCategory::Nondeterminism]);
+        assert_eq!(sequences(filter.apply(&entries)), vec![2]);
+    }
+
+    #[test]
+    fn promise_subtree_filter_includes_descendants_only() {
+        let root = PromiseId::new([1; 32]);
+        let entries = journal(&root);
+        let filter = EventFilter::promise_subtree(root.child(0).unwrap());
+        assert_eq!(sequences(filter.apply(&entries)), vec![2]);
+        let filter = EventFilter::promise_subtree(root);
+        assert_eq!(sequences(filter.apply(&entries)), vec![2, 3]);
+    }
+
+    #[test]
+    fn terminal_only_on_terminal_journal_yields_exactly_one_entry() {
+        let entries = journal(&PromiseId::new([1; 32]));
+        assert_eq!(
+            sequences(EventFilter::terminal_only().apply(&entries)),
+            vec![4]
+        );
+    }
+
+    #[test]
+    fn criteria_combine_with_and() {
+        let entries = journal(&PromiseId::new([1; 32]));
+        let filter = EventFilter {
+            terminal_only: true,
+            ..EventFilter::categories([EventCategory::ControlFlow])
+        };
+        assert_eq!(filter.apply(&entries).count(), 0);
+    }
+
+    #[test]
+    fn filter_round_trips_through_json() {
+        let filter = EventFilter {
+            names: Some(["SignalDelivered".to_string()].into()),
+            ..EventFilter::promise_subtree(PromiseId::new([1; 32]))
+        };
+        let json = serde_json::to_string(&filter).unwrap();
+        let back: EventFilter = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, filter);
+        let empty: EventFilter = serde_json::from_str("{}").unwrap();
+        assert_eq!(empty, EventFilter::default());
+    }
+}
@@ -0,0 +1,183 @@
+//! Liveness heartbeats from long-running invokes.
+//!
+//! A worker running an attempt reports progress through a [`HeartbeatSink`],
+//! which journals it as `InvokeHeartbeat` at most once per interval per
+//! promise. SE-5 only accepts heartbeats for in-flight attempts; replay and
+//! status ignore them, so journals without heartbeats behave as before.
+//!
+//! [`diagnostics::pending_invokes`](crate::diagnostics::pending_invokes)
+//! reports how long each in-flight attempt has been silent.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use invariant_types::{JournalEntry, Payload, PromiseId};
+
+use crate::command::Command;
+use crate::error::JournalError;
+use crate::state::ExecutionState;
+
+/// Rate-limited writer of `InvokeHeartbeat` entries.
+///
+/// Handed to the code running an attempt. Heartbeats arriving faster than
+/// `min_interval` for the same attempt are dropped rather than journaled,
+/// so a chatty worker cannot flood the journal.
+#[derive(Clone, Debug)]
+pub struct HeartbeatSink {
+    min_interval: Duration,
+    /// Append time of the last journaled heartbeat, per promise, with the
+    /// attempt it was for.
+    last: HashMap<PromiseId, (u32, DateTime<Utc>)>,
+}
+
+impl HeartbeatSink {
+    pub fn new(min_interval: Duration) -> Self {
+        Self {
+            min_interval,
+            last: HashMap::new(),
+        }
+    }
+
+    /// Journal a heartbeat for `attempt` of `promise_id`, unless one was
+    /// journaled for that attempt less than `min_interval` before `now`.
+    ///
+    /// `at` is the worker's clock, recorded as-is; `now` is the append time
+    /// the interval is measured on. Returns the appended entry, or `None`
+    /// when the heartbeat was dropped.
+    ///
+    /// # Errors
+    ///
+    /// As [`ExecutionState::handle`]; SE-5 rejects a heartbeat for an
+    /// attempt that is not in flight. Nothing is recorded on error.
+    pub fn beat(
+        &mut self,
+        state: &mut ExecutionState,
+        promise_id: PromiseId,
+        attempt: u32,
+        at: DateTime<Utc>,
+        progress: Option<Payload>,
+        now: DateTime<Utc>,
+    ) -> Result<Option<JournalEntry>, JournalError> {
+        if let Some(&(last_attempt, last_at)) = self.last.get(&promise_id)
+            && last_attempt == attempt
+            && (now - last_at)
+                .to_std()
+                .map_or(true, |elapsed| elapsed < self.min_interval)
+        {
+            return Ok(None);
+        }
+        let result = state.handle(
+            Command::RecordHeartbeat {
+                promise_id: promise_id.clone(),
+                attempt,
+                at,
+                progress,
+            },
+            now,
+        )?;
+        self.last.insert(promise_id, (attempt, now));
+        Ok(Some(result.entry))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::JournalViolation;
+    use chrono::TimeDelta;
+    use invariant_types::{Codec, EventType, InvokeKind};
+
+    fn payload() -> Payload {
+        Payload::new(vec![], Codec::Json)
+    }
+
+    /// An execution with one scheduled and started invoke.
+    fn running(now: DateTime<Utc>) -> (ExecutionState, PromiseId) {
+        let mut state = ExecutionState::new(vec![1], payload(), None, "k".into(), now).unwrap();
+        let pid = state
+            .handle(
+                Command::ScheduleInvoke {
+                    kind: InvokeKind::Function,
+                    function_name: "slow".into(),
+                    input: payload(),
+                    retry_policy: None,
+                    sla: None,
+                },
+                now,
+            )
+            .unwrap()
+            .allocated_id
+            .unwrap();
+        state
+            .handle(
+                Command::StartInvoke {
+                    promise_id: pid.clone(),
+                    attempt: 1,
+                },
+                now,
+            )
+            .unwrap();
+        (state, pid)
+    }
+
+    #[test]
+    fn heartbeats_within_the_interval_are_dropped() {
+        let t0 = DateTime::UNIX_EPOCH;
+        let (mut state, pid) = running(t0);
+        let mut sink = HeartbeatSink::new(Duration::from_secs(30));
+        let mut beat = |state: &mut ExecutionState, attempt, secs| {
+            let now = t0 + TimeDelta::seconds(secs);
+            sink.beat(state, pid.clone(), attempt, now, None, now)
+                .unwrap()
+                .is_some()
+        };
+
+        assert!(beat(&mut state, 1, 0));
+        assert!(!beat(&mut state, 1, 10));
+        assert!(!beat(&mut state, 1, 29));
+        assert!(beat(&mut state, 1, 30));
+        // A clock step backwards does not bypass the limit.
+        assert!(!beat(&mut state, 1, 5));
+
+        state
+            .handle(
+                Command::StartInvoke {
+                    promise_id: pid.clone(),
+                    attempt: 2,
+                },
+                t0,
+            )
+            .unwrap();
+        // A new attempt is not limited by the previous one's heartbeats.
+        assert!(beat(&mut state, 2, 31));
+
+        let heartbeats = state
+            .journal()
+            .iter()
+            .filter(|e| matches!(e.event, EventType::InvokeHeartbeat { .. }))
+            .count();
+        assert_eq!(heartbeats, 3);
+    }
+
+    #[test]
+    fn rejected_heartbeat_does_not_start_the_interval() {
+        let now = DateTime::UNIX_EPOCH;
+        let (mut state, pid) = running(now);
+        let mut sink = HeartbeatSink::new(Duration::from_secs(30));
+
+        let err = sink
+            .beat(&mut state, pid.clone(), 2, now, None, now)
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            JournalError::InvariantViolation(v)
+                if matches!(*v, JournalViolation::HeartbeatNotInFlight { attempt: 2, .. })
+        ));
+        assert!(
+            sink.beat(&mut state, pid, 1, now, None, now)
+                .unwrap()
+                .is_some()
+        );
+    }
+}
@@ -0,0 +1,416 @@
+//! Which events an [`InvariantState`] would accept next.
+//!
+//! Replay engines and SDKs ask this before producing an entry, so they can
+//! fail with a precise message instead of an invariant violation after the
+//! fact. Every answer is derived from the same state the checks read and
+//! mirrors their conditions exactly: an admissible event passes
+//! [`InvariantState::check_append`] and an inadmissible one is rejected.
+//!
+//! Events whose only constraints are on their own contents (a new
+//! `InvokeScheduled`, `TimerScheduled`, `JoinSetCreated`) are admissible whenever the journal
+//! [`is_open`](AdmissibleEvents::is_open).
+
+use super::InvariantState;
+use invariant_types::{JoinSetId, Payload, PromiseId, SignalDeliveryId, SignalName};
+
+/// Read-only summary of the events an [`InvariantState`] admits next.
+///
+/// Obtained from [`InvariantState::admissible_events`]; borrows the state
+/// it summarizes. Sequence numbering (S-1) is assumed: the event is
+/// appended at the next position.
+#[derive(Clone, Copy, Debug)]
+pub struct AdmissibleEvents<'a> {
+    state: &'a InvariantState,
+}
+
+impl InvariantState {
+    /// Summarize which events this state would accept next.
+    pub fn admissible_events(&self) -> AdmissibleEvents<'_> {
+        AdmissibleEvents { state: self }
+    }
+}
+
+impl<'a> AdmissibleEvents<'a> {
+    /// Whether the journal accepts another entry at all: it has started
+    /// (S-2) and has no terminal event (S-3/S-4).
+    ///
+    /// An empty journal admits only `ExecutionStarted`.
+    pub fn is_open(&self) -> bool {
+        self.state.len > 0 && self.state.terminal_seq.is_none()
+    }
+
+    /// Whether `ExecutionCompleted`, `ExecutionFailed` or `ContinuedAsNew`
+    /// is admissible.
+    pub fn terminal(&self) -> bool {
+        self.is_open()
+    }
+
+    /// Whether `ExecutionCancelled` is admissible (S-5).
+    pub fn cancelled(&self) -> bool {
+        self.is_open() && self.state.has_cancel_requested
+    }
+
+    /// Whether `ComponentUpgraded` from `old_digest` is admissible (S-7).
+    pub fn component_upgraded(&self, old_digest: &[u8]) -> bool {
+        self.is_open() && self.state.component_digest.as_deref() == Some(old_digest)
+    }
+
+    /// Whether `InvokeStarted` for `promise_id` is admissible, for any
+    /// attempt (SE-1, SE-4).
+    pub fn invoke_started(&self, promise_id: &PromiseId) -> bool {
+        self.is_open()
+            && self.state.is_scheduled(promise_id)
+            && !self.state.is_completed(promise_id)
+    }
+
+    /// Whether `InvokeCompleted` for `promise_id` is admissible, for any
+    /// attempt (SE-2, SE-4).
+    pub fn invoke_completed(&self, promise_id: &PromiseId) -> bool {
+        self.is_open() && self.state.is_started(promise_id) && !self.state.is_completed(promise_id)
+    }
+
+    /// Whether `InvokeRetrying` for `failed_attempt` of `promise_id` is
+    /// admissible (SE-3, SE-4).
+    pub fn invoke_retrying(&self, promise_id: &PromiseId, failed_attempt: u32) -> bool {
+        self.is_open()
+            && !self.state.is_completed(promise_id)
+            && self
+                .state
+                .started_attempts
+                .contains(&(promise_id.clone(), failed_attempt))
+    }
+
+    /// Whether `InvokeHeartbeat` for `attempt` of `promise_id` is
+    /// admissible: the attempt is in flight (SE-5).
+    pub fn invoke_heartbeat(&self, promise_id: &PromiseId, attempt: u32) -> bool {
+        let key = (promise_id.clone(), attempt);
+        self.is_open()
+            && self.state.started_attempts.contains(&key)
+            && !self.state.retried_attempts.contains(&key)
+            && !self.state.is_completed(promise_id)
+    }
+
+    /// Promises that admit `InvokeCompleted`: started and not completed.
+    pub fn completable_invokes(&self) -> impl Iterator<Item = &'a PromiseId> + 'a {
+        let state = self.state;
+        let open = self.is_open();
+        state
+            .started_pids
+            .iter()
+            .filter(move |pid| open && !state.is_completed(pid))
+    }
+
+    /// Whether `TimerFired` for `promise_id` is admissible (CF-1).
+    pub fn timer_fired(&self, promise_id: &PromiseId) -> bool {
+        self.is_open() && self.state.scheduled_timer_pids.contains(promise_id)
+    }
+
+    /// Whether `join_set_id` accepts submissions: created and not yet
+    /// awaited (JS-1, JS-2).
+    pub fn accepts_submissions(&self, join_set_id: &JoinSetId) -> bool {
+        self.is_open()
+            && self.state.created_joinsets.contains(join_set_id)
+            && !self.state.awaited_joinsets.contains(join_set_id)
+    }
+
+    /// Join sets that accept submissions.
+    pub fn open_join_sets(&self) -> impl Iterator<Item = &'a JoinSetId> + 'a {
+        let admissible = *self;
+        self.state
+            .created_joinsets
+            .iter()
+            .filter(move |js| admissible.accepts_submissions(js))
+    }
+
+    /// Whether `JoinSetSubmitted` of `promise_id` to `join_set_id` is
+    /// admissible (JS-1, JS-2, JS-7, JS-8).
+    pub fn join_set_submitted(&self, join_set_id: &JoinSetId, promise_id: &PromiseId) -> bool {
+        self.accepts_submissions(join_set_id)
+            && self
+                .state
+                .owner_of(promise_id)
+                .is_none_or(|owner| owner == join_set_id)
+            && join_set_id
+                .parent()
+                .is_none_or(|scope| scope.is_ancestor_of(promise_id))
+    }
+
+    /// Whether `JoinSetAwaited` of `promise_id` from `join_set_id` is
+    /// admissible: a completed, unconsumed member (JS-3..JS-6).
+    pub fn join_set_awaited(&self, join_set_id: &JoinSetId, promise_id: &PromiseId) -> bool {
+        let (submitted, awaited) = self
+            .state
+            .joinset_counts
+            .get(join_set_id)
+            .copied()
+            .unwrap_or((0, 0));
+        self.is_open()
+            && self.state.is_submitted(join_set_id, promise_id)
+            && self.state.is_completed(promise_id)
+            && !self.state.is_consumed(join_set_id, promise_id)
+            && awaited.saturating_add(1) <= submitted
+    }
+
+    /// The promise a `SignalReceived` must resolve while the execution is
+    /// blocked on a signal (CF-5). `None` when any promise may receive one.
+    pub fn signal_receiver(&self) -> Option<&'a PromiseId> {
+        self.state
+            .signal_await
+            .as_ref()
+            .map(|awaited| &awaited.promise_id)
+    }
+
+    /// Deliveries a `SignalReceived` may consume, with the payload it must
+    /// carry (CF-2, CF-3), sorted by name then delivery ID.
+    pub fn receivable_signals(&self) -> Vec<(&'a str, SignalDeliveryId, &'a Payload)> {
+        if !self.is_open() {
+            return Vec::new();
+        }
+        self.state.pending_signal_deliveries()
+    }
+
+    /// Whether `SignalReceived` of delivery `(signal_name, delivery_id)`
+    /// with `payload`, resolving `promise_id`, is admissible (CF-2, CF-3,
+    /// CF-5).
+    pub fn signal_received(
+        &self,
+        signal_name: &SignalName,
+        delivery_id: SignalDeliveryId,
+        payload: &Payload,
+        promise_id: &PromiseId,
+    ) -> bool {
+        let key = (signal_name.to_string(), delivery_id);
+        self.is_open()
+            && self.state.is_signal_buffered(&key)
+            && self.state.delivered_signals.get(&key) == Some(payload)
+            && self
+                .signal_receiver()
+                .is_none_or(|awaited| awaited == promise_id)
+            && self
+                .state
+                .receives_next_delivery(signal_name.as_str(), delivery_id)
+    }
+
+    /// Whether `SignalDeliveryExpired` of `(signal_name, delivery_id)` is
+    /// admissible: the delivery is still buffered (CF-8).
+    pub fn signal_expired(&self, signal_name: &SignalName, delivery_id: SignalDeliveryId) -> bool {
+        self.is_open()
+            && self
+                .state
+                .is_signal_buffered(&(signal_name.to_string(), delivery_id))
+    }
+
+    /// Whether `SignalDelivered` of `signal_name` as `delivery_id`, carrying
+    /// `external_id`, is admissible: the key is new (CF-9) and so is the
+    /// producer's message ID (CF-7).
+    pub fn signal_delivered(
+        &self,
+        signal_name: &SignalName,
+        delivery_id: SignalDeliveryId,
+        external_id: Option<&str>,
+    ) -> bool {
+        self.is_open()
+            && !self
+                .state
+                .delivered_signals
+                .contains_key(&(signal_name.to_string(), delivery_id))
+            && external_id.is_none_or(|id| {
+                !self
+                    .state
+                    .external_deliveries
+                    .contains(&(signal_name.to_string(), id.to_string()))
+            })
+    }
+
+    /// Whether the execution is blocked, so `AwaitProgress` may be
+    /// recorded (CF-6). The event must still list only resolved members and
+    /// count the unresolved ones.
+    pub fn is_blocked(&self) -> bool {
+        self.is_open() && self.state.active_await.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use chrono::DateTime;
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::testing::arb_valid_journal;
+    use invariant_types::{Codec, EventType, ExecutionError, JournalEntry};
+
+    /// Whether `state` accepts `event` as its next entry.
+    fn accepts(state: &InvariantState, event: EventType) -> bool {
+        let entry = JournalEntry {
+            sequence: state.len as u64,
+            timestamp: DateTime::UNIX_EPOCH,
+            event,
+        };
+        state.clone().check_append(&entry).is_ok()
+    }
+
+    proptest! {
+        #[test]
+        fn admissible_answers_match_check_append(
+            j in arb_valid_journal(48),
+            split in any::<prop::sample::Index>(),
+        ) {
+            let at = split.index(j.entries.len() + 1);
+            let mut state = InvariantState::new();
+            for entry in &j.entries[..at] {
+                state.check_append(entry).unwrap();
+            }
+            let admissible = state.admissible_events();
+
+            // Every identifier the journal ever mentions, plus one it never
+            // does, as candidates for the next entry.
+            let mut pids = HashSet::from([PromiseId::new([0xee; 32])]);
+            let mut join_sets = HashSet::new();
+            let mut deliveries = Vec::new();
+            for entry in &j.entries {
+                match &entry.event {
+                    EventType::InvokeScheduled { promise_id, .. }
+                    | EventType::TimerScheduled { promise_id, .. }
+                    | EventType::SignalReceived { promise_id, .. } => {
+                        pids.insert(promise_id.clone());
+                    }
+                    EventType::JoinSetCreated { join_set_id } => {
+                        join_sets.insert(join_set_id.clone());
+                    }
+                    EventType::SignalDelivered {
+                        signal_name,
+                        payload,
+                        delivery_id,
+                        ..
+                    } => deliveries.push((
+                        SignalName::new(signal_name.as_str()).unwrap(),
+                        *delivery_id,
+                        payload.clone(),
+                    )),
+                    _ => {}
+                }
+            }
+            let other = Payload::new(b"other".to_vec(), Codec::Json);
+
+            let terminal = EventType::ExecutionFailed {
+                error: ExecutionError::new(invariant_types::ErrorKind::Uncategorized, "boom"),
+            };
+            prop_assert_eq!(admissible.terminal(), accepts(&state, terminal));
+            prop_assert_eq!(
+                admissible.cancelled(),
+                accepts(&state, EventType::ExecutionCancelled { reason: "stop".into() })
+            );
+
+            for pid in &pids {
+                prop_assert_eq!(
+                    admissible.invoke_started(pid),
+                    accepts(&state, EventType::InvokeStarted { promise_id: pid.clone(), attempt: 9 })
+                );
+                prop_assert_eq!(
+                    admissible.invoke_completed(pid),
+                    accepts(&state, EventType::InvokeCompleted {
+                        promise_id: pid.clone(),
+                        result: other.clone(),
+                        attempt: 1,
+                    })
+                );
+                prop_assert_eq!(
+                    admissible.completable_invokes().any(|p| p == pid),
+                    admissible.invoke_completed(pid)
+                );
+                for attempt in 1..=3 {
+                    prop_assert_eq!(
+                        admissible.invoke_retrying(pid, attempt),
+                        accepts(&state, EventType::InvokeRetrying {
+                            promise_id: pid.clone(),
+                            failed_attempt: attempt,
+                            error: ExecutionError::new(
+                                invariant_types::ErrorKind::Uncategorized,
+                                "flaky",
+                            ),
+                            retry_at: DateTime::UNIX_EPOCH,
+                        })
+                    );
+                    prop_assert_eq!(
+                        admissible.invoke_heartbeat(pid, attempt),
+                        accepts(&state, EventType::InvokeHeartbeat {
+                            promise_id: pid.clone(),
+                            attempt,
+                            at: DateTime::UNIX_EPOCH,
+                            progress: None,
+                        })
+                    );
+                }
+                prop_assert_eq!(
+                    admissible.timer_fired(pid),
+                    accepts(&state, EventType::TimerFired { promise_id: pid.clone() })
+                );
+                for js in &join_sets {
+                    prop_assert_eq!(
+                        admissible.join_set_submitted(js, pid),
+                        accepts(&state, EventType::JoinSetSubmitted {
+                            join_set_id: js.clone(),
+                            promise_id: pid.clone(),
+                        })
+                    );
+                    prop_assert_eq!(
+                        admissible.join_set_awaited(js, pid),
+                        accepts(&state, EventType::JoinSetAwaited {
+                            join_set_id: js.clone(),
+                            promise_id: pid.clone(),
+                            result: other.clone(),
+                        })
+                    );
+                }
+                for (name, delivery_id, payload) in &deliveries {
+                    for payload in [payload, &other] {
+                        prop_assert_eq!(
+                            admissible.signal_received(name, *delivery_id, payload, pid),
+                            accepts(&state, EventType::SignalReceived {
+                                promise_id: pid.clone(),
+                                signal_name: name.to_string(),
+                                payload: payload.clone(),
+                                delivery_id: *delivery_id,
+                            })
+                        );
+                    }
+                }
+            }
+            for js in &join_sets {
+                prop_assert_eq!(
+                    admissible.open_join_sets().any(|open| open == js),
+                    admissible.accepts_submissions(js)
+                );
+            }
+            for (name, delivery_id, payload) in &deliveries {
+                for delivery_id in [*delivery_id, SignalDeliveryId::MAX] {
+                    prop_assert_eq!(
+                        admissible.signal_delivered(name, delivery_id, None),
+                        accepts(&state, EventType::SignalDelivered {
+                            signal_name: name.to_string(),
+                            payload: payload.clone(),
+                            delivery_id,
+                            external_id: None,
+                        })
+                    );
+                }
+                prop_assert_eq!(
+                    admissible.signal_expired(name, *delivery_id),
+                    accepts(&state, EventType::SignalDeliveryExpired {
+                        signal_name: name.to_string(),
+                        delivery_id: *delivery_id,
+                    })
+                );
+                prop_assert_eq!(
+                    admissible
+                        .receivable_signals()
+                        .iter()
+                        .any(|(n, id, _)| *n == name.as_str() && id == delivery_id),
+                    admissible.signal_expired(name, *delivery_id)
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,189 @@
+//! Batch validation that can be stopped part-way.
+//!
+//! Request handlers validating untrusted journals under a time budget pass a
+//! [`CancelToken`] to [`validate_journal_cancellable`]. The scan polls it
+//! every [`CANCEL_CHECK_INTERVAL`] entries and, once it fires, returns what
+//! it found in the entries scanned so far.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
+
+use super::{ValidationOptions, scan_report};
+use crate::error::JournalViolation;
+use invariant_types::ExecutionJournal;
+
+/// Entries scanned between polls of the [`CancelToken`].
+pub const CANCEL_CHECK_INTERVAL: usize = 1024;
+
+/// Cooperative cancellation flag, optionally with a deadline.
+///
+/// Clones share the flag, so one clone can cancel a scan running on
+/// another. The deadline is per clone.
+#[derive(Clone, Debug, Default)]
+pub struct CancelToken {
+    cancelled: Arc<AtomicBool>,
+    deadline: Option<Instant>,
+}
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Also treat the token as cancelled from `deadline` on.
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Cancel every scan holding a clone of this token.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether [`cancel`](Self::cancel) was called or the deadline passed.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+            || self
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline)
+    }
+}
+
+/// Outcome of [`validate_journal_cancellable`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CancellableResult {
+    /// Violations in the first `entries_scanned` entries.
+    pub violations: Vec<JournalViolation>,
+    pub entries_scanned: usize,
+    /// Whether the scan stopped before the end of the journal.
+    pub cancelled: bool,
+}
+
+/// [`validate_journal`](super::validate_journal), stopping once `cancel` fires.
+///
+/// Until the token fires the result matches `validate_journal` exactly, with
+/// `entries_scanned` equal to the journal length. A cancelled scan stops on
+/// a multiple of [`CANCEL_CHECK_INTERVAL`] and reports nothing about later
+/// entries.
+pub fn validate_journal_cancellable(
+    journal: &ExecutionJournal,
+    cancel: &CancelToken,
+) -> CancellableResult {
+    let (report, entries_scanned) =
+        scan_report(journal, &ValidationOptions::default(), Some(cancel));
+    CancellableResult {
+        violations: report.violations,
+        entries_scanned,
+        cancelled: entries_scanned < journal.entries.len(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use super::*;
+    use crate::invariants::validate_journal;
+    use chrono::DateTime;
+    use invariant_types::{Codec, EventType, ExecutionId, JournalEntry, Payload};
+
+    fn exec_id() -> ExecutionId {
+        ExecutionId::derive(&[1], "k", None)
+    }
+
+    /// `ExecutionStarted`, then `n` starts of invokes never scheduled: one
+    /// SE-1 violation per entry.
+    fn noisy(n: u32) -> ExecutionJournal {
+        let started = EventType::ExecutionStarted {
+            component_digest: vec![1],
+            input: Payload::new(vec![], Codec::Json),
+            parent_id: None,
+            idempotency_key: "k".into(),
+        };
+        let events = std::iter::once(started).chain((0..n).map(|seq| EventType::InvokeStarted {
+            promise_id: exec_id().child(seq).unwrap(),
+            attempt: 1,
+        }));
+        ExecutionJournal {
+            execution_id: exec_id(),
+            entries: events
+                .enumerate()
+                .map(|(i, event)| JournalEntry {
+                    sequence: i as u64,
+                    timestamp: DateTime::UNIX_EPOCH,
+                    event,
+                })
+                .collect(),
+            provenance: None,
+        }
+    }
+
+    #[test]
+    fn untriggered_token_matches_validate_journal() {
+        let mut clean = noisy(0);
+        clean.entries.push(JournalEntry {
+            sequence: 1,
+            timestamp: DateTime::UNIX_EPOCH,
+            event: EventType::ExecutionResumed,
+        });
+        let mut wrong_id = noisy(3);
+        wrong_id.execution_id = ExecutionId::derive(&[2], "k", None);
+        let empty = ExecutionJournal {
+            entries: vec![],
+            ..noisy(0)
+        };
+
+        for journal in [
+            clean,
+            wrong_id,
+            empty,
+            noisy(3 * CANCEL_CHECK_INTERVAL as u32),
+        ] {
+            let result = validate_journal_cancellable(&journal, &CancelToken::new());
+            assert_eq!(result.violations, validate_journal(&journal));
+            assert_eq!(result.entries_scanned, journal.entries.len());
+            assert!(!result.cancelled);
+        }
+    }
+
+    #[test]
+    fn expired_deadline_stops_before_the_first_entry() {
+        let token = CancelToken::new().with_deadline(Instant::now());
+        let result = validate_journal_cancellable(&noisy(10), &token);
+        assert_eq!(
+            result,
+            CancellableResult {
+                violations: vec![],
+                entries_scanned: 0,
+                cancelled: true,
+            }
+        );
+    }
+
+    #[test]
+    fn cancelled_scan_reports_nothing_past_the_entries_scanned() {
+        let journal = noisy(200_000);
+        let token = CancelToken::new();
+        let canceller = token.clone();
+        let result = thread::scope(|s| {
+            let scan = s.spawn(|| validate_journal_cancellable(&journal, &token));
+            canceller.cancel();
+            scan.join().unwrap()
+        });
+
+        assert!(result.cancelled);
+        assert_eq!(result.entries_scanned % CANCEL_CHECK_INTERVAL, 0);
+        // Entry 0 is clean; every later scanned entry reports exactly once.
+        assert_eq!(
+            result.violations.len(),
+            result.entries_scanned.saturating_sub(1)
+        );
+        assert!(result.violations.iter().all(|v| matches!(
+            v,
+            JournalViolation::StartedWithoutScheduled { started_seq, .. }
+                if (*started_seq as usize) < result.entries_scanned
+        )));
+    }
+}
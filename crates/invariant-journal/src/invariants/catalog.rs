@@ -0,0 +1,752 @@
+//! Static catalog of every invariant the journal validator enforces.
+//!
+//! The catalog is the single list that maps invariant IDs to their group and
+//! the [`JournalViolation`](crate::error::JournalViolation) variant they
+//! produce. Codes, explanations and strictness profiles should key off it
+//! rather than keeping their own lists.
+
+/// Which checker sub-module enforces an invariant.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum InvariantGroup {
+    Structural,
+    SideEffects,
+    ControlFlow,
+    JoinSet,
+}
+
+/// One row of the invariant catalog.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InvariantInfo {
+    /// Invariant ID (e.g. `"SE-2"`). `None` for representation guards that
+    /// have no spec counterpart.
+    pub id: Option<&'static str>,
+    pub group: InvariantGroup,
+    /// [`JournalViolation::name`](crate::error::JournalViolation::name) of the
+    /// variant produced when the invariant fails.
+    pub violation: &'static str,
+    /// Only checked by [`ExecutionState::recover`](crate::state::ExecutionState::recover),
+    /// not by [`validate_journal`](super::validate_journal).
+    pub recovery_only: bool,
+    pub description: &'static str,
+}
+
+const fn row(
+    id: Option<&'static str>,
+    group: InvariantGroup,
+    violation: &'static str,
+    description: &'static str,
+) -> InvariantInfo {
+    InvariantInfo {
+        id,
+        group,
+        violation,
+        recovery_only: false,
+        description,
+    }
+}
+
+static CATALOG: &[InvariantInfo] = &[
+    row(
+        Some("S-1"),
+        InvariantGroup::Structural,
+        "NonMonotonicSequence",
+        "Sequence numbers equal their 0-based array index.",
+    ),
+    row(
+        Some("S-2"),
+        InvariantGroup::Structural,
+        "MissingExecutionStarted",
+        "The first event is ExecutionStarted.",
+    ),
+    row(
+        Some("S-3"),
+        InvariantGroup::Structural,
+        "MultipleTerminalEvents",
+        "At most one terminal event per journal.",
+    ),
+    row(
+        Some("S-4"),
+        InvariantGroup::Structural,
+        "TerminalNotLast",
+        "A terminal event is the last entry.",
+    ),
+    row(
+        Some("S-5"),
+        InvariantGroup::Structural,
+        "CancelledWithoutRequest",
+        "ExecutionCancelled requires a preceding CancelRequested.",
+    ),
+    InvariantInfo {
+        recovery_only: true,
+        ..row(
+            Some("S-6"),
+            InvariantGroup::Structural,
+            "AllocatedChildMismatch",
+            "Allocated child IDs match deterministic derivation from the execution root.",
+        )
+    },
+    row(
+        Some("S-7"),
+        InvariantGroup::Structural,
+        "UpgradeDigestMismatch",
+        "A ComponentUpgraded replaces the component digest currently in effect.",
+    ),
+    row(
+        Some("SE-1"),
+        InvariantGroup::SideEffects,
+        "StartedWithoutScheduled",
+        "InvokeStarted requires a preceding InvokeScheduled.",
+    ),
+    row(
+        Some("SE-2"),
+        InvariantGroup::SideEffects,
+        "CompletedWithoutStarted",
+        "InvokeCompleted requires a preceding InvokeStarted.",
+    ),
+    row(
+        Some("SE-3"),
+        InvariantGroup::SideEffects,
+        "RetryingWithoutStarted",
+        "InvokeRetrying requires a started attempt with the same number.",
+    ),
+    row(
+        Some("SE-4"),
+        InvariantGroup::SideEffects,
+        "EventAfterCompleted",
+        "No Started, Retrying or second Completed after InvokeCompleted.",
+    ),
+    row(
+        Some("SE-5"),
+        InvariantGroup::SideEffects,
+        "HeartbeatNotInFlight",
+        "InvokeHeartbeat names a started attempt not yet retried or completed.",
+    ),
+    row(
+        Some("CF-1"),
+        InvariantGroup::ControlFlow,
+        "TimerFiredWithoutScheduled",
+        "TimerFired requires a preceding TimerScheduled.",
+    ),
+    row(
+        Some("CF-2"),
+        InvariantGroup::ControlFlow,
+        "SignalReceivedWithoutDelivery",
+        "SignalReceived matches a prior delivery by name, id and payload.",
+    ),
+    row(
+        Some("CF-3"),
+        InvariantGroup::ControlFlow,
+        "SignalConsumedTwice",
+        "Each signal delivery is consumed at most once.",
+    ),
+    row(
+        Some("CF-4"),
+        InvariantGroup::ControlFlow,
+        "AwaitSignalInconsistent",
+        "A signal await waits on exactly its own promise.",
+    ),
+    row(
+        Some("CF-5"),
+        InvariantGroup::ControlFlow,
+        "SignalReceivedForWrongPromise",
+        "SignalReceived resolves the outstanding signal await, if any.",
+    ),
+    row(
+        Some("CF-6"),
+        InvariantGroup::ControlFlow,
+        "ProgressInconsistent",
+        "AwaitProgress appears only while blocked and lists resolved members of the await.",
+    ),
+    row(
+        Some("CF-7"),
+        InvariantGroup::ControlFlow,
+        "DuplicateExternalDelivery",
+        "A producer external_id is delivered at most once per signal name.",
+    ),
+    row(
+        Some("CF-8"),
+        InvariantGroup::ControlFlow,
+        "SignalExpiryInvalid",
+        "SignalDeliveryExpired names a delivered, unconsumed, unexpired delivery.",
+    ),
+    row(
+        Some("CF-9"),
+        InvariantGroup::ControlFlow,
+        "ConflictingSignalDelivery",
+        "Each (signal_name, delivery_id) key is delivered at most once.",
+    ),
+    row(
+        None,
+        InvariantGroup::ControlFlow,
+        "AwaitWaitingOnDuplicate",
+        "ExecutionAwaiting.waiting_on contains no duplicates.",
+    ),
+    row(
+        None,
+        InvariantGroup::ControlFlow,
+        "AnySignalNamesInvalid",
+        "AwaitKind::AnySignal lists at least one signal name, each once.",
+    ),
+    row(
+        None,
+        InvariantGroup::ControlFlow,
+        "AnySignalWrongDelivery",
+        "An AnySignal await is resolved by the lowest pending delivery of its names.",
+    ),
+    row(
+        None,
+        InvariantGroup::ControlFlow,
+        "ProgressRemainingMismatch",
+        "AwaitProgress reports how many members of the await are still unresolved.",
+    ),
+    row(
+        Some("JS-1"),
+        InvariantGroup::JoinSet,
+        "SubmitWithoutCreate",
+        "JoinSetSubmitted requires a preceding JoinSetCreated.",
+    ),
+    row(
+        Some("JS-2"),
+        InvariantGroup::JoinSet,
+        "SubmitAfterAwait",
+        "No submissions after the first JoinSetAwaited.",
+    ),
+    row(
+        Some("JS-3"),
+        InvariantGroup::JoinSet,
+        "AwaitedNotMember",
+        "An awaited promise was submitted to the same set.",
+    ),
+    row(
+        Some("JS-4"),
+        InvariantGroup::JoinSet,
+        "AwaitedNotCompleted",
+        "An awaited promise has completed.",
+    ),
+    row(
+        Some("JS-5"),
+        InvariantGroup::JoinSet,
+        "DoubleConsume",
+        "Each (join set, promise) pair is consumed at most once.",
+    ),
+    row(
+        Some("JS-6"),
+        InvariantGroup::JoinSet,
+        "ConsumeExceedsSubmit",
+        "Awaits per set never exceed submissions.",
+    ),
+    row(
+        Some("JS-7"),
+        InvariantGroup::JoinSet,
+        "PromiseInMultipleJoinSets",
+        "A promise belongs to at most one join set.",
+    ),
+    row(
+        Some("JS-8"),
+        InvariantGroup::JoinSet,
+        "SubmittedOutsideJoinSetTree",
+        "A submitted promise lives under the scope that created its join set.",
+    ),
+];
+
+/// Every invariant enforced by this crate, in check-group order.
+pub fn invariant_catalog() -> &'static [InvariantInfo] {
+    CATALOG
+}
+
+/// [`JournalViolation`](crate::error::JournalViolation) names with no
+/// catalog row, because they report something other than a journal
+/// invariant.
+///
+/// `ExecutionIdMismatch`, `PayloadTooLarge` and `InvokeRejected` are opt-in
+/// [`ValidationOptions`](super::ValidationOptions) guards, and
+/// `SequenceOverflow` is unreachable by any storable journal.
+pub static UNCATALOGUED: &[&str] = &[
+    "ExecutionIdMismatch",
+    "SequenceOverflow",
+    "PayloadTooLarge",
+    "InvokeRejected",
+];
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use chrono::DateTime;
+    use invariant_types::{
+        AwaitKind, Codec, EventType, ExecutionId, ExecutionJournal, InvokeKind, JoinSetId,
+        JournalEntry, Payload, PromiseId,
+    };
+
+    use super::*;
+    use crate::error::{JournalError, JournalViolation};
+    use crate::invariants::validate_journal;
+    use crate::state::ExecutionState;
+
+    const DIGEST: &[u8] = &[1, 2, 3];
+    const KEY: &str = "catalog";
+
+    fn exec_id() -> ExecutionId {
+        ExecutionId::derive(DIGEST, KEY, None)
+    }
+
+    fn child(seq: u32) -> PromiseId {
+        exec_id().child(seq).unwrap()
+    }
+
+    fn js(seq: u32) -> JoinSetId {
+        JoinSetId(child(seq))
+    }
+
+    fn payload() -> Payload {
+        Payload::new(vec![], Codec::Json)
+    }
+
+    fn started() -> EventType {
+        EventType::ExecutionStarted {
+            component_digest: DIGEST.to_vec(),
+            input: payload(),
+            parent_id: None,
+            idempotency_key: KEY.into(),
+        }
+    }
+
+    fn scheduled(p: &PromiseId) -> EventType {
+        EventType::InvokeScheduled {
+            promise_id: p.clone(),
+            kind: InvokeKind::Function,
+            function_name: "f".into(),
+            input: payload(),
+            retry_policy: None,
+            sla: None,
+        }
+    }
+
+    fn invoke_started(p: &PromiseId, attempt: u32) -> EventType {
+        EventType::InvokeStarted {
+            promise_id: p.clone(),
+            attempt,
+        }
+    }
+
+    fn invoke_completed(p: &PromiseId) -> EventType {
+        EventType::InvokeCompleted {
+            promise_id: p.clone(),
+            result: payload(),
+            attempt: 1,
+        }
+    }
+
+    fn delivered() -> EventType {
+        EventType::SignalDelivered {
+            signal_name: "sig".into(),
+            payload: payload(),
+            delivery_id: 0,
+            external_id: None,
+        }
+    }
+
+    fn received(p: &PromiseId) -> EventType {
+        EventType::SignalReceived {
+            promise_id: p.clone(),
+            signal_name: "sig".into(),
+            payload: payload(),
+            delivery_id: 0,
+        }
+    }
+
+    fn submitted(set: &JoinSetId, p: &PromiseId) -> EventType {
+        EventType::JoinSetSubmitted {
+            join_set_id: set.clone(),
+            promise_id: p.clone(),
+        }
+    }
+
+    fn awaited(set: &JoinSetId, p: &PromiseId) -> EventType {
+        EventType::JoinSetAwaited {
+            join_set_id: set.clone(),
+            promise_id: p.clone(),
+            result: payload(),
+        }
+    }
+
+    /// Number events by position to build a journal.
+    fn journal(events: Vec<EventType>) -> ExecutionJournal {
+        ExecutionJournal {
+            execution_id: exec_id(),
+            entries: events
+                .into_iter()
+                .enumerate()
+                .map(|(i, event)| JournalEntry {
+                    sequence: i as u64,
+                    timestamp: DateTime::UNIX_EPOCH,
+                    event,
+                })
+                .collect(),
+            provenance: None,
+        }
+    }
+
+    /// Minimal journal violating the invariant behind `violation`.
+    fn violating_journal(violation: &str) -> ExecutionJournal {
+        let (p, q) = (child(0), child(1));
+        let set = js(2);
+        let complete_p = || vec![scheduled(&p), invoke_started(&p, 1), invoke_completed(&p)];
+        let events = match violation {
+            "NonMonotonicSequence" => {
+                let mut j = journal(vec![started(), EventType::ExecutionResumed]);
+                j.entries[1].sequence = 5;
+                return j;
+            }
+            "MissingExecutionStarted" => vec![EventType::ExecutionResumed],
+            "MultipleTerminalEvents" => vec![
+                started(),
+                EventType::ExecutionCompleted { result: payload() },
+                EventType::ExecutionCompleted { result: payload() },
+            ],
+            "TerminalNotLast" => vec![
+                started(),
+                EventType::ExecutionCompleted { result: payload() },
+                EventType::ExecutionResumed,
+            ],
+            "CancelledWithoutRequest" => vec![
+                started(),
+                EventType::ExecutionCancelled { reason: "x".into() },
+            ],
+            "AllocatedChildMismatch" => vec![started(), scheduled(&child(7))],
+            "UpgradeDigestMismatch" => vec![
+                started(),
+                EventType::ComponentUpgraded {
+                    old_digest: vec![9],
+                    new_digest: vec![2],
+                    approved_by: "ops".into(),
+                },
+            ],
+            "StartedWithoutScheduled" => vec![started(), invoke_started(&p, 1)],
+            "CompletedWithoutStarted" => vec![started(), scheduled(&p), invoke_completed(&p)],
+            "RetryingWithoutStarted" => vec![
+                started(),
+                scheduled(&p),
+                EventType::InvokeRetrying {
+                    promise_id: p.clone(),
+                    failed_attempt: 1,
+                    error: invariant_types::ExecutionError::new(
+                        invariant_types::ErrorKind::Timeout,
+                        "slow",
+                    ),
+                    retry_at: DateTime::UNIX_EPOCH,
+                },
+            ],
+            "EventAfterCompleted" => {
+                let mut events = vec![started()];
+                events.extend(complete_p());
+                events.push(invoke_started(&p, 2));
+                events
+            }
+            "HeartbeatNotInFlight" => {
+                let mut events = vec![started()];
+                events.extend(complete_p());
+                events.push(EventType::InvokeHeartbeat {
+                    promise_id: p.clone(),
+                    attempt: 1,
+                    at: DateTime::UNIX_EPOCH,
+                    progress: None,
+                });
+                events
+            }
+            "TimerFiredWithoutScheduled" => vec![
+                started(),
+                EventType::TimerFired {
+                    promise_id: p.clone(),
+                },
+            ],
+            "SignalReceivedWithoutDelivery" => vec![started(), received(&p)],
+            "SignalConsumedTwice" => vec![started(), delivered(), received(&p), received(&q)],
+            "AwaitSignalInconsistent" => vec![
+                started(),
+                EventType::ExecutionAwaiting {
+                    waiting_on: vec![],
+                    kind: AwaitKind::Signal {
+                        name: "sig".into(),
+                        promise_id: p.clone(),
+                    },
+                },
+            ],
+            "SignalReceivedForWrongPromise" => vec![
+                started(),
+                delivered(),
+                EventType::ExecutionAwaiting {
+                    waiting_on: vec![p.clone()],
+                    kind: AwaitKind::Signal {
+                        name: "sig".into(),
+                        promise_id: p.clone(),
+                    },
+                },
+                received(&q),
+            ],
+            "AnySignalNamesInvalid" => vec![
+                started(),
+                EventType::ExecutionAwaiting {
+                    waiting_on: vec![p.clone()],
+                    kind: AwaitKind::AnySignal {
+                        names: vec![],
+                        promise_id: p.clone(),
+                    },
+                },
+            ],
+            "AnySignalWrongDelivery" => vec![
+                started(),
+                delivered(),
+                EventType::SignalDelivered {
+                    signal_name: "alt".into(),
+                    payload: payload(),
+                    delivery_id: 0,
+                    external_id: None,
+                },
+                EventType::ExecutionAwaiting {
+                    waiting_on: vec![p.clone()],
+                    kind: AwaitKind::AnySignal {
+                        names: vec!["sig".into(), "alt".into()],
+                        promise_id: p.clone(),
+                    },
+                },
+                received(&p),
+            ],
+            "AwaitWaitingOnDuplicate" => vec![
+                started(),
+                EventType::ExecutionAwaiting {
+                    waiting_on: vec![p.clone(), p.clone()],
+                    kind: AwaitKind::Any,
+                },
+            ],
+            "ProgressInconsistent" => vec![
+                started(),
+                EventType::AwaitProgress {
+                    waiting_on_remaining: 0,
+                    resolved_since_await: vec![],
+                },
+            ],
+            "ProgressRemainingMismatch" => vec![
+                started(),
+                scheduled(&p),
+                EventType::ExecutionAwaiting {
+                    waiting_on: vec![p.clone()],
+                    kind: AwaitKind::All,
+                },
+                EventType::AwaitProgress {
+                    waiting_on_remaining: 0,
+                    resolved_since_await: vec![],
+                },
+            ],
+            "DuplicateExternalDelivery" => {
+                let with_external = |delivery_id| EventType::SignalDelivered {
+                    signal_name: "sig".into(),
+                    payload: payload(),
+                    delivery_id,
+                    external_id: Some("msg-1".into()),
+                };
+                vec![started(), with_external(0), with_external(1)]
+            }
+            "SignalExpiryInvalid" => vec![
+                started(),
+                delivered(),
+                received(&p),
+                EventType::SignalDeliveryExpired {
+                    signal_name: "sig".into(),
+                    delivery_id: 0,
+                },
+            ],
+            "ConflictingSignalDelivery" => vec![started(), delivered(), delivered()],
+            "SubmitWithoutCreate" => vec![started(), submitted(&set, &p)],
+            "SubmitAfterAwait" => {
+                let mut events = vec![started()];
+                events.extend(complete_p());
+                events.extend([
+                    EventType::JoinSetCreated {
+                        join_set_id: set.clone(),
+                    },
+                    submitted(&set, &p),
+                    awaited(&set, &p),
+                    submitted(&set, &q),
+                ]);
+                events
+            }
+            "AwaitedNotMember" => vec![
+                started(),
+                EventType::JoinSetCreated {
+                    join_set_id: set.clone(),
+                },
+                awaited(&set, &p),
+            ],
+            "AwaitedNotCompleted" => vec![
+                started(),
+                EventType::JoinSetCreated {
+                    join_set_id: set.clone(),
+                },
+                submitted(&set, &p),
+                awaited(&set, &p),
+            ],
+            "DoubleConsume" | "ConsumeExceedsSubmit" => {
+                let mut events = vec![started()];
+                events.extend(complete_p());
+                events.extend([
+                    EventType::JoinSetCreated {
+                        join_set_id: set.clone(),
+                    },
+                    submitted(&set, &p),
+                ]);
+                if violation == "DoubleConsume" {
+                    events.extend([awaited(&set, &p), awaited(&set, &p)]);
+                } else {
+                    // The JS-3 violation still counts as an await, so the
+                    // valid await that follows exceeds the submit count.
+                    events.extend([awaited(&set, &q), awaited(&set, &p)]);
+                }
+                events
+            }
+            "PromiseInMultipleJoinSets" => {
+                let other = js(3);
+                vec![
+                    started(),
+                    EventType::JoinSetCreated {
+                        join_set_id: set.clone(),
+                    },
+                    EventType::JoinSetCreated {
+                        join_set_id: other.clone(),
+                    },
+                    submitted(&set, &p),
+                    submitted(&other, &p),
+                ]
+            }
+            "SubmittedOutsideJoinSetTree" => vec![
+                started(),
+                EventType::JoinSetCreated {
+                    join_set_id: set.clone(),
+                },
+                submitted(&set, &PromiseId::new([9; 32]).child(0).unwrap()),
+            ],
+            other => panic!("catalog entry {other} has no violating journal"),
+        };
+        journal(events)
+    }
+
+    fn produced(info: &InvariantInfo, j: &ExecutionJournal) -> Vec<&'static str> {
+        if info.recovery_only {
+            match ExecutionState::recover(j.entries.clone()) {
+                Err(JournalError::InvariantViolation(v)) => vec![v.name()],
+                _ => vec![],
+            }
+        } else {
+            validate_journal(j)
+                .iter()
+                .map(JournalViolation::name)
+                .collect()
+        }
+    }
+
+    #[test]
+    fn catalog_ids_and_violations_are_unique() {
+        let ids: Vec<_> = invariant_catalog().iter().filter_map(|i| i.id).collect();
+        assert_eq!(ids.len(), ids.iter().collect::<HashSet<_>>().len());
+        let names: Vec<_> = invariant_catalog().iter().map(|i| i.violation).collect();
+        assert_eq!(names.len(), names.iter().collect::<HashSet<_>>().len());
+    }
+
+    #[test]
+    fn every_violation_is_catalogued_or_listed_as_uncatalogued() {
+        let catalogued: HashSet<_> = invariant_catalog().iter().map(|i| i.violation).collect();
+        for name in JournalViolation::NAMES {
+            assert!(
+                catalogued.contains(name) != UNCATALOGUED.contains(&name),
+                "{name} must be in exactly one of the catalog and UNCATALOGUED"
+            );
+        }
+        let names: HashSet<_> = JournalViolation::NAMES.into_iter().collect();
+        assert_eq!(names.len(), JournalViolation::NAMES.len());
+        assert!(
+            catalogued
+                .iter()
+                .chain(UNCATALOGUED)
+                .all(|n| names.contains(n))
+        );
+    }
+
+    #[test]
+    fn every_catalog_entry_is_reachable() {
+        for info in invariant_catalog() {
+            let j = violating_journal(info.violation);
+            let found = produced(info, &j);
+            assert!(
+                found.contains(&info.violation),
+                "{:?} ({}) not produced; got {found:?}",
+                info.id,
+                info.violation
+            );
+        }
+    }
+
+    #[test]
+    fn minimal_passing_journal_per_group() {
+        let (p, t, s) = (child(0), child(1), child(2));
+        let set = js(3);
+        let per_group = [
+            (
+                InvariantGroup::Structural,
+                vec![
+                    started(),
+                    EventType::CancelRequested { reason: "x".into() },
+                    EventType::ExecutionCancelled { reason: "x".into() },
+                ],
+            ),
+            (
+                InvariantGroup::SideEffects,
+                vec![
+                    started(),
+                    scheduled(&p),
+                    invoke_started(&p, 1),
+                    invoke_completed(&p),
+                ],
+            ),
+            (
+                InvariantGroup::ControlFlow,
+                vec![
+                    started(),
+                    EventType::TimerScheduled {
+                        promise_id: t.clone(),
+                        duration: std::time::Duration::from_secs(1),
+                        fire_at: DateTime::UNIX_EPOCH,
+                    },
+                    EventType::TimerFired { promise_id: t },
+                    delivered(),
+                    EventType::ExecutionAwaiting {
+                        waiting_on: vec![s.clone()],
+                        kind: AwaitKind::Signal {
+                            name: "sig".into(),
+                            promise_id: s.clone(),
+                        },
+                    },
+                    received(&s),
+                    EventType::ExecutionResumed,
+                ],
+            ),
+            (
+                InvariantGroup::JoinSet,
+                vec![
+                    started(),
+                    scheduled(&p),
+                    invoke_started(&p, 1),
+                    invoke_completed(&p),
+                    EventType::JoinSetCreated {
+                        join_set_id: set.clone(),
+                    },
+                    submitted(&set, &p),
+                    awaited(&set, &p),
+                ],
+            ),
+        ];
+        for (group, events) in per_group {
+            let j = journal(events);
+            assert_eq!(validate_journal(&j), vec![], "{group:?}");
+        }
+    }
+}
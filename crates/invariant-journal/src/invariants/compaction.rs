@@ -0,0 +1,312 @@
+//! Bounded retention of settled invoke lifecycles.
+//!
+//! A long-running execution schedules, starts and completes promises for as
+//! long as it lives, and [`InvariantState`] keeps every one of them in four
+//! promise sets (plus the owner map and two pair sets when a join set owns
+//! it). Once a lifecycle is settled the promise can only reappear in a
+//! violation, so those entries are needed solely to detect duplicates.
+//!
+//! [`StateCompaction::SealedLifecycles`] folds them into one `sealed` entry
+//! per promise. The sealed map is an exact set rather than a filter or a
+//! list of hashes: a false positive would report SE-4 or JS-5 on a valid
+//! journal, and a hash collision is a false positive. Every lookup the
+//! checks make goes through the accessors below, which answer for sealed
+//! promises exactly as the uncompacted sets would, so verdicts never depend
+//! on the mode.
+//!
+//! Attempt sets (`started_attempts`, `retried_attempts`) are not compacted:
+//! they have no per-promise index to remove by, and are only consulted for
+//! promises that SE-4 has not already rejected as completed.
+
+use super::InvariantState;
+use super::delta::{Change, StateDelta};
+use invariant_types::{JoinSetId, PromiseId};
+
+/// How an [`InvariantState`] retains settled invoke lifecycles.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum StateCompaction {
+    /// Keep every promise in the per-purpose sets.
+    #[default]
+    Off,
+    /// Fold a promise into a single sealed entry once it is scheduled,
+    /// started, completed and, if a join set owns it, awaited by that set.
+    SealedLifecycles,
+}
+
+impl InvariantState {
+    /// An empty state retaining settled lifecycles as `compaction` says.
+    pub fn with_compaction(compaction: StateCompaction) -> Self {
+        Self {
+            compaction,
+            ..Self::default()
+        }
+    }
+
+    /// Number of promises folded into sealed entries.
+    pub fn sealed_len(&self) -> usize {
+        self.sealed.len()
+    }
+
+    pub(crate) fn is_scheduled(&self, promise_id: &PromiseId) -> bool {
+        self.scheduled_pids.contains(promise_id) || self.sealed.contains_key(promise_id)
+    }
+
+    pub(crate) fn is_started(&self, promise_id: &PromiseId) -> bool {
+        self.started_pids.contains(promise_id) || self.sealed.contains_key(promise_id)
+    }
+
+    pub(crate) fn is_completed(&self, promise_id: &PromiseId) -> bool {
+        self.completed_pids.contains(promise_id) || self.sealed.contains_key(promise_id)
+    }
+
+    pub(crate) fn is_resolved(&self, promise_id: &PromiseId) -> bool {
+        self.resolved_pids.contains(promise_id) || self.sealed.contains_key(promise_id)
+    }
+
+    /// The join set owning `promise_id`, first writer wins (JS-7).
+    pub(crate) fn owner_of(&self, promise_id: &PromiseId) -> Option<&JoinSetId> {
+        self.pid_owner
+            .get(promise_id)
+            .or_else(|| self.sealed.get(promise_id)?.as_ref())
+    }
+
+    pub(crate) fn is_submitted(&self, join_set_id: &JoinSetId, promise_id: &PromiseId) -> bool {
+        self.sealed_owner_is(join_set_id, promise_id)
+            || self
+                .submitted_pairs
+                .contains(&(join_set_id.clone(), promise_id.clone()))
+    }
+
+    pub(crate) fn is_consumed(&self, join_set_id: &JoinSetId, promise_id: &PromiseId) -> bool {
+        self.sealed_owner_is(join_set_id, promise_id)
+            || self
+                .consumed_pairs
+                .contains(&(join_set_id.clone(), promise_id.clone()))
+    }
+
+    fn sealed_owner_is(&self, join_set_id: &JoinSetId, promise_id: &PromiseId) -> bool {
+        matches!(self.sealed.get(promise_id), Some(Some(owner)) if owner == join_set_id)
+    }
+
+    /// Append a [`Change::Seal`] to `delta` if, once its changes are
+    /// applied, `promise_id` has a settled lifecycle.
+    ///
+    /// A promise is sealed at most once. Entries recorded for it after
+    /// sealing, such as a later submission to a join set, stay in the
+    /// regular sets and are answered from there.
+    pub(super) fn seal_if_settled(&self, delta: &mut StateDelta, promise_id: &PromiseId) {
+        if self.compaction == StateCompaction::Off || self.sealed.contains_key(promise_id) {
+            return;
+        }
+        let pending = |change: Change| delta.changes.contains(&change);
+        let settled = self.scheduled_pids.contains(promise_id)
+            && self.started_pids.contains(promise_id)
+            && (self.completed_pids.contains(promise_id)
+                || pending(Change::CompletedPid(promise_id.clone())))
+            && (self.resolved_pids.contains(promise_id)
+                || pending(Change::ResolvedPid(promise_id.clone())));
+        if !settled {
+            return;
+        }
+        let owner = self.pid_owner.get(promise_id);
+        if let Some(js) = owner {
+            let pair = (js.clone(), promise_id.clone());
+            let consumed = self.consumed_pairs.contains(&pair)
+                || pending(Change::ConsumedPair(pair.0.clone(), pair.1.clone()));
+            if !self.submitted_pairs.contains(&pair) || !consumed {
+                return;
+            }
+        }
+        delta.push(Change::Seal {
+            promise_id: promise_id.clone(),
+            owner: owner.cloned(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::error::JournalViolation;
+    use crate::invariants::{ValidationOptions, validate_journal_report};
+    use crate::testing::arb_valid_journal;
+    use chrono::DateTime;
+    use invariant_types::{Codec, EventType, ExecutionJournal, InvokeKind, JournalEntry, Payload};
+
+    /// Corrupt `journal` by moving a copy of entry `from` to position `to`,
+    /// renumbering so only the copied event itself is out of place.
+    fn with_copy(journal: &ExecutionJournal, from: usize, to: usize) -> ExecutionJournal {
+        let mut journal = journal.clone();
+        let copy = journal.entries[from].clone();
+        journal.entries.insert(to, copy);
+        for (i, entry) in journal.entries.iter_mut().enumerate() {
+            entry.sequence = i as u64;
+        }
+        journal
+    }
+
+    /// Whether `check_append` accepts each entry in turn.
+    fn verdicts(entries: &[JournalEntry], mut state: InvariantState) -> Vec<bool> {
+        entries
+            .iter()
+            .map(|entry| state.check_append(entry).is_ok())
+            .collect()
+    }
+
+    #[test]
+    fn awaited_member_is_sealed_and_still_rejects_duplicates() {
+        let p = PromiseId::new([1; 32]);
+        let js = JoinSetId(PromiseId::new([2; 32]));
+        let mut state = InvariantState::with_compaction(StateCompaction::SealedLifecycles);
+        let mut seq = 0;
+        let mut append = |state: &mut InvariantState, event| {
+            let entry = JournalEntry {
+                sequence: seq,
+                timestamp: DateTime::UNIX_EPOCH,
+                event,
+            };
+            let result = state.check_append(&entry);
+            if result.is_ok() {
+                seq += 1;
+            }
+            result
+        };
+        let payload = || Payload::new(vec![], Codec::Json);
+
+        append(
+            &mut state,
+            EventType::ExecutionStarted {
+                component_digest: vec![1],
+                input: payload(),
+                parent_id: None,
+                idempotency_key: "k".into(),
+            },
+        )
+        .unwrap();
+        for event in [
+            EventType::JoinSetCreated {
+                join_set_id: js.clone(),
+            },
+            EventType::InvokeScheduled {
+                promise_id: p.clone(),
+                kind: InvokeKind::Function,
+                function_name: "f".into(),
+                input: payload(),
+                retry_policy: None,
+                sla: None,
+            },
+            EventType::JoinSetSubmitted {
+                join_set_id: js.clone(),
+                promise_id: p.clone(),
+            },
+            EventType::InvokeStarted {
+                promise_id: p.clone(),
+                attempt: 1,
+            },
+            EventType::InvokeCompleted {
+                promise_id: p.clone(),
+                result: payload(),
+                attempt: 1,
+            },
+        ] {
+            append(&mut state, event).unwrap();
+        }
+        // Completed but not yet consumed by its owner.
+        assert_eq!(state.sealed_len(), 0);
+
+        let awaited = EventType::JoinSetAwaited {
+            join_set_id: js.clone(),
+            promise_id: p.clone(),
+            result: payload(),
+        };
+        append(&mut state, awaited.clone()).unwrap();
+        assert_eq!(state.sealed_len(), 1);
+        assert!(state.completed_pids.is_empty() && state.consumed_pairs.is_empty());
+        assert!(state.pid_owner.is_empty() && state.submitted_pairs.is_empty());
+
+        assert!(matches!(
+            *append(&mut state, awaited).unwrap_err(),
+            JournalViolation::DoubleConsume { .. }
+        ));
+        assert!(matches!(
+            *append(
+                &mut state,
+                EventType::InvokeStarted {
+                    promise_id: p.clone(),
+                    attempt: 2,
+                },
+            )
+            .unwrap_err(),
+            JournalViolation::EventAfterCompleted { .. }
+        ));
+        let other = JoinSetId(PromiseId::new([3; 32]));
+        append(
+            &mut state,
+            EventType::JoinSetCreated {
+                join_set_id: other.clone(),
+            },
+        )
+        .unwrap();
+        assert!(matches!(
+            *append(
+                &mut state,
+                EventType::JoinSetSubmitted {
+                    join_set_id: other,
+                    promise_id: p,
+                },
+            )
+            .unwrap_err(),
+            JournalViolation::PromiseInMultipleJoinSets { .. }
+        ));
+    }
+
+    proptest! {
+        #[test]
+        fn compaction_never_changes_a_verdict(
+            j in arb_valid_journal(256),
+            copy in any::<Option<(prop::sample::Index, prop::sample::Index)>>(),
+        ) {
+            let j = match copy {
+                Some((from, to)) => {
+                    let len = j.entries.len();
+                    with_copy(&j, from.index(len), to.index(len + 1))
+                }
+                None => j,
+            };
+
+            let compacting = ValidationOptions {
+                compaction: StateCompaction::SealedLifecycles,
+                ..ValidationOptions::default()
+            };
+            prop_assert_eq!(
+                validate_journal_report(&j, &compacting),
+                validate_journal_report(&j, &ValidationOptions::default())
+            );
+
+            let plain = verdicts(&j.entries, InvariantState::new());
+            let compact = verdicts(
+                &j.entries,
+                InvariantState::with_compaction(StateCompaction::SealedLifecycles),
+            );
+            prop_assert_eq!(compact, plain);
+        }
+
+        #[test]
+        fn sealing_reverts_exactly(j in arb_valid_journal(64)) {
+            let mut state = InvariantState::with_compaction(StateCompaction::SealedLifecycles);
+            let before = state.clone();
+            let mut deltas = Vec::new();
+            for entry in &j.entries {
+                let delta = state.delta_for(entry);
+                state.apply(&delta);
+                deltas.push(delta);
+            }
+            for delta in deltas.iter().rev() {
+                state.revert(delta);
+            }
+            prop_assert_eq!(state, before);
+        }
+    }
+}
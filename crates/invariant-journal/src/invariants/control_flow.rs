@@ -1,13 +1,26 @@
-//! Control-flow invariants (CF-1 through CF-4).
+//! Control-flow invariants (CF-1 through CF-9).
 //!
 //! These checks enforce the causal ordering of timer, signal, and await
 //! events. Timers follow a two-phase Scheduled → Fired lifecycle (CF-1).
 //! Signals follow a Delivered → Received lifecycle with payload integrity
-//! (CF-2) and at-most-once consumption (CF-3). The await-signal consistency
+//! (CF-2) and at-most-once consumption (CF-3). A buffered delivery may
+//! instead end in `SignalDeliveryExpired`, after which it can be neither
+//! received (CF-2) nor expired again (CF-8). The await-signal consistency
 //! rule (CF-4) ensures that `ExecutionAwaiting` with `Signal` kind carries
 //! exactly one promise in `waiting_on`, matching the Quint spec's
-//! `awaitSignalConsistent` invariant. We also enforce set-like semantics
-//! for `waiting_on` by rejecting duplicate promise IDs.
+//! `awaitSignalConsistent` invariant; `AnySignal` follows the same rule and
+//! must list distinct signal names. We also enforce set-like semantics
+//! for `waiting_on` by rejecting duplicate promise IDs. CF-5 ties the
+//! `SignalReceived` that ends a signal wait back to the awaited promise,
+//! and for `AnySignal` to the lowest pending delivery of the listed names.
+//! CF-6 keeps the optional `AwaitProgress` hint honest: it may only appear
+//! while blocked and only list members of the active await that have
+//! actually resolved, with a `waiting_on_remaining` count that matches the
+//! members still unresolved. CF-7 rejects a second `SignalDelivered` carrying the
+//! same producer `external_id` for a signal name, and CF-9 rejects a second
+//! `SignalDelivered` for a `(name, delivery_id)` key already delivered, even
+//! with an identical payload, so the payload CF-2 compares against is fixed
+//! by the first delivery.
 
 use invariant_types::{AwaitKind, EventType, JournalEntry};
 use std::collections::HashSet;
@@ -18,8 +31,9 @@ use super::InvariantState;
 
 /// Validate control-flow invariants against the current accumulated state.
 ///
-/// The `SignalReceived` arm enforces two invariants in precedence order:
-/// CF-2 (matching delivery exists) before CF-3 (not already consumed).
+/// The `SignalReceived` arm enforces three invariants in precedence order:
+/// CF-2 (matching delivery exists) before CF-3 (not already consumed)
+/// before CF-5 (resolves the outstanding signal await).
 /// This mirrors the SE-4-before-SE-1 pattern in `side_effects`: existence
 /// is checked first because a "consumed twice" error is misleading when
 /// there was never a valid delivery to consume.
@@ -40,15 +54,17 @@ pub(crate) fn check(
         // CF-2 / CF-3: SignalReceived must match prior delivery and be consumed once.
         // Precedence: CF-2 (missing/mismatched delivery) before CF-3 (double consume).
         EventType::SignalReceived {
+            promise_id,
             signal_name,
             payload,
             delivery_id,
-            ..
         } => {
             let key = (signal_name.clone(), *delivery_id);
 
             match state.delivered_signals.get(&key) {
-                Some(delivered_payload) if delivered_payload == payload => {}
+                Some(delivered_payload)
+                    if delivered_payload == payload
+                        && !state.expired_signal_deliveries.contains(&key) => {}
                 _ => {
                     return Err(Box::new(JournalViolation::SignalReceivedWithoutDelivery {
                         signal_name: signal_name.clone(),
@@ -65,6 +81,26 @@ pub(crate) fn check(
                     second_seq: entry.sequence,
                 }));
             }
+
+            // CF-5: while blocked on a signal, only the awaited promise may be
+            // resolved. Without an outstanding wait the signal is pre-buffered.
+            if let Some(awaited) = &state.signal_await
+                && &awaited.promise_id != promise_id
+            {
+                return Err(Box::new(JournalViolation::SignalReceivedForWrongPromise {
+                    promise_id: promise_id.clone(),
+                    seq: entry.sequence,
+                }));
+            }
+            // An AnySignal await takes the deterministic winner, so replay
+            // resolves it the same way whatever order deliveries arrived in.
+            if !state.receives_next_delivery(signal_name, *delivery_id) {
+                return Err(Box::new(JournalViolation::AnySignalWrongDelivery {
+                    signal_name: signal_name.clone(),
+                    delivery_id: *delivery_id,
+                    seq: entry.sequence,
+                }));
+            }
         }
         EventType::ExecutionAwaiting { waiting_on, kind } => {
             // Quint models waiting_on as a set. Rust stores Vec for schema compatibility,
@@ -80,8 +116,27 @@ pub(crate) fn check(
                 }
             }
 
-            // CF-4: AwaitKind::Signal must wait on exactly one promise.
-            if let AwaitKind::Signal { promise_id, .. } = kind {
+            // CF-4: AwaitKind::AnySignal lists at least one name, each once.
+            if let AwaitKind::AnySignal { names, .. } = kind {
+                if names.is_empty() {
+                    return Err(Box::new(JournalViolation::AnySignalNamesInvalid {
+                        awaiting_seq: entry.sequence,
+                        duplicate: None,
+                    }));
+                }
+                let mut seen = HashSet::with_capacity(names.len());
+                if let Some(name) = names.iter().find(|name| !seen.insert(*name)) {
+                    return Err(Box::new(JournalViolation::AnySignalNamesInvalid {
+                        awaiting_seq: entry.sequence,
+                        duplicate: Some(name.clone()),
+                    }));
+                }
+            }
+
+            // CF-4: signal awaits must wait on exactly one promise.
+            if let AwaitKind::Signal { promise_id, .. } | AwaitKind::AnySignal { promise_id, .. } =
+                kind
+            {
                 if waiting_on.len() != 1 {
                     return Err(Box::new(JournalViolation::AwaitSignalInconsistent {
                         awaiting_seq: entry.sequence,
@@ -96,6 +151,79 @@ pub(crate) fn check(
                 }
             }
         }
+        // CF-8: only a buffered delivery can expire.
+        EventType::SignalDeliveryExpired {
+            signal_name,
+            delivery_id,
+        } => {
+            if !state.is_signal_buffered(&(signal_name.clone(), *delivery_id)) {
+                return Err(Box::new(JournalViolation::SignalExpiryInvalid {
+                    signal_name: signal_name.clone(),
+                    delivery_id: *delivery_id,
+                    expired_seq: entry.sequence,
+                }));
+            }
+        }
+        // CF-9: each `(name, delivery_id)` key is delivered once.
+        // CF-7: a producer's message ID is delivered at most once per signal name.
+        EventType::SignalDelivered {
+            signal_name,
+            delivery_id,
+            external_id,
+            ..
+        } => {
+            if state
+                .delivered_signals
+                .contains_key(&(signal_name.clone(), *delivery_id))
+            {
+                return Err(Box::new(JournalViolation::ConflictingSignalDelivery {
+                    signal_name: signal_name.clone(),
+                    delivery_id: *delivery_id,
+                    seq: entry.sequence,
+                }));
+            }
+            if let Some(external_id) = external_id
+                && state
+                    .external_deliveries
+                    .contains(&(signal_name.clone(), external_id.clone()))
+            {
+                return Err(Box::new(JournalViolation::DuplicateExternalDelivery {
+                    signal_name: signal_name.clone(),
+                    external_id: external_id.clone(),
+                    second_seq: entry.sequence,
+                }));
+            }
+        }
+        // CF-6: AwaitProgress only while blocked, listing resolved members
+        // and counting the unresolved ones.
+        EventType::AwaitProgress {
+            waiting_on_remaining,
+            resolved_since_await,
+        } => {
+            let Some(active) = &state.active_await else {
+                return Err(Box::new(JournalViolation::ProgressInconsistent {
+                    seq: entry.sequence,
+                    promise_id: None,
+                }));
+            };
+            if let Some(pid) = resolved_since_await
+                .iter()
+                .find(|pid| !active.contains(*pid) || !state.is_resolved(pid))
+            {
+                return Err(Box::new(JournalViolation::ProgressInconsistent {
+                    seq: entry.sequence,
+                    promise_id: Some(pid.clone()),
+                }));
+            }
+            let pending = active.iter().filter(|pid| !state.is_resolved(pid)).count();
+            if usize::try_from(*waiting_on_remaining) != Ok(pending) {
+                return Err(Box::new(JournalViolation::ProgressRemainingMismatch {
+                    seq: entry.sequence,
+                    reported: *waiting_on_remaining,
+                    pending,
+                }));
+            }
+        }
         _ => {}
     }
 
@@ -105,7 +233,9 @@ pub(crate) fn check(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::invariants::SignalAwait;
     use invariant_types::{Codec, Payload, PromiseId};
+    use std::collections::{BTreeSet, HashMap};
 
     fn pid(tag: u8) -> PromiseId {
         PromiseId::new([tag; 32])
@@ -382,6 +512,65 @@ mod tests {
         assert!(check(&state, &entry).is_ok());
     }
 
+    fn any_signal_await(seq: u64, names: &[&str]) -> JournalEntry {
+        mk_entry(
+            seq,
+            EventType::ExecutionAwaiting {
+                waiting_on: vec![pid(20)],
+                kind: AwaitKind::AnySignal {
+                    names: names.iter().map(|n| n.to_string()).collect(),
+                    promise_id: pid(20),
+                },
+            },
+        )
+    }
+
+    #[test]
+    fn cf4_any_signal_requires_distinct_names() {
+        let state = InvariantState::default();
+        assert!(check(&state, &any_signal_await(15, &["approved", "rejected"])).is_ok());
+        assert_eq!(
+            *check(&state, &any_signal_await(15, &[])).unwrap_err(),
+            JournalViolation::AnySignalNamesInvalid {
+                awaiting_seq: 15,
+                duplicate: None,
+            }
+        );
+        assert_eq!(
+            *check(
+                &state,
+                &any_signal_await(16, &["approved", "rejected", "approved"])
+            )
+            .unwrap_err(),
+            JournalViolation::AnySignalNamesInvalid {
+                awaiting_seq: 16,
+                duplicate: Some("approved".into()),
+            }
+        );
+    }
+
+    #[test]
+    fn cf4_any_signal_waits_on_its_own_promise() {
+        let state = InvariantState::default();
+        let entry = mk_entry(
+            17,
+            EventType::ExecutionAwaiting {
+                waiting_on: vec![pid(21)],
+                kind: AwaitKind::AnySignal {
+                    names: vec!["approved".into()],
+                    promise_id: pid(22),
+                },
+            },
+        );
+        assert_eq!(
+            *check(&state, &entry).unwrap_err(),
+            JournalViolation::AwaitSignalInconsistent {
+                awaiting_seq: 17,
+                waiting_on_count: 1,
+            }
+        );
+    }
+
     #[test]
     fn cf4_non_signal_await_does_not_apply_signal_cardinality_rule() {
         let state = InvariantState::default();
@@ -488,4 +677,428 @@ mod tests {
             }
         );
     }
+
+    fn signal_await(promise_id: PromiseId) -> SignalAwait {
+        SignalAwait {
+            promise_id,
+            any_of: None,
+        }
+    }
+
+    #[test]
+    fn cf5_signal_received_for_awaited_promise_passes() {
+        let awaited = pid(7);
+        let state = InvariantState {
+            delivered_signals: std::iter::once((("sig".to_string(), 1), payload(b"ok"))).collect(),
+            signal_await: Some(signal_await(awaited.clone())),
+            ..Default::default()
+        };
+        let entry = mk_entry(
+            8,
+            EventType::SignalReceived {
+                promise_id: awaited,
+                signal_name: "sig".to_string(),
+                payload: payload(b"ok"),
+                delivery_id: 1,
+            },
+        );
+
+        assert!(check(&state, &entry).is_ok());
+    }
+
+    #[test]
+    fn cf5_signal_received_for_other_promise_reports_wrong_promise() {
+        let state = InvariantState {
+            delivered_signals: std::iter::once((("sig".to_string(), 1), payload(b"ok"))).collect(),
+            signal_await: Some(signal_await(pid(7))),
+            ..Default::default()
+        };
+        let entry = mk_entry(
+            8,
+            EventType::SignalReceived {
+                promise_id: pid(8),
+                signal_name: "sig".to_string(),
+                payload: payload(b"ok"),
+                delivery_id: 1,
+            },
+        );
+
+        let err = check(&state, &entry).unwrap_err();
+        assert_eq!(
+            *err,
+            JournalViolation::SignalReceivedForWrongPromise {
+                promise_id: pid(8),
+                seq: 8,
+            }
+        );
+    }
+
+    #[test]
+    fn cf5_signal_received_while_not_blocked_passes() {
+        let state = InvariantState {
+            delivered_signals: std::iter::once((("sig".to_string(), 1), payload(b"ok"))).collect(),
+            ..Default::default()
+        };
+        let entry = mk_entry(
+            3,
+            EventType::SignalReceived {
+                promise_id: pid(8),
+                signal_name: "sig".to_string(),
+                payload: payload(b"ok"),
+                delivery_id: 1,
+            },
+        );
+
+        assert!(check(&state, &entry).is_ok());
+    }
+
+    #[test]
+    fn cf5_outstanding_wait_cleared_by_matching_receive() {
+        let awaited = pid(7);
+        let mut state = InvariantState::default();
+        state.apply_entry(&mk_entry(
+            0,
+            EventType::ExecutionAwaiting {
+                waiting_on: vec![awaited.clone()],
+                kind: AwaitKind::Signal {
+                    name: "sig".to_string(),
+                    promise_id: awaited.clone(),
+                },
+            },
+        ));
+        assert_eq!(state.signal_await, Some(signal_await(awaited.clone())));
+
+        state.apply_entry(&mk_entry(
+            1,
+            EventType::SignalReceived {
+                promise_id: awaited,
+                signal_name: "sig".to_string(),
+                payload: payload(b"ok"),
+                delivery_id: 1,
+            },
+        ));
+        assert_eq!(state.signal_await, None);
+    }
+
+    /// Deliveries of `names` in order, then an `AnySignal` await on
+    /// `listed`.
+    fn blocked_on_any_signal(names: &[&str], listed: &[&str]) -> InvariantState {
+        let mut state = InvariantState::default();
+        for (seq, name) in names.iter().enumerate() {
+            state.apply_entry(&mk_entry(
+                seq as u64,
+                EventType::SignalDelivered {
+                    signal_name: name.to_string(),
+                    payload: payload(b"ok"),
+                    delivery_id: 0,
+                    external_id: None,
+                },
+            ));
+        }
+        state.apply_entry(&any_signal_await(names.len() as u64, listed));
+        state
+    }
+
+    fn received(name: &str) -> EventType {
+        EventType::SignalReceived {
+            promise_id: pid(20),
+            signal_name: name.to_string(),
+            payload: payload(b"ok"),
+            delivery_id: 0,
+        }
+    }
+
+    #[test]
+    fn cf5_any_signal_receive_skips_expired_and_consumed_deliveries() {
+        let mut state = InvariantState::default();
+        for delivery_id in 0..3 {
+            state.apply_entry(&mk_entry(
+                delivery_id,
+                EventType::SignalDelivered {
+                    signal_name: "approved".to_string(),
+                    payload: payload(b"ok"),
+                    delivery_id,
+                    external_id: None,
+                },
+            ));
+        }
+        state.apply_entry(&mk_entry(
+            3,
+            EventType::SignalReceived {
+                promise_id: pid(19),
+                signal_name: "approved".to_string(),
+                payload: payload(b"ok"),
+                delivery_id: 0,
+            },
+        ));
+        state.apply_entry(&mk_entry(
+            4,
+            EventType::SignalDeliveryExpired {
+                signal_name: "approved".to_string(),
+                delivery_id: 1,
+            },
+        ));
+        state.apply_entry(&any_signal_await(5, &["approved"]));
+
+        assert!(state.receives_next_delivery("approved", 2));
+        assert!(!state.receives_next_delivery("approved", 1));
+        assert_eq!(
+            state.pending_signals,
+            HashMap::from([("approved".to_string(), BTreeSet::from([2]))])
+        );
+    }
+
+    #[test]
+    fn cf5_any_signal_receive_takes_lowest_pending_delivery() {
+        let state = blocked_on_any_signal(&["rejected", "approved"], &["rejected", "approved"]);
+
+        assert!(check(&state, &mk_entry(3, received("approved"))).is_ok());
+        assert_eq!(
+            *check(&state, &mk_entry(3, received("rejected"))).unwrap_err(),
+            JournalViolation::AnySignalWrongDelivery {
+                signal_name: "rejected".to_string(),
+                delivery_id: 0,
+                seq: 3,
+            }
+        );
+    }
+
+    #[test]
+    fn cf5_any_signal_receive_of_unlisted_name_reports_wrong_delivery() {
+        let state = blocked_on_any_signal(&["approved", "other"], &["rejected", "other"]);
+
+        assert!(check(&state, &mk_entry(3, received("other"))).is_ok());
+        assert!(matches!(
+            *check(&state, &mk_entry(3, received("approved"))).unwrap_err(),
+            JournalViolation::AnySignalWrongDelivery { .. }
+        ));
+    }
+
+    fn progress(resolved: Vec<PromiseId>) -> EventType {
+        EventType::AwaitProgress {
+            waiting_on_remaining: 1,
+            resolved_since_await: resolved,
+        }
+    }
+
+    fn progress_remaining(remaining: u32, resolved: Vec<PromiseId>) -> EventType {
+        EventType::AwaitProgress {
+            waiting_on_remaining: remaining,
+            resolved_since_await: resolved,
+        }
+    }
+
+    fn blocked_on_all(members: &[PromiseId]) -> InvariantState {
+        let mut state = InvariantState::default();
+        state.apply_entry(&mk_entry(
+            0,
+            EventType::ExecutionAwaiting {
+                waiting_on: members.to_vec(),
+                kind: AwaitKind::All,
+            },
+        ));
+        state
+    }
+
+    #[test]
+    fn cf6_progress_while_not_blocked_reports_progress_inconsistent() {
+        let state = InvariantState::default();
+        let err = check(&state, &mk_entry(3, progress(vec![]))).unwrap_err();
+        assert_eq!(
+            *err,
+            JournalViolation::ProgressInconsistent {
+                seq: 3,
+                promise_id: None,
+            }
+        );
+    }
+
+    #[test]
+    fn cf6_progress_listing_resolved_member_passes() {
+        let (a, b) = (pid(1), pid(2));
+        let mut state = blocked_on_all(&[a.clone(), b]);
+        state.apply_entry(&mk_entry(
+            1,
+            EventType::TimerFired {
+                promise_id: a.clone(),
+            },
+        ));
+
+        assert!(check(&state, &mk_entry(2, progress(vec![a]))).is_ok());
+    }
+
+    #[test]
+    fn cf6_progress_listing_unresolved_member_reports_progress_inconsistent() {
+        let (a, b) = (pid(1), pid(2));
+        let state = blocked_on_all(&[a, b.clone()]);
+
+        let err = check(&state, &mk_entry(2, progress(vec![b.clone()]))).unwrap_err();
+        assert_eq!(
+            *err,
+            JournalViolation::ProgressInconsistent {
+                seq: 2,
+                promise_id: Some(b),
+            }
+        );
+    }
+
+    #[test]
+    fn cf6_progress_listing_non_member_reports_progress_inconsistent() {
+        let (a, stranger) = (pid(1), pid(9));
+        let mut state = blocked_on_all(&[a]);
+        state.apply_entry(&mk_entry(
+            1,
+            EventType::TimerFired {
+                promise_id: stranger.clone(),
+            },
+        ));
+
+        let err = check(&state, &mk_entry(2, progress(vec![stranger.clone()]))).unwrap_err();
+        assert_eq!(
+            *err,
+            JournalViolation::ProgressInconsistent {
+                seq: 2,
+                promise_id: Some(stranger),
+            }
+        );
+    }
+
+    #[test]
+    fn cf6_progress_with_wrong_remaining_count_reports_mismatch() {
+        let (a, b, c) = (pid(1), pid(2), pid(3));
+        let mut state = blocked_on_all(&[a.clone(), b, c]);
+        state.apply_entry(&mk_entry(
+            1,
+            EventType::TimerFired {
+                promise_id: a.clone(),
+            },
+        ));
+
+        assert!(check(&state, &mk_entry(2, progress_remaining(2, vec![a.clone()]))).is_ok());
+        for reported in [0, 1, 3] {
+            let err = check(
+                &state,
+                &mk_entry(2, progress_remaining(reported, vec![a.clone()])),
+            )
+            .unwrap_err();
+            assert_eq!(
+                *err,
+                JournalViolation::ProgressRemainingMismatch {
+                    seq: 2,
+                    reported,
+                    pending: 2,
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn cf6_resume_ends_the_active_await() {
+        let a = pid(1);
+        let mut state = blocked_on_all(std::slice::from_ref(&a));
+        state.apply_entry(&mk_entry(1, EventType::TimerFired { promise_id: a }));
+        state.apply_entry(&mk_entry(2, EventType::ExecutionResumed));
+
+        assert!(check(&state, &mk_entry(3, progress(vec![]))).is_err());
+    }
+
+    fn delivered_with_external(name: &str, delivery_id: u64, external_id: &str) -> EventType {
+        EventType::SignalDelivered {
+            signal_name: name.to_string(),
+            payload: payload(b"ok"),
+            delivery_id,
+            external_id: Some(external_id.to_string()),
+        }
+    }
+
+    #[test]
+    fn cf7_repeated_external_id_reports_duplicate_external_delivery() {
+        let mut state = InvariantState::default();
+        state.apply_entry(&mk_entry(1, delivered_with_external("sig", 0, "m-1")));
+
+        let err = check(
+            &state,
+            &mk_entry(2, delivered_with_external("sig", 1, "m-1")),
+        )
+        .unwrap_err();
+        assert_eq!(
+            *err,
+            JournalViolation::DuplicateExternalDelivery {
+                signal_name: "sig".to_string(),
+                external_id: "m-1".to_string(),
+                second_seq: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn cf7_external_id_is_scoped_per_signal_name() {
+        let mut state = InvariantState::default();
+        state.apply_entry(&mk_entry(1, delivered_with_external("sig", 0, "m-1")));
+
+        assert!(
+            check(
+                &state,
+                &mk_entry(2, delivered_with_external("other", 0, "m-1"))
+            )
+            .is_ok()
+        );
+        assert!(
+            check(
+                &state,
+                &mk_entry(2, delivered_with_external("sig", 1, "m-2"))
+            )
+            .is_ok()
+        );
+    }
+
+    fn delivered(name: &str, delivery_id: u64, bytes: &[u8]) -> EventType {
+        EventType::SignalDelivered {
+            signal_name: name.to_string(),
+            payload: payload(bytes),
+            delivery_id,
+            external_id: None,
+        }
+    }
+
+    #[test]
+    fn cf9_redelivery_with_differing_payload_reports_conflicting_signal_delivery() {
+        let mut state = InvariantState::default();
+        state.apply_entry(&mk_entry(1, delivered("sig", 0, b"ok")));
+
+        let err = check(&state, &mk_entry(2, delivered("sig", 0, b"no"))).unwrap_err();
+        assert_eq!(
+            *err,
+            JournalViolation::ConflictingSignalDelivery {
+                signal_name: "sig".to_string(),
+                delivery_id: 0,
+                seq: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn cf9_identical_redelivery_reports_conflicting_signal_delivery() {
+        let mut state = InvariantState::default();
+        state.apply_entry(&mk_entry(1, delivered("sig", 0, b"ok")));
+
+        let err = check(&state, &mk_entry(2, delivered("sig", 0, b"ok"))).unwrap_err();
+        assert_eq!(
+            *err,
+            JournalViolation::ConflictingSignalDelivery {
+                signal_name: "sig".to_string(),
+                delivery_id: 0,
+                seq: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn cf9_delivery_key_is_scoped_per_signal_name() {
+        let mut state = InvariantState::default();
+        state.apply_entry(&mk_entry(1, delivered("sig", 0, b"ok")));
+
+        assert!(check(&state, &mk_entry(2, delivered("other", 0, b"ok"))).is_ok());
+        assert!(check(&state, &mk_entry(2, delivered("sig", 1, b"ok"))).is_ok());
+    }
 }
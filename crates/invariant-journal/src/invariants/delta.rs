@@ -0,0 +1,370 @@
+//! Reversible state updates for speculative validation.
+//!
+//! [`InvariantState::delta_for`] describes what ingesting an entry would
+//! change without changing it. Applying that [`StateDelta`] and later
+//! reverting it restores the state exactly, so callers can try a run of
+//! entries and roll back without cloning every tracked set.
+
+use super::{InvariantState, SignalAwait};
+use invariant_types::{JoinSetId, Payload, PromiseId, SignalDeliveryId};
+use std::collections::HashSet;
+
+/// The updates one entry makes to an [`InvariantState`].
+///
+/// Only effective changes are recorded: inserting a key that is already
+/// present is not a change, so reverting never removes something the entry
+/// did not add. A delta is only meaningful against the state it was
+/// computed from; deltas must be reverted in the reverse order they were
+/// applied.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StateDelta {
+    pub(crate) changes: Vec<Change>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Change {
+    Len,
+    TerminalSeq(u64),
+    CancelRequested,
+    ComponentDigest {
+        old: Option<Vec<u8>>,
+        new: Vec<u8>,
+    },
+    ScheduledPid(PromiseId),
+    StartedPid(PromiseId),
+    StartedAttempt(PromiseId, u32),
+    RetriedAttempt(PromiseId, u32),
+    CompletedPid(PromiseId),
+    ResolvedPid(PromiseId),
+    ScheduledTimerPid(PromiseId),
+    DeliveredSignal {
+        key: (String, SignalDeliveryId),
+        old: Option<Payload>,
+        new: Payload,
+    },
+    ExternalDelivery(String, String),
+    ConsumedSignal(String, SignalDeliveryId),
+    ExpiredSignal(String, SignalDeliveryId),
+    SignalAwait {
+        old: Option<SignalAwait>,
+        new: Option<SignalAwait>,
+    },
+    ActiveAwait {
+        old: Option<HashSet<PromiseId>>,
+        new: Option<HashSet<PromiseId>>,
+    },
+    CreatedJoinSet(JoinSetId),
+    AwaitedJoinSet(JoinSetId),
+    SubmittedPair(JoinSetId, PromiseId),
+    ConsumedPair(JoinSetId, PromiseId),
+    JoinSetCounts {
+        join_set_id: JoinSetId,
+        old: Option<(u32, u32)>,
+        new: (u32, u32),
+    },
+    CompletedJoinSet(JoinSetId),
+    PidOwner(PromiseId, JoinSetId),
+    /// Fold a settled lifecycle into `sealed`. Every entry it stands for is
+    /// present when applied, so reverting reinserts exactly those.
+    Seal {
+        promise_id: PromiseId,
+        owner: Option<JoinSetId>,
+    },
+}
+
+impl StateDelta {
+    pub(crate) fn push(&mut self, change: Change) {
+        self.changes.push(change);
+    }
+}
+
+impl InvariantState {
+    /// Apply a delta computed by [`delta_for`](Self::delta_for) against
+    /// the current state.
+    pub fn apply(&mut self, delta: &StateDelta) {
+        self.apply_owned(delta.clone());
+    }
+
+    pub(crate) fn apply_owned(&mut self, delta: StateDelta) {
+        for change in delta.changes {
+            match change {
+                Change::Len => self.len += 1,
+                Change::TerminalSeq(seq) => self.terminal_seq = Some(seq),
+                Change::CancelRequested => self.has_cancel_requested = true,
+                Change::ComponentDigest { new, .. } => self.component_digest = Some(new),
+                Change::ScheduledPid(pid) => {
+                    self.scheduled_pids.insert(pid);
+                }
+                Change::StartedPid(pid) => {
+                    self.started_pids.insert(pid);
+                }
+                Change::StartedAttempt(pid, attempt) => {
+                    self.started_attempts.insert((pid, attempt));
+                }
+                Change::RetriedAttempt(pid, attempt) => {
+                    self.retried_attempts.insert((pid, attempt));
+                }
+                Change::CompletedPid(pid) => {
+                    self.completed_pids.insert(pid);
+                }
+                Change::ResolvedPid(pid) => {
+                    self.resolved_pids.insert(pid);
+                }
+                Change::ScheduledTimerPid(pid) => {
+                    self.scheduled_timer_pids.insert(pid);
+                }
+                Change::DeliveredSignal { key, new, .. } => {
+                    self.delivered_signals.insert(key.clone(), new);
+                    self.sync_pending_signal(&key);
+                }
+                Change::ExternalDelivery(name, external_id) => {
+                    self.external_deliveries.insert((name, external_id));
+                }
+                Change::ConsumedSignal(name, delivery_id) => {
+                    let key = (name, delivery_id);
+                    self.consumed_signal_deliveries.insert(key.clone());
+                    self.sync_pending_signal(&key);
+                }
+                Change::ExpiredSignal(name, delivery_id) => {
+                    let key = (name, delivery_id);
+                    self.expired_signal_deliveries.insert(key.clone());
+                    self.sync_pending_signal(&key);
+                }
+                Change::SignalAwait { new, .. } => self.signal_await = new,
+                Change::ActiveAwait { new, .. } => self.active_await = new,
+                Change::CreatedJoinSet(js) => {
+                    self.created_joinsets.insert(js);
+                }
+                Change::AwaitedJoinSet(js) => {
+                    self.awaited_joinsets.insert(js);
+                }
+                Change::SubmittedPair(js, pid) => {
+                    self.submitted_pairs.insert((js, pid));
+                }
+                Change::ConsumedPair(js, pid) => {
+                    self.consumed_pairs.insert((js, pid));
+                }
+                Change::JoinSetCounts {
+                    join_set_id, new, ..
+                } => {
+                    self.joinset_counts.insert(join_set_id, new);
+                }
+                Change::CompletedJoinSet(js) => {
+                    self.completed_join_sets.insert(js);
+                }
+                Change::PidOwner(pid, js) => {
+                    self.pid_owner.insert(pid, js);
+                }
+                Change::Seal { promise_id, owner } => {
+                    self.scheduled_pids.remove(&promise_id);
+                    self.started_pids.remove(&promise_id);
+                    self.completed_pids.remove(&promise_id);
+                    self.resolved_pids.remove(&promise_id);
+                    if let Some(js) = &owner {
+                        self.pid_owner.remove(&promise_id);
+                        let pair = (js.clone(), promise_id.clone());
+                        self.submitted_pairs.remove(&pair);
+                        self.consumed_pairs.remove(&pair);
+                    }
+                    self.sealed.insert(promise_id, owner);
+                }
+            }
+        }
+    }
+
+    /// Undo a delta previously passed to [`apply`](Self::apply).
+    ///
+    /// Every delta applied after it must already have been reverted.
+    pub fn revert(&mut self, delta: &StateDelta) {
+        for change in delta.changes.iter().rev() {
+            match change {
+                Change::Len => self.len -= 1,
+                Change::TerminalSeq(_) => self.terminal_seq = None,
+                Change::CancelRequested => self.has_cancel_requested = false,
+                Change::ComponentDigest { old, .. } => self.component_digest = old.clone(),
+                Change::ScheduledPid(pid) => {
+                    self.scheduled_pids.remove(pid);
+                }
+                Change::StartedPid(pid) => {
+                    self.started_pids.remove(pid);
+                }
+                Change::StartedAttempt(pid, attempt) => {
+                    self.started_attempts.remove(&(pid.clone(), *attempt));
+                }
+                Change::RetriedAttempt(pid, attempt) => {
+                    self.retried_attempts.remove(&(pid.clone(), *attempt));
+                }
+                Change::CompletedPid(pid) => {
+                    self.completed_pids.remove(pid);
+                }
+                Change::ResolvedPid(pid) => {
+                    self.resolved_pids.remove(pid);
+                }
+                Change::ScheduledTimerPid(pid) => {
+                    self.scheduled_timer_pids.remove(pid);
+                }
+                Change::DeliveredSignal { key, old, .. } => {
+                    match old {
+                        Some(payload) => {
+                            self.delivered_signals.insert(key.clone(), payload.clone());
+                        }
+                        None => {
+                            self.delivered_signals.remove(key);
+                        }
+                    }
+                    self.sync_pending_signal(key);
+                }
+                Change::ExternalDelivery(name, external_id) => {
+                    self.external_deliveries
+                        .remove(&(name.clone(), external_id.clone()));
+                }
+                Change::ConsumedSignal(name, delivery_id) => {
+                    let key = (name.clone(), *delivery_id);
+                    self.consumed_signal_deliveries.remove(&key);
+                    self.sync_pending_signal(&key);
+                }
+                Change::ExpiredSignal(name, delivery_id) => {
+                    let key = (name.clone(), *delivery_id);
+                    self.expired_signal_deliveries.remove(&key);
+                    self.sync_pending_signal(&key);
+                }
+                Change::SignalAwait { old, .. } => self.signal_await = old.clone(),
+                Change::ActiveAwait { old, .. } => self.active_await = old.clone(),
+                Change::CreatedJoinSet(js) => {
+                    self.created_joinsets.remove(js);
+                }
+                Change::AwaitedJoinSet(js) => {
+                    self.awaited_joinsets.remove(js);
+                }
+                Change::SubmittedPair(js, pid) => {
+                    self.submitted_pairs.remove(&(js.clone(), pid.clone()));
+                }
+                Change::ConsumedPair(js, pid) => {
+                    self.consumed_pairs.remove(&(js.clone(), pid.clone()));
+                }
+                Change::JoinSetCounts {
+                    join_set_id, old, ..
+                } => match old {
+                    Some(counts) => {
+                        self.joinset_counts.insert(join_set_id.clone(), *counts);
+                    }
+                    None => {
+                        self.joinset_counts.remove(join_set_id);
+                    }
+                },
+                Change::CompletedJoinSet(js) => {
+                    self.completed_join_sets.remove(js);
+                }
+                Change::PidOwner(pid, _) => {
+                    self.pid_owner.remove(pid);
+                }
+                Change::Seal { promise_id, owner } => {
+                    self.sealed.remove(promise_id);
+                    self.scheduled_pids.insert(promise_id.clone());
+                    self.started_pids.insert(promise_id.clone());
+                    self.completed_pids.insert(promise_id.clone());
+                    self.resolved_pids.insert(promise_id.clone());
+                    if let Some(js) = owner {
+                        self.pid_owner.insert(promise_id.clone(), js.clone());
+                        let pair = (js.clone(), promise_id.clone());
+                        self.submitted_pairs.insert(pair.clone());
+                        self.consumed_pairs.insert(pair);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::testing::arb_valid_journal;
+    use invariant_types::JournalEntry;
+
+    fn state_after(entries: &[JournalEntry]) -> InvariantState {
+        let mut state = InvariantState::new();
+        for entry in entries {
+            state.check_append(entry).unwrap();
+        }
+        state
+    }
+
+    /// Drop entry `index` and renumber the rest, usually breaking a later
+    /// entry's dependency on it.
+    fn without(entries: &[JournalEntry], index: usize) -> Vec<JournalEntry> {
+        let mut entries = entries.to_vec();
+        entries.remove(index);
+        for (i, entry) in entries.iter_mut().enumerate() {
+            entry.sequence = i as u64;
+        }
+        entries
+    }
+
+    proptest! {
+        #[test]
+        fn apply_then_revert_restores_the_state(
+            j in arb_valid_journal(48),
+            split in any::<prop::sample::Index>(),
+        ) {
+            let at = split.index(j.entries.len() + 1);
+            let mut state = state_after(&j.entries[..at]);
+            let before = state.clone();
+
+            let mut deltas = Vec::new();
+            for entry in &j.entries[at..] {
+                let delta = state.delta_for(entry);
+                let mut direct = state.clone();
+                direct.apply_entry(entry);
+                state.apply(&delta);
+                prop_assert_eq!(&state, &direct);
+                deltas.push(delta);
+            }
+            for delta in deltas.iter().rev() {
+                state.revert(delta);
+            }
+            prop_assert_eq!(state, before);
+        }
+
+        #[test]
+        fn batch_agrees_with_sequential_check_append(
+            j in arb_valid_journal(48),
+            split in any::<prop::sample::Index>(),
+            dropped in any::<Option<prop::sample::Index>>(),
+        ) {
+            let entries = match dropped {
+                Some(index) => without(&j.entries, index.index(j.entries.len())),
+                None => j.entries.clone(),
+            };
+            // The batch starts after the longest clean prefix up to `split`.
+            let at = (0..=split.index(entries.len() + 1))
+                .rev()
+                .find(|&at| {
+                    let mut state = InvariantState::new();
+                    entries[..at].iter().all(|e| state.check_append(e).is_ok())
+                })
+                .unwrap_or(0);
+            let (prefix, batch) = entries.split_at(at);
+
+            let mut speculative = state_after(prefix);
+            let before = speculative.clone();
+            let result = speculative.check_append_batch(batch);
+
+            let mut sequential = before.clone();
+            let first = batch
+                .iter()
+                .find_map(|entry| sequential.check_append(entry).err());
+
+            match (result, first) {
+                (Ok(()), None) => prop_assert_eq!(speculative, sequential),
+                (Err(v), Some(first)) => {
+                    prop_assert_eq!(v, first);
+                    prop_assert_eq!(speculative, before);
+                }
+                (result, first) => prop_assert!(false, "batch {result:?}, sequential {first:?}"),
+            }
+        }
+    }
+}
@@ -0,0 +1,38 @@
+//! Per-kind validation hooks for `InvokeScheduled`.
+//!
+//! Invocations of some kinds will carry metadata worth sanity-checking, such
+//! as an HTTP invoke's URL and method. Validators registered in
+//! [`ValidationOptions::invoke_validators`](super::ValidationOptions::invoke_validators)
+//! run on every `InvokeScheduled` during batch validation. Like the payload
+//! limit they are opt-in and absent from the invariant catalog.
+
+use std::fmt;
+
+use invariant_types::{InvokeKind, PromiseId};
+
+/// Hook checking a scheduled invocation.
+///
+/// The default implementation accepts everything, so a validator only
+/// overrides the cases it cares about.
+pub trait InvokeValidator: fmt::Debug + Send + Sync {
+    /// Check one `InvokeScheduled`. An `Err` is reported as
+    /// [`JournalViolation::InvokeRejected`](crate::error::JournalViolation::InvokeRejected)
+    /// with the returned reason.
+    fn validate(
+        &self,
+        _promise_id: &PromiseId,
+        _kind: &InvokeKind,
+        _function_name: &str,
+    ) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// Validator for [`InvokeKind::Http`] invocations.
+///
+/// `InvokeKind::Http` carries no request metadata yet, so there is nothing to
+/// check; once it does, this is where the URL is checked for well-formedness.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct HttpInvokeValidator;
+
+impl InvokeValidator for HttpInvokeValidator {}
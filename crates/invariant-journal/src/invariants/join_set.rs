@@ -1,4 +1,4 @@
-//! JoinSet invariants (JS-1 through JS-7).
+//! JoinSet invariants (JS-1 through JS-8).
 //!
 //! These checks enforce the lifecycle and ownership rules for concurrent
 //! join sets. A join set progresses through three phases: Created →
@@ -10,6 +10,11 @@
 //! (JS-7), and each `(join_set_id, promise_id)` pair may be consumed at
 //! most once (JS-5). The global count invariant (JS-6) ensures awaits
 //! never exceed submissions per set.
+//!
+//! A join set and the promises submitted to it are allocated by the same
+//! scope, so members are siblings or descendants of the set's own position.
+//! JS-8 rejects members outside the subtree of the set's parent; a
+//! root-level set has no parent to compare against and is not checked.
 
 use invariant_types::{EventType, JournalEntry};
 
@@ -20,7 +25,8 @@ use super::InvariantState;
 /// Validate join-set invariants against the current accumulated state.
 ///
 /// The `JoinSetSubmitted` arm checks in order: JS-2 (frozen after await)
-/// before JS-1 (missing create) before JS-7 (multi-owner). JS-2 takes
+/// before JS-1 (missing create) before JS-7 (multi-owner) before JS-8
+/// (outside the set's call tree). JS-2 takes
 /// priority because submitting to a frozen set is a stronger violation
 /// than a missing create.
 ///
@@ -54,7 +60,7 @@ pub(crate) fn check(
             }
 
             // JS-7: a promise may belong to only one join set.
-            if let Some(first_js) = state.pid_owner.get(promise_id)
+            if let Some(first_js) = state.owner_of(promise_id)
                 && first_js != join_set_id
             {
                 return Err(Box::new(JournalViolation::PromiseInMultipleJoinSets {
@@ -63,16 +69,26 @@ pub(crate) fn check(
                     second_js: join_set_id.clone(),
                 }));
             }
+
+            // JS-8: the member must be allocated under the scope that created
+            // the set. Root-level sets carry no scope, so they are skipped.
+            if let Some(scope) = join_set_id.parent()
+                && !scope.is_ancestor_of(promise_id)
+            {
+                return Err(Box::new(JournalViolation::SubmittedOutsideJoinSetTree {
+                    join_set_id: join_set_id.clone(),
+                    promise_id: promise_id.clone(),
+                    seq: entry.sequence,
+                }));
+            }
         }
         EventType::JoinSetAwaited {
             join_set_id,
             promise_id,
             ..
         } => {
-            let pair = (join_set_id.clone(), promise_id.clone());
-
             // JS-3: awaited promise must be submitted to this set.
-            if !state.submitted_pairs.contains(&pair) {
+            if !state.is_submitted(join_set_id, promise_id) {
                 return Err(Box::new(JournalViolation::AwaitedNotMember {
                     join_set_id: join_set_id.clone(),
                     promise_id: promise_id.clone(),
@@ -81,7 +97,7 @@ pub(crate) fn check(
             }
 
             // JS-4: awaited promise must be completed.
-            if !state.completed_pids.contains(promise_id) {
+            if !state.is_completed(promise_id) {
                 return Err(Box::new(JournalViolation::AwaitedNotCompleted {
                     promise_id: promise_id.clone(),
                     awaited_seq: entry.sequence,
@@ -89,7 +105,7 @@ pub(crate) fn check(
             }
 
             // JS-5: the same (join_set_id, promise_id) cannot be consumed twice.
-            if state.consumed_pairs.contains(&pair) {
+            if state.is_consumed(join_set_id, promise_id) {
                 return Err(Box::new(JournalViolation::DoubleConsume {
                     join_set_id: join_set_id.clone(),
                     promise_id: promise_id.clone(),
@@ -238,6 +254,70 @@ mod tests {
         );
     }
 
+    #[test]
+    fn js8_submit_sibling_of_joinset_passes() {
+        let scope = pid(20);
+        let join_set_id = JoinSetId(scope.child(0).unwrap());
+        let state = InvariantState {
+            created_joinsets: std::iter::once(join_set_id.clone()).collect(),
+            ..Default::default()
+        };
+        let entry = mk_entry(
+            3,
+            EventType::JoinSetSubmitted {
+                join_set_id,
+                promise_id: scope.child(1).unwrap(),
+            },
+        );
+
+        assert!(check(&state, &entry).is_ok());
+    }
+
+    #[test]
+    fn js8_submit_outside_tree_reports_submitted_outside_join_set_tree() {
+        let join_set_id = JoinSetId(pid(20).child(0).unwrap());
+        let stranger = pid(21).child(1).unwrap();
+        let state = InvariantState {
+            created_joinsets: std::iter::once(join_set_id.clone()).collect(),
+            ..Default::default()
+        };
+        let entry = mk_entry(
+            3,
+            EventType::JoinSetSubmitted {
+                join_set_id: join_set_id.clone(),
+                promise_id: stranger.clone(),
+            },
+        );
+
+        let err = check(&state, &entry).unwrap_err();
+        assert_eq!(
+            *err,
+            JournalViolation::SubmittedOutsideJoinSetTree {
+                join_set_id,
+                promise_id: stranger,
+                seq: 3,
+            }
+        );
+    }
+
+    #[test]
+    fn js8_root_level_joinset_is_not_checked() {
+        let join_set_id = js(22);
+        let state = InvariantState {
+            created_joinsets: std::iter::once(join_set_id.clone()).collect(),
+            ..Default::default()
+        };
+        let entry = mk_entry(
+            3,
+            EventType::JoinSetSubmitted {
+                join_set_id,
+                promise_id: pid(23).child(0).unwrap(),
+            },
+        );
+
+        assert!(check(&state, &entry).is_ok());
+    }
+
     #[test]
     fn js7_submit_same_promise_to_different_joinset_reports_promise_in_multiple_join_sets() {
         let first_js = js(5);
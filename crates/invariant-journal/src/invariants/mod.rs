@@ -5,33 +5,72 @@
 //!   Used at append time to reject invalid entries before they hit the journal.
 //! - **Batch** ([`validate_journal`]): O(n) full scan that collects all violations.
 //!   Used for diagnostics and journal recovery.
+//! - **Probe** ([`validate_quick`]): bounded look at both ends of a journal that
+//!   rejects obvious damage before a full audit.
 //!
-//! Invariants are grouped into four sub-modules (21 checks total):
-//! - [`structural`] (S-1..S-5): Sequence numbering, lifecycle bookends, terminal uniqueness.
-//! - [`side_effects`] (SE-1..SE-4): Invoke lifecycle ordering/finality
-//!   (Scheduled -> Started -> Completed).
-//! - [`control_flow`] (CF-1..CF-4): Timer, signal, and await consistency.
-//! - [`join_set`] (JS-1..JS-7): JoinSet creation, submission, and consumption rules.
+//! Invariants are grouped into four sub-modules (29 checks total):
+//! - [`structural`] (S-1..S-5, S-7): Sequence numbering, lifecycle bookends, terminal
+//!   uniqueness, component upgrade chaining.
+//! - [`side_effects`] (SE-1..SE-5): Invoke lifecycle ordering/finality
+//!   (Scheduled -> Started -> Completed) and heartbeat liveness.
+//! - [`control_flow`] (CF-1..CF-9): Timer, signal, and await consistency.
+//! - [`join_set`] (JS-1..JS-8): JoinSet creation, submission, and consumption rules.
 //!
 //! Each sub-module exposes a single `check(&InvariantState, &JournalEntry) -> Result<(), JournalViolation>`
-//! function. Sub-modules are read-only over state; all mutations happen in [`InvariantState::apply_entry`].
+//! function. Sub-modules are read-only over state; every mutation is a [`StateDelta`] computed
+//! by [`InvariantState::delta_for`], which can be reverted for speculative validation.
 
+mod admissible;
+mod cancel;
+mod catalog;
+mod compaction;
 mod control_flow;
+mod delta;
+mod invoke;
 mod join_set;
+mod quick;
 mod side_effects;
 mod structural;
 
+pub use admissible::AdmissibleEvents;
+pub use cancel::{
+    CANCEL_CHECK_INTERVAL, CancelToken, CancellableResult, validate_journal_cancellable,
+};
+pub use catalog::{InvariantGroup, InvariantInfo, UNCATALOGUED, invariant_catalog};
+pub use compaction::StateCompaction;
+pub use delta::StateDelta;
+pub use invoke::{HttpInvokeValidator, InvokeValidator};
+pub use quick::{QUICK_MAX_ENTRIES, QUICK_WINDOW, QuickVerdict, validate_quick};
+
+use std::sync::Arc;
+
+use delta::Change;
+
 use crate::error::JournalViolation;
+use crate::overrides::OverrideLedger;
 use invariant_types::{
-    EventType, ExecutionJournal, JoinSetId, JournalEntry, Payload, PromiseId, SignalDeliveryId,
+    AwaitKind, EventType, ExecutionId, ExecutionJournal, JoinSetId, JournalEntry, Payload,
+    PromiseId, SignalDeliveryId, SignalName,
 };
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+/// An outstanding signal await (CF-5).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct SignalAwait {
+    /// The promise the resolving `SignalReceived` must name.
+    pub(crate) promise_id: PromiseId,
+    /// For `AnySignal`, the listed names: the resolving receive must take
+    /// the next pending delivery among them, as
+    /// [`next_signal_delivery`](crate::resolution::next_signal_delivery)
+    /// picks it.
+    pub(crate) any_of: Option<Vec<String>>,
+}
 
 /// Accumulated state for O(1) incremental invariant checking.
 ///
-/// Fields are `pub(crate)` for sub-module checkers; only [`apply_entry`]
-/// mutates them.
-#[derive(Clone, Debug, Default)]
+/// Fields are `pub(crate)` for sub-module checkers; only [`StateDelta`]s
+/// from [`delta_for`](Self::delta_for) mutate them.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct InvariantState {
     /// Number of entries ingested so far. Used by S-1 (expected sequence == len).
     pub(crate) len: usize,
@@ -44,28 +83,61 @@ pub struct InvariantState {
     /// before `ExecutionCancelled` is allowed.
     pub(crate) has_cancel_requested: bool,
 
+    /// Component digest currently in effect: the start digest, replaced by
+    /// each `ComponentUpgraded`. Checked by S-7.
+    pub(crate) component_digest: Option<Vec<u8>>,
+
     /// Promise IDs from `InvokeScheduled` events. Checked by SE-1.
     pub(crate) scheduled_pids: HashSet<PromiseId>,
 
     /// Promise IDs from `InvokeStarted` events. Checked by SE-2.
     pub(crate) started_pids: HashSet<PromiseId>,
 
-    /// `(promise_id, attempt)` pairs from `InvokeStarted` events. Checked by SE-3.
+    /// `(promise_id, attempt)` pairs from `InvokeStarted` events. Checked by SE-3
+    /// and SE-5.
     pub(crate) started_attempts: HashSet<(PromiseId, u32)>,
 
+    /// `(promise_id, failed_attempt)` pairs from `InvokeRetrying` events.
+    /// Checked by SE-5.
+    pub(crate) retried_attempts: HashSet<(PromiseId, u32)>,
+
     /// Promise IDs from `InvokeCompleted` events. Checked by SE-4 and JS-4.
     pub(crate) completed_pids: HashSet<PromiseId>,
 
+    /// Promise IDs resolved for wait satisfaction (`InvokeCompleted`,
+    /// `TimerFired`, `SignalReceived`). Checked by CF-6.
+    pub(crate) resolved_pids: HashSet<PromiseId>,
+
     /// Promise IDs from `TimerScheduled` events. Checked by CF-1.
     pub(crate) scheduled_timer_pids: HashSet<PromiseId>,
 
     /// Delivered signals keyed by `(name, delivery_id)`, with payload stored
-    /// for the equality check in CF-2.
+    /// for the equality check in CF-2. A key is delivered at most once (CF-9).
     pub(crate) delivered_signals: HashMap<(String, SignalDeliveryId), Payload>,
 
+    /// `(signal_name, external_id)` pairs from `SignalDelivered`. Checked by CF-7.
+    pub(crate) external_deliveries: HashSet<(String, String)>,
+
     /// Signal deliveries already consumed by a `SignalReceived`. Checked by CF-3.
     pub(crate) consumed_signal_deliveries: HashSet<(String, SignalDeliveryId)>,
 
+    /// Signal deliveries dropped by `SignalDeliveryExpired`. Checked by CF-2
+    /// and CF-8.
+    pub(crate) expired_signal_deliveries: HashSet<(String, SignalDeliveryId)>,
+
+    /// Buffered (delivered, unconsumed, unexpired) delivery IDs per signal
+    /// name; names with none are absent. Derived from the three sets above
+    /// so CF-5 can find the next `AnySignal` delivery without a scan.
+    pub(crate) pending_signals: HashMap<String, BTreeSet<SignalDeliveryId>>,
+
+    /// The outstanding `ExecutionAwaiting` of `Signal` or `AnySignal` kind,
+    /// if the execution is blocked on a signal. Checked by CF-5.
+    pub(crate) signal_await: Option<SignalAwait>,
+
+    /// `waiting_on` of the outstanding `ExecutionAwaiting`, while blocked.
+    /// Checked by CF-6.
+    pub(crate) active_await: Option<HashSet<PromiseId>>,
+
     /// Join set IDs from `JoinSetCreated` events. Checked by JS-1.
     pub(crate) created_joinsets: HashSet<JoinSetId>,
 
@@ -82,8 +154,21 @@ pub struct InvariantState {
     /// Per join set: `(submitted_count, awaited_count)`. Checked by JS-6.
     pub(crate) joinset_counts: HashMap<JoinSetId, (u32, u32)>,
 
+    /// Join sets whose every submitted member has been awaited
+    /// (`awaited == submitted > 0`). Lets cleanup find fully consumed sets
+    /// without comparing counts.
+    pub(crate) completed_join_sets: HashSet<JoinSetId>,
+
     /// Maps each promise to its owning join set (first writer wins). Checked by JS-7.
     pub(crate) pid_owner: HashMap<PromiseId, JoinSetId>,
+
+    /// Whether settled invoke lifecycles are folded into `sealed`.
+    pub(crate) compaction: StateCompaction,
+
+    /// Promises whose invoke lifecycle is sealed, with the join set that
+    /// owned them when sealed. Each stands for its entries in the promise
+    /// and join-set-pair sets above; see [`StateCompaction`].
+    pub(crate) sealed: HashMap<PromiseId, Option<JoinSetId>>,
 }
 
 impl InvariantState {
@@ -91,17 +176,142 @@ impl InvariantState {
         Self::default()
     }
 
+    /// Whether every member submitted to `js_id` has been awaited.
+    ///
+    /// A join set with no submissions is never complete.
+    pub fn is_joinset_complete(&self, js_id: &JoinSetId) -> bool {
+        self.completed_join_sets.contains(js_id)
+    }
+
+    /// Signals delivered but neither consumed nor expired, sorted by name
+    /// then delivery ID.
+    ///
+    /// The incremental counterpart of scanning for `SignalDelivered` entries
+    /// without a matching `SignalReceived` or `SignalDeliveryExpired`.
+    pub fn pending_signal_deliveries(&self) -> Vec<(&str, SignalDeliveryId, &Payload)> {
+        let mut pending: Vec<_> = self
+            .delivered_signals
+            .iter()
+            .filter(|(key, _)| self.is_signal_buffered(key))
+            .map(|((name, id), payload)| (name.as_str(), *id, payload))
+            .collect();
+        pending.sort_by(|a, b| (a.0, a.1).cmp(&(b.0, b.1)));
+        pending
+    }
+
+    /// Whether the delivery `key` was delivered and is neither consumed nor
+    /// expired.
+    pub(crate) fn is_signal_buffered(&self, key: &(String, SignalDeliveryId)) -> bool {
+        self.delivered_signals.contains_key(key)
+            && !self.consumed_signal_deliveries.contains(key)
+            && !self.expired_signal_deliveries.contains(key)
+    }
+
+    /// Whether a `SignalReceived` of `(signal_name, delivery_id)` takes the
+    /// delivery the outstanding signal await expects (CF-5).
+    ///
+    /// Only an `AnySignal` await constrains the delivery: it must be the
+    /// lowest pending `(name, delivery_id)` among the listed names. Looks at
+    /// the head of each listed name's queue, so the cost grows with the
+    /// number of names, not with the number of buffered deliveries.
+    pub(crate) fn receives_next_delivery(
+        &self,
+        signal_name: &str,
+        delivery_id: SignalDeliveryId,
+    ) -> bool {
+        let Some(names) = self.signal_await.as_ref().and_then(|a| a.any_of.as_ref()) else {
+            return true;
+        };
+        let next = names
+            .iter()
+            .filter_map(|name| Some((name.as_str(), *self.pending_signals.get(name)?.first()?)))
+            .min();
+        next == Some((signal_name, delivery_id))
+    }
+
+    /// Bring `pending_signals` in line with whether `key` is buffered, after
+    /// a change to one of the sets it is derived from.
+    pub(crate) fn sync_pending_signal(&mut self, key: &(String, SignalDeliveryId)) {
+        let (name, delivery_id) = key;
+        if self.is_signal_buffered(key) {
+            self.pending_signals
+                .entry(name.clone())
+                .or_default()
+                .insert(*delivery_id);
+        } else if let Some(ids) = self.pending_signals.get_mut(name) {
+            ids.remove(delivery_id);
+            if ids.is_empty() {
+                self.pending_signals.remove(name);
+            }
+        }
+    }
+
+    /// Number of `SignalDelivered` entries seen for `name`.
+    ///
+    /// O(number of delivered signals); keep per-name counters if this
+    /// becomes hot.
+    pub fn delivered_signal_count(&self, name: &SignalName) -> usize {
+        self.delivered_signals
+            .keys()
+            .filter(|(signal_name, _)| signal_name == name.as_str())
+            .count()
+    }
+
+    /// Number of deliveries of `name` consumed by a `SignalReceived`.
+    ///
+    /// O(number of consumed signals).
+    pub fn consumed_signal_count(&self, name: &SignalName) -> usize {
+        self.consumed_signal_deliveries
+            .iter()
+            .filter(|(signal_name, _)| signal_name == name.as_str())
+            .count()
+    }
+
+    /// Borrow a read-only view of the accumulated state.
+    pub fn snapshot(&self) -> InvariantStateSnapshot<'_> {
+        InvariantStateSnapshot { state: self }
+    }
+
     /// Validate and ingest a single journal entry.
     ///
-    /// Runs all 21 invariant checks against the current accumulated state,
+    /// Runs all 29 invariant checks against the current accumulated state,
     /// then updates state on success.
     pub fn check_append(&mut self, entry: &JournalEntry) -> Result<(), Box<JournalViolation>> {
+        self.check_entry(entry)?;
+        self.apply_entry(entry);
+        Ok(())
+    }
+
+    /// Validate and ingest `entries` as a unit.
+    ///
+    /// Each entry is checked against the state left by the ones before it,
+    /// exactly as repeated [`check_append`](Self::check_append) would. On
+    /// the first violation every entry of the batch is reverted, leaving
+    /// the state as it was before the call.
+    pub fn check_append_batch(
+        &mut self,
+        entries: &[JournalEntry],
+    ) -> Result<(), Box<JournalViolation>> {
+        let mut applied = Vec::with_capacity(entries.len());
+        for entry in entries {
+            if let Err(violation) = self.check_entry(entry) {
+                for delta in applied.iter().rev() {
+                    self.revert(delta);
+                }
+                return Err(violation);
+            }
+            let delta = self.delta_for(entry);
+            self.apply(&delta);
+            applied.push(delta);
+        }
+        Ok(())
+    }
+
+    fn check_entry(&self, entry: &JournalEntry) -> Result<(), Box<JournalViolation>> {
         structural::check(self, entry)?;
         side_effects::check(self, entry)?;
         control_flow::check(self, entry)?;
-        join_set::check(self, entry)?;
-        self.apply_entry(entry);
-        Ok(())
+        join_set::check(self, entry)
     }
 
     /// Run all invariant groups, collecting up to one violation per group.
@@ -110,12 +320,15 @@ impl InvariantState {
     /// all four groups run regardless of earlier failures. Used by
     /// [`validate_journal`] to surface multiple independent issues in a
     /// single pass over a corrupt journal.
-    fn collect_entry_violations(
+    ///
+    /// `entry_index` is the entry's position in the journal being validated.
+    pub(crate) fn collect_entry_violations(
         &self,
+        entry_index: usize,
         entry: &JournalEntry,
         violations: &mut Vec<JournalViolation>,
     ) {
-        if let Err(v) = structural::check(self, entry) {
+        if let Err(v) = structural::check_at(self, entry_index, entry) {
             violations.push(*v);
         }
         if let Err(v) = side_effects::check(self, entry) {
@@ -129,22 +342,119 @@ impl InvariantState {
         }
     }
 
+    /// Ingest every entry of an already-validated journal without checking it.
+    ///
+    /// Bulk form of the update [`check_append`](Self::check_append) performs
+    /// per entry. A counting pass sizes each tracked set first, so building
+    /// state for a large journal allocates once per set instead of growing
+    /// repeatedly. Callers are expected to have run [`validate_journal`];
+    /// state built from an invalid journal is meaningless.
+    pub fn apply_entries_from_journal(&mut self, journal: &ExecutionJournal) {
+        let (mut scheduled, mut started, mut completed, mut timers, mut fired) = (0, 0, 0, 0, 0);
+        let (mut retried, mut delivered, mut external, mut received, mut expired) = (0, 0, 0, 0, 0);
+        let (mut created, mut submitted, mut awaited) = (0, 0, 0);
+        for entry in &journal.entries {
+            match entry.event {
+                EventType::InvokeScheduled { .. } => scheduled += 1,
+                EventType::InvokeStarted { .. } => started += 1,
+                EventType::InvokeRetrying { .. } => retried += 1,
+                EventType::InvokeCompleted { .. } => completed += 1,
+                EventType::TimerScheduled { .. } => timers += 1,
+                EventType::TimerFired { .. } => fired += 1,
+                EventType::SignalDelivered {
+                    ref external_id, ..
+                } => {
+                    delivered += 1;
+                    external += usize::from(external_id.is_some());
+                }
+                EventType::SignalReceived { .. } => received += 1,
+                EventType::SignalDeliveryExpired { .. } => expired += 1,
+                EventType::JoinSetCreated { .. } => created += 1,
+                EventType::JoinSetSubmitted { .. } => submitted += 1,
+                EventType::JoinSetAwaited { .. } => awaited += 1,
+                _ => {}
+            }
+        }
+
+        self.scheduled_pids.reserve(scheduled);
+        self.started_pids.reserve(started);
+        self.started_attempts.reserve(started);
+        self.retried_attempts.reserve(retried);
+        self.completed_pids.reserve(completed);
+        self.resolved_pids.reserve(completed + fired + received);
+        self.scheduled_timer_pids.reserve(timers);
+        self.delivered_signals.reserve(delivered);
+        self.external_deliveries.reserve(external);
+        self.consumed_signal_deliveries.reserve(received);
+        self.expired_signal_deliveries.reserve(expired);
+        // One queue per signal name, at most one per delivery.
+        self.pending_signals.reserve(delivered);
+        self.created_joinsets.reserve(created);
+        self.awaited_joinsets.reserve(created);
+        self.joinset_counts.reserve(created);
+        self.completed_join_sets.reserve(created);
+        self.submitted_pairs.reserve(submitted);
+        self.pid_owner.reserve(submitted);
+        self.consumed_pairs.reserve(awaited);
+        if self.compaction != StateCompaction::Off {
+            self.sealed.reserve(completed);
+        }
+
+        for entry in &journal.entries {
+            self.apply_entry(entry);
+        }
+    }
+
     /// Update auxiliary state after a validated entry.
-    fn apply_entry(&mut self, entry: &JournalEntry) {
+    ///
+    /// Applies [`delta_for`](Self::delta_for) and discards the delta.
+    pub(crate) fn apply_entry(&mut self, entry: &JournalEntry) {
+        let delta = self.delta_for(entry);
+        self.apply_owned(delta);
+    }
+
+    /// The state updates ingesting `entry` would make, without making them.
+    ///
+    /// Pass the result to [`apply`](Self::apply), and later to
+    /// [`revert`](Self::revert) to undo it.
+    pub fn delta_for(&self, entry: &JournalEntry) -> StateDelta {
+        let mut delta = StateDelta::default();
         match &entry.event {
             // S-3/S-4: record first terminal sequence number
             EventType::ExecutionCompleted { .. }
             | EventType::ExecutionFailed { .. }
-            | EventType::ExecutionCancelled { .. } => {
-                self.terminal_seq.get_or_insert(entry.sequence);
+            | EventType::ExecutionCancelled { .. }
+            | EventType::ContinuedAsNew { .. } => {
+                if self.terminal_seq.is_none() {
+                    delta.push(Change::TerminalSeq(entry.sequence));
+                }
+            }
+            // S-7: the digest every later upgrade must chain from.
+            EventType::ExecutionStarted {
+                component_digest: new,
+                ..
             }
-            // S-5: gate for ExecutionCancelled
+            | EventType::ComponentUpgraded {
+                new_digest: new, ..
+            } => {
+                delta.push(Change::ComponentDigest {
+                    old: self.component_digest.clone(),
+                    new: new.clone(),
+                });
+            }
+            // S-5: gate for ExecutionCancelled.
+            // CF-6: cancelling ends the blocked state.
             EventType::CancelRequested { .. } => {
-                self.has_cancel_requested = true;
+                if !self.has_cancel_requested {
+                    delta.push(Change::CancelRequested);
+                }
+                self.replace_active_await(&mut delta, None);
             }
             // SE-1: InvokeStarted requires this
             EventType::InvokeScheduled { promise_id, .. } => {
-                self.scheduled_pids.insert(promise_id.clone());
+                if !self.is_scheduled(promise_id) {
+                    delta.push(Change::ScheduledPid(promise_id.clone()));
+                }
             }
             // SE-2: InvokeCompleted requires started pid.
             // SE-3: InvokeRetrying requires started (pid, attempt).
@@ -152,104 +462,1132 @@ impl InvariantState {
                 promise_id,
                 attempt,
             } => {
-                let pid = promise_id.clone();
-                self.started_pids.insert(pid.clone());
-                self.started_attempts.insert((pid, *attempt));
+                if !self.is_started(promise_id) {
+                    delta.push(Change::StartedPid(promise_id.clone()));
+                }
+                if !self
+                    .started_attempts
+                    .contains(&(promise_id.clone(), *attempt))
+                {
+                    delta.push(Change::StartedAttempt(promise_id.clone(), *attempt));
+                }
             }
-            // SE-4: blocks further Started/Retrying/Completed; JS-4: gate for JoinSetAwaited
+            // SE-4: blocks further Started/Retrying/Completed; JS-4: gate for JoinSetAwaited.
+            // CF-6: resolves the promise for progress reports.
+            // Completing a promise no join set owns seals its lifecycle.
             EventType::InvokeCompleted { promise_id, .. } => {
-                self.completed_pids.insert(promise_id.clone());
+                if !self.is_completed(promise_id) {
+                    delta.push(Change::CompletedPid(promise_id.clone()));
+                }
+                self.resolve(&mut delta, promise_id);
+                self.seal_if_settled(&mut delta, promise_id);
+            }
+            // SE-5: a retried attempt no longer heartbeats.
+            EventType::InvokeRetrying {
+                promise_id,
+                failed_attempt,
+                ..
+            } => {
+                if !self
+                    .retried_attempts
+                    .contains(&(promise_id.clone(), *failed_attempt))
+                {
+                    delta.push(Change::RetriedAttempt(promise_id.clone(), *failed_attempt));
+                }
             }
             // CF-1: TimerFired requires this
             EventType::TimerScheduled { promise_id, .. } => {
-                self.scheduled_timer_pids.insert(promise_id.clone());
+                if !self.scheduled_timer_pids.contains(promise_id) {
+                    delta.push(Change::ScheduledTimerPid(promise_id.clone()));
+                }
+            }
+            // CF-6: resolves the promise for progress reports
+            EventType::TimerFired { promise_id } => {
+                self.resolve(&mut delta, promise_id);
             }
             // CF-2: SignalReceived checks name + delivery_id + payload match
+            // CF-9: a later delivery of the same key is rejected
+            // CF-7: records the producer's message ID
             EventType::SignalDelivered {
                 signal_name,
                 payload,
                 delivery_id,
+                external_id,
             } => {
-                self.delivered_signals
-                    .insert((signal_name.clone(), *delivery_id), payload.clone());
+                let key = (signal_name.clone(), *delivery_id);
+                delta.push(Change::DeliveredSignal {
+                    old: self.delivered_signals.get(&key).cloned(),
+                    key,
+                    new: payload.clone(),
+                });
+                if let Some(external_id) = external_id {
+                    let key = (signal_name.clone(), external_id.clone());
+                    if !self.external_deliveries.contains(&key) {
+                        delta.push(Change::ExternalDelivery(key.0, key.1));
+                    }
+                }
             }
-            // CF-3: tracks consumed deliveries for duplicate detection
+            // CF-3: tracks consumed deliveries for duplicate detection.
+            // CF-5: receiving the awaited signal settles the outstanding wait.
+            // CF-6: resolves the promise for progress reports.
             EventType::SignalReceived {
                 signal_name,
                 delivery_id,
+                promise_id,
                 ..
             } => {
-                self.consumed_signal_deliveries
-                    .insert((signal_name.clone(), *delivery_id));
+                if !self
+                    .consumed_signal_deliveries
+                    .contains(&(signal_name.clone(), *delivery_id))
+                {
+                    delta.push(Change::ConsumedSignal(signal_name.clone(), *delivery_id));
+                }
+                self.resolve(&mut delta, promise_id);
+                if self
+                    .signal_await
+                    .as_ref()
+                    .is_some_and(|awaited| &awaited.promise_id == promise_id)
+                {
+                    self.replace_signal_await(&mut delta, None);
+                }
+            }
+            // CF-5: a signal await becomes outstanding.
+            // CF-6: any await becomes the active one.
+            EventType::ExecutionAwaiting { waiting_on, kind } => {
+                let awaited = match kind {
+                    AwaitKind::Signal { promise_id, .. } => Some(SignalAwait {
+                        promise_id: promise_id.clone(),
+                        any_of: None,
+                    }),
+                    AwaitKind::AnySignal { names, promise_id } => Some(SignalAwait {
+                        promise_id: promise_id.clone(),
+                        any_of: Some(names.clone()),
+                    }),
+                    _ => None,
+                };
+                if awaited.is_some() {
+                    self.replace_signal_await(&mut delta, awaited);
+                }
+                self.replace_active_await(&mut delta, Some(waiting_on.iter().cloned().collect()));
+            }
+            // CF-5/CF-6: resuming ends any outstanding wait
+            EventType::ExecutionResumed => {
+                self.replace_signal_await(&mut delta, None);
+                self.replace_active_await(&mut delta, None);
             }
             // JS-1: JoinSetSubmitted requires this
             EventType::JoinSetCreated { join_set_id } => {
-                self.created_joinsets.insert(join_set_id.clone());
+                if !self.created_joinsets.contains(join_set_id) {
+                    delta.push(Change::CreatedJoinSet(join_set_id.clone()));
+                }
             }
             // JS-2 (submitted_pairs), JS-6 (counts), JS-7 (pid_owner)
             EventType::JoinSetSubmitted {
                 join_set_id,
                 promise_id,
             } => {
-                self.submitted_pairs
-                    .insert((join_set_id.clone(), promise_id.clone()));
+                if !self.is_submitted(join_set_id, promise_id) {
+                    delta.push(Change::SubmittedPair(
+                        join_set_id.clone(),
+                        promise_id.clone(),
+                    ));
+                }
 
-                let counts = self
-                    .joinset_counts
-                    .entry(join_set_id.clone())
-                    .or_insert((0, 0));
-                counts.0 = counts.0.saturating_add(1);
+                let old = self.joinset_counts.get(join_set_id).copied();
+                let (submitted, awaited) = old.unwrap_or((0, 0));
+                delta.push(Change::JoinSetCounts {
+                    join_set_id: join_set_id.clone(),
+                    old,
+                    new: (submitted.saturating_add(1), awaited),
+                });
 
-                self.pid_owner
-                    .entry(promise_id.clone())
-                    .or_insert(join_set_id.clone());
+                if self.owner_of(promise_id).is_none() {
+                    delta.push(Change::PidOwner(promise_id.clone(), join_set_id.clone()));
+                }
             }
-            // JS-2 (freezes set), JS-5 (consumed_pairs), JS-6 (counts)
+            // JS-2 (freezes set), JS-5 (consumed_pairs), JS-6 (counts),
+            // plus completion tracking once the last member is awaited.
+            // Consuming a completed member seals its lifecycle.
             EventType::JoinSetAwaited {
                 join_set_id,
                 promise_id,
                 ..
             } => {
-                self.awaited_joinsets.insert(join_set_id.clone());
-                self.consumed_pairs
-                    .insert((join_set_id.clone(), promise_id.clone()));
+                if !self.awaited_joinsets.contains(join_set_id) {
+                    delta.push(Change::AwaitedJoinSet(join_set_id.clone()));
+                }
+                if !self.is_consumed(join_set_id, promise_id) {
+                    delta.push(Change::ConsumedPair(
+                        join_set_id.clone(),
+                        promise_id.clone(),
+                    ));
+                }
 
-                let counts = self
-                    .joinset_counts
-                    .entry(join_set_id.clone())
-                    .or_insert((0, 0));
-                counts.1 = counts.1.saturating_add(1);
+                let old = self.joinset_counts.get(join_set_id).copied();
+                let (submitted, awaited) = old.unwrap_or((0, 0));
+                let awaited = awaited.saturating_add(1);
+                delta.push(Change::JoinSetCounts {
+                    join_set_id: join_set_id.clone(),
+                    old,
+                    new: (submitted, awaited),
+                });
+                if submitted > 0
+                    && submitted == awaited
+                    && !self.completed_join_sets.contains(join_set_id)
+                {
+                    delta.push(Change::CompletedJoinSet(join_set_id.clone()));
+                }
+                self.seal_if_settled(&mut delta, promise_id);
+            }
+            // CF-2: an expired delivery can no longer be received.
+            // CF-8: it cannot be expired twice.
+            EventType::SignalDeliveryExpired {
+                signal_name,
+                delivery_id,
+            } => {
+                if !self
+                    .expired_signal_deliveries
+                    .contains(&(signal_name.clone(), *delivery_id))
+                {
+                    delta.push(Change::ExpiredSignal(signal_name.clone(), *delivery_id));
+                }
             }
             // Events that don't contribute to invariant state:
-            // ExecutionStarted, ExecutionAwaiting, ExecutionResumed,
-            // InvokeRetrying, TimerFired, RandomGenerated, TimeRecorded
+            // InvokeHeartbeat, RandomGenerated, TimeRecorded,
+            // AwaitProgress
             _ => {}
         }
-        self.len += 1;
+        delta.push(Change::Len);
+        delta
+    }
+
+    fn resolve(&self, delta: &mut StateDelta, promise_id: &PromiseId) {
+        if !self.is_resolved(promise_id) {
+            delta.push(Change::ResolvedPid(promise_id.clone()));
+        }
+    }
+
+    fn replace_signal_await(&self, delta: &mut StateDelta, new: Option<SignalAwait>) {
+        if self.signal_await != new {
+            delta.push(Change::SignalAwait {
+                old: self.signal_await.clone(),
+                new,
+            });
+        }
+    }
+
+    fn replace_active_await(&self, delta: &mut StateDelta, new: Option<HashSet<PromiseId>>) {
+        if self.active_await != new {
+            delta.push(Change::ActiveAwait {
+                old: self.active_await.clone(),
+                new,
+            });
+        }
     }
 }
 
+/// Zero-copy, read-only view of an [`InvariantState`].
+///
+/// Obtained from [`InvariantState::snapshot`]; borrows the state it views.
+/// Under [`StateCompaction::SealedLifecycles`] the promise sets omit
+/// promises whose lifecycle has been sealed.
+#[derive(Clone, Copy, Debug)]
+pub struct InvariantStateSnapshot<'a> {
+    state: &'a InvariantState,
+}
+
+impl<'a> InvariantStateSnapshot<'a> {
+    /// Promise IDs from `InvokeScheduled` events.
+    pub fn scheduled_pids(&self) -> &'a HashSet<PromiseId> {
+        &self.state.scheduled_pids
+    }
+
+    /// Promise IDs from `InvokeStarted` events.
+    pub fn started_pids(&self) -> &'a HashSet<PromiseId> {
+        &self.state.started_pids
+    }
+
+    /// Promise IDs from `InvokeCompleted` events.
+    pub fn completed_pids(&self) -> &'a HashSet<PromiseId> {
+        &self.state.completed_pids
+    }
+
+    /// Join set IDs from `JoinSetCreated` events.
+    pub fn created_joinsets(&self) -> &'a HashSet<JoinSetId> {
+        &self.state.created_joinsets
+    }
+
+    /// Join sets that have had at least one `JoinSetAwaited`.
+    pub fn awaited_joinsets(&self) -> &'a HashSet<JoinSetId> {
+        &self.state.awaited_joinsets
+    }
+}
+
+/// Optional settings for [`validate_journal_with`] on top of the invariant
+/// checks.
+///
+/// Every field is off by default: ingestion guards (`max_payload_bytes`,
+/// `invoke_validators`, `verify_execution_id`), the operator
+/// [`OverrideLedger`], and scan tuning. None of them are journal invariants,
+/// so the guards' violations are [`UNCATALOGUED`].
+#[derive(Clone, Debug, Default)]
+pub struct ValidationOptions {
+    /// Largest payload, in bytes, a single event may embed. Oversized
+    /// payloads are reported as [`JournalViolation::PayloadTooLarge`].
+    pub max_payload_bytes: Option<usize>,
+    /// Operator overrides recorded for this journal. Violations the ledger
+    /// sanctions are reported as [`ValidationReport::warnings`] instead.
+    pub overrides: Option<OverrideLedger>,
+    /// Report [`JournalViolation::ExecutionIdMismatch`] when the journal's
+    /// execution ID is not the derivation of its `ExecutionStarted`.
+    ///
+    /// Off by default because the incremental path never sees the ID, and
+    /// journals stored under an ID of the caller's choosing would otherwise
+    /// validate differently in batch.
+    pub verify_execution_id: bool,
+    /// Hooks run on every `InvokeScheduled`. Rejections are reported as
+    /// [`JournalViolation::InvokeRejected`].
+    pub invoke_validators: Vec<Arc<dyn InvokeValidator>>,
+    /// After an S-2 violation (the first entry is not `ExecutionStarted`),
+    /// report only structural violations.
+    ///
+    /// A journal that lost its head, the common truncation corruption, is
+    /// otherwise checked against state that was never initialized, and every
+    /// later entry can trip spurious side-effect, control-flow or join-set
+    /// violations. Ingestion guards (`max_payload_bytes`,
+    /// `invoke_validators`) do not depend on that state and still apply.
+    pub structural_only_after_missing_start: bool,
+    /// How the scan's invariant state retains settled lifecycles. Verdicts
+    /// are the same in every mode; only memory use differs.
+    pub compaction: StateCompaction,
+}
+
+/// Outcome of [`validate_journal_report`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ValidationReport {
+    pub violations: Vec<JournalViolation>,
+    /// Violations sanctioned by [`ValidationOptions::overrides`].
+    pub warnings: Vec<JournalViolation>,
+}
+
 /// Batch-validate an entire journal, returning all detected violations.
 ///
+/// Equivalent to [`validate_journal_with`] using default
+/// [`ValidationOptions`].
+pub fn validate_journal(journal: &ExecutionJournal) -> Vec<JournalViolation> {
+    validate_journal_with(journal, &ValidationOptions::default())
+}
+
+/// Batch-validate an entire journal under `options`.
+///
+/// Returns the [`ValidationReport::violations`] of
+/// [`validate_journal_report`]; sanctioned overrides are left out.
+pub fn validate_journal_with(
+    journal: &ExecutionJournal,
+    options: &ValidationOptions,
+) -> Vec<JournalViolation> {
+    validate_journal_report(journal, options).violations
+}
+
+/// Batch-validate an entire journal under `options`, separating sanctioned
+/// overrides from violations.
+///
 /// Creates a fresh [`InvariantState`] and feeds every entry through
 /// [`InvariantState::collect_entry_violations`], always applying state
 /// regardless of errors so that later entries are checked against accurate
 /// accumulated state. An empty journal is reported as
-/// [`JournalViolation::MissingExecutionStarted`].
-pub fn validate_journal(journal: &ExecutionJournal) -> Vec<JournalViolation> {
+/// [`JournalViolation::MissingExecutionStarted`]. With
+/// [`ValidationOptions::verify_execution_id`], an execution ID that does
+/// not derive from the start event is reported as
+/// [`JournalViolation::ExecutionIdMismatch`].
+///
+/// With [`ValidationOptions::structural_only_after_missing_start`], an S-2
+/// failure switches the rest of the scan to structural checks only.
+pub fn validate_journal_report(
+    journal: &ExecutionJournal,
+    options: &ValidationOptions,
+) -> ValidationReport {
+    scan_report(journal, options, None).0
+}
+
+/// The scan behind [`validate_journal_report`], stopping early once `cancel`
+/// fires.
+///
+/// The token is polled before every [`CANCEL_CHECK_INTERVAL`]th entry.
+/// Returns the report for the entries scanned and how many that was.
+pub(crate) fn scan_report(
+    journal: &ExecutionJournal,
+    options: &ValidationOptions,
+    cancel: Option<&CancelToken>,
+) -> (ValidationReport, usize) {
+    let mut report = ValidationReport::default();
     if journal.entries.is_empty() {
-        return vec![JournalViolation::MissingExecutionStarted {
-            first_event: "<empty>".to_string(),
-        }];
+        report
+            .violations
+            .push(JournalViolation::MissingExecutionStarted {
+                first_event: "<empty>".to_string(),
+            });
+        return (report, 0);
     }
 
-    let mut state = InvariantState::new();
-    let mut violations = Vec::new();
+    if options.verify_execution_id
+        && let Some(violation) = check_execution_id(journal)
+    {
+        report.violations.push(violation);
+    }
+
+    let mut state = InvariantState::with_compaction(options.compaction);
+    let mut found = Vec::new();
+    let mut poisoned = false;
 
-    for entry in &journal.entries {
-        state.collect_entry_violations(entry, &mut violations);
+    for (index, entry) in journal.entries.iter().enumerate() {
+        if index % CANCEL_CHECK_INTERVAL == 0 && cancel.is_some_and(CancelToken::is_cancelled) {
+            return (report, index);
+        }
+        if poisoned {
+            if let Err(v) = structural::check_at(&state, index, entry) {
+                found.push(*v);
+            }
+        } else {
+            state.collect_entry_violations(index, entry, &mut found);
+            // Structural checks run first, so an S-2 failure leads `found`.
+            if options.structural_only_after_missing_start
+                && matches!(
+                    found.first(),
+                    Some(JournalViolation::MissingExecutionStarted { .. })
+                )
+            {
+                found.truncate(1);
+                poisoned = true;
+            }
+        }
+        if let Some(max) = options.max_payload_bytes {
+            check_payload_sizes(entry, max, &mut found);
+        }
+        check_invoke(entry, &options.invoke_validators, &mut found);
+        for violation in found.drain(..) {
+            let sanctioned = options
+                .overrides
+                .as_ref()
+                .is_some_and(|ledger| ledger.sanctions(entry.sequence, &violation));
+            if sanctioned {
+                report.warnings.push(violation);
+            } else {
+                report.violations.push(violation);
+            }
+        }
         state.apply_entry(entry);
     }
 
-    violations
+    (report, journal.entries.len())
+}
+
+/// Run [`validate_journal`] over each journal, keyed by execution ID.
+///
+/// Only failing journals appear in the map. Journals sharing an execution ID
+/// have their violations concatenated in input order.
+pub fn validate_many(journals: &[ExecutionJournal]) -> HashMap<ExecutionId, Vec<JournalViolation>> {
+    let mut report: HashMap<ExecutionId, Vec<JournalViolation>> = HashMap::new();
+    for journal in journals {
+        let violations = validate_journal(journal);
+        if !violations.is_empty() {
+            report
+                .entry(journal.execution_id.clone())
+                .or_default()
+                .extend(violations);
+        }
+    }
+    report
+}
+
+/// Number of leading entries that [`InvariantState::check_append`] accepts.
+///
+/// Feeds entries through a fresh [`InvariantState`] and stops at the first
+/// rejection, so `journal.entries[..n]` is the longest valid prefix. Returns
+/// `journal.entries.len()` when the whole journal is clean. Recovery tooling
+/// uses this to salvage the usable head of a corrupt journal.
+pub fn valid_prefix_len(journal: &ExecutionJournal) -> usize {
+    let mut state = InvariantState::new();
+    journal
+        .entries
+        .iter()
+        .take_while(|entry| state.check_append(entry).is_ok())
+        .count()
+}
+
+/// The journal's execution ID must be the derivation of its start event.
+pub(crate) fn check_execution_id(journal: &ExecutionJournal) -> Option<JournalViolation> {
+    let EventType::ExecutionStarted {
+        component_digest,
+        idempotency_key,
+        parent_id,
+        ..
+    } = &journal.entries.first()?.event
+    else {
+        return None;
+    };
+    let expected = ExecutionId::derive(component_digest, idempotency_key, parent_id.as_ref());
+    (expected != journal.execution_id).then(|| JournalViolation::ExecutionIdMismatch {
+        expected,
+        actual: journal.execution_id.clone(),
+    })
+}
+
+fn check_invoke(
+    entry: &JournalEntry,
+    validators: &[Arc<dyn InvokeValidator>],
+    violations: &mut Vec<JournalViolation>,
+) {
+    let EventType::InvokeScheduled {
+        promise_id,
+        kind,
+        function_name,
+        ..
+    } = &entry.event
+    else {
+        return;
+    };
+    for validator in validators {
+        if let Err(reason) = validator.validate(promise_id, kind, function_name) {
+            violations.push(JournalViolation::InvokeRejected {
+                promise_id: promise_id.clone(),
+                seq: entry.sequence,
+                reason,
+            });
+        }
+    }
+}
+
+fn check_payload_sizes(entry: &JournalEntry, max: usize, violations: &mut Vec<JournalViolation>) {
+    for payload in entry.event.payloads() {
+        if payload.is_larger_than(max) {
+            violations.push(JournalViolation::PayloadTooLarge {
+                seq: entry.sequence,
+                size: payload.byte_size(),
+                max,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::DateTime;
+    use invariant_types::{Codec, ExecutionId, InvokeKind};
+
+    fn payload() -> Payload {
+        Payload::new(vec![], Codec::Json)
+    }
+
+    fn mk_entry(sequence: u64, event: EventType) -> JournalEntry {
+        JournalEntry {
+            sequence,
+            timestamp: DateTime::UNIX_EPOCH,
+            event,
+        }
+    }
+
+    fn started() -> EventType {
+        EventType::ExecutionStarted {
+            component_digest: vec![1],
+            input: payload(),
+            parent_id: None,
+            idempotency_key: "k".into(),
+        }
+    }
+
+    fn journal(entries: Vec<JournalEntry>) -> ExecutionJournal {
+        ExecutionJournal {
+            execution_id: ExecutionId::derive(&[1], "k", None),
+            entries,
+            provenance: None,
+        }
+    }
+
+    #[test]
+    fn apply_entries_from_journal_matches_check_append() {
+        let p = PromiseId::new([1; 32]);
+        let js = JoinSetId(PromiseId::new([2; 32]));
+        let j = journal(vec![
+            mk_entry(0, started()),
+            mk_entry(
+                1,
+                EventType::JoinSetCreated {
+                    join_set_id: js.clone(),
+                },
+            ),
+            mk_entry(
+                2,
+                EventType::InvokeScheduled {
+                    promise_id: p.clone(),
+                    kind: invariant_types::InvokeKind::Function,
+                    function_name: "f".into(),
+                    input: payload(),
+                    retry_policy: None,
+                    sla: None,
+                },
+            ),
+            mk_entry(
+                3,
+                EventType::JoinSetSubmitted {
+                    join_set_id: js.clone(),
+                    promise_id: p.clone(),
+                },
+            ),
+            mk_entry(
+                4,
+                EventType::InvokeStarted {
+                    promise_id: p.clone(),
+                    attempt: 1,
+                },
+            ),
+            mk_entry(
+                5,
+                EventType::InvokeCompleted {
+                    promise_id: p.clone(),
+                    result: payload(),
+                    attempt: 1,
+                },
+            ),
+            mk_entry(
+                6,
+                EventType::JoinSetAwaited {
+                    join_set_id: js.clone(),
+                    promise_id: p.clone(),
+                    result: payload(),
+                },
+            ),
+            mk_entry(
+                7,
+                EventType::SignalDelivered {
+                    signal_name: "sig".into(),
+                    payload: payload(),
+                    delivery_id: 0,
+                    external_id: Some("msg-1".into()),
+                },
+            ),
+            mk_entry(
+                8,
+                EventType::SignalDelivered {
+                    signal_name: "sig".into(),
+                    payload: payload(),
+                    delivery_id: 1,
+                    external_id: None,
+                },
+            ),
+            mk_entry(
+                9,
+                EventType::SignalDeliveryExpired {
+                    signal_name: "sig".into(),
+                    delivery_id: 0,
+                },
+            ),
+        ]);
+        assert!(validate_journal(&j).is_empty());
+
+        let mut incremental = InvariantState::new();
+        for entry in &j.entries {
+            incremental.check_append(entry).unwrap();
+        }
+        let mut bulk = InvariantState::new();
+        bulk.apply_entries_from_journal(&j);
+
+        assert_eq!(bulk, incremental);
+        assert_eq!(
+            bulk.check_append(&mk_entry(10, EventType::ExecutionResumed)),
+            incremental.check_append(&mk_entry(10, EventType::ExecutionResumed))
+        );
+    }
+
+    #[test]
+    fn validate_journal_reports_array_index_for_out_of_order_entries() {
+        let j = journal(vec![
+            mk_entry(0, started()),
+            mk_entry(2, EventType::ExecutionResumed),
+            mk_entry(1, EventType::ExecutionResumed),
+            mk_entry(3, EventType::ExecutionResumed),
+        ]);
+
+        let violations = validate_journal(&j);
+
+        assert_eq!(
+            violations,
+            vec![
+                JournalViolation::NonMonotonicSequence {
+                    entry_index: 1,
+                    expected: 1,
+                    actual: 2,
+                },
+                JournalViolation::NonMonotonicSequence {
+                    entry_index: 2,
+                    expected: 2,
+                    actual: 1,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn validate_journal_accepts_well_formed_journal() {
+        let j = journal(vec![
+            mk_entry(0, started()),
+            mk_entry(1, EventType::ExecutionCompleted { result: payload() }),
+        ]);
+        assert!(validate_journal(&j).is_empty());
+    }
+
+    #[test]
+    fn valid_prefix_len_stops_at_first_rejected_entry() {
+        let j = journal(vec![
+            mk_entry(0, started()),
+            mk_entry(1, EventType::ExecutionResumed),
+            mk_entry(2, EventType::ExecutionResumed),
+            mk_entry(3, EventType::ExecutionCompleted { result: payload() }),
+            // Entry 4 follows a terminal event (S-4).
+            mk_entry(4, EventType::ExecutionResumed),
+            mk_entry(5, EventType::ExecutionResumed),
+        ]);
+        assert_eq!(valid_prefix_len(&j), 4);
+    }
+
+    #[test]
+    fn valid_prefix_len_of_clean_journal_is_full_length() {
+        let j = journal(vec![
+            mk_entry(0, started()),
+            mk_entry(1, EventType::ExecutionCompleted { result: payload() }),
+        ]);
+        assert_eq!(valid_prefix_len(&j), 2);
+        assert_eq!(valid_prefix_len(&journal(vec![])), 0);
+    }
+
+    #[test]
+    fn pending_signal_deliveries_excludes_consumed() {
+        let delivered = |delivery_id, byte| EventType::SignalDelivered {
+            signal_name: "sig".into(),
+            payload: Payload::new(vec![byte], Codec::Json),
+            delivery_id,
+            external_id: None,
+        };
+        let mut state = InvariantState::new();
+        for (seq, event) in [
+            started(),
+            delivered(0, 0xa),
+            delivered(1, 0xb),
+            EventType::SignalReceived {
+                promise_id: PromiseId::new([1; 32]),
+                signal_name: "sig".into(),
+                payload: Payload::new(vec![0xa], Codec::Json),
+                delivery_id: 0,
+            },
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            state.check_append(&mk_entry(seq as u64, event)).unwrap();
+        }
+
+        let pending_payload = Payload::new(vec![0xb], Codec::Json);
+        assert_eq!(
+            state.pending_signal_deliveries(),
+            vec![("sig", 1, &pending_payload)]
+        );
+    }
+
+    #[test]
+    fn signal_counts_are_per_name() {
+        let delivered = |name: &str, delivery_id| EventType::SignalDelivered {
+            signal_name: name.into(),
+            payload: payload(),
+            delivery_id,
+            external_id: None,
+        };
+        let received = |tag, name: &str, delivery_id| EventType::SignalReceived {
+            promise_id: PromiseId::new([tag; 32]),
+            signal_name: name.into(),
+            payload: payload(),
+            delivery_id,
+        };
+        let mut state = InvariantState::new();
+        for (seq, event) in [
+            started(),
+            delivered("approve", 0),
+            delivered("approve", 1),
+            delivered("approve", 2),
+            delivered("approved", 0),
+            received(1, "approve", 0),
+            received(2, "approved", 0),
+            received(3, "approve", 1),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            state.check_append(&mk_entry(seq as u64, event)).unwrap();
+        }
+
+        let signal = |name| SignalName::new(name).unwrap();
+        assert_eq!(state.delivered_signal_count(&signal("approve")), 3);
+        assert_eq!(state.consumed_signal_count(&signal("approve")), 2);
+        assert_eq!(state.delivered_signal_count(&signal("approved")), 1);
+        assert_eq!(state.consumed_signal_count(&signal("approved")), 1);
+        assert_eq!(state.delivered_signal_count(&signal("missing")), 0);
+        assert_eq!(state.consumed_signal_count(&signal("missing")), 0);
+    }
+
+    #[test]
+    fn joinset_completes_when_last_member_awaited() {
+        let js = JoinSetId(PromiseId::new([9; 32]));
+        let members = [PromiseId::new([1; 32]), PromiseId::new([2; 32])];
+        let mut state = InvariantState::new();
+        let mut seq = 0u64;
+        let mut append = |state: &mut InvariantState, event| {
+            state.check_append(&mk_entry(seq, event)).unwrap();
+            seq += 1;
+        };
+
+        append(&mut state, started());
+        append(
+            &mut state,
+            EventType::JoinSetCreated {
+                join_set_id: js.clone(),
+            },
+        );
+        for p in &members {
+            append(
+                &mut state,
+                EventType::InvokeScheduled {
+                    promise_id: p.clone(),
+                    kind: invariant_types::InvokeKind::Function,
+                    function_name: "f".into(),
+                    input: payload(),
+                    retry_policy: None,
+                    sla: None,
+                },
+            );
+            append(
+                &mut state,
+                EventType::JoinSetSubmitted {
+                    join_set_id: js.clone(),
+                    promise_id: p.clone(),
+                },
+            );
+            append(
+                &mut state,
+                EventType::InvokeStarted {
+                    promise_id: p.clone(),
+                    attempt: 1,
+                },
+            );
+            append(
+                &mut state,
+                EventType::InvokeCompleted {
+                    promise_id: p.clone(),
+                    result: payload(),
+                    attempt: 1,
+                },
+            );
+        }
+        assert!(!state.is_joinset_complete(&js));
+
+        append(
+            &mut state,
+            EventType::JoinSetAwaited {
+                join_set_id: js.clone(),
+                promise_id: members[0].clone(),
+                result: payload(),
+            },
+        );
+        assert!(!state.is_joinset_complete(&js));
+
+        append(
+            &mut state,
+            EventType::JoinSetAwaited {
+                join_set_id: js.clone(),
+                promise_id: members[1].clone(),
+                result: payload(),
+            },
+        );
+        assert!(state.is_joinset_complete(&js));
+    }
+
+    #[test]
+    fn joinset_without_submissions_is_never_complete() {
+        let js = JoinSetId(PromiseId::new([9; 32]));
+        let mut state = InvariantState::new();
+        state.check_append(&mk_entry(0, started())).unwrap();
+        state
+            .check_append(&mk_entry(
+                1,
+                EventType::JoinSetCreated {
+                    join_set_id: js.clone(),
+                },
+            ))
+            .unwrap();
+        assert!(!state.is_joinset_complete(&js));
+
+        // Even a stray await (rejected by validation, but applied in batch
+        // mode) does not mark an empty set complete.
+        state.apply_entry(&mk_entry(
+            2,
+            EventType::JoinSetAwaited {
+                join_set_id: js.clone(),
+                promise_id: PromiseId::new([1; 32]),
+                result: payload(),
+            },
+        ));
+        assert!(!state.is_joinset_complete(&js));
+    }
+
+    #[test]
+    fn oversized_heartbeat_progress_is_reported() {
+        let p = PromiseId::new([1; 32]);
+        let j = journal(vec![
+            mk_entry(0, started()),
+            mk_entry(
+                1,
+                EventType::InvokeScheduled {
+                    promise_id: p.clone(),
+                    kind: InvokeKind::Function,
+                    function_name: "slow".into(),
+                    input: payload(),
+                    retry_policy: None,
+                    sla: None,
+                },
+            ),
+            mk_entry(
+                2,
+                EventType::InvokeStarted {
+                    promise_id: p.clone(),
+                    attempt: 1,
+                },
+            ),
+            mk_entry(
+                3,
+                EventType::InvokeHeartbeat {
+                    promise_id: p,
+                    attempt: 1,
+                    at: DateTime::UNIX_EPOCH,
+                    progress: Some(Payload::new(vec![0; 1025], Codec::Json)),
+                },
+            ),
+        ]);
+        assert!(validate_journal(&j).is_empty());
+
+        let options = ValidationOptions {
+            max_payload_bytes: Some(1024),
+            ..ValidationOptions::default()
+        };
+        assert_eq!(
+            validate_journal_with(&j, &options),
+            vec![JournalViolation::PayloadTooLarge {
+                seq: 3,
+                size: 1025,
+                max: 1024,
+            }]
+        );
+    }
+
+    #[test]
+    fn oversized_payload_reported_only_when_limit_set() {
+        let j = journal(vec![
+            mk_entry(0, started()),
+            mk_entry(
+                1,
+                EventType::ExecutionCompleted {
+                    result: Payload::new(vec![0; 1025], Codec::Cbor),
+                },
+            ),
+        ]);
+        assert!(validate_journal(&j).is_empty());
+
+        let options = ValidationOptions {
+            max_payload_bytes: Some(1024),
+            overrides: None,
+            verify_execution_id: false,
+            invoke_validators: Vec::new(),
+            structural_only_after_missing_start: false,
+            compaction: StateCompaction::Off,
+        };
+        assert_eq!(
+            validate_journal_with(&j, &options),
+            vec![JournalViolation::PayloadTooLarge {
+                seq: 1,
+                size: 1025,
+                max: 1024,
+            }]
+        );
+
+        let roomy = ValidationOptions {
+            max_payload_bytes: Some(1025),
+            overrides: None,
+            verify_execution_id: false,
+            invoke_validators: Vec::new(),
+            structural_only_after_missing_start: false,
+            compaction: StateCompaction::Off,
+        };
+        assert!(validate_journal_with(&j, &roomy).is_empty());
+    }
+
+    #[test]
+    fn missing_start_suppresses_domain_violations_when_requested() {
+        // Head lost: the journal begins mid-invoke and later ends twice.
+        let p = PromiseId::new([1; 32]);
+        let j = ExecutionJournal {
+            execution_id: ExecutionId::derive(&[1], "k", None),
+            entries: vec![
+                mk_entry(
+                    0,
+                    EventType::InvokeStarted {
+                        promise_id: p.clone(),
+                        attempt: 1,
+                    },
+                ),
+                mk_entry(
+                    1,
+                    EventType::InvokeCompleted {
+                        promise_id: p,
+                        result: payload(),
+                        attempt: 1,
+                    },
+                ),
+                mk_entry(2, EventType::ExecutionCompleted { result: payload() }),
+                mk_entry(3, EventType::ExecutionCompleted { result: payload() }),
+            ],
+            provenance: None,
+        };
+
+        let noisy = validate_journal(&j);
+        assert!(noisy.len() > 2, "{noisy:?}");
+        assert!(
+            noisy
+                .iter()
+                .any(|v| matches!(v, JournalViolation::StartedWithoutScheduled { .. }))
+        );
+
+        let quiet = validate_journal_with(
+            &j,
+            &ValidationOptions {
+                structural_only_after_missing_start: true,
+                ..ValidationOptions::default()
+            },
+        );
+        assert_eq!(
+            quiet,
+            vec![
+                JournalViolation::MissingExecutionStarted {
+                    first_event: "InvokeStarted".into(),
+                },
+                JournalViolation::MultipleTerminalEvents {
+                    first_at: 2,
+                    second_at: 3,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn validate_many_keys_only_failing_journals() {
+        let clean = |key: &str| ExecutionJournal {
+            execution_id: ExecutionId::derive(&[1], key, None),
+            entries: vec![mk_entry(0, started())],
+            provenance: None,
+        };
+        let mut corrupt = clean("c");
+        corrupt
+            .entries
+            .push(mk_entry(5, EventType::ExecutionResumed));
+
+        let report = validate_many(&[clean("a"), corrupt.clone(), clean("b")]);
+        assert_eq!(report.len(), 1);
+        assert!(matches!(
+            report[&corrupt.execution_id].as_slice(),
+            [JournalViolation::NonMonotonicSequence { .. }, ..]
+        ));
+    }
+
+    #[test]
+    fn registered_invoke_validator_rejects_function_name() {
+        #[derive(Debug)]
+        struct DenyList(&'static str);
+
+        impl InvokeValidator for DenyList {
+            fn validate(
+                &self,
+                _promise_id: &PromiseId,
+                _kind: &InvokeKind,
+                function_name: &str,
+            ) -> Result<(), String> {
+                if function_name == self.0 {
+                    return Err(format!("`{function_name}` is not allowed"));
+                }
+                Ok(())
+            }
+        }
+
+        let scheduled = |n: u8, kind: InvokeKind, name: &str| EventType::InvokeScheduled {
+            promise_id: PromiseId::new([n; 32]),
+            kind,
+            function_name: name.into(),
+            input: payload(),
+            retry_policy: None,
+            sla: None,
+        };
+        let j = journal(vec![
+            mk_entry(0, started()),
+            mk_entry(1, scheduled(1, InvokeKind::Function, "charge_card")),
+            mk_entry(2, scheduled(2, InvokeKind::Http, "drop_tables")),
+        ]);
+        assert!(validate_journal(&j).is_empty());
+
+        let options = ValidationOptions {
+            invoke_validators: vec![
+                Arc::new(HttpInvokeValidator),
+                Arc::new(DenyList("drop_tables")),
+            ],
+            ..ValidationOptions::default()
+        };
+        assert_eq!(
+            validate_journal_with(&j, &options),
+            vec![JournalViolation::InvokeRejected {
+                promise_id: PromiseId::new([2; 32]),
+                seq: 2,
+                reason: "`drop_tables` is not allowed".into(),
+            }]
+        );
+    }
+
+    #[test]
+    fn snapshot_exposes_accumulated_sets() {
+        let p = PromiseId::new([1; 32]);
+        let js = JoinSetId(PromiseId::new([2; 32]));
+        let mut state = InvariantState::new();
+        let events = [
+            started(),
+            EventType::InvokeScheduled {
+                promise_id: p.clone(),
+                kind: invariant_types::InvokeKind::Function,
+                function_name: "f".into(),
+                input: payload(),
+                retry_policy: None,
+                sla: None,
+            },
+            EventType::InvokeStarted {
+                promise_id: p.clone(),
+                attempt: 1,
+            },
+            EventType::JoinSetCreated {
+                join_set_id: js.clone(),
+            },
+        ];
+        for (seq, event) in events.into_iter().enumerate() {
+            state.check_append(&mk_entry(seq as u64, event)).unwrap();
+        }
+
+        let snapshot = state.snapshot();
+        assert!(snapshot.scheduled_pids().contains(&p));
+        assert!(snapshot.started_pids().contains(&p));
+        assert!(snapshot.completed_pids().is_empty());
+        assert!(snapshot.created_joinsets().contains(&js));
+        assert!(snapshot.awaited_joinsets().is_empty());
+    }
 }
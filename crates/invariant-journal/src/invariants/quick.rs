@@ -0,0 +1,285 @@
+//! Cheap validity probe for journals imported in bulk.
+//!
+//! [`validate_quick`] looks at a bounded number of entries — the first and
+//! last [`QUICK_WINDOW`] — plus the execution ID, so its cost does not grow
+//! with the journal. It rejects obviously broken journals before they are
+//! queued for a full audit; it is not a substitute for [`validate_journal`].
+//!
+//! Every [`QuickVerdict::Invalid`] carries a violation that
+//! [`validate_journal`] also reports for the same journal.
+//!
+//! [`validate_journal`]: super::validate_journal
+
+use super::check_execution_id;
+use crate::error::JournalViolation;
+use invariant_types::{EventType, ExecutionJournal, JournalEntry};
+
+/// Entries inspected at each end of the journal.
+pub const QUICK_WINDOW: usize = 16;
+
+/// Journals longer than this are never judged by the probe.
+pub const QUICK_MAX_ENTRIES: usize = 1 << 24;
+
+/// Outcome of [`validate_quick`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum QuickVerdict {
+    /// Nothing the probe inspected is wrong.
+    ///
+    /// This is **not** a claim that the journal is valid: entries outside
+    /// the inspected windows, and every invariant beyond the ones listed on
+    /// [`validate_quick`], are unchecked.
+    LooksValid,
+    /// The journal is broken. [`validate_journal`](super::validate_journal)
+    /// reports this violation too, usually alongside others.
+    Invalid(JournalViolation),
+    /// The probe saw something suspicious it cannot judge on its own.
+    NeedsFullValidation(String),
+}
+
+/// Probe `journal` for obvious damage in bounded time.
+///
+/// Checks, in order:
+/// - the journal is non-empty and no longer than [`QUICK_MAX_ENTRIES`],
+/// - sequences of the first and last [`QUICK_WINDOW`] entries equal their
+///   index (S-1),
+/// - the first event is `ExecutionStarted` (S-2),
+/// - the execution ID is the derivation of that start event (a mismatch
+///   needs full validation with
+///   [`verify_execution_id`](super::ValidationOptions::verify_execution_id)),
+/// - a terminal event within the inspected entries is the last entry
+///   (S-3/S-4).
+pub fn validate_quick(journal: &ExecutionJournal) -> QuickVerdict {
+    let entries = &journal.entries;
+    let len = entries.len();
+    if len == 0 {
+        return QuickVerdict::Invalid(JournalViolation::MissingExecutionStarted {
+            first_event: "<empty>".to_string(),
+        });
+    }
+    if len > QUICK_MAX_ENTRIES {
+        return QuickVerdict::NeedsFullValidation(format!(
+            "{len} entries exceeds the probe limit of {QUICK_MAX_ENTRIES}"
+        ));
+    }
+
+    // Windows `0..head` and `tail..len`; they cover the whole journal when
+    // `tail == head`.
+    let head = len.min(QUICK_WINDOW);
+    let tail = len.saturating_sub(QUICK_WINDOW).max(head);
+    let whole = tail == head;
+
+    // S-1 first: the batch path reports a misnumbered entry 0 as S-1, not S-2.
+    for index in (0..head).chain(tail..len) {
+        let entry = &entries[index];
+        if entry.sequence != index as u64 {
+            return QuickVerdict::Invalid(JournalViolation::NonMonotonicSequence {
+                entry_index: index,
+                expected: index as u64,
+                actual: entry.sequence,
+            });
+        }
+    }
+
+    // S-2
+    if !matches!(entries[0].event, EventType::ExecutionStarted { .. }) {
+        return QuickVerdict::Invalid(JournalViolation::MissingExecutionStarted {
+            first_event: entries[0].event.name().to_string(),
+        });
+    }
+
+    // Batch validation only reports this when asked to, so it is not
+    // `Invalid` here.
+    if check_execution_id(journal).is_some() {
+        return QuickVerdict::NeedsFullValidation(
+            "execution ID does not derive from ExecutionStarted".to_string(),
+        );
+    }
+
+    // S-3 / S-4: the violation names the first terminal, so it is only
+    // exact when the terminal and the entry after it lie in the verified
+    // prefix. Opaque `Unknown` entries are exempt, as in the batch path.
+    let prefix = if whole { len } else { head };
+    if let Some(t) = (0..prefix).find(|&i| entries[i].event.is_terminal()) {
+        let extra =
+            (t + 1..prefix).find(|&i| !matches!(entries[i].event, EventType::Unknown { .. }));
+        match extra {
+            Some(extra) => {
+                return QuickVerdict::Invalid(after_terminal(&entries[t], &entries[extra]));
+            }
+            None if prefix < len => {
+                return QuickVerdict::NeedsFullValidation(format!(
+                    "terminal event at seq {t} is followed by uninspected entries"
+                ));
+            }
+            None => {}
+        }
+    } else if !whole && (tail..len - 1).any(|i| entries[i].event.is_terminal()) {
+        return QuickVerdict::NeedsFullValidation(
+            "terminal event before the last entry".to_string(),
+        );
+    }
+
+    QuickVerdict::LooksValid
+}
+
+/// The structural violation for `extra` following the first terminal.
+fn after_terminal(terminal: &JournalEntry, extra: &JournalEntry) -> JournalViolation {
+    if extra.event.is_terminal() {
+        JournalViolation::MultipleTerminalEvents {
+            first_at: terminal.sequence,
+            second_at: extra.sequence,
+        }
+    } else {
+        JournalViolation::TerminalNotLast {
+            terminal_seq: terminal.sequence,
+            extra_entry_seq: extra.sequence,
+            extra_event_name: extra.event.name().to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::invariants::{ValidationOptions, validate_journal, validate_journal_with};
+    use chrono::DateTime;
+    use invariant_types::{Codec, ExecutionId, InvokeKind, Payload};
+
+    fn payload() -> Payload {
+        Payload::new(vec![], Codec::Json)
+    }
+
+    fn exec_id() -> ExecutionId {
+        ExecutionId::derive(&[1], "k", None)
+    }
+
+    fn started() -> EventType {
+        EventType::ExecutionStarted {
+            component_digest: vec![1],
+            input: payload(),
+            parent_id: None,
+            idempotency_key: "k".into(),
+        }
+    }
+
+    fn scheduled(seq: u32) -> EventType {
+        EventType::InvokeScheduled {
+            promise_id: exec_id().child(seq).unwrap(),
+            kind: InvokeKind::Function,
+            function_name: "f".into(),
+            input: payload(),
+            retry_policy: None,
+            sla: None,
+        }
+    }
+
+    fn completed() -> EventType {
+        EventType::ExecutionCompleted { result: payload() }
+    }
+
+    fn journal(events: Vec<EventType>) -> ExecutionJournal {
+        ExecutionJournal {
+            execution_id: exec_id(),
+            entries: events
+                .into_iter()
+                .enumerate()
+                .map(|(i, event)| JournalEntry {
+                    sequence: i as u64,
+                    timestamp: DateTime::UNIX_EPOCH,
+                    event,
+                })
+                .collect(),
+            provenance: None,
+        }
+    }
+
+    /// `ExecutionStarted` followed by `n` scheduled invokes.
+    fn long(n: u32) -> Vec<EventType> {
+        std::iter::once(started())
+            .chain((0..n).map(scheduled))
+            .collect()
+    }
+
+    #[test]
+    fn clean_journals_look_valid() {
+        assert_eq!(
+            validate_quick(&journal(vec![started(), completed()])),
+            QuickVerdict::LooksValid
+        );
+        let mut events = long(5 * QUICK_WINDOW as u32);
+        events.push(completed());
+        assert_eq!(validate_quick(&journal(events)), QuickVerdict::LooksValid);
+    }
+
+    #[test]
+    fn every_invalid_verdict_is_reported_by_full_validation() {
+        let n = 4 * QUICK_WINDOW as u32;
+        let mut cases = vec![
+            journal(vec![]),
+            journal(vec![EventType::ExecutionResumed]),
+            journal(vec![started(), completed(), completed()]),
+            journal(vec![started(), completed(), scheduled(0)]),
+        ];
+
+        let mut misnumbered_head = journal(long(n));
+        misnumbered_head.entries[0].sequence = 3;
+        cases.push(misnumbered_head);
+
+        let mut misnumbered_tail = journal(long(n));
+        misnumbered_tail.entries.last_mut().unwrap().sequence += 1;
+        cases.push(misnumbered_tail);
+
+        let mut terminal_in_head = long(n);
+        terminal_in_head.insert(2, completed());
+        cases.push(journal(terminal_in_head));
+
+        for j in &cases {
+            let QuickVerdict::Invalid(violation) = validate_quick(j) else {
+                panic!("expected Invalid for {j:?}");
+            };
+            let full = validate_journal(j);
+            assert!(full.contains(&violation), "{violation:?} not in {full:?}");
+        }
+    }
+
+    #[test]
+    fn underived_execution_id_needs_full_validation() {
+        let mut wrong_id = journal(long(4 * QUICK_WINDOW as u32));
+        wrong_id.execution_id = ExecutionId::derive(&[1], "other", None);
+
+        assert!(matches!(
+            validate_quick(&wrong_id),
+            QuickVerdict::NeedsFullValidation(_)
+        ));
+        assert!(validate_journal(&wrong_id).is_empty());
+        let verifying = ValidationOptions {
+            verify_execution_id: true,
+            ..ValidationOptions::default()
+        };
+        assert!(matches!(
+            validate_journal_with(&wrong_id, &verifying).as_slice(),
+            [JournalViolation::ExecutionIdMismatch { .. }]
+        ));
+    }
+
+    #[test]
+    fn terminal_far_from_the_start_needs_full_validation() {
+        let mut events = long(4 * QUICK_WINDOW as u32);
+        let at = events.len() - 3;
+        events.insert(at, completed());
+        let j = journal(events);
+        assert!(matches!(
+            validate_quick(&j),
+            QuickVerdict::NeedsFullValidation(_)
+        ));
+        assert!(!validate_journal(&j).is_empty());
+    }
+
+    #[test]
+    fn damage_outside_the_windows_goes_unnoticed() {
+        let mut j = journal(long(4 * QUICK_WINDOW as u32));
+        j.entries[2 * QUICK_WINDOW].sequence = 0;
+        assert_eq!(validate_quick(&j), QuickVerdict::LooksValid);
+        assert!(!validate_journal(&j).is_empty());
+    }
+}
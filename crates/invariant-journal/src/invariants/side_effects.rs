@@ -1,4 +1,4 @@
-//! Side-effect invariants (SE-1 through SE-4).
+//! Side-effect invariants (SE-1 through SE-5).
 //!
 //! These checks enforce the three-phase invoke lifecycle:
 //! Scheduled → Started → Completed. Each phase is gated on its predecessor,
@@ -8,6 +8,10 @@
 //! SE-3 is intentionally stricter than the Quint spec: it checks the
 //! `(promise_id, failed_attempt)` pair rather than just `promise_id`,
 //! ensuring that a retry references the exact attempt that was started.
+//!
+//! SE-5 is local to this crate: an `InvokeHeartbeat` must name an attempt
+//! that is still in flight, so a stale worker cannot report liveness for
+//! an attempt the journal has already moved past.
 
 use invariant_types::{EventType, JournalEntry};
 
@@ -29,7 +33,7 @@ pub(crate) fn check(
         // InvokeStarted: SE-4 (finality) then SE-1 (requires prior Scheduled).
         EventType::InvokeStarted { promise_id, .. } => {
             // SE-4: reject if this promise already completed.
-            if state.completed_pids.contains(promise_id) {
+            if state.is_completed(promise_id) {
                 return Err(Box::new(JournalViolation::EventAfterCompleted {
                     promise_id: promise_id.clone(),
                     offending_seq: entry.sequence,
@@ -37,7 +41,7 @@ pub(crate) fn check(
                 }));
             }
             // SE-1: Started requires a preceding Scheduled for the same promise.
-            if !state.scheduled_pids.contains(promise_id) {
+            if !state.is_scheduled(promise_id) {
                 return Err(Box::new(JournalViolation::StartedWithoutScheduled {
                     promise_id: promise_id.clone(),
                     started_seq: entry.sequence,
@@ -49,14 +53,14 @@ pub(crate) fn check(
         // Started is a more fundamental violation than a second Completed.
         EventType::InvokeCompleted { promise_id, .. } => {
             // SE-2: Completed requires a preceding Started for the same promise.
-            if !state.started_pids.contains(promise_id) {
+            if !state.is_started(promise_id) {
                 return Err(Box::new(JournalViolation::CompletedWithoutStarted {
                     promise_id: promise_id.clone(),
                     completed_seq: entry.sequence,
                 }));
             }
             // SE-4: reject duplicate Completed for an already-completed promise.
-            if state.completed_pids.contains(promise_id) {
+            if state.is_completed(promise_id) {
                 return Err(Box::new(JournalViolation::EventAfterCompleted {
                     promise_id: promise_id.clone(),
                     offending_seq: entry.sequence,
@@ -71,7 +75,7 @@ pub(crate) fn check(
             ..
         } => {
             // SE-4: reject if this promise already completed.
-            if state.completed_pids.contains(promise_id) {
+            if state.is_completed(promise_id) {
                 return Err(Box::new(JournalViolation::EventAfterCompleted {
                     promise_id: promise_id.clone(),
                     offending_seq: entry.sequence,
@@ -92,6 +96,24 @@ pub(crate) fn check(
                 }));
             }
         }
+        // SE-5: only an in-flight attempt heartbeats.
+        EventType::InvokeHeartbeat {
+            promise_id,
+            attempt,
+            ..
+        } => {
+            let key = (promise_id.clone(), *attempt);
+            if !state.started_attempts.contains(&key)
+                || state.retried_attempts.contains(&key)
+                || state.is_completed(promise_id)
+            {
+                return Err(Box::new(JournalViolation::HeartbeatNotInFlight {
+                    promise_id: promise_id.clone(),
+                    attempt: *attempt,
+                    heartbeat_seq: entry.sequence,
+                }));
+            }
+        }
         _ => {}
     }
     Ok(())
@@ -397,4 +419,39 @@ mod tests {
 
         assert!(check(&state, &entry).is_ok());
     }
+
+    #[test]
+    fn se5_heartbeat_requires_an_in_flight_attempt() {
+        let p = pid(5);
+        let heartbeat = |attempt| {
+            mk_entry(
+                9,
+                EventType::InvokeHeartbeat {
+                    promise_id: p.clone(),
+                    attempt,
+                    at: Utc::now(),
+                    progress: Some(payload()),
+                },
+            )
+        };
+        let mut state = InvariantState {
+            started_pids: std::iter::once(p.clone()).collect(),
+            started_attempts: [(p.clone(), 1), (p.clone(), 2)].into_iter().collect(),
+            retried_attempts: std::iter::once((p.clone(), 1)).collect(),
+            ..Default::default()
+        };
+        assert!(check(&state, &heartbeat(2)).is_ok());
+
+        let not_in_flight = |attempt| JournalViolation::HeartbeatNotInFlight {
+            promise_id: p.clone(),
+            attempt,
+            heartbeat_seq: 9,
+        };
+        // Superseded by a retry, and never started.
+        assert_eq!(*check(&state, &heartbeat(1)).unwrap_err(), not_in_flight(1));
+        assert_eq!(*check(&state, &heartbeat(3)).unwrap_err(), not_in_flight(3));
+
+        state.completed_pids.insert(p.clone());
+        assert_eq!(*check(&state, &heartbeat(2)).unwrap_err(), not_in_flight(2));
+    }
 }
@@ -1,4 +1,4 @@
-//! Structural invariants (S-1 through S-5).
+//! Structural invariants (S-1 through S-5, and S-7).
 //!
 //! These checks enforce the physical integrity of the journal as an
 //! append-only, 0-indexed event log with well-defined lifecycle bookends.
@@ -18,15 +18,36 @@ use invariant_types::{EventType, JournalEntry};
 pub(crate) fn check(
     state: &InvariantState,
     entry: &JournalEntry,
+) -> Result<(), Box<JournalViolation>> {
+    check_at(state, state.len, entry)
+}
+
+/// [`check`] with an explicit array index for the entry.
+///
+/// The batch path passes its loop index so `NonMonotonicSequence` reports
+/// the entry's true position in the journal.
+pub(crate) fn check_at(
+    state: &InvariantState,
+    entry_index: usize,
+    entry: &JournalEntry,
 ) -> Result<(), Box<JournalViolation>> {
     // S-1: Sequence numbers must equal their 0-based array index.
-    // `state.len` is the count of entries already ingested, so the next
-    // entry must carry `sequence == len`.
-    debug_assert!(state.len <= u64::MAX as usize);
-    let expected = state.len as u64;
+    // Two guards come first. Applying the entry increments `len`, which must
+    // not overflow `usize`.
+    if entry_index.checked_add(1).is_none() {
+        return Err(Box::new(JournalViolation::SequenceOverflow {
+            len: entry_index,
+        }));
+    }
+    // The index is the expected sequence, so it must fit in a `u64`.
+    let Ok(expected) = u64::try_from(entry_index) else {
+        return Err(Box::new(JournalViolation::SequenceOverflow {
+            len: entry_index,
+        }));
+    };
     if entry.sequence != expected {
         return Err(Box::new(JournalViolation::NonMonotonicSequence {
-            entry_index: state.len,
+            entry_index,
             expected,
             actual: entry.sequence,
         }));
@@ -39,6 +60,11 @@ pub(crate) fn check(
         }));
     }
 
+    // Events from a newer schema are opaque; only position is checked.
+    if matches!(entry.event, EventType::Unknown { .. }) {
+        return Ok(());
+    }
+
     // S-3 / S-4: Terminal event finality.
     // Once a terminal event has been recorded, the journal is sealed:
     //   - Another terminal is a uniqueness violation (S-3).
@@ -52,7 +78,8 @@ pub(crate) fn check(
         }
         return Err(Box::new(JournalViolation::TerminalNotLast {
             terminal_seq: first_at,
-            journal_len: state.len.saturating_add(1),
+            extra_entry_seq: entry.sequence,
+            extra_event_name: entry.event.name().to_string(),
         }));
     }
 
@@ -63,6 +90,17 @@ pub(crate) fn check(
         }));
     }
 
+    // S-7: An upgrade must replace the digest currently in effect.
+    if let EventType::ComponentUpgraded { old_digest, .. } = &entry.event
+        && state.component_digest.as_ref() != Some(old_digest)
+    {
+        return Err(Box::new(JournalViolation::UpgradeDigestMismatch {
+            upgrade_seq: entry.sequence,
+            expected: state.component_digest.clone().unwrap_or_default(),
+            actual: old_digest.clone(),
+        }));
+    }
+
     Ok(())
 }
 
@@ -133,6 +171,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn s1_sequence_overflow_at_length_boundary() {
+        let full = InvariantState {
+            len: usize::MAX,
+            ..Default::default()
+        };
+        let err = check(&full, &mk_entry(u64::MAX, completed_event())).unwrap_err();
+        assert_eq!(*err, JournalViolation::SequenceOverflow { len: usize::MAX });
+
+        // One below the boundary still numbers normally.
+        let last = InvariantState {
+            len: usize::MAX - 1,
+            ..Default::default()
+        };
+        let seq = (usize::MAX - 1) as u64;
+        assert!(check(&last, &mk_entry(seq, completed_event())).is_ok());
+    }
+
     #[test]
     fn s2_first_event_must_be_execution_started() {
         let state = InvariantState::new();
@@ -180,7 +236,8 @@ mod tests {
             *err,
             JournalViolation::TerminalNotLast {
                 terminal_seq: 3,
-                journal_len: 5,
+                extra_entry_seq: 4,
+                extra_event_name: "CancelRequested".to_string(),
             }
         );
     }
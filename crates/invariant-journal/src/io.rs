@@ -0,0 +1,393 @@
+//! Forensic loading of journals with entries lost to storage damage.
+//!
+//! Every other API in this crate assumes an intact journal and rejects one
+//! with holes at S-1. [`read_partial`] accepts gapped input for salvage
+//! work only: a [`PartialJournal`] offers answers that tolerate holes, each
+//! flagged by how far it can be trusted, and does not expose its entries
+//! to validation or replay until the caller calls
+//! [`PartialJournal::acknowledge_gaps`].
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::ops::Range;
+
+use invariant_types::{EventType, ExecutionStatus, JournalEntry, PromiseId, SignalDeliveryId};
+
+use crate::status::derive_status;
+
+/// How far a [`BestEffortAnalysis`] answer can be trusted.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Availability<T> {
+    /// Computed from an unbroken run of entries starting at sequence 0.
+    Reliable(T),
+    /// Computed from the surviving entries only; lost entries may change it.
+    Unreliable(T),
+    /// The entries needed for this answer are missing.
+    Unavailable,
+}
+
+impl<T> Availability<T> {
+    pub fn is_reliable(&self) -> bool {
+        matches!(self, Self::Reliable(_))
+    }
+}
+
+/// Entries recovered from damaged storage, with the sequences that are lost.
+#[derive(Clone, Debug)]
+pub struct PartialJournal {
+    entries: Vec<JournalEntry>,
+    gaps: Vec<Range<u64>>,
+    conflicts: Vec<u64>,
+}
+
+/// Load `entries` without requiring contiguous sequence numbers.
+///
+/// Entries are sorted by sequence and duplicates dropped, keeping the first
+/// in storage order. Copies that differ from the kept entry are recorded as
+/// conflicts, since either one may be the damaged copy. Missing ranges,
+/// including any before the first surviving entry, are recorded as gaps;
+/// losses after the last surviving entry are undetectable.
+pub fn read_partial(mut entries: Vec<JournalEntry>) -> PartialJournal {
+    entries.sort_by_key(|e| e.sequence);
+    let mut conflicts = Vec::new();
+    entries.dedup_by(|later, kept| {
+        if later.sequence != kept.sequence {
+            return false;
+        }
+        if later != kept && conflicts.last() != Some(&kept.sequence) {
+            conflicts.push(kept.sequence);
+        }
+        true
+    });
+
+    let mut gaps = Vec::new();
+    let mut next = 0;
+    for e in &entries {
+        if e.sequence > next {
+            gaps.push(next..e.sequence);
+        }
+        // Sequence u64::MAX is necessarily the last entry, so nothing
+        // after it can be missing.
+        match e.sequence.checked_add(1) {
+            Some(n) => next = n,
+            None => break,
+        }
+    }
+    PartialJournal {
+        entries,
+        gaps,
+        conflicts,
+    }
+}
+
+/// Hole-tolerant answers from [`PartialJournal::best_effort_analysis`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BestEffortAnalysis {
+    /// Surviving entry count per event name.
+    pub event_counts: Availability<BTreeMap<&'static str, usize>>,
+    /// Signals delivered and neither received nor expired, by name and
+    /// delivery ID.
+    pub inbox: Availability<Vec<(String, SignalDeliveryId)>>,
+    /// Sequences of the surviving entries that reference each promise.
+    pub promise_slices: Availability<HashMap<PromiseId, Vec<u64>>>,
+    /// Status derived from the entries before the first gap or conflict.
+    pub status: Availability<ExecutionStatus>,
+}
+
+impl PartialJournal {
+    /// Missing sequence ranges, in order.
+    pub fn gaps(&self) -> &[Range<u64>] {
+        &self.gaps
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.gaps.is_empty()
+    }
+
+    /// Sequences that appeared more than once with different contents, in
+    /// order. Only the first copy of each is kept.
+    pub fn conflicts(&self) -> &[u64] {
+        &self.conflicts
+    }
+
+    /// Number of surviving entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Run the analyses that tolerate missing entries.
+    ///
+    /// With gaps or conflicts, counts, inbox and promise slices come back
+    /// [`Availability::Unreliable`]. Status is derived only up to the first
+    /// gap or conflict, so it is unreliable whenever either exists and
+    /// unavailable if sequence 0 itself is lost or conflicting.
+    pub fn best_effort_analysis(&self) -> BestEffortAnalysis {
+        let mut counts: BTreeMap<&'static str, usize> = BTreeMap::new();
+        let mut delivered: Vec<(String, SignalDeliveryId)> = Vec::new();
+        let mut closed: HashSet<(&str, SignalDeliveryId)> = HashSet::new();
+        let mut slices: HashMap<PromiseId, Vec<u64>> = HashMap::new();
+        for e in &self.entries {
+            *counts.entry(e.event.name()).or_default() += 1;
+            match &e.event {
+                EventType::SignalDelivered {
+                    signal_name,
+                    delivery_id,
+                    ..
+                } => delivered.push((signal_name.clone(), *delivery_id)),
+                EventType::SignalReceived {
+                    signal_name,
+                    delivery_id,
+                    ..
+                }
+                | EventType::SignalDeliveryExpired {
+                    signal_name,
+                    delivery_id,
+                } => {
+                    closed.insert((signal_name, *delivery_id));
+                }
+                _ => {}
+            }
+            if let Some(pid) = e.event.promise_id() {
+                slices.entry(pid.clone()).or_default().push(e.sequence);
+            }
+        }
+        delivered.retain(|(name, id)| !closed.contains(&(name.as_str(), *id)));
+
+        let first_gap = self.gaps.first().map(|gap| gap.start);
+        let first_conflict = self.conflicts.first().copied();
+        let status = match first_gap.into_iter().chain(first_conflict).min() {
+            None => Availability::Reliable(derive_status(&self.entries)),
+            Some(0) => Availability::Unavailable,
+            Some(end) => {
+                let prefix = self.entries.partition_point(|e| e.sequence < end);
+                Availability::Unreliable(derive_status(&self.entries[..prefix]))
+            }
+        };
+
+        BestEffortAnalysis {
+            event_counts: self.flag(counts),
+            inbox: self.flag(delivered),
+            promise_slices: self.flag(slices),
+            status,
+        }
+    }
+
+    /// Reliable only if nothing is missing or conflicting.
+    fn flag<T>(&self, value: T) -> Availability<T> {
+        if self.is_complete() && self.conflicts.is_empty() {
+            Availability::Reliable(value)
+        } else {
+            Availability::Unreliable(value)
+        }
+    }
+
+    /// Give up the gap tracking and take the surviving entries.
+    ///
+    /// This is the only way to reach the entries. Their sequences still have
+    /// holes, so validation will report S-1 at every gap and replay will not
+    /// reproduce the original run; the caller takes responsibility for
+    /// whatever it feeds them into.
+    pub fn acknowledge_gaps(self) -> Vec<JournalEntry> {
+        self.entries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Utc};
+    use invariant_types::{Codec, InvokeKind, Payload};
+
+    use super::*;
+
+    fn payload() -> Payload {
+        Payload::new(vec![], Codec::Json)
+    }
+
+    fn pid(n: u8) -> PromiseId {
+        PromiseId::new([n; 32])
+    }
+
+    fn entry(sequence: u64, event: EventType) -> JournalEntry {
+        JournalEntry {
+            sequence,
+            timestamp: DateTime::<Utc>::UNIX_EPOCH,
+            event,
+        }
+    }
+
+    fn delivered(delivery_id: SignalDeliveryId) -> EventType {
+        EventType::SignalDelivered {
+            signal_name: "approve".into(),
+            payload: payload(),
+            delivery_id,
+            external_id: None,
+        }
+    }
+
+    /// Ten entries, of which 3 and 6..8 are lost.
+    fn recovered() -> Vec<JournalEntry> {
+        let p = pid(1);
+        let all = vec![
+            EventType::ExecutionStarted {
+                component_digest: vec![1],
+                input: payload(),
+                parent_id: None,
+                idempotency_key: "k".into(),
+            },
+            EventType::InvokeScheduled {
+                promise_id: p.clone(),
+                kind: InvokeKind::Function,
+                function_name: "f".into(),
+                input: payload(),
+                retry_policy: None,
+                sla: None,
+            },
+            EventType::InvokeStarted {
+                promise_id: p.clone(),
+                attempt: 1,
+            },
+            delivered(0),
+            EventType::SignalReceived {
+                promise_id: pid(2),
+                signal_name: "approve".into(),
+                payload: payload(),
+                delivery_id: 0,
+            },
+            delivered(1),
+            EventType::ExecutionResumed,
+            EventType::ExecutionResumed,
+            EventType::InvokeCompleted {
+                promise_id: p,
+                result: payload(),
+                attempt: 1,
+            },
+            EventType::ExecutionCompleted { result: payload() },
+        ];
+        let mut entries: Vec<_> = all
+            .into_iter()
+            .enumerate()
+            .map(|(seq, event)| entry(seq as u64, event))
+            .filter(|e| !matches!(e.sequence, 3 | 6 | 7))
+            .collect();
+        // Storage order is not guaranteed.
+        entries.reverse();
+        entries
+    }
+
+    #[test]
+    fn two_holes_are_recorded_and_flag_every_analysis() {
+        let partial = read_partial(recovered());
+        assert_eq!(partial.gaps(), [3..4, 6..8]);
+        assert_eq!(partial.len(), 7);
+
+        let analysis = partial.best_effort_analysis();
+        let Availability::Unreliable(counts) = &analysis.event_counts else {
+            panic!("counts should be unreliable: {:?}", analysis.event_counts);
+        };
+        assert_eq!(counts["SignalDelivered"], 1);
+        // Delivery 0's SignalDelivered was lost; only delivery 1 is pending.
+        assert_eq!(
+            analysis.inbox,
+            Availability::Unreliable(vec![("approve".into(), 1)])
+        );
+        let Availability::Unreliable(slices) = &analysis.promise_slices else {
+            panic!("slices should be unreliable");
+        };
+        assert_eq!(slices[&pid(1)], vec![1, 2, 8]);
+        // Derived from entries 0..3 only: the terminal entry is ignored.
+        assert_eq!(
+            analysis.status,
+            Availability::Unreliable(ExecutionStatus::Running)
+        );
+
+        assert_eq!(partial.acknowledge_gaps().len(), 7);
+    }
+
+    #[test]
+    fn lost_start_makes_status_unavailable() {
+        let mut entries = recovered();
+        entries.retain(|e| e.sequence != 0);
+        let partial = read_partial(entries);
+        assert_eq!(partial.gaps(), [0..1, 3..4, 6..8]);
+        assert_eq!(
+            partial.best_effort_analysis().status,
+            Availability::Unavailable
+        );
+    }
+
+    #[test]
+    fn entry_at_max_sequence_does_not_overflow() {
+        let entries = vec![
+            entry(0, EventType::ExecutionResumed),
+            entry(u64::MAX, EventType::ExecutionResumed),
+        ];
+        let partial = read_partial(entries);
+        assert_eq!(partial.gaps(), std::slice::from_ref(&(1..u64::MAX)));
+        assert_eq!(partial.len(), 2);
+    }
+
+    #[test]
+    fn expired_deliveries_leave_the_inbox() {
+        let mut entries: Vec<_> = recovered()
+            .into_iter()
+            .filter(|e| e.sequence == 0)
+            .collect();
+        entries.extend([
+            entry(1, delivered(0)),
+            entry(2, delivered(1)),
+            entry(
+                3,
+                EventType::SignalDeliveryExpired {
+                    signal_name: "approve".into(),
+                    delivery_id: 0,
+                },
+            ),
+        ]);
+        assert_eq!(
+            read_partial(entries).best_effort_analysis().inbox,
+            Availability::Reliable(vec![("approve".into(), 1)])
+        );
+    }
+
+    #[test]
+    fn conflicting_copies_are_recorded_and_flag_every_analysis() {
+        let mut entries: Vec<_> = recovered().into_iter().filter(|e| e.sequence < 3).collect();
+        // An identical copy is a harmless duplicate.
+        entries.push(entries[0].clone());
+        // A different entry claiming sequence 1 is a conflict.
+        entries.push(entry(1, EventType::ExecutionResumed));
+        let partial = read_partial(entries);
+        assert!(partial.is_complete());
+        assert_eq!(partial.conflicts(), [1]);
+        assert_eq!(partial.len(), 3);
+
+        let analysis = partial.best_effort_analysis();
+        assert!(!analysis.event_counts.is_reliable());
+        assert!(!analysis.inbox.is_reliable());
+        assert!(!analysis.promise_slices.is_reliable());
+        // Derived from entry 0 only.
+        assert_eq!(
+            analysis.status,
+            Availability::Unreliable(ExecutionStatus::Running)
+        );
+    }
+
+    #[test]
+    fn complete_input_is_reliable() {
+        let entries: Vec<_> = recovered().into_iter().filter(|e| e.sequence < 3).collect();
+        let partial = read_partial(entries);
+        assert!(partial.is_complete());
+
+        let analysis = partial.best_effort_analysis();
+        assert!(analysis.event_counts.is_reliable());
+        assert!(analysis.inbox.is_reliable());
+        assert!(analysis.promise_slices.is_reliable());
+        assert_eq!(
+            analysis.status,
+            Availability::Reliable(ExecutionStatus::Running)
+        );
+    }
+}
@@ -1,7 +1,25 @@
+#[cfg(any(test, feature = "testing"))]
+pub mod assertions;
+pub mod causality;
 pub mod command;
+pub mod conformance;
+pub mod continuation;
+pub mod diagnostics;
 pub mod error;
+pub mod export;
+pub mod filter;
+pub mod heartbeat;
 pub mod invariants;
+pub mod io;
+pub mod overrides;
 pub mod replay;
 pub mod resolution;
+pub mod signals;
 pub mod state;
+#[cfg(feature = "stats")]
+pub mod stats;
 pub mod status;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+
+pub use status::derive_next_status;
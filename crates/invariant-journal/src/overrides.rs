@@ -0,0 +1,91 @@
+//! Ledger of operator overrides that forced entries past the checker.
+//!
+//! Occasionally an operator must append an entry the invariants reject, e.g.
+//! marking a provably completed external call whose `InvokeStarted` was
+//! lost. [`ExecutionState::append_override`](crate::state::ExecutionState::append_override)
+//! applies such an entry and records what it broke in an [`OverrideLedger`].
+//! The ledger is persisted next to the journal; passing it in
+//! [`ValidationOptions::overrides`](crate::invariants::ValidationOptions::overrides)
+//! turns exactly the recorded violations into warnings, so an audit can tell
+//! sanctioned overrides from corruption.
+//!
+//! Sequence integrity and terminal finality (S-1, S-3, S-4) can never be
+//! overridden.
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::JournalViolation;
+use crate::invariants::invariant_catalog;
+
+/// Who forced an entry through, and why.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OverrideJustification {
+    pub operator: String,
+    pub reason: String,
+}
+
+/// One sanctioned entry.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OverrideRecord {
+    /// Sequence of the forced entry.
+    pub seq: u64,
+    /// Invariant IDs the entry violated, e.g. `"SE-1"`. Guards without an ID
+    /// are recorded by violation name.
+    pub codes: Vec<String>,
+    pub justification: OverrideJustification,
+}
+
+/// Append-only list of [`OverrideRecord`]s for one journal.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OverrideLedger {
+    records: Vec<OverrideRecord>,
+}
+
+impl OverrideLedger {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn records(&self) -> &[OverrideRecord] {
+        &self.records
+    }
+
+    /// Whether `violation` at `seq` was recorded as sanctioned.
+    ///
+    /// Never true for a violation no override may bypass, even if a
+    /// hand-edited ledger lists its code.
+    pub fn sanctions(&self, seq: u64, violation: &JournalViolation) -> bool {
+        if !overridable(violation) {
+            return false;
+        }
+        let code = code(violation);
+        self.records
+            .iter()
+            .any(|r| r.seq == seq && r.codes.iter().any(|c| c == code))
+    }
+
+    pub(crate) fn record(&mut self, record: OverrideRecord) {
+        self.records.push(record);
+    }
+}
+
+/// Whether an override may bypass `violation`.
+pub(crate) fn overridable(violation: &JournalViolation) -> bool {
+    !matches!(
+        violation,
+        JournalViolation::NonMonotonicSequence { .. }
+            | JournalViolation::SequenceOverflow { .. }
+            | JournalViolation::MultipleTerminalEvents { .. }
+            | JournalViolation::TerminalNotLast { .. }
+    )
+}
+
+/// Catalog ID of `violation`, or its name if it has none.
+pub(crate) fn code(violation: &JournalViolation) -> &'static str {
+    let name = violation.name();
+    invariant_catalog()
+        .iter()
+        .find(|info| info.violation == name)
+        .and_then(|info| info.id)
+        .unwrap_or(name)
+}
@@ -1,4 +1,5 @@
-use std::collections::HashMap;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
 
 use chrono::{DateTime, Utc};
 use invariant_types::{EventType, JournalEntry, Payload, PromiseId};
@@ -20,6 +21,40 @@ pub enum CachedResult {
     Signal(Payload),
 }
 
+/// A cache insert that replaced a promise's result with a different one.
+///
+/// Returned by [`ReplayCache::apply_tracked`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CacheOverwrite {
+    pub promise_id: PromiseId,
+    /// Sequence of the entry whose value replaced the old one.
+    pub seq: u64,
+}
+
+/// The cache entry `entry` contributes, if its event kind is cached.
+fn cached_result(entry: &JournalEntry) -> Option<(PromiseId, CachedResult)> {
+    match &entry.event {
+        EventType::InvokeCompleted {
+            promise_id, result, ..
+        } => Some((promise_id.clone(), CachedResult::Invoke(result.clone()))),
+        EventType::RandomGenerated { promise_id, value } => {
+            Some((promise_id.clone(), CachedResult::Random(value.clone())))
+        }
+        EventType::TimeRecorded { promise_id, time } => {
+            Some((promise_id.clone(), CachedResult::Time(*time)))
+        }
+        EventType::TimerFired { promise_id } => Some((promise_id.clone(), CachedResult::Timer)),
+        EventType::SignalReceived {
+            promise_id,
+            payload,
+            ..
+        } => Some((promise_id.clone(), CachedResult::Signal(payload.clone()))),
+        // Liveness only; replay never reads a heartbeat.
+        EventType::InvokeHeartbeat { .. } => None,
+        _ => None,
+    }
+}
+
 /// Batch-built replay cache keyed by `PromiseId`.
 ///
 /// Construction is a single O(n) scan over journal entries.
@@ -58,33 +93,24 @@ impl ReplayCache {
 
     /// Index a single journal entry into the cache.
     pub fn insert_event(&mut self, entry: &JournalEntry) {
-        match &entry.event {
-            EventType::InvokeCompleted {
-                promise_id, result, ..
-            } => {
-                self.results
-                    .insert(promise_id.clone(), CachedResult::Invoke(result.clone()));
-            }
-            EventType::RandomGenerated { promise_id, value } => {
-                self.results
-                    .insert(promise_id.clone(), CachedResult::Random(value.clone()));
-            }
-            EventType::TimeRecorded { promise_id, time } => {
-                self.results
-                    .insert(promise_id.clone(), CachedResult::Time(*time));
-            }
-            EventType::TimerFired { promise_id } => {
-                self.results.insert(promise_id.clone(), CachedResult::Timer);
-            }
-            EventType::SignalReceived {
+        if let Some((promise_id, result)) = cached_result(entry) {
+            self.results.insert(promise_id, result);
+        }
+    }
+
+    /// [`insert_event`](Self::insert_event), reporting a silent overwrite.
+    ///
+    /// Returns `Some` only when `entry` replaced a cached result with a
+    /// different one. Re-inserting an identical result, or caching a promise
+    /// for the first time, returns `None`.
+    pub fn apply_tracked(&mut self, entry: &JournalEntry) -> Option<CacheOverwrite> {
+        let (promise_id, result) = cached_result(entry)?;
+        match self.results.insert(promise_id.clone(), result) {
+            Some(old) if self.results[&promise_id] != old => Some(CacheOverwrite {
                 promise_id,
-                payload,
-                ..
-            } => {
-                self.results
-                    .insert(promise_id.clone(), CachedResult::Signal(payload.clone()));
-            }
-            _ => {}
+                seq: entry.sequence,
+            }),
+            _ => None,
         }
     }
 
@@ -94,9 +120,12 @@ impl ReplayCache {
     }
 
     /// Typed accessor for invoke results.
-    pub fn get_invoke(&self, pid: &PromiseId) -> Option<&Payload> {
+    ///
+    /// Always borrowed here; the `Cow` matches [`BoundedReplayCache`], which
+    /// returns re-fetched payloads owned.
+    pub fn get_invoke(&self, pid: &PromiseId) -> Option<Cow<'_, Payload>> {
         match self.lookup(pid) {
-            Some(CachedResult::Invoke(payload)) => Some(payload),
+            Some(CachedResult::Invoke(payload)) => Some(Cow::Borrowed(payload)),
             _ => None,
         }
     }
@@ -123,9 +152,11 @@ impl ReplayCache {
     }
 
     /// Typed accessor for received signal payloads.
-    pub fn get_signal(&self, pid: &PromiseId) -> Option<&Payload> {
+    ///
+    /// Always borrowed here; see [`get_invoke`](Self::get_invoke).
+    pub fn get_signal(&self, pid: &PromiseId) -> Option<Cow<'_, Payload>> {
         match self.lookup(pid) {
-            Some(CachedResult::Signal(payload)) => Some(payload),
+            Some(CachedResult::Signal(payload)) => Some(Cow::Borrowed(payload)),
             _ => None,
         }
     }
@@ -141,6 +172,229 @@ impl ReplayCache {
     }
 }
 
+/// Re-fetches a spilled payload given the sequence number of the journal
+/// entry that recorded it (`InvokeCompleted` or `SignalReceived`).
+///
+/// Typically backed by the journal store. Returning `None` means the entry
+/// could not be read; the accessor then reports the result as missing.
+pub type ResultFetcher = Box<dyn Fn(u64) -> Option<Payload> + Send + Sync>;
+
+/// Which typed accessor a payload slot answers to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum PayloadKind {
+    Invoke,
+    Signal,
+}
+
+/// A payload result: always located in the journal, resident while it fits.
+#[derive(Debug)]
+struct PayloadSlot {
+    kind: PayloadKind,
+    sequence: u64,
+    resident: Option<Payload>,
+    /// LRU clock value of the last insert or read; key into `lru`.
+    last_used: u64,
+}
+
+/// [`ReplayCache`] that keeps at most `budget_bytes` of payload data resident.
+///
+/// Invoke results and signal payloads are the only unbounded values in the
+/// cache. Each is recorded by its journal sequence number; the bytes stay
+/// resident only while they fit the budget. Payloads larger than the spill
+/// threshold are never kept, and under budget pressure the least recently
+/// used payload is evicted. A spilled payload is re-read through the
+/// [`ResultFetcher`] on access and returned owned, without being re-admitted:
+/// replay reads each result once, in journal order.
+///
+/// Random bytes, times and timer completions are small and always resident.
+pub struct BoundedReplayCache {
+    values: ReplayCache,
+    payloads: HashMap<PromiseId, PayloadSlot>,
+    /// Resident payloads by `last_used`, oldest first.
+    lru: BTreeMap<u64, PromiseId>,
+    clock: u64,
+    budget_bytes: usize,
+    spill_threshold: usize,
+    resident_bytes: usize,
+    fetcher: ResultFetcher,
+    fetches: u64,
+}
+
+impl std::fmt::Debug for BoundedReplayCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BoundedReplayCache")
+            .field("len", &self.len())
+            .field("budget_bytes", &self.budget_bytes)
+            .field("spill_threshold", &self.spill_threshold)
+            .field("resident_bytes", &self.resident_bytes)
+            .field("fetches", &self.fetches)
+            .finish_non_exhaustive()
+    }
+}
+
+impl BoundedReplayCache {
+    /// Empty cache holding at most `budget_bytes` of resident payload bytes.
+    ///
+    /// The spill threshold defaults to the whole budget; see
+    /// [`with_spill_threshold`](Self::with_spill_threshold).
+    pub fn new(budget_bytes: usize, fetcher: ResultFetcher) -> Self {
+        Self {
+            values: ReplayCache::new(),
+            payloads: HashMap::new(),
+            lru: BTreeMap::new(),
+            clock: 0,
+            budget_bytes,
+            spill_threshold: budget_bytes,
+            resident_bytes: 0,
+            fetcher,
+            fetches: 0,
+        }
+    }
+
+    /// Never keep payloads larger than `bytes` resident.
+    pub fn with_spill_threshold(mut self, bytes: usize) -> Self {
+        self.spill_threshold = bytes;
+        self
+    }
+
+    /// Build from a full journal history in one pass.
+    ///
+    /// Caches the same event kinds as [`ReplayCache::build`].
+    pub fn build(entries: &[JournalEntry], budget_bytes: usize, fetcher: ResultFetcher) -> Self {
+        let mut cache = Self::new(budget_bytes, fetcher);
+        for entry in entries {
+            cache.insert_event(entry);
+        }
+        cache
+    }
+
+    /// Index a single journal entry into the cache.
+    pub fn insert_event(&mut self, entry: &JournalEntry) {
+        match &entry.event {
+            EventType::InvokeCompleted {
+                promise_id, result, ..
+            } => self.insert_payload(promise_id, PayloadKind::Invoke, entry.sequence, result),
+            EventType::SignalReceived {
+                promise_id,
+                payload,
+                ..
+            } => self.insert_payload(promise_id, PayloadKind::Signal, entry.sequence, payload),
+            _ => self.values.insert_event(entry),
+        }
+    }
+
+    fn insert_payload(
+        &mut self,
+        pid: &PromiseId,
+        kind: PayloadKind,
+        sequence: u64,
+        payload: &Payload,
+    ) {
+        if let Some(old) = self.payloads.remove(pid) {
+            self.release(&old);
+        }
+        let size = payload.bytes.len();
+        let resident = size <= self.spill_threshold && size <= self.budget_bytes;
+        self.clock += 1;
+        if resident {
+            self.lru.insert(self.clock, pid.clone());
+            self.resident_bytes += size;
+        }
+        self.payloads.insert(
+            pid.clone(),
+            PayloadSlot {
+                kind,
+                sequence,
+                resident: resident.then(|| payload.clone()),
+                last_used: self.clock,
+            },
+        );
+        self.evict_to_budget();
+    }
+
+    /// Drop least recently used payloads until the budget holds.
+    fn evict_to_budget(&mut self) {
+        while self.resident_bytes > self.budget_bytes {
+            let Some((_, pid)) = self.lru.pop_first() else {
+                break;
+            };
+            if let Some(slot) = self.payloads.get_mut(&pid)
+                && let Some(payload) = slot.resident.take()
+            {
+                self.resident_bytes -= payload.bytes.len();
+            }
+        }
+    }
+
+    fn release(&mut self, slot: &PayloadSlot) {
+        if let Some(payload) = &slot.resident {
+            self.lru.remove(&slot.last_used);
+            self.resident_bytes -= payload.bytes.len();
+        }
+    }
+
+    fn get_payload(&mut self, pid: &PromiseId, kind: PayloadKind) -> Option<Cow<'_, Payload>> {
+        let slot = self
+            .payloads
+            .get_mut(pid)
+            .filter(|slot| slot.kind == kind)?;
+        if slot.resident.is_none() {
+            self.fetches += 1;
+            return (self.fetcher)(slot.sequence).map(Cow::Owned);
+        }
+        self.clock += 1;
+        self.lru.remove(&slot.last_used);
+        self.lru.insert(self.clock, pid.clone());
+        slot.last_used = self.clock;
+        slot.resident.as_ref().map(Cow::Borrowed)
+    }
+
+    /// Typed accessor for invoke results; owned when re-fetched.
+    pub fn get_invoke(&mut self, pid: &PromiseId) -> Option<Cow<'_, Payload>> {
+        self.get_payload(pid, PayloadKind::Invoke)
+    }
+
+    /// Typed accessor for received signal payloads; owned when re-fetched.
+    pub fn get_signal(&mut self, pid: &PromiseId) -> Option<Cow<'_, Payload>> {
+        self.get_payload(pid, PayloadKind::Signal)
+    }
+
+    /// Typed accessor for random bytes.
+    pub fn get_random(&self, pid: &PromiseId) -> Option<&[u8]> {
+        self.values.get_random(pid)
+    }
+
+    /// Typed accessor for recorded wall-clock time.
+    pub fn get_time(&self, pid: &PromiseId) -> Option<DateTime<Utc>> {
+        self.values.get_time(pid)
+    }
+
+    /// True if timer completion was recorded for this promise.
+    pub fn is_timer_complete(&self, pid: &PromiseId) -> bool {
+        self.values.is_timer_complete(pid)
+    }
+
+    /// Bytes of payload data currently resident.
+    pub fn resident_bytes(&self) -> usize {
+        self.resident_bytes
+    }
+
+    /// Number of payloads re-read through the fetcher so far.
+    pub fn fetch_count(&self) -> u64 {
+        self.fetches
+    }
+
+    /// Number of cached promise results.
+    pub fn len(&self) -> usize {
+        self.values.len() + self.payloads.len()
+    }
+
+    /// True when no promise results are cached.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::time::Duration;
@@ -218,6 +472,7 @@ mod tests {
                     signal_name: "sig".into(),
                     payload: payload(&[3]),
                     delivery_id: 2,
+                    external_id: None,
                 },
             ),
             entry(
@@ -234,11 +489,11 @@ mod tests {
 
         assert_eq!(cache.len(), 5);
         assert!(!cache.is_empty());
-        assert_eq!(cache.get_invoke(&p_invoke), Some(&payload(&[1])));
+        assert_eq!(cache.get_invoke(&p_invoke).as_deref(), Some(&payload(&[1])));
         assert_eq!(cache.get_random(&p_random), Some([7, 8, 9].as_slice()));
         assert!(cache.get_time(&p_time).is_some());
         assert!(cache.is_timer_complete(&p_timer));
-        assert_eq!(cache.get_signal(&p_signal), Some(&payload(&[2])));
+        assert_eq!(cache.get_signal(&p_signal).as_deref(), Some(&payload(&[2])));
     }
 
     #[test]
@@ -259,4 +514,120 @@ mod tests {
         assert!(!cache.is_timer_complete(&p_invoke));
         assert!(cache.get_signal(&p_invoke).is_none());
     }
+
+    #[test]
+    fn apply_tracked_reports_only_value_changing_overwrites() {
+        let p = pid(21);
+        let completed = |sequence, byte| {
+            entry(
+                sequence,
+                EventType::InvokeCompleted {
+                    promise_id: p.clone(),
+                    result: payload(&[byte]),
+                    attempt: 1,
+                },
+            )
+        };
+        let mut cache = ReplayCache::new();
+
+        assert_eq!(cache.apply_tracked(&completed(0, 1)), None);
+        // Benign re-insert of the same value.
+        assert_eq!(cache.apply_tracked(&completed(1, 1)), None);
+        assert_eq!(
+            cache.apply_tracked(&completed(2, 2)),
+            Some(CacheOverwrite {
+                promise_id: p.clone(),
+                seq: 2,
+            })
+        );
+        assert_eq!(cache.get_invoke(&p).as_deref(), Some(&payload(&[2])));
+        // Uncached events are never overwrites.
+        assert_eq!(
+            cache.apply_tracked(&entry(3, EventType::ExecutionResumed)),
+            None
+        );
+    }
+
+    // ── BoundedReplayCache ──
+
+    const MIB: usize = 1024 * 1024;
+
+    /// Deterministic stand-in for the payload recorded at `sequence`.
+    fn nominal_payload(sequence: u64, len: usize) -> Payload {
+        let mut bytes = vec![sequence as u8; len];
+        bytes[..8].copy_from_slice(&sequence.to_le_bytes());
+        payload(&bytes)
+    }
+
+    fn invoke_completed(sequence: u64, len: usize) -> JournalEntry {
+        entry(
+            sequence,
+            EventType::InvokeCompleted {
+                promise_id: PromiseId::new([0; 32]).child(sequence as u32).unwrap(),
+                result: nominal_payload(sequence, len),
+                attempt: 1,
+            },
+        )
+    }
+
+    #[test]
+    fn bounded_cache_replays_1gb_under_64mb_budget() {
+        const ENTRIES: u64 = 1024;
+        let budget = 64 * MIB;
+        let fetcher: ResultFetcher = Box::new(|seq| Some(nominal_payload(seq, MIB)));
+        let mut cache = BoundedReplayCache::new(budget, fetcher);
+
+        // Stream entries so only the budget's worth is ever held.
+        for seq in 0..ENTRIES {
+            cache.insert_event(&invoke_completed(seq, MIB));
+            assert!(cache.resident_bytes() <= budget);
+        }
+        assert_eq!(cache.len(), ENTRIES as usize);
+        assert_eq!(cache.fetch_count(), 0);
+
+        for seq in 0..ENTRIES {
+            let pid = PromiseId::new([0; 32]).child(seq as u32).unwrap();
+            let result = cache.get_invoke(&pid).expect("cached");
+            assert_eq!(result.bytes.len(), MIB);
+            assert_eq!(result.bytes[..8], seq.to_le_bytes());
+            assert_eq!(result.bytes[MIB - 1], seq as u8);
+        }
+        // Only the last 64 results stayed resident.
+        assert_eq!(cache.fetch_count(), ENTRIES - 64);
+    }
+
+    #[test]
+    fn bounded_cache_spills_oversized_payloads_and_owns_fetches() {
+        let fetcher: ResultFetcher = Box::new(|seq| Some(nominal_payload(seq, 64)));
+        let mut cache = BoundedReplayCache::new(1024, fetcher).with_spill_threshold(32);
+        cache.insert_event(&invoke_completed(0, 16));
+        cache.insert_event(&invoke_completed(1, 64));
+        assert_eq!(cache.resident_bytes(), 16);
+
+        let small = PromiseId::new([0; 32]).child(0).unwrap();
+        let large = PromiseId::new([0; 32]).child(1).unwrap();
+        assert!(matches!(cache.get_invoke(&small), Some(Cow::Borrowed(_))));
+        assert!(matches!(cache.get_invoke(&large), Some(Cow::Owned(_))));
+        assert_eq!(cache.fetch_count(), 1);
+        assert!(cache.get_signal(&large).is_none());
+        assert_eq!(cache.fetch_count(), 1);
+    }
+
+    #[test]
+    fn bounded_cache_evicts_least_recently_used() {
+        let fetcher: ResultFetcher = Box::new(|seq| Some(nominal_payload(seq, 16)));
+        let mut cache = BoundedReplayCache::new(32, fetcher);
+        let pid = |seq: u64| PromiseId::new([0; 32]).child(seq as u32).unwrap();
+
+        cache.insert_event(&invoke_completed(0, 16));
+        cache.insert_event(&invoke_completed(1, 16));
+        // Touch 0 so 1 becomes the eviction candidate.
+        assert!(matches!(cache.get_invoke(&pid(0)), Some(Cow::Borrowed(_))));
+        cache.insert_event(&invoke_completed(2, 16));
+
+        assert!(matches!(cache.get_invoke(&pid(0)), Some(Cow::Borrowed(_))));
+        assert!(matches!(cache.get_invoke(&pid(1)), Some(Cow::Owned(_))));
+        assert!(matches!(cache.get_invoke(&pid(2)), Some(Cow::Borrowed(_))));
+        assert_eq!(cache.fetch_count(), 1);
+    }
 }
@@ -1,53 +1,107 @@
-use invariant_types::{EventType, JoinSetId, JournalEntry, PromiseId, SignalDeliveryId};
+use std::collections::{BTreeSet, HashMap};
+
+use chrono::{DateTime, Utc};
+use indexmap::{IndexMap, IndexSet};
+use invariant_types::{
+    AwaitKind, EventType, ExecutionId, ExecutionJournal, JoinSetId, JournalEntry, Payload,
+    PromiseId, SignalDeliveryId, SignalName,
+};
+
+use crate::causality::{EventSelector, first_seq};
 
 /// Returns true if the invocation identified by `pid` was ever scheduled.
 ///
 /// Scan complexity: O(n).
 pub fn is_invoke_scheduled(entries: &[JournalEntry], pid: &PromiseId) -> bool {
-    entries.iter().any(|e| match &e.event {
-        EventType::InvokeScheduled { promise_id, .. } => promise_id == pid,
-        _ => false,
-    })
+    first_seq(entries, EventSelector::InvokeScheduledOf(pid)).is_some()
 }
 
 /// Returns true if the invocation identified by `pid` was ever started.
 ///
 /// Scan complexity: O(n).
 pub fn is_invoke_started(entries: &[JournalEntry], pid: &PromiseId) -> bool {
-    entries.iter().any(|e| match &e.event {
-        EventType::InvokeStarted { promise_id, .. } => promise_id == pid,
-        _ => false,
-    })
+    first_seq(entries, EventSelector::InvokeStartedOf(pid)).is_some()
 }
 
 /// Returns true if the invocation identified by `pid` was ever completed.
 ///
 /// Scan complexity: O(n).
 pub fn is_invoke_completed(entries: &[JournalEntry], pid: &PromiseId) -> bool {
-    entries.iter().any(|e| match &e.event {
-        EventType::InvokeCompleted { promise_id, .. } => promise_id == pid,
-        _ => false,
-    })
+    first_seq(entries, EventSelector::InvokeCompletedOf(pid)).is_some()
+}
+
+/// Full history of one invocation, gathered in a single pass.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct InvokeLifecycle {
+    /// An `InvokeScheduled` exists for the promise.
+    pub scheduled: bool,
+    /// Attempt numbers from `InvokeStarted`, in journal order.
+    pub started_attempts: Vec<u32>,
+    /// Number of `InvokeRetrying` entries.
+    pub retries: u32,
+    /// Attempt number from the first `InvokeCompleted`, if any.
+    pub completed_attempt: Option<u32>,
+}
+
+/// Returns the scheduled/started/retrying/completed history of invocation `pid`.
+///
+/// Equivalent to calling the individual `is_invoke_*` helpers and
+/// [`retry_count`], but in one scan.
+/// Scan complexity: O(n).
+pub fn invoke_lifecycle(entries: &[JournalEntry], pid: &PromiseId) -> InvokeLifecycle {
+    let mut lifecycle = InvokeLifecycle::default();
+    for e in entries {
+        match &e.event {
+            EventType::InvokeScheduled { promise_id, .. } if promise_id == pid => {
+                lifecycle.scheduled = true;
+            }
+            EventType::InvokeStarted {
+                promise_id,
+                attempt,
+            } if promise_id == pid => lifecycle.started_attempts.push(*attempt),
+            EventType::InvokeRetrying { promise_id, .. } if promise_id == pid => {
+                lifecycle.retries = lifecycle.retries.saturating_add(1);
+            }
+            EventType::InvokeCompleted {
+                promise_id,
+                attempt,
+                ..
+            } if promise_id == pid => {
+                lifecycle.completed_attempt.get_or_insert(*attempt);
+            }
+            _ => {}
+        }
+    }
+    lifecycle
+}
+
+/// Returns scheduled invoke promise IDs in journal order.
+///
+/// Replay compares this with the order re-execution schedules invokes;
+/// the first mismatch is where the workflow diverged.
+/// Scan complexity: O(n).
+pub fn schedule_order(entries: &[JournalEntry]) -> Vec<PromiseId> {
+    entries
+        .iter()
+        .filter_map(|e| match &e.event {
+            EventType::InvokeScheduled { promise_id, .. } => Some(promise_id.clone()),
+            _ => None,
+        })
+        .collect()
 }
 
 /// Returns true if the timer identified by `pid` was ever scheduled.
 ///
 /// Scan complexity: O(n).
 pub fn is_timer_scheduled(entries: &[JournalEntry], pid: &PromiseId) -> bool {
-    entries.iter().any(|e| match &e.event {
-        EventType::TimerScheduled { promise_id, .. } => promise_id == pid,
-        _ => false,
-    })
+    first_seq(entries, EventSelector::TimerScheduledOf(pid)).is_some()
 }
 
 /// Returns true if the timer identified by `pid` was ever fired.
 ///
 /// Scan complexity: O(n).
 pub fn is_timer_fired(entries: &[JournalEntry], pid: &PromiseId) -> bool {
-    entries.iter().any(|e| match &e.event {
-        EventType::TimerFired { promise_id } => promise_id == pid,
-        _ => false,
-    })
+    first_seq(entries, EventSelector::TimerFiredOf(pid)).is_some()
 }
 
 /// Returns true if a signal delivery `(name, delivery_id)` exists in the journal.
@@ -56,17 +110,14 @@ pub fn is_timer_fired(entries: &[JournalEntry], pid: &PromiseId) -> bool {
 /// Scan complexity: O(n).
 pub fn is_signal_delivered(
     entries: &[JournalEntry],
-    name: &str,
+    name: &SignalName,
     delivery_id: SignalDeliveryId,
 ) -> bool {
-    entries.iter().any(|e| match &e.event {
-        EventType::SignalDelivered {
-            signal_name,
-            delivery_id: did,
-            ..
-        } => signal_name == name && *did == delivery_id,
-        _ => false,
-    })
+    first_seq(
+        entries,
+        EventSelector::SignalDeliveredOf(name.as_str(), delivery_id),
+    )
+    .is_some()
 }
 
 /// Returns true if a signal delivery `(name, delivery_id)` was consumed by workflow code.
@@ -75,34 +126,74 @@ pub fn is_signal_delivered(
 /// Scan complexity: O(n).
 pub fn is_signal_consumed(
     entries: &[JournalEntry],
-    name: &str,
+    name: &SignalName,
     delivery_id: SignalDeliveryId,
 ) -> bool {
-    entries.iter().any(|e| match &e.event {
-        EventType::SignalReceived {
-            signal_name,
-            delivery_id: did,
-            ..
-        } => signal_name == name && *did == delivery_id,
-        _ => false,
-    })
+    first_seq(
+        entries,
+        EventSelector::SignalReceivedOf(name.as_str(), delivery_id),
+    )
+    .is_some()
+}
+
+/// The pending delivery an `AwaitKind::AnySignal` over `names` consumes next.
+///
+/// Among deliveries of the listed names neither received nor expired,
+/// returns the lowest `(name, delivery_id)`, comparing names bytewise. The
+/// choice ignores arrival order, so replay and recovery pick the same winner
+/// however the deliveries interleaved. Returns `None` while nothing is pending.
+/// Scan complexity: O(n log n).
+pub fn next_signal_delivery<'a>(
+    entries: &'a [JournalEntry],
+    names: &[String],
+) -> Option<(&'a str, SignalDeliveryId)> {
+    let mut pending = BTreeSet::new();
+    for e in entries {
+        match &e.event {
+            EventType::SignalDelivered {
+                signal_name,
+                delivery_id,
+                ..
+            } if names.contains(signal_name) => {
+                pending.insert((signal_name.as_str(), *delivery_id));
+            }
+            EventType::SignalReceived {
+                signal_name,
+                delivery_id,
+                ..
+            }
+            | EventType::SignalDeliveryExpired {
+                signal_name,
+                delivery_id,
+            } => {
+                pending.remove(&(signal_name.as_str(), *delivery_id));
+            }
+            _ => {}
+        }
+    }
+    pending.pop_first()
 }
 
 /// Returns true if join set `js_id` was created.
 ///
 /// Scan complexity: O(n).
 pub fn is_join_set_created(entries: &[JournalEntry], js_id: &JoinSetId) -> bool {
-    entries.iter().any(|e| match &e.event {
-        EventType::JoinSetCreated { join_set_id } => join_set_id == js_id,
-        _ => false,
-    })
+    first_seq(entries, EventSelector::JoinSetCreatedOf(js_id)).is_some()
+}
+
+/// Returns the sequence of the `JoinSetCreated` entry for `js_id`, if any.
+///
+/// Pair with [`join_set_members`] to render a join-set lifespan.
+/// Scan complexity: O(n).
+pub fn join_set_created_at(entries: &[JournalEntry], js_id: &JoinSetId) -> Option<u64> {
+    first_seq(entries, EventSelector::JoinSetCreatedOf(js_id))
 }
 
 /// Returns submitted members for join set `js_id` in journal order.
 ///
-/// Duplicates are preserved if the journal contains them.
+/// A promise submitted more than once appears once, at its first position.
 /// Scan complexity: O(n).
-pub fn join_set_members(entries: &[JournalEntry], js_id: &JoinSetId) -> Vec<PromiseId> {
+pub fn join_set_members(entries: &[JournalEntry], js_id: &JoinSetId) -> IndexSet<PromiseId> {
     entries
         .iter()
         .filter_map(|e| match &e.event {
@@ -117,9 +208,9 @@ pub fn join_set_members(entries: &[JournalEntry], js_id: &JoinSetId) -> Vec<Prom
 
 /// Returns consumed members for join set `js_id` in journal order.
 ///
-/// Duplicates are preserved if the journal contains them.
+/// A promise consumed more than once appears once, at its first position.
 /// Scan complexity: O(n).
-pub fn join_set_consumed(entries: &[JournalEntry], js_id: &JoinSetId) -> Vec<PromiseId> {
+pub fn join_set_consumed(entries: &[JournalEntry], js_id: &JoinSetId) -> IndexSet<PromiseId> {
     entries
         .iter()
         .filter_map(|e| match &e.event {
@@ -133,6 +224,21 @@ pub fn join_set_consumed(entries: &[JournalEntry], js_id: &JoinSetId) -> Vec<Pro
         .collect()
 }
 
+/// Pairs each consumed member of `js_id`, in consumption order, with its
+/// index in submission order.
+///
+/// An increasing index sequence means results were consumed in submission
+/// order; anything else shows the reordering. Consumed promises that were
+/// never submitted (a JS-3 violation) are skipped.
+/// Scan complexity: O(n).
+pub fn consumption_order(entries: &[JournalEntry], js_id: &JoinSetId) -> Vec<(usize, PromiseId)> {
+    let members = join_set_members(entries, js_id);
+    join_set_consumed(entries, js_id)
+        .into_iter()
+        .filter_map(|pid| Some((members.get_index_of(&pid)?, pid)))
+        .collect()
+}
+
 /// Returns the first join set that submitted `pid`, if any.
 ///
 /// "First" is based on journal order.
@@ -147,13 +253,89 @@ pub fn promise_owner(entries: &[JournalEntry], pid: &PromiseId) -> Option<JoinSe
     })
 }
 
+/// Returns the owning join set of every submitted promise.
+///
+/// Batch analog of [`promise_owner`]: the first submit wins, matching
+/// `pid_owner` in `InvariantState`.
+/// Scan complexity: O(n).
+pub fn promise_owners(entries: &[JournalEntry]) -> HashMap<PromiseId, JoinSetId> {
+    let mut owners = HashMap::new();
+    for e in entries {
+        if let EventType::JoinSetSubmitted {
+            join_set_id,
+            promise_id,
+        } = &e.event
+        {
+            owners
+                .entry(promise_id.clone())
+                .or_insert_with(|| join_set_id.clone());
+        }
+    }
+    owners
+}
+
 /// Returns true if a cancellation request appears anywhere in the journal.
 ///
 /// Scan complexity: O(n).
 pub fn has_cancel_requested(entries: &[JournalEntry]) -> bool {
-    entries
-        .iter()
-        .any(|e| matches!(e.event, EventType::CancelRequested { .. }))
+    first_seq(entries, EventSelector::CancelRequested).is_some()
+}
+
+/// Digest of the component build in effect at the end of `entries`.
+///
+/// The latest `ComponentUpgraded.new_digest`, else the `ExecutionStarted`
+/// digest; `None` without either.
+///
+/// Scan complexity: O(n).
+pub fn component_digest(entries: &[JournalEntry]) -> Option<&[u8]> {
+    entries.iter().rev().find_map(|e| match &e.event {
+        EventType::ComponentUpgraded { new_digest, .. } => Some(new_digest.as_slice()),
+        EventType::ExecutionStarted {
+            component_digest, ..
+        } => Some(component_digest.as_slice()),
+        _ => None,
+    })
+}
+
+/// Cancellation request and its finalization, by sequence number.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CancellationInfo {
+    /// Sequence of the first `CancelRequested`.
+    pub requested_at: u64,
+    pub requested_reason: String,
+    /// Sequence of the `ExecutionCancelled`, once cleanup finished.
+    pub cancelled_at: Option<u64>,
+    pub cancelled_reason: Option<String>,
+}
+
+/// Returns the cancellation lifecycle, or `None` if no cancel was requested.
+///
+/// S-5 guarantees `ExecutionCancelled` is preceded by `CancelRequested`, so
+/// a finalization without a request is not reported.
+/// Scan complexity: O(n).
+pub fn cancellation_info(entries: &[JournalEntry]) -> Option<CancellationInfo> {
+    let mut info: Option<CancellationInfo> = None;
+    for e in entries {
+        match &e.event {
+            EventType::CancelRequested { reason } if info.is_none() => {
+                info = Some(CancellationInfo {
+                    requested_at: e.sequence,
+                    requested_reason: reason.clone(),
+                    cancelled_at: None,
+                    cancelled_reason: None,
+                });
+            }
+            EventType::ExecutionCancelled { reason } => {
+                if let Some(info) = info.as_mut() {
+                    info.cancelled_at = Some(e.sequence);
+                    info.cancelled_reason = Some(reason.clone());
+                }
+                break;
+            }
+            _ => {}
+        }
+    }
+    info
 }
 
 /// Returns the first terminal event in journal order, if present.
@@ -170,6 +352,175 @@ pub fn terminal_event(entries: &[JournalEntry]) -> Option<&EventType> {
     })
 }
 
+/// Work a terminated execution left behind, from [`abandoned_promises`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Abandoned {
+    /// Unresolved promises, in scheduling order.
+    pub promises: Vec<PromiseId>,
+    /// Signal deliveries never received or expired, by name and delivery
+    /// ID, in delivery order.
+    pub deliveries: Vec<(String, SignalDeliveryId)>,
+}
+
+impl Abandoned {
+    pub fn is_empty(&self) -> bool {
+        self.promises.is_empty() && self.deliveries.is_empty()
+    }
+}
+
+/// Work a terminated execution left unresolved, judged at the first
+/// terminal event.
+///
+/// Promises cover invokes scheduled or started without an
+/// `InvokeCompleted`, timers that never fired, and signal awaits with no
+/// matching `SignalReceived`. Deliveries cover `SignalDelivered` entries
+/// that no `SignalReceived` consumed and no `SignalDeliveryExpired`
+/// retired. Returns nothing if the journal has not terminated, since its
+/// open work may still resolve.
+/// Scan complexity: O(n).
+pub fn abandoned_promises(entries: &[JournalEntry]) -> Abandoned {
+    let Some(terminal) = entries.iter().position(|e| e.event.is_terminal()) else {
+        return Abandoned::default();
+    };
+    let mut open: IndexSet<PromiseId> = IndexSet::new();
+    let mut unconsumed: IndexSet<(&str, SignalDeliveryId)> = IndexSet::new();
+    for e in &entries[..terminal] {
+        match &e.event {
+            EventType::InvokeScheduled { promise_id, .. }
+            | EventType::InvokeStarted { promise_id, .. }
+            | EventType::TimerScheduled { promise_id, .. }
+            | EventType::ExecutionAwaiting {
+                kind: AwaitKind::Signal { promise_id, .. } | AwaitKind::AnySignal { promise_id, .. },
+                ..
+            } => {
+                open.insert(promise_id.clone());
+            }
+            EventType::InvokeCompleted { promise_id, .. }
+            | EventType::TimerFired { promise_id } => {
+                open.shift_remove(promise_id);
+            }
+            EventType::SignalReceived {
+                promise_id,
+                signal_name,
+                delivery_id,
+                ..
+            } => {
+                open.shift_remove(promise_id);
+                unconsumed.shift_remove(&(signal_name.as_str(), *delivery_id));
+            }
+            EventType::SignalDelivered {
+                signal_name,
+                delivery_id,
+                ..
+            } => {
+                unconsumed.insert((signal_name, *delivery_id));
+            }
+            EventType::SignalDeliveryExpired {
+                signal_name,
+                delivery_id,
+            } => {
+                unconsumed.shift_remove(&(signal_name.as_str(), *delivery_id));
+            }
+            _ => {}
+        }
+    }
+    Abandoned {
+        promises: open.into_iter().collect(),
+        deliveries: unconsumed
+            .into_iter()
+            .map(|(name, id)| (name.to_string(), id))
+            .collect(),
+    }
+}
+
+/// Defining inputs of an execution, as recorded by its `ExecutionStarted`.
+///
+/// Spawning a run from the same spec reproduces the original identity; a
+/// fork swaps in a new `idempotency_key`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RestartSpec {
+    pub component_digest: Vec<u8>,
+    pub input: Payload,
+    pub parent_id: Option<PromiseId>,
+    pub idempotency_key: String,
+}
+
+impl RestartSpec {
+    /// The execution ID a run started from this spec derives.
+    pub fn execution_id(&self) -> ExecutionId {
+        ExecutionId::derive(
+            &self.component_digest,
+            &self.idempotency_key,
+            self.parent_id.as_ref(),
+        )
+    }
+}
+
+/// Returns the restart spec from the first `ExecutionStarted`, if any.
+///
+/// Scan complexity: O(n), though a valid journal has it at index 0.
+pub fn restart_spec(entries: &[JournalEntry]) -> Option<RestartSpec> {
+    entries.iter().find_map(|e| match &e.event {
+        EventType::ExecutionStarted {
+            component_digest,
+            input,
+            parent_id,
+            idempotency_key,
+        } => Some(RestartSpec {
+            component_digest: component_digest.clone(),
+            input: input.clone(),
+            parent_id: parent_id.clone(),
+            idempotency_key: idempotency_key.clone(),
+        }),
+        _ => None,
+    })
+}
+
+/// Returns true if `a` and `b` are runs of the same logical execution.
+pub fn same_execution(a: &ExecutionJournal, b: &ExecutionJournal) -> bool {
+    a.execution_id == b.execution_id
+}
+
+/// Returns true if `child` was started from a promise in `parent`'s call
+/// tree, i.e. its `ExecutionStarted.parent_id` is `parent`'s root or one of
+/// the root's descendants.
+///
+/// Scan complexity: O(n) over `child`, though a valid journal has
+/// `ExecutionStarted` at index 0.
+pub fn is_fork_of(child: &ExecutionJournal, parent: &ExecutionJournal) -> bool {
+    let root = parent.execution_id.as_promise_id();
+    child
+        .entries
+        .iter()
+        .find_map(|e| match &e.event {
+            EventType::ExecutionStarted { parent_id, .. } => Some(parent_id.as_ref()),
+            _ => None,
+        })
+        .flatten()
+        .is_some_and(|pid| pid == root || root.is_ancestor_of(pid))
+}
+
+/// Interleaves the entries of `journals` into one timestamp-ordered timeline,
+/// each tagged with the execution it came from.
+///
+/// Meant for tracing a parent and its children end to end. The result is a
+/// read-only view, not a valid journal: sequences repeat across executions.
+/// Timestamps are wall-clock, so skew between hosts shows up as misordering.
+/// Entries with equal timestamps keep the order of `journals`, then sequence
+/// order.
+///
+/// Complexity: O(n log n) over all entries.
+pub fn merge_timelines<'a>(
+    journals: &[&'a ExecutionJournal],
+) -> Vec<(ExecutionId, &'a JournalEntry)> {
+    let mut timeline: Vec<_> = journals
+        .iter()
+        .flat_map(|j| j.entries.iter().map(|e| (j.execution_id.clone(), e)))
+        .collect();
+    timeline.sort_by_key(|(_, e)| e.timestamp);
+    timeline
+}
+
 /// Counts retry attempts (`InvokeRetrying`) for invocation `pid`.
 ///
 /// Scan complexity: O(n).
@@ -183,14 +534,52 @@ pub fn retry_count(entries: &[JournalEntry], pid: &PromiseId) -> usize {
         .count()
 }
 
+/// Invocations whose [`retry_count`] exceeds `limit`, with their count, in
+/// order of first retry.
+///
+/// Advisory diagnostics for audits (e.g. a retry policy tightened mid-run);
+/// the invariant checker never enforces an attempt ceiling.
+/// Scan complexity: O(n).
+pub fn retries_over_limit(entries: &[JournalEntry], limit: u32) -> Vec<(PromiseId, usize)> {
+    let mut counts: IndexMap<&PromiseId, usize> = IndexMap::new();
+    for e in entries {
+        if let EventType::InvokeRetrying { promise_id, .. } = &e.event {
+            *counts.entry(promise_id).or_default() += 1;
+        }
+    }
+    counts
+        .into_iter()
+        .filter(|&(_, count)| count > limit as usize)
+        .map(|(pid, count)| (pid.clone(), count))
+        .collect()
+}
+
+/// `(failed_attempt, retry_at)` for each `InvokeRetrying` of `pid`, in
+/// journal order.
+///
+/// Plotting the pairs shows the invoke's actual backoff cadence.
+/// Scan complexity: O(n).
+pub fn retry_timeline(entries: &[JournalEntry], pid: &PromiseId) -> Vec<(u32, DateTime<Utc>)> {
+    entries
+        .iter()
+        .filter_map(|e| match &e.event {
+            EventType::InvokeRetrying {
+                promise_id,
+                failed_attempt,
+                retry_at,
+                ..
+            } if promise_id == pid => Some((*failed_attempt, *retry_at)),
+            _ => None,
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use std::time::Duration;
 
-    use chrono::Utc;
-    use invariant_types::{
-        Codec, ErrorKind, ExecutionError, InvokeKind, JoinSetId, Payload, PromiseId,
-    };
+    use chrono::TimeDelta;
+    use invariant_types::{Codec, ErrorKind, ExecutionError, InvokeKind, JoinSetId};
 
     use super::*;
 
@@ -198,6 +587,10 @@ mod tests {
         PromiseId::new([tag; 32])
     }
 
+    fn signal(name: &str) -> SignalName {
+        SignalName::new(name).unwrap()
+    }
+
     fn payload() -> Payload {
         Payload::new(vec![], Codec::Json)
     }
@@ -210,6 +603,140 @@ mod tests {
         }
     }
 
+    fn started() -> EventType {
+        EventType::ExecutionStarted {
+            component_digest: vec![1],
+            input: payload(),
+            parent_id: None,
+            idempotency_key: "k".into(),
+        }
+    }
+
+    // ── Abandoned promises ──
+
+    #[test]
+    fn abandoned_promises_lists_unresolved_work_at_completion() {
+        let (done, dropped, timer, awaited) = (pid(1), pid(2), pid(3), pid(4));
+        let scheduled = |p: &PromiseId| EventType::InvokeScheduled {
+            promise_id: p.clone(),
+            kind: InvokeKind::Function,
+            function_name: "f".into(),
+            input: payload(),
+            retry_policy: None,
+            sla: None,
+        };
+        let mut entries = vec![
+            entry(0, started()),
+            entry(1, scheduled(&done)),
+            entry(2, scheduled(&dropped)),
+            entry(
+                3,
+                EventType::InvokeStarted {
+                    promise_id: dropped.clone(),
+                    attempt: 1,
+                },
+            ),
+            entry(
+                4,
+                EventType::InvokeCompleted {
+                    promise_id: done.clone(),
+                    result: payload(),
+                    attempt: 1,
+                },
+            ),
+            entry(
+                5,
+                EventType::TimerScheduled {
+                    promise_id: timer.clone(),
+                    duration: Duration::from_secs(1),
+                    fire_at: Utc::now(),
+                },
+            ),
+            entry(
+                6,
+                EventType::TimerFired {
+                    promise_id: timer.clone(),
+                },
+            ),
+            entry(
+                7,
+                EventType::ExecutionAwaiting {
+                    waiting_on: vec![awaited.clone()],
+                    kind: AwaitKind::Signal {
+                        name: "approve".into(),
+                        promise_id: awaited.clone(),
+                    },
+                },
+            ),
+            entry(
+                8,
+                EventType::SignalReceived {
+                    promise_id: awaited.clone(),
+                    signal_name: "approve".into(),
+                    payload: payload(),
+                    delivery_id: 0,
+                },
+            ),
+            entry(9, EventType::ExecutionResumed),
+        ];
+        // Still running: nothing is abandoned yet.
+        assert!(abandoned_promises(&entries).is_empty());
+
+        entries.push(entry(
+            10,
+            EventType::ExecutionCompleted { result: payload() },
+        ));
+        assert_eq!(
+            abandoned_promises(&entries),
+            Abandoned {
+                promises: vec![dropped],
+                deliveries: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn abandoned_promises_lists_buffered_deliveries_at_termination() {
+        let delivered = |delivery_id| EventType::SignalDelivered {
+            signal_name: "approve".into(),
+            payload: payload(),
+            delivery_id,
+            external_id: None,
+        };
+        let entries = vec![
+            entry(0, started()),
+            entry(1, delivered(0)),
+            entry(2, delivered(1)),
+            entry(3, delivered(2)),
+            entry(
+                4,
+                EventType::SignalReceived {
+                    promise_id: pid(1),
+                    signal_name: "approve".into(),
+                    payload: payload(),
+                    delivery_id: 0,
+                },
+            ),
+            entry(
+                5,
+                EventType::SignalDeliveryExpired {
+                    signal_name: "approve".into(),
+                    delivery_id: 2,
+                },
+            ),
+            entry(6, EventType::ExecutionCompleted { result: payload() }),
+            // Deliveries after the terminal event are not the run's to consume.
+            entry(7, delivered(3)),
+        ];
+        assert_eq!(
+            abandoned_promises(&entries),
+            Abandoned {
+                promises: vec![],
+                deliveries: vec![("approve".into(), 1)],
+            }
+        );
+    }
+
     // ── Invoke lifecycle ──
 
     #[test]
@@ -223,6 +750,7 @@ mod tests {
                 function_name: "work".into(),
                 input: payload(),
                 retry_policy: None,
+                sla: None,
             },
         )];
         assert!(is_invoke_scheduled(&entries, &p));
@@ -240,6 +768,7 @@ mod tests {
                 function_name: "work".into(),
                 input: payload(),
                 retry_policy: None,
+                sla: None,
             },
         )];
         assert!(!is_invoke_scheduled(&entries, &other));
@@ -274,6 +803,109 @@ mod tests {
         assert!(!is_invoke_completed(&entries, &pid(99)));
     }
 
+    #[test]
+    fn schedule_order_follows_journal_order() {
+        let scheduled = |p: PromiseId| EventType::InvokeScheduled {
+            promise_id: p,
+            kind: InvokeKind::Function,
+            function_name: "work".into(),
+            input: payload(),
+            retry_policy: None,
+            sla: None,
+        };
+        let entries = vec![
+            entry(0, scheduled(pid(3))),
+            entry(
+                1,
+                EventType::InvokeStarted {
+                    promise_id: pid(3),
+                    attempt: 1,
+                },
+            ),
+            entry(2, scheduled(pid(1))),
+            entry(3, scheduled(pid(2))),
+        ];
+
+        assert_eq!(schedule_order(&entries), vec![pid(3), pid(1), pid(2)]);
+        assert!(schedule_order(&[]).is_empty());
+    }
+
+    #[test]
+    fn invoke_lifecycle_tracks_retry_then_completion() {
+        let p = pid(1);
+        let other = pid(2);
+        let entries = vec![
+            entry(
+                0,
+                EventType::InvokeScheduled {
+                    promise_id: p.clone(),
+                    kind: InvokeKind::Function,
+                    function_name: "work".into(),
+                    input: payload(),
+                    retry_policy: None,
+                    sla: None,
+                },
+            ),
+            entry(
+                1,
+                EventType::InvokeStarted {
+                    promise_id: p.clone(),
+                    attempt: 1,
+                },
+            ),
+            entry(
+                2,
+                EventType::InvokeStarted {
+                    promise_id: other.clone(),
+                    attempt: 1,
+                },
+            ),
+            entry(
+                3,
+                EventType::InvokeRetrying {
+                    promise_id: p.clone(),
+                    failed_attempt: 1,
+                    error: ExecutionError::new(ErrorKind::Timeout, "slow"),
+                    retry_at: Utc::now(),
+                },
+            ),
+            entry(
+                4,
+                EventType::InvokeStarted {
+                    promise_id: p.clone(),
+                    attempt: 2,
+                },
+            ),
+            entry(
+                5,
+                EventType::InvokeCompleted {
+                    promise_id: p.clone(),
+                    result: payload(),
+                    attempt: 2,
+                },
+            ),
+        ];
+
+        assert_eq!(
+            invoke_lifecycle(&entries, &p),
+            InvokeLifecycle {
+                scheduled: true,
+                started_attempts: vec![1, 2],
+                retries: 1,
+                completed_attempt: Some(2),
+            }
+        );
+        assert_eq!(
+            invoke_lifecycle(&entries, &other),
+            InvokeLifecycle {
+                scheduled: false,
+                started_attempts: vec![1],
+                retries: 0,
+                completed_attempt: None,
+            }
+        );
+    }
+
     // ── Timer lifecycle ──
 
     #[test]
@@ -314,9 +946,10 @@ mod tests {
                 signal_name: "approval".into(),
                 payload: payload(),
                 delivery_id: 42,
+                external_id: None,
             },
         )];
-        assert!(is_signal_delivered(&entries, "approval", 42));
+        assert!(is_signal_delivered(&entries, &signal("approval"), 42));
     }
 
     #[test]
@@ -327,10 +960,11 @@ mod tests {
                 signal_name: "approval".into(),
                 payload: payload(),
                 delivery_id: 42,
+                external_id: None,
             },
         )];
-        assert!(!is_signal_delivered(&entries, "approval", 99));
-        assert!(!is_signal_delivered(&entries, "other", 42));
+        assert!(!is_signal_delivered(&entries, &signal("approval"), 99));
+        assert!(!is_signal_delivered(&entries, &signal("other"), 42));
     }
 
     #[test]
@@ -344,9 +978,9 @@ mod tests {
                 delivery_id: 7,
             },
         )];
-        assert!(is_signal_consumed(&entries, "approval", 7));
-        assert!(!is_signal_consumed(&entries, "approval", 99));
-        assert!(!is_signal_consumed(&entries, "other", 7));
+        assert!(is_signal_consumed(&entries, &signal("approval"), 7));
+        assert!(!is_signal_consumed(&entries, &signal("approval"), 99));
+        assert!(!is_signal_consumed(&entries, &signal("other"), 7));
     }
 
     // ── JoinSet queries ──
@@ -364,6 +998,29 @@ mod tests {
         assert!(!is_join_set_created(&entries, &JoinSetId(pid(99))));
     }
 
+    #[test]
+    fn join_set_created_at_returns_creating_sequence() {
+        let js = JoinSetId(pid(10));
+        let other_js = JoinSetId(pid(20));
+        let entries = vec![
+            entry(
+                0,
+                EventType::JoinSetCreated {
+                    join_set_id: other_js.clone(),
+                },
+            ),
+            entry(
+                1,
+                EventType::JoinSetCreated {
+                    join_set_id: js.clone(),
+                },
+            ),
+        ];
+        assert_eq!(join_set_created_at(&entries, &js), Some(1));
+        assert_eq!(join_set_created_at(&entries, &other_js), Some(0));
+        assert_eq!(join_set_created_at(&entries, &JoinSetId(pid(99))), None);
+    }
+
     #[test]
     fn join_set_members_returns_ordered() {
         let js = JoinSetId(pid(10));
@@ -405,7 +1062,23 @@ mod tests {
         ];
 
         let members = join_set_members(&entries, &js);
-        assert_eq!(members, vec![p1, p2, p3]);
+        assert_eq!(members.into_iter().collect::<Vec<_>>(), vec![p1, p2, p3]);
+    }
+
+    #[test]
+    fn join_set_members_deduplicates_resubmits() {
+        let js = JoinSetId(pid(10));
+        let p = pid(1);
+
+        let submit = || EventType::JoinSetSubmitted {
+            join_set_id: js.clone(),
+            promise_id: p.clone(),
+        };
+        let entries = vec![entry(0, submit()), entry(1, submit())];
+
+        let members = join_set_members(&entries, &js);
+        assert_eq!(members.len(), 1);
+        assert!(members.contains(&p));
     }
 
     #[test]
@@ -434,7 +1107,36 @@ mod tests {
         ];
 
         let consumed = join_set_consumed(&entries, &js);
-        assert_eq!(consumed, vec![p1, p2]);
+        assert_eq!(consumed.into_iter().collect::<Vec<_>>(), vec![p1, p2]);
+    }
+
+    #[test]
+    fn consumption_order_exposes_reverse_consumption() {
+        let js = JoinSetId(pid(10));
+        let submitted = [pid(1), pid(2), pid(3)];
+        let mut entries: Vec<_> = submitted
+            .iter()
+            .map(|p| EventType::JoinSetSubmitted {
+                join_set_id: js.clone(),
+                promise_id: p.clone(),
+            })
+            .collect();
+        entries.extend(submitted.iter().rev().map(|p| EventType::JoinSetAwaited {
+            join_set_id: js.clone(),
+            promise_id: p.clone(),
+            result: payload(),
+        }));
+        let entries: Vec<_> = entries
+            .into_iter()
+            .enumerate()
+            .map(|(i, event)| entry(i as u64, event))
+            .collect();
+
+        assert_eq!(
+            consumption_order(&entries, &js),
+            vec![(2, pid(3)), (1, pid(2)), (0, pid(1))]
+        );
+        assert!(consumption_order(&entries, &JoinSetId(pid(11))).is_empty());
     }
 
     #[test]
@@ -465,6 +1167,47 @@ mod tests {
         assert_eq!(promise_owner(&entries, &pid(99)), None);
     }
 
+    #[test]
+    fn promise_owners_maps_each_promise_to_first_set() {
+        let js_a = JoinSetId(pid(10));
+        let js_b = JoinSetId(pid(20));
+        let p1 = pid(1);
+        let p2 = pid(2);
+
+        let entries = vec![
+            entry(
+                0,
+                EventType::JoinSetSubmitted {
+                    join_set_id: js_a.clone(),
+                    promise_id: p1.clone(),
+                },
+            ),
+            entry(
+                1,
+                EventType::JoinSetSubmitted {
+                    join_set_id: js_b.clone(),
+                    promise_id: p2.clone(),
+                },
+            ),
+            // Resubmit to a different set — first writer wins.
+            entry(
+                2,
+                EventType::JoinSetSubmitted {
+                    join_set_id: js_b.clone(),
+                    promise_id: p1.clone(),
+                },
+            ),
+        ];
+
+        let owners = promise_owners(&entries);
+        assert_eq!(owners.len(), 2);
+        assert_eq!(owners.get(&p1), Some(&js_a));
+        assert_eq!(owners.get(&p2), Some(&js_b));
+        for pid in [&p1, &p2] {
+            assert_eq!(owners.get(pid), promise_owner(&entries, pid).as_ref());
+        }
+    }
+
     // ── Cancel / Terminal / Retry ──
 
     #[test]
@@ -489,6 +1232,64 @@ mod tests {
         assert!(has_cancel_requested(&with));
     }
 
+    #[test]
+    fn cancellation_info_requested_only() {
+        let entries = vec![
+            entry(0, started()),
+            entry(1, EventType::ExecutionResumed),
+            entry(
+                2,
+                EventType::CancelRequested {
+                    reason: "user".into(),
+                },
+            ),
+        ];
+        assert_eq!(
+            cancellation_info(&entries),
+            Some(CancellationInfo {
+                requested_at: 2,
+                requested_reason: "user".into(),
+                cancelled_at: None,
+                cancelled_reason: None,
+            })
+        );
+        assert_eq!(cancellation_info(&entries[..2]), None);
+    }
+
+    #[test]
+    fn cancellation_info_fully_cancelled() {
+        let entries = vec![
+            entry(0, started()),
+            entry(
+                1,
+                EventType::CancelRequested {
+                    reason: "user".into(),
+                },
+            ),
+            entry(
+                2,
+                EventType::CancelRequested {
+                    reason: "again".into(),
+                },
+            ),
+            entry(
+                3,
+                EventType::ExecutionCancelled {
+                    reason: "cleaned up".into(),
+                },
+            ),
+        ];
+        assert_eq!(
+            cancellation_info(&entries),
+            Some(CancellationInfo {
+                requested_at: 1,
+                requested_reason: "user".into(),
+                cancelled_at: Some(3),
+                cancelled_reason: Some("cleaned up".into()),
+            })
+        );
+    }
+
     #[test]
     fn terminal_event_returns_first() {
         let entries = vec![
@@ -509,6 +1310,7 @@ mod tests {
                     function_name: "f".into(),
                     input: payload(),
                     retry_policy: None,
+                    sla: None,
                 },
             ),
             entry(2, EventType::ExecutionCompleted { result: payload() }),
@@ -564,6 +1366,219 @@ mod tests {
         assert_eq!(retry_count(&entries, &pid(99)), 0);
     }
 
+    #[test]
+    fn retry_timeline_orders_attempts_with_backoff() {
+        let (p, other) = (pid(1), pid(2));
+        let base = Utc::now();
+        let retrying =
+            |p: &PromiseId, failed_attempt: u32, after_secs: i64| EventType::InvokeRetrying {
+                promise_id: p.clone(),
+                failed_attempt,
+                error: ExecutionError::new(ErrorKind::Timeout, "slow"),
+                retry_at: base + TimeDelta::seconds(after_secs),
+            };
+
+        let entries = vec![
+            entry(0, retrying(&p, 1, 1)),
+            entry(1, retrying(&other, 1, 3)),
+            entry(2, retrying(&p, 2, 2)),
+            entry(3, retrying(&p, 3, 4)),
+        ];
+
+        assert_eq!(
+            retry_timeline(&entries, &p),
+            vec![
+                (1, base + TimeDelta::seconds(1)),
+                (2, base + TimeDelta::seconds(2)),
+                (3, base + TimeDelta::seconds(4)),
+            ]
+        );
+        assert!(retry_timeline(&entries, &pid(99)).is_empty());
+    }
+
+    #[test]
+    fn next_signal_delivery_ignores_arrival_order() {
+        let names = vec!["rejected".to_string(), "approved".to_string()];
+        let delivered = |name: &str, delivery_id| EventType::SignalDelivered {
+            signal_name: name.into(),
+            payload: payload(),
+            delivery_id,
+            external_id: None,
+        };
+        let forward = vec![
+            entry(0, delivered("rejected", 0)),
+            entry(1, delivered("other", 0)),
+            entry(2, delivered("approved", 1)),
+        ];
+        let backward = vec![
+            entry(0, delivered("approved", 1)),
+            entry(1, delivered("rejected", 0)),
+        ];
+        assert_eq!(
+            next_signal_delivery(&forward, &names),
+            Some(("approved", 1))
+        );
+        assert_eq!(
+            next_signal_delivery(&backward, &names),
+            Some(("approved", 1))
+        );
+
+        let mut consumed = backward.clone();
+        consumed.push(entry(
+            2,
+            EventType::SignalReceived {
+                promise_id: pid(1),
+                signal_name: "approved".into(),
+                payload: payload(),
+                delivery_id: 1,
+            },
+        ));
+        assert_eq!(
+            next_signal_delivery(&consumed, &names),
+            Some(("rejected", 0))
+        );
+        assert_eq!(next_signal_delivery(&consumed, &names[1..]), None);
+    }
+
+    #[test]
+    fn retries_over_limit_reports_only_excess() {
+        let (over, within) = (pid(1), pid(2));
+        let retrying = |p: &PromiseId, failed_attempt: u32| EventType::InvokeRetrying {
+            promise_id: p.clone(),
+            failed_attempt,
+            error: ExecutionError::new(ErrorKind::Timeout, "slow"),
+            retry_at: Utc::now(),
+        };
+        let entries = vec![
+            entry(0, retrying(&within, 1)),
+            entry(1, retrying(&over, 1)),
+            entry(2, retrying(&over, 2)),
+            entry(3, retrying(&within, 2)),
+            entry(4, retrying(&over, 3)),
+        ];
+
+        assert_eq!(retries_over_limit(&entries, 2), vec![(over, 3)]);
+        assert!(retries_over_limit(&entries, 3).is_empty());
+    }
+
+    // ── Execution identity ──
+
+    fn journal_from(parent_id: Option<PromiseId>, key: &str) -> ExecutionJournal {
+        let execution_id = ExecutionId::derive(&[1], key, parent_id.as_ref());
+        ExecutionJournal {
+            execution_id,
+            entries: vec![entry(
+                0,
+                EventType::ExecutionStarted {
+                    component_digest: vec![1],
+                    input: payload(),
+                    parent_id,
+                    idempotency_key: key.into(),
+                },
+            )],
+            provenance: None,
+        }
+    }
+
+    #[test]
+    fn same_execution_compares_ids() {
+        let a = journal_from(None, "k");
+        assert!(same_execution(&a, &journal_from(None, "k")));
+        assert!(!same_execution(&a, &journal_from(None, "other")));
+    }
+
+    #[test]
+    fn is_fork_of_follows_parent_id_into_call_tree() {
+        let parent = journal_from(None, "k");
+        let root = parent.execution_id.as_promise_id().clone();
+        let nested = root.child(2).unwrap().child(0).unwrap();
+
+        assert!(is_fork_of(&journal_from(Some(root.clone()), "c"), &parent));
+        assert!(is_fork_of(&journal_from(Some(nested), "c"), &parent));
+
+        let unrelated = journal_from(None, "u");
+        assert!(!is_fork_of(&journal_from(Some(root), "c"), &unrelated));
+        assert!(!is_fork_of(&journal_from(None, "c"), &parent));
+        assert!(!is_fork_of(&parent, &parent));
+    }
+
+    #[test]
+    fn merge_timelines_interleaves_parent_and_child_by_timestamp() {
+        let at = |secs| DateTime::UNIX_EPOCH + TimeDelta::seconds(secs);
+        let stamped = |j: &mut ExecutionJournal, secs: &[i64]| {
+            let start = j.entries[0].event.clone();
+            j.entries = secs
+                .iter()
+                .enumerate()
+                .map(|(i, &s)| JournalEntry {
+                    sequence: i as u64,
+                    timestamp: at(s),
+                    event: if i == 0 {
+                        start.clone()
+                    } else {
+                        EventType::ExecutionResumed
+                    },
+                })
+                .collect();
+        };
+        let mut parent = journal_from(None, "p");
+        let mut child = journal_from(Some(parent.execution_id.as_promise_id().clone()), "c");
+        stamped(&mut parent, &[0, 3, 5]);
+        stamped(&mut child, &[1, 3, 4]);
+
+        let merged = merge_timelines(&[&parent, &child]);
+        let order: Vec<_> = merged
+            .iter()
+            .map(|(id, e)| (id == &parent.execution_id, e.timestamp))
+            .collect();
+        assert_eq!(
+            order,
+            [
+                (true, at(0)),
+                (false, at(1)),
+                (true, at(3)),
+                (false, at(3)),
+                (false, at(4)),
+                (true, at(5)),
+            ]
+        );
+        assert!(std::ptr::eq(merged[3].1, &child.entries[1]));
+    }
+
+    // ── Restart ──
+
+    #[test]
+    fn restart_spec_extracted_from_started() {
+        let parent = pid(5);
+        let entries = vec![
+            entry(
+                0,
+                EventType::ExecutionStarted {
+                    component_digest: vec![1, 2, 3],
+                    input: Payload::from_str("order-42"),
+                    parent_id: Some(parent.clone()),
+                    idempotency_key: "order-42".into(),
+                },
+            ),
+            entry(1, EventType::ExecutionCompleted { result: payload() }),
+        ];
+
+        let spec = restart_spec(&entries).unwrap();
+        assert_eq!(
+            spec,
+            RestartSpec {
+                component_digest: vec![1, 2, 3],
+                input: Payload::from_str("order-42"),
+                parent_id: Some(parent.clone()),
+                idempotency_key: "order-42".into(),
+            }
+        );
+        assert_eq!(
+            spec.execution_id(),
+            ExecutionId::derive(&[1, 2, 3], "order-42", Some(&parent))
+        );
+    }
+
     // ── Empty journal ──
 
     #[test]
@@ -575,16 +1590,20 @@ mod tests {
         assert!(!is_invoke_scheduled(empty, &p));
         assert!(!is_invoke_started(empty, &p));
         assert!(!is_invoke_completed(empty, &p));
+        assert_eq!(invoke_lifecycle(empty, &p), InvokeLifecycle::default());
         assert!(!is_timer_scheduled(empty, &p));
         assert!(!is_timer_fired(empty, &p));
-        assert!(!is_signal_delivered(empty, "s", 1));
-        assert!(!is_signal_consumed(empty, "s", 1));
+        assert!(!is_signal_delivered(empty, &signal("s"), 1));
+        assert!(!is_signal_consumed(empty, &signal("s"), 1));
         assert!(!is_join_set_created(empty, &js));
+        assert!(join_set_created_at(empty, &js).is_none());
         assert!(join_set_members(empty, &js).is_empty());
         assert!(join_set_consumed(empty, &js).is_empty());
         assert!(promise_owner(empty, &p).is_none());
+        assert!(promise_owners(empty).is_empty());
         assert!(!has_cancel_requested(empty));
         assert!(terminal_event(empty).is_none());
         assert_eq!(retry_count(empty, &p), 0);
+        assert!(restart_spec(empty).is_none());
     }
 }
@@ -0,0 +1,365 @@
+//! Queries for exactly-once signal ingestion.
+//!
+//! Producers backed by a message queue tag each `SignalDelivered` with the
+//! message's `external_id`. CF-7 keeps that ID unique per signal name, so on
+//! startup an ingestion adapter can diff its outstanding messages against
+//! the journal: anything already present is acked instead of redelivered.
+//!
+//! [`SignalBufferLimits`] bounds how many deliveries may sit unconsumed in
+//! one execution. [`ExecutionState::deliver_signal`] enforces it when
+//! appending; validation does not, so journals written before a limit was
+//! lowered still load.
+//!
+//! [`ExecutionState::deliver_signal`]: crate::state::ExecutionState::deliver_signal
+
+use std::collections::HashSet;
+
+use invariant_types::{EventType, JournalEntry, SignalDeliveryId, SignalName};
+
+/// What to do with a delivery that would exceed a [`SignalBufferLimits`] bound.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Append nothing; the producer gets [`DeliveryOutcome::RejectedBuffer`].
+    #[default]
+    Reject,
+    /// Expire the oldest buffered deliveries, in journal order, to make room.
+    /// Each drop is journaled as `SignalDeliveryExpired`.
+    DropOldest,
+}
+
+/// Bounds on unconsumed `SignalDelivered` entries per execution.
+///
+/// `None` leaves a bound off. A delivery is admitted while fewer than
+/// `per_signal` deliveries of its name, and fewer than `total` overall, are
+/// buffered.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SignalBufferLimits {
+    pub per_signal: Option<usize>,
+    pub total: Option<usize>,
+    pub overflow: OverflowPolicy,
+}
+
+/// Result of [`ExecutionState::deliver_signal`](crate::state::ExecutionState::deliver_signal).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DeliveryOutcome {
+    /// The delivery was appended as `entry`, after the `SignalDeliveryExpired`
+    /// entries in `expired` (empty unless [`OverflowPolicy::DropOldest`] made
+    /// room).
+    Delivered {
+        entry: JournalEntry,
+        expired: Vec<JournalEntry>,
+    },
+    /// Nothing was appended. `unconsumed` is the buffered count of the bound
+    /// that was hit: the signal's own when `per_signal` is exceeded,
+    /// otherwise the execution's total.
+    RejectedBuffer { unconsumed: usize },
+}
+
+/// External IDs already delivered for `signal_name`.
+///
+/// Scan complexity: O(n).
+pub fn acked_external_ids(entries: &[JournalEntry], signal_name: &SignalName) -> HashSet<String> {
+    entries
+        .iter()
+        .filter_map(|e| match &e.event {
+            EventType::SignalDelivered {
+                signal_name: name,
+                external_id: Some(external_id),
+                ..
+            } if name == signal_name.as_str() => Some(external_id.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The delivery ID assigned to `external_id` for `signal_name`, if it was
+/// already delivered.
+///
+/// Adapters call this before appending: `Some` means the message is a
+/// redelivery and should be acked with the existing ID.
+/// Scan complexity: O(n).
+pub fn external_delivery_id(
+    entries: &[JournalEntry],
+    signal_name: &SignalName,
+    external_id: &str,
+) -> Option<SignalDeliveryId> {
+    entries.iter().find_map(|e| match &e.event {
+        EventType::SignalDelivered {
+            signal_name: name,
+            delivery_id,
+            external_id: Some(id),
+            ..
+        } if name == signal_name.as_str() && id == external_id => Some(*delivery_id),
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use invariant_types::{Codec, Payload};
+
+    use super::*;
+    use crate::command::Command;
+    use crate::error::{JournalError, JournalViolation};
+    use crate::state::ExecutionState;
+
+    fn payload(byte: u8) -> Payload {
+        Payload::new(vec![byte], Codec::Json)
+    }
+
+    fn signal(name: &str) -> SignalName {
+        SignalName::new(name).unwrap()
+    }
+
+    fn deliver(name: &str, delivery_id: SignalDeliveryId, external_id: Option<&str>) -> Command {
+        Command::DeliverSignal {
+            signal_name: name.into(),
+            payload: payload(delivery_id as u8),
+            delivery_id,
+            external_id: external_id.map(Into::into),
+        }
+    }
+
+    fn state() -> ExecutionState {
+        ExecutionState::new(vec![1], payload(0), None, "k".into(), Utc::now()).unwrap()
+    }
+
+    #[test]
+    fn acked_ids_are_scoped_to_signal_name() {
+        let mut state = state();
+        let now = Utc::now();
+        state
+            .handle(deliver("shipment", 0, Some("m-1")), now)
+            .unwrap();
+        state.handle(deliver("shipment", 1, None), now).unwrap();
+        state
+            .handle(deliver("shipment", 2, Some("m-2")), now)
+            .unwrap();
+        state
+            .handle(deliver("refund", 0, Some("m-1")), now)
+            .unwrap();
+
+        let acked = acked_external_ids(state.journal(), &signal("shipment"));
+        assert_eq!(acked, HashSet::from(["m-1".into(), "m-2".into()]));
+        assert_eq!(
+            acked_external_ids(state.journal(), &signal("refund")),
+            HashSet::from(["m-1".into()])
+        );
+        assert!(acked_external_ids(state.journal(), &signal("other")).is_empty());
+    }
+
+    #[test]
+    fn redelivery_of_external_id_is_idempotent() {
+        let mut state = state();
+        let now = Utc::now();
+        state
+            .handle(deliver("shipment", 0, Some("m-1")), now)
+            .unwrap();
+        let len = state.journal().len();
+
+        // The adapter finds the existing delivery and acks without appending.
+        assert_eq!(
+            external_delivery_id(state.journal(), &signal("shipment"), "m-1"),
+            Some(0)
+        );
+        assert_eq!(
+            external_delivery_id(state.journal(), &signal("shipment"), "m-2"),
+            None
+        );
+
+        // Appending anyway is rejected by CF-7 and leaves the journal as is.
+        let err = state
+            .handle(deliver("shipment", 1, Some("m-1")), now)
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            JournalError::InvariantViolation(v)
+                if matches!(*v, JournalViolation::DuplicateExternalDelivery { second_seq: 2, .. })
+        ));
+        assert_eq!(state.journal().len(), len);
+    }
+
+    fn limited(
+        state: &mut ExecutionState,
+        name: &str,
+        delivery_id: SignalDeliveryId,
+        limits: &SignalBufferLimits,
+    ) -> DeliveryOutcome {
+        state
+            .deliver_signal(
+                name.into(),
+                payload(delivery_id as u8),
+                delivery_id,
+                None,
+                limits,
+                Utc::now(),
+            )
+            .unwrap()
+    }
+
+    fn buffered(state: &ExecutionState) -> Vec<(String, SignalDeliveryId)> {
+        state
+            .pending_signal_deliveries()
+            .into_iter()
+            .map(|(name, id, _)| (name.to_string(), id))
+            .collect()
+    }
+
+    #[test]
+    fn reject_policy_appends_nothing_over_limit() {
+        let limits = SignalBufferLimits {
+            per_signal: Some(2),
+            total: Some(3),
+            overflow: OverflowPolicy::Reject,
+        };
+        let mut state = state();
+        for (name, id) in [("a", 0), ("a", 1), ("b", 0)] {
+            assert!(matches!(
+                limited(&mut state, name, id, &limits),
+                DeliveryOutcome::Delivered { ref expired, .. } if expired.is_empty()
+            ));
+        }
+        let len = state.journal().len();
+
+        assert_eq!(
+            limited(&mut state, "a", 2, &limits),
+            DeliveryOutcome::RejectedBuffer { unconsumed: 2 }
+        );
+        assert_eq!(
+            limited(&mut state, "c", 0, &limits),
+            DeliveryOutcome::RejectedBuffer { unconsumed: 3 }
+        );
+        assert_eq!(state.journal().len(), len);
+
+        // Consuming a delivery frees its slot.
+        state
+            .handle(
+                Command::ConsumeSignal {
+                    signal_name: "a".into(),
+                    payload: payload(0),
+                    delivery_id: 0,
+                },
+                Utc::now(),
+            )
+            .unwrap();
+        assert!(matches!(
+            limited(&mut state, "a", 2, &limits),
+            DeliveryOutcome::Delivered { .. }
+        ));
+    }
+
+    #[test]
+    fn drop_oldest_expires_in_journal_order() {
+        let limits = SignalBufferLimits {
+            per_signal: Some(2),
+            total: Some(3),
+            overflow: OverflowPolicy::DropOldest,
+        };
+        let mut state = state();
+        for (name, id) in [("a", 0), ("b", 0), ("a", 1)] {
+            limited(&mut state, name, id, &limits);
+        }
+
+        // Per-signal bound: the oldest `a` goes, not the older `b`.
+        let DeliveryOutcome::Delivered { expired, .. } = limited(&mut state, "a", 2, &limits)
+        else {
+            panic!("expected delivery");
+        };
+        assert_eq!(expired.len(), 1);
+        assert_eq!(
+            expired[0].event,
+            EventType::SignalDeliveryExpired {
+                signal_name: "a".into(),
+                delivery_id: 0,
+            }
+        );
+        // Total bound: the oldest buffered delivery of any name goes.
+        let DeliveryOutcome::Delivered { expired, .. } = limited(&mut state, "c", 0, &limits)
+        else {
+            panic!("expected delivery");
+        };
+        assert!(matches!(
+            &expired[..],
+            [JournalEntry { event: EventType::SignalDeliveryExpired { signal_name, delivery_id: 0 }, .. }]
+                if signal_name == "b"
+        ));
+        assert_eq!(
+            buffered(&state),
+            [("a".into(), 1), ("a".into(), 2), ("c".into(), 0)]
+        );
+
+        // An expired delivery can no longer be received (CF-2).
+        let err = state
+            .handle(
+                Command::ConsumeSignal {
+                    signal_name: "a".into(),
+                    payload: payload(0),
+                    delivery_id: 0,
+                },
+                Utc::now(),
+            )
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            JournalError::InvariantViolation(v)
+                if matches!(*v, JournalViolation::SignalReceivedWithoutDelivery { .. })
+        ));
+    }
+
+    #[test]
+    fn drop_oldest_commits_nothing_when_the_delivery_is_rejected() {
+        let limits = SignalBufferLimits {
+            per_signal: Some(1),
+            total: None,
+            overflow: OverflowPolicy::DropOldest,
+        };
+        let mut state = state();
+        state
+            .handle(deliver("a", 0, Some("m-1")), Utc::now())
+            .unwrap();
+        let len = state.journal().len();
+
+        let err = state
+            .deliver_signal(
+                "a".into(),
+                payload(1),
+                1,
+                Some("m-1".into()),
+                &limits,
+                Utc::now(),
+            )
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            JournalError::InvariantViolation(v)
+                if matches!(*v, JournalViolation::DuplicateExternalDelivery { .. })
+        ));
+        assert_eq!(state.journal().len(), len);
+        assert_eq!(buffered(&state), [("a".into(), 0)]);
+    }
+
+    #[test]
+    fn recovery_rebuilds_the_buffered_count() {
+        let limits = SignalBufferLimits {
+            per_signal: Some(2),
+            total: None,
+            overflow: OverflowPolicy::DropOldest,
+        };
+        let mut state = state();
+        for id in 0..4 {
+            limited(&mut state, "a", id, &limits);
+        }
+        let mut recovered = ExecutionState::recover(state.journal().to_vec()).unwrap();
+        assert_eq!(buffered(&recovered), [("a".into(), 2), ("a".into(), 3)]);
+
+        let rejecting = SignalBufferLimits {
+            overflow: OverflowPolicy::Reject,
+            ..limits
+        };
+        assert_eq!(
+            limited(&mut recovered, "a", 4, &rejecting),
+            DeliveryOutcome::RejectedBuffer { unconsumed: 2 }
+        );
+    }
+}
@@ -3,13 +3,16 @@ use std::collections::HashSet;
 use chrono::{DateTime, Utc};
 use invariant_types::{
     DomainError, EventType, ExecutionId, ExecutionStatus, JournalEntry, Payload, PromiseId,
+    SignalDeliveryId,
 };
 
 use crate::{
     command::{Command, CommandKind, CommandResult, allocating_to_event, non_allocating_to_event},
     error::{JournalError, JournalViolation},
     invariants::InvariantState,
+    overrides::{self, OverrideJustification, OverrideLedger, OverrideRecord},
     replay::ReplayCache,
+    signals::{DeliveryOutcome, OverflowPolicy, SignalBufferLimits},
     status::{self, derive_next_status},
 };
 
@@ -199,6 +202,173 @@ impl ExecutionState {
         })
     }
 
+    /// Append `event` even if invariants reject it, recording the override.
+    ///
+    /// An entry that passes every check is appended as usual and leaves
+    /// `ledger` alone. Otherwise the entry is applied anyway and a record of
+    /// the violated invariant codes and `justification` is appended to
+    /// `ledger`. Overrides never allocate child IDs, so `event` should not
+    /// be one that carries a freshly allocated promise.
+    ///
+    /// # Errors
+    ///
+    /// - [`JournalError::InvariantViolation`] — the entry breaks sequence
+    ///   integrity or terminal finality (S-1, S-3, S-4), which no override
+    ///   may bypass. State and ledger are unchanged.
+    pub fn append_override(
+        &mut self,
+        event: EventType,
+        justification: OverrideJustification,
+        ledger: &mut OverrideLedger,
+        now: DateTime<Utc>,
+    ) -> Result<JournalEntry, JournalError> {
+        let entry = JournalEntry {
+            sequence: self.journal.len() as u64,
+            timestamp: now,
+            event,
+        };
+
+        let mut violations = Vec::new();
+        self.invariant_state
+            .collect_entry_violations(self.journal.len(), &entry, &mut violations);
+        if let Some(forbidden) = violations.iter().find(|v| !overrides::overridable(v)) {
+            return Err(JournalError::InvariantViolation(Box::new(
+                forbidden.clone(),
+            )));
+        }
+
+        if !violations.is_empty() {
+            ledger.record(OverrideRecord {
+                seq: entry.sequence,
+                codes: violations
+                    .iter()
+                    .map(|v| overrides::code(v).to_string())
+                    .collect(),
+                justification,
+            });
+        }
+        self.invariant_state.apply_entry(&entry);
+        self.status = derive_next_status(self.status.clone(), &entry.event);
+        self.replay_cache.insert_event(&entry);
+        self.journal.push(entry.clone());
+        Ok(entry)
+    }
+
+    /// Deliver a signal, enforcing `limits` on the unconsumed buffer.
+    ///
+    /// Under the limits this is [`handle`](Self::handle) with
+    /// [`Command::DeliverSignal`]. Over them, [`OverflowPolicy::Reject`]
+    /// appends nothing, while [`OverflowPolicy::DropOldest`] first appends a
+    /// `SignalDeliveryExpired` for each of the oldest buffered deliveries it
+    /// takes to make room. A bound of zero cannot be made room under and
+    /// always rejects.
+    ///
+    /// Either every entry is appended or none is. Finding the oldest
+    /// deliveries scans the journal, so an overflowing `DropOldest` delivery
+    /// is O(n).
+    ///
+    /// # Errors
+    ///
+    /// As [`handle`](Self::handle); e.g. CF-7 for a redelivered
+    /// `external_id`. The aggregate is unchanged.
+    pub fn deliver_signal(
+        &mut self,
+        signal_name: String,
+        payload: Payload,
+        delivery_id: SignalDeliveryId,
+        external_id: Option<String>,
+        limits: &SignalBufferLimits,
+        now: DateTime<Utc>,
+    ) -> Result<DeliveryOutcome, JournalError> {
+        let buffered = self.invariant_state.pending_signal_deliveries();
+        let mut named = buffered
+            .iter()
+            .filter(|(name, ..)| *name == signal_name)
+            .count();
+        let mut total = buffered.len();
+        let over = |named: usize, total: usize| {
+            (
+                limits.per_signal.is_some_and(|limit| named >= limit),
+                limits.total.is_some_and(|limit| total >= limit),
+            )
+        };
+
+        let mut drop = Vec::new();
+        let (over_named, over_total) = over(named, total);
+        if (over_named || over_total) && limits.overflow == OverflowPolicy::DropOldest {
+            for entry in &self.journal {
+                let (over_named, over_total) = over(named, total);
+                if !over_named && !over_total {
+                    break;
+                }
+                let EventType::SignalDelivered {
+                    signal_name: name,
+                    delivery_id: id,
+                    ..
+                } = &entry.event
+                else {
+                    continue;
+                };
+                let key = (name.clone(), *id);
+                if (over_named && *name != signal_name)
+                    || !self.invariant_state.is_signal_buffered(&key)
+                {
+                    continue;
+                }
+                if *name == signal_name {
+                    named -= 1;
+                }
+                total -= 1;
+                drop.push(key);
+            }
+        }
+        match over(named, total) {
+            (true, _) => return Ok(DeliveryOutcome::RejectedBuffer { unconsumed: named }),
+            (false, true) => return Ok(DeliveryOutcome::RejectedBuffer { unconsumed: total }),
+            (false, false) => {}
+        }
+
+        let deliver = Command::DeliverSignal {
+            signal_name,
+            payload,
+            delivery_id,
+            external_id,
+        };
+        if !drop.is_empty() {
+            // The expiries never affect whether the delivery itself passes,
+            // so check it up front to avoid committing them alone.
+            let CommandKind::NonAllocating(cmd) = deliver.clone().classify() else {
+                unreachable!("DeliverSignal does not allocate");
+            };
+            let index = self.journal.len() + drop.len();
+            let entry = JournalEntry {
+                sequence: index as u64,
+                timestamp: now,
+                event: non_allocating_to_event(cmd),
+            };
+            let mut violations = Vec::new();
+            self.invariant_state
+                .collect_entry_violations(index, &entry, &mut violations);
+            if let Some(violation) = violations.into_iter().next() {
+                return Err(JournalError::InvariantViolation(Box::new(violation)));
+            }
+        }
+
+        let mut expired = Vec::with_capacity(drop.len());
+        for (signal_name, delivery_id) in drop {
+            let result = self.handle(
+                Command::ExpireSignal {
+                    signal_name,
+                    delivery_id,
+                },
+                now,
+            )?;
+            expired.push(result.entry);
+        }
+        let entry = self.handle(deliver, now)?.entry;
+        Ok(DeliveryOutcome::Delivered { entry, expired })
+    }
+
     // ── Accessors ──
 
     /// The root promise ID for this execution.
@@ -240,6 +410,23 @@ impl ExecutionState {
     pub fn next_child_seq(&self) -> u32 {
         self.next_child_seq.current()
     }
+
+    /// Signals delivered but neither consumed nor expired, sorted by name
+    /// then delivery ID.
+    pub fn pending_signal_deliveries(&self) -> Vec<(&str, SignalDeliveryId, &Payload)> {
+        self.invariant_state.pending_signal_deliveries()
+    }
+
+    /// Digest of the component build currently in effect.
+    ///
+    /// The `ExecutionStarted` digest until a `ComponentUpgraded` replaces
+    /// it. The execution ID stays derived from the start digest.
+    pub fn component_digest(&self) -> &[u8] {
+        self.invariant_state
+            .component_digest
+            .as_deref()
+            .unwrap_or_default()
+    }
 }
 
 /// Reconstruct the child-allocation counter and set from journal entries.
@@ -335,9 +522,15 @@ impl ChildSeqCounter {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::assertions::JournalAssert;
+    use crate::causality::EventSelector;
+    use crate::invariants::{ValidationOptions, validate_journal, validate_journal_report};
+    use crate::resolution;
+    use crate::status::{ResumeRequirement, can_resume, required_to_resume, wait_resolvers};
     use chrono::Utc;
     use invariant_types::{
-        AwaitKind, Codec, ErrorKind, ExecutionError, InvokeKind, JoinSetId, Payload,
+        AwaitKind, Codec, ErrorKind, ExecutionError, ExecutionJournal, ExecutionStatusKind,
+        FunctionName, InvokeKind, JoinSetId, Payload,
     };
     use std::time::Duration;
 
@@ -472,6 +665,193 @@ mod tests {
         assert_eq!(state.next_child_seq(), 0);
     }
 
+    #[test]
+    fn recover_selects_latest_upgraded_digest() {
+        let mut state = new_state();
+        let id = state.execution_id().clone();
+        for (old, new) in [(DIGEST.to_vec(), vec![0xB0]), (vec![0xB0], vec![0xC0])] {
+            state
+                .handle(
+                    Command::UpgradeComponent {
+                        old_digest: old,
+                        new_digest: new,
+                        approved_by: "ops".into(),
+                    },
+                    Utc::now(),
+                )
+                .unwrap();
+        }
+        assert_eq!(*state.status(), ExecutionStatus::Running);
+
+        let recovered = ExecutionState::recover(state.journal().to_vec()).unwrap();
+        assert_eq!(recovered.component_digest(), [0xC0]);
+        // Identity stays derived from the start digest.
+        assert_eq!(*recovered.execution_id(), id);
+    }
+
+    #[test]
+    fn upgrade_with_stale_old_digest_rejected() {
+        let mut state = new_state();
+        let upgrade = |old: Vec<u8>, new: Vec<u8>| Command::UpgradeComponent {
+            old_digest: old,
+            new_digest: new,
+            approved_by: "ops".into(),
+        };
+        state
+            .handle(upgrade(DIGEST.to_vec(), vec![0xB0]), Utc::now())
+            .unwrap();
+
+        let err = state
+            .handle(upgrade(DIGEST.to_vec(), vec![0xC0]), Utc::now())
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            JournalError::InvariantViolation(v) if matches!(
+                *v,
+                JournalViolation::UpgradeDigestMismatch { upgrade_seq: 2, ref expected, .. }
+                    if expected == &[0xB0]
+            )
+        ));
+        assert_eq!(state.component_digest(), [0xB0]);
+        assert_eq!(state.journal().len(), 2);
+    }
+
+    #[test]
+    fn compensation_invoke_lifecycle_legal_while_cancelling() {
+        let mut state = new_state();
+        let now = Utc::now();
+
+        state
+            .handle(
+                Command::RequestCancel {
+                    reason: "stop".into(),
+                },
+                now,
+            )
+            .expect("RequestCancel on Running must succeed");
+        assert_eq!(*state.status(), ExecutionStatus::Cancelling);
+
+        // Compensation invoke: full lifecycle including a retry.
+        let pid = state
+            .handle(
+                Command::ScheduleInvoke {
+                    kind: InvokeKind::Function,
+                    function_name: "release_hold".into(),
+                    input: payload(),
+                    retry_policy: None,
+                    sla: None,
+                },
+                now,
+            )
+            .expect("ScheduleInvoke while Cancelling must succeed")
+            .allocated_id
+            .expect("ScheduleInvoke allocates a child");
+        let lifecycle = [
+            Command::StartInvoke {
+                promise_id: pid.clone(),
+                attempt: 1,
+            },
+            Command::RetryInvoke {
+                promise_id: pid.clone(),
+                failed_attempt: 1,
+                error: ExecutionError::new(ErrorKind::Timeout, "slow"),
+                retry_at: now,
+            },
+            Command::StartInvoke {
+                promise_id: pid.clone(),
+                attempt: 2,
+            },
+            Command::CompleteInvoke {
+                promise_id: pid.clone(),
+                result: payload(),
+                attempt: 2,
+            },
+        ];
+        for cmd in lifecycle {
+            state
+                .handle(cmd, now)
+                .expect("invoke lifecycle while Cancelling must succeed");
+            assert_eq!(*state.status(), ExecutionStatus::Cancelling);
+        }
+        assert_eq!(
+            state.replay_cache().get_invoke(&pid).as_deref(),
+            Some(&payload())
+        );
+
+        state
+            .handle(
+                Command::Cancel {
+                    reason: "stopped".into(),
+                },
+                now,
+            )
+            .expect("Cancel after compensation must succeed");
+        assert_eq!(*state.status(), ExecutionStatus::Cancelled);
+
+        let recovered =
+            ExecutionState::recover(state.journal().to_vec()).expect("journal must recover");
+        assert_eq!(recovered.status(), state.status());
+        assert_eq!(recovered.next_child_seq(), state.next_child_seq());
+    }
+
+    #[test]
+    fn in_flight_invoke_completes_after_cancel_requested() {
+        let mut state = new_state();
+        let now = Utc::now();
+
+        let pid = state
+            .handle(
+                Command::ScheduleInvoke {
+                    kind: InvokeKind::Function,
+                    function_name: "charge".into(),
+                    input: payload(),
+                    retry_policy: None,
+                    sla: None,
+                },
+                now,
+            )
+            .expect("ScheduleInvoke must succeed")
+            .allocated_id
+            .expect("ScheduleInvoke allocates a child");
+        state
+            .handle(
+                Command::StartInvoke {
+                    promise_id: pid.clone(),
+                    attempt: 1,
+                },
+                now,
+            )
+            .expect("StartInvoke must succeed");
+        state
+            .handle(
+                Command::RequestCancel {
+                    reason: "stop".into(),
+                },
+                now,
+            )
+            .expect("RequestCancel mid-invoke must succeed");
+        state
+            .handle(
+                Command::CompleteInvoke {
+                    promise_id: pid,
+                    result: payload(),
+                    attempt: 1,
+                },
+                now,
+            )
+            .expect("CompleteInvoke while Cancelling must succeed");
+        assert_eq!(*state.status(), ExecutionStatus::Cancelling);
+        state
+            .handle(
+                Command::Cancel {
+                    reason: "stopped".into(),
+                },
+                now,
+            )
+            .expect("Cancel must succeed");
+        assert!(state.is_terminal());
+    }
+
     // ── Task 8: Allocating commands ──
 
     #[test]
@@ -486,6 +866,7 @@ mod tests {
                     function_name: "do_work".into(),
                     input: payload(),
                     retry_policy: None,
+                    sla: None,
                 },
                 now,
             )
@@ -574,6 +955,7 @@ mod tests {
                     function_name: "a".into(),
                     input: payload(),
                     retry_policy: None,
+                    sla: None,
                 },
                 now,
             )
@@ -613,6 +995,7 @@ mod tests {
                     function_name: "work".into(),
                     input: payload(),
                     retry_policy: None,
+                    sla: None,
                 },
                 now,
             )
@@ -651,6 +1034,7 @@ mod tests {
                     function_name: "work".into(),
                     input: payload(),
                     retry_policy: None,
+                    sla: None,
                 },
                 now,
             )
@@ -681,7 +1065,7 @@ mod tests {
             .expect("CompleteInvoke after Start must succeed");
 
         assert_eq!(
-            state.replay_cache().get_invoke(&child_0),
+            state.replay_cache().get_invoke(&child_0).as_deref(),
             Some(&result_payload)
         );
     }
@@ -731,6 +1115,7 @@ mod tests {
                     signal_name: "approval".into(),
                     payload: sig_payload.clone(),
                     delivery_id: 1,
+                    external_id: None,
                 },
                 now,
             )
@@ -751,11 +1136,83 @@ mod tests {
 
         let child_0 = state.execution_id().child(0).unwrap();
         assert_eq!(
-            state.replay_cache().get_signal(&child_0),
+            state.replay_cache().get_signal(&child_0).as_deref(),
             Some(&sig_payload)
         );
     }
 
+    #[test]
+    fn any_signal_await_recovers_between_delivery_and_receipt() {
+        let names = vec!["rejected".to_string(), "approved".to_string()];
+        for order in [["rejected", "approved"], ["approved", "rejected"]] {
+            let mut state = new_state();
+            let now = Utc::now();
+            let awaited = state.execution_id().child(state.next_child_seq()).unwrap();
+            state
+                .handle(
+                    Command::Await {
+                        waiting_on: vec![awaited.clone()],
+                        kind: AwaitKind::AnySignal {
+                            names: names.clone(),
+                            promise_id: awaited.clone(),
+                        },
+                    },
+                    now,
+                )
+                .unwrap();
+            for name in order {
+                state
+                    .handle(
+                        Command::DeliverSignal {
+                            signal_name: name.into(),
+                            payload: Payload::new(name.as_bytes().to_vec(), Codec::Json),
+                            delivery_id: 0,
+                            external_id: None,
+                        },
+                        now,
+                    )
+                    .unwrap();
+            }
+
+            // Crash before the guest consumed either delivery.
+            let mut state = ExecutionState::recover(state.journal().to_vec()).unwrap();
+            assert_eq!(
+                required_to_resume(state.status()),
+                Some(ResumeRequirement::AnySignal(names.clone(), awaited.clone()))
+            );
+            let (winner, delivery_id) =
+                resolution::next_signal_delivery(state.journal(), &names).unwrap();
+            assert_eq!((winner, delivery_id), ("approved", 0));
+
+            let received = state
+                .handle(
+                    Command::ConsumeSignal {
+                        signal_name: winner.to_string(),
+                        payload: Payload::new(winner.as_bytes().to_vec(), Codec::Json),
+                        delivery_id,
+                    },
+                    now,
+                )
+                .unwrap();
+            assert_eq!(received.allocated_id, Some(awaited.clone()));
+            assert!(can_resume(state.status(), &wait_resolvers(state.journal())));
+            state.handle(Command::Resume, now).unwrap();
+
+            assert!(
+                validate_journal(&ExecutionJournal {
+                    execution_id: state.execution_id().clone(),
+                    entries: state.journal().to_vec(),
+                    provenance: None,
+                })
+                .is_empty()
+            );
+            assert_eq!(
+                resolution::next_signal_delivery(state.journal(), &names),
+                Some(("rejected", 0))
+            );
+        }
+    }
+
     // ── Task 10: JoinSet commands ──
 
     #[test]
@@ -779,6 +1236,7 @@ mod tests {
                     function_name: "task_a".into(),
                     input: payload(),
                     retry_policy: None,
+                    sla: None,
                 },
                 now,
             )
@@ -849,6 +1307,7 @@ mod tests {
                     function_name: "a".into(),
                     input: payload(),
                     retry_policy: None,
+                    sla: None,
                 },
                 now,
             )
@@ -863,6 +1322,7 @@ mod tests {
                     function_name: "b".into(),
                     input: payload(),
                     retry_policy: None,
+                    sla: None,
                 },
                 now,
             )
@@ -923,6 +1383,18 @@ mod tests {
             )
             .unwrap();
 
+        // Progress hint while still blocked leaves status unchanged
+        state
+            .handle(
+                Command::ReportAwaitProgress {
+                    waiting_on_remaining: 1,
+                    resolved_since_await: vec![pid_b.clone()],
+                },
+                now,
+            )
+            .unwrap();
+        assert!(matches!(state.status(), ExecutionStatus::Blocked { .. }));
+
         // Resume
         state.handle(Command::Resume, now).unwrap();
         assert_eq!(*state.status(), ExecutionStatus::Running);
@@ -955,6 +1427,7 @@ mod tests {
                     function_name: "work".into(),
                     input: payload(),
                     retry_policy: None,
+                    sla: None,
                 },
                 now,
             )
@@ -1010,6 +1483,138 @@ mod tests {
         assert!(state.allocated_children().is_empty());
     }
 
+    #[test]
+    fn sanctioned_override_survives_validation_only_with_ledger() {
+        let mut state = new_state();
+        let mut ledger = OverrideLedger::new();
+        let now = Utc::now();
+        let lost = state.execution_id().child(0).unwrap();
+        let justification = OverrideJustification {
+            operator: "oncall@example.com".into(),
+            reason: "charge confirmed by provider; InvokeStarted lost".into(),
+        };
+
+        // SE-1: started without a schedule. Forced through and recorded.
+        let entry = state
+            .append_override(
+                EventType::InvokeStarted {
+                    promise_id: lost.clone(),
+                    attempt: 1,
+                },
+                justification.clone(),
+                &mut ledger,
+                now,
+            )
+            .unwrap();
+        assert_eq!(
+            ledger.records(),
+            [OverrideRecord {
+                seq: entry.sequence,
+                codes: vec!["SE-1".into()],
+                justification,
+            }]
+        );
+
+        // Later entries are checked against the overridden state as usual.
+        state
+            .handle(
+                Command::CompleteInvoke {
+                    promise_id: lost,
+                    result: payload(),
+                    attempt: 1,
+                },
+                now,
+            )
+            .unwrap();
+
+        let journal = ExecutionJournal {
+            execution_id: state.execution_id().clone(),
+            entries: state.journal().to_vec(),
+            provenance: None,
+        };
+        let without = validate_journal_report(&journal, &ValidationOptions::default());
+        assert!(matches!(
+            without.violations.as_slice(),
+            [JournalViolation::StartedWithoutScheduled { .. }]
+        ));
+
+        let with = validate_journal_report(
+            &journal,
+            &ValidationOptions {
+                overrides: Some(ledger),
+                ..ValidationOptions::default()
+            },
+        );
+        assert!(with.violations.is_empty());
+        assert!(matches!(
+            with.warnings.as_slice(),
+            [JournalViolation::StartedWithoutScheduled { .. }]
+        ));
+    }
+
+    #[test]
+    fn override_cannot_bypass_terminal_finality() {
+        let mut state = new_state();
+        let mut ledger = OverrideLedger::new();
+        let now = Utc::now();
+        state
+            .handle(Command::Complete { result: payload() }, now)
+            .unwrap();
+
+        let err = state
+            .append_override(
+                EventType::ExecutionResumed,
+                OverrideJustification {
+                    operator: "oncall@example.com".into(),
+                    reason: "reopen".into(),
+                },
+                &mut ledger,
+                now,
+            )
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            JournalError::InvariantViolation(v) if matches!(*v, JournalViolation::TerminalNotLast { .. })
+        ));
+        assert_eq!(state.journal().len(), 2);
+        assert!(ledger.records().is_empty());
+    }
+
+    #[test]
+    fn forged_ledger_cannot_sanction_sequence_corruption() {
+        let mut state = new_state();
+        state
+            .handle(Command::Complete { result: payload() }, Utc::now())
+            .unwrap();
+        let mut journal = ExecutionJournal {
+            execution_id: state.execution_id().clone(),
+            entries: state.journal().to_vec(),
+            provenance: None,
+        };
+        journal.entries[1].sequence = 5;
+        let ledger: OverrideLedger = serde_json::from_value(serde_json::json!({
+            "records": [{
+                "seq": 5,
+                "codes": ["S-1"],
+                "justification": { "operator": "mallory", "reason": "hide the gap" },
+            }],
+        }))
+        .unwrap();
+
+        let report = validate_journal_report(
+            &journal,
+            &ValidationOptions {
+                overrides: Some(ledger),
+                ..ValidationOptions::default()
+            },
+        );
+        assert!(matches!(
+            report.violations.as_slice(),
+            [JournalViolation::NonMonotonicSequence { actual: 5, .. }]
+        ));
+        assert!(report.warnings.is_empty());
+    }
+
     #[test]
     fn second_terminal_rejected_state_unchanged() {
         let mut state = new_state();
@@ -1084,6 +1689,7 @@ mod tests {
                     function_name: "fetch".into(),
                     input: payload(),
                     retry_policy: None,
+                    sla: None,
                 },
                 now,
             )
@@ -1176,6 +1782,7 @@ mod tests {
                     function_name: "fetch_user".into(),
                     input: Payload::new(vec![42], Codec::Json),
                     retry_policy: None,
+                    sla: None,
                 },
                 now,
             )
@@ -1235,6 +1842,7 @@ mod tests {
                     function_name: "send_email".into(),
                     input: payload(),
                     retry_policy: None,
+                    sla: None,
                 },
                 now,
             )
@@ -1260,6 +1868,7 @@ mod tests {
                     function_name: "send_sms".into(),
                     input: payload(),
                     retry_policy: None,
+                    sla: None,
                 },
                 now,
             )
@@ -1430,15 +2039,15 @@ mod tests {
             Some(&[0x1a, 0x2b][..])
         );
         assert_eq!(
-            state.replay_cache().get_invoke(&child_1),
+            state.replay_cache().get_invoke(&child_1).as_deref(),
             Some(&user_payload)
         );
         assert_eq!(
-            state.replay_cache().get_invoke(&child_4),
+            state.replay_cache().get_invoke(&child_4).as_deref(),
             Some(&sms_payload)
         );
         assert_eq!(
-            state.replay_cache().get_invoke(&child_3),
+            state.replay_cache().get_invoke(&child_3).as_deref(),
             Some(&email_payload)
         );
 
@@ -1453,5 +2062,20 @@ mod tests {
         assert_eq!(recovered.allocated_children(), state.allocated_children());
         assert_eq!(recovered.is_terminal(), state.is_terminal());
         assert_eq!(recovered.execution_id(), state.execution_id());
+
+        // ── History assertions ──
+        let journal = ExecutionJournal {
+            execution_id: state.execution_id().clone(),
+            entries: state.journal().to_vec(),
+            provenance: None,
+        };
+        JournalAssert::new(&journal)
+            .assert_invoked_at_most(&FunctionName::new("fetch_user").unwrap(), 1)
+            .assert_no_retries_for(&FunctionName::new("send_sms").unwrap())
+            .assert_order(
+                EventSelector::InvokeCompletedOf(&child_4),
+                EventSelector::InvokeCompletedOf(&child_3),
+            )
+            .assert_terminal(ExecutionStatusKind::Completed);
     }
 }
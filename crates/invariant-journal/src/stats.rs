@@ -0,0 +1,223 @@
+//! Event counts and timing distributions for a journal, enabled by the
+//! `stats` feature.
+//!
+//! Timings come from entry timestamps, which are wall-clock and debug-only
+//! (see [`JournalEntry`]). They are good enough for performance analysis but
+//! never feed replay. Negative spans from clock skew are clamped to zero.
+
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{DateTime, Utc};
+use invariant_types::{EventType, ExecutionJournal, JournalEntry, PromiseId};
+
+/// Summary of one journal, built by [`JournalStats::compute`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct JournalStats {
+    /// Total number of entries.
+    pub entries: usize,
+    /// Entry count per event name.
+    pub events: BTreeMap<&'static str, usize>,
+    /// `InvokeCompleted` minus `InvokeScheduled` timestamp for each completed
+    /// invocation, in completion order.
+    pub invoke_duration_ms: Vec<u64>,
+    /// `TimerFired` minus `TimerScheduled` timestamp for each fired timer, in
+    /// firing order.
+    pub timer_actual_delay_ms: Vec<u64>,
+    /// Mean of `invoke_duration_ms`; `0.0` when nothing completed.
+    pub mean_invoke_duration_ms: f64,
+    /// Nearest-rank percentiles of `invoke_duration_ms`; `0` when nothing
+    /// completed.
+    pub p50_invoke_duration_ms: u64,
+    pub p95_invoke_duration_ms: u64,
+    pub p99_invoke_duration_ms: u64,
+}
+
+impl JournalStats {
+    /// Compute counts and timings in one pass over `journal`.
+    ///
+    /// Invocations and timers are paired with their scheduling entry by
+    /// promise ID; completions without one are skipped.
+    /// Complexity: O(n) plus sorting the invoke durations.
+    pub fn compute(journal: &ExecutionJournal) -> Self {
+        let mut stats = Self {
+            entries: journal.entries.len(),
+            ..Self::default()
+        };
+        let mut invokes_scheduled: HashMap<&PromiseId, DateTime<Utc>> = HashMap::new();
+        let mut timers_scheduled: HashMap<&PromiseId, DateTime<Utc>> = HashMap::new();
+
+        for entry in &journal.entries {
+            *stats.events.entry(entry.event.name()).or_default() += 1;
+            match &entry.event {
+                EventType::InvokeScheduled { promise_id, .. } => {
+                    invokes_scheduled.insert(promise_id, entry.timestamp);
+                }
+                EventType::InvokeCompleted { promise_id, .. } => {
+                    if let Some(scheduled) = invokes_scheduled.remove(promise_id) {
+                        stats.invoke_duration_ms.push(span_ms(scheduled, entry));
+                    }
+                }
+                EventType::TimerScheduled { promise_id, .. } => {
+                    timers_scheduled.insert(promise_id, entry.timestamp);
+                }
+                EventType::TimerFired { promise_id } => {
+                    if let Some(scheduled) = timers_scheduled.remove(promise_id) {
+                        stats.timer_actual_delay_ms.push(span_ms(scheduled, entry));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let mut sorted = stats.invoke_duration_ms.clone();
+        sorted.sort_unstable();
+        if !sorted.is_empty() {
+            stats.mean_invoke_duration_ms =
+                sorted.iter().map(|&ms| ms as f64).sum::<f64>() / sorted.len() as f64;
+        }
+        stats.p50_invoke_duration_ms = percentile(&sorted, 50);
+        stats.p95_invoke_duration_ms = percentile(&sorted, 95);
+        stats.p99_invoke_duration_ms = percentile(&sorted, 99);
+        stats
+    }
+}
+
+fn span_ms(from: DateTime<Utc>, to: &JournalEntry) -> u64 {
+    u64::try_from((to.timestamp - from).num_milliseconds()).unwrap_or(0)
+}
+
+/// Nearest-rank percentile of an ascending slice; `0` if empty.
+fn percentile(sorted: &[u64], p: usize) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = (p * sorted.len()).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use chrono::TimeDelta;
+    use invariant_types::{Codec, ExecutionId, InvokeKind, Payload};
+
+    use super::*;
+
+    fn payload() -> Payload {
+        Payload::new(vec![], Codec::Json)
+    }
+
+    fn pid(n: u8) -> PromiseId {
+        PromiseId::new([n; 32])
+    }
+
+    /// Journal whose entry timestamps are `at_ms` milliseconds after the epoch.
+    fn journal(events: Vec<(i64, EventType)>) -> ExecutionJournal {
+        ExecutionJournal {
+            execution_id: ExecutionId::derive(&[1], "k", None),
+            entries: events
+                .into_iter()
+                .enumerate()
+                .map(|(seq, (at_ms, event))| JournalEntry {
+                    sequence: seq as u64,
+                    timestamp: DateTime::UNIX_EPOCH + TimeDelta::milliseconds(at_ms),
+                    event,
+                })
+                .collect(),
+            provenance: None,
+        }
+    }
+
+    fn scheduled(p: PromiseId) -> EventType {
+        EventType::InvokeScheduled {
+            promise_id: p,
+            kind: InvokeKind::Function,
+            function_name: "f".into(),
+            input: payload(),
+            retry_policy: None,
+            sla: None,
+        }
+    }
+
+    fn completed(p: PromiseId) -> EventType {
+        EventType::InvokeCompleted {
+            promise_id: p,
+            result: payload(),
+            attempt: 1,
+        }
+    }
+
+    fn started() -> EventType {
+        EventType::ExecutionStarted {
+            component_digest: vec![1],
+            input: payload(),
+            parent_id: None,
+            idempotency_key: "k".into(),
+        }
+    }
+
+    #[test]
+    fn pairs_invokes_and_timers_by_promise() {
+        let (a, b, t) = (pid(1), pid(2), pid(3));
+        let stats = JournalStats::compute(&journal(vec![
+            (0, started()),
+            (10, scheduled(a.clone())),
+            (20, scheduled(b.clone())),
+            (
+                25,
+                EventType::TimerScheduled {
+                    promise_id: t.clone(),
+                    duration: Duration::from_millis(100),
+                    fire_at: DateTime::UNIX_EPOCH + TimeDelta::milliseconds(125),
+                },
+            ),
+            (70, completed(b)),
+            (110, completed(a)),
+            (140, EventType::TimerFired { promise_id: t }),
+            // Completion with no matching schedule is ignored.
+            (150, completed(pid(9))),
+        ]));
+
+        assert_eq!(stats.entries, 8);
+        assert_eq!(stats.events["InvokeCompleted"], 3);
+        assert_eq!(stats.invoke_duration_ms, vec![50, 100]);
+        assert_eq!(stats.timer_actual_delay_ms, vec![115]);
+        assert_eq!(stats.mean_invoke_duration_ms, 75.0);
+        assert_eq!(stats.p50_invoke_duration_ms, 50);
+        assert_eq!(stats.p95_invoke_duration_ms, 100);
+        assert_eq!(stats.p99_invoke_duration_ms, 100);
+    }
+
+    #[test]
+    fn percentiles_use_nearest_rank() {
+        let mut events = vec![(0, started())];
+        for n in 1..=100u8 {
+            events.push((0, scheduled(pid(n))));
+            events.push((i64::from(n), completed(pid(n))));
+        }
+        let stats = JournalStats::compute(&journal(events));
+
+        assert_eq!(stats.invoke_duration_ms.len(), 100);
+        assert_eq!(stats.mean_invoke_duration_ms, 50.5);
+        assert_eq!(stats.p50_invoke_duration_ms, 50);
+        assert_eq!(stats.p95_invoke_duration_ms, 95);
+        assert_eq!(stats.p99_invoke_duration_ms, 99);
+    }
+
+    #[test]
+    fn empty_timings_and_clock_skew() {
+        let a = pid(1);
+        let stats = JournalStats::compute(&journal(vec![
+            (0, started()),
+            (50, scheduled(a.clone())),
+            (40, completed(a)),
+        ]));
+        assert_eq!(stats.invoke_duration_ms, vec![0]);
+
+        let idle = JournalStats::compute(&journal(vec![(0, started())]));
+        assert!(idle.invoke_duration_ms.is_empty());
+        assert_eq!(idle.mean_invoke_duration_ms, 0.0);
+        assert_eq!(idle.p99_invoke_duration_ms, 0);
+    }
+}
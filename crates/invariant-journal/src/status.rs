@@ -1,6 +1,8 @@
 use std::collections::HashSet;
 
-use invariant_types::{AwaitKind, EventType, ExecutionStatus, JournalEntry, PromiseId};
+use invariant_types::{
+    AwaitKind, EventType, ExecutionId, ExecutionJournal, ExecutionStatus, JournalEntry, PromiseId,
+};
 
 /// Derive the current execution status by replaying journal events left-to-right.
 ///
@@ -30,7 +32,9 @@ pub fn derive_status(entries: &[JournalEntry]) -> ExecutionStatus {
 /// Apply a single-event status transition.
 ///
 /// Events that do not affect status return `current_status` unchanged.
-pub(crate) fn derive_next_status(
+/// Folding this over a journal from `Running` is exactly [`derive_status`];
+/// engines tracking status incrementally call it once per appended event.
+pub fn derive_next_status(
     current_status: ExecutionStatus,
     event_type: &EventType,
 ) -> ExecutionStatus {
@@ -41,10 +45,21 @@ pub(crate) fn derive_next_status(
             kind: kind.clone(),
         },
         EventType::ExecutionResumed => ExecutionStatus::Running,
+        // A progress hint while blocked; the wait itself is unchanged.
+        EventType::AwaitProgress { .. } => current_status,
         EventType::CancelRequested { .. } => ExecutionStatus::Cancelling,
         EventType::ExecutionCancelled { .. } => ExecutionStatus::Cancelled,
-        EventType::ExecutionCompleted { .. } => ExecutionStatus::Completed,
+        // The run finished by handing off; the continuation is a new execution.
+        EventType::ExecutionCompleted { .. } | EventType::ContinuedAsNew { .. } => {
+            ExecutionStatus::Completed
+        }
         EventType::ExecutionFailed { .. } => ExecutionStatus::Failed,
+        // Switching component builds does not change what the run is doing.
+        EventType::ComponentUpgraded { .. } => current_status,
+        // Dropping a buffered signal does not wake or block anything.
+        EventType::SignalDeliveryExpired { .. } => current_status,
+        // A running attempt reporting liveness.
+        EventType::InvokeHeartbeat { .. } => current_status,
         _ => current_status,
     }
 }
@@ -104,7 +119,8 @@ pub fn wait_resolvers(entries: &[JournalEntry]) -> HashSet<PromiseId> {
 /// - TimerFired
 /// - SignalReceived
 ///
-/// For non-blocked statuses, this returns `false`.
+/// For non-blocked statuses, and for await kinds added after this function
+/// was written, this returns `false`.
 pub fn can_resume(status: &ExecutionStatus, resolved: &HashSet<PromiseId>) -> bool {
     match status {
         ExecutionStatus::Blocked { waiting_on, kind } => match kind {
@@ -112,11 +128,11 @@ pub fn can_resume(status: &ExecutionStatus, resolved: &HashSet<PromiseId>) -> bo
                 waiting_on.iter().all(|pid| resolved.contains(pid))
             }
             AwaitKind::Any => waiting_on.iter().any(|pid| resolved.contains(pid)),
-            AwaitKind::Signal { promise_id, .. } => {
+            AwaitKind::Signal { promise_id, .. } | AwaitKind::AnySignal { promise_id, .. } => {
                 debug_assert_eq!(
                     waiting_on.len(),
                     1,
-                    "CF-4 violated: AwaitKind::Signal must have exactly one waiting_on promise"
+                    "CF-4 violated: signal awaits must have exactly one waiting_on promise"
                 );
                 let Some(waiting_pid) = waiting_on.first() else {
                     return false;
@@ -124,7 +140,7 @@ pub fn can_resume(status: &ExecutionStatus, resolved: &HashSet<PromiseId>) -> bo
 
                 debug_assert_eq!(
                     waiting_pid, promise_id,
-                    "CF-4 violated: AwaitKind::Signal.promise_id must match waiting_on[0]"
+                    "CF-4 violated: signal await promise_id must match waiting_on[0]"
                 );
                 if waiting_pid != promise_id {
                     return false;
@@ -132,17 +148,145 @@ pub fn can_resume(status: &ExecutionStatus, resolved: &HashSet<PromiseId>) -> bo
 
                 resolved.contains(waiting_pid)
             }
+            _ => false,
         },
         _ => false,
     }
 }
 
+/// Returns the executions in `journals` that are currently blocked on `pid`.
+///
+/// Fan-out query for a scheduler that just resolved `pid` and needs to know
+/// which executions to wake. Empty journals are skipped.
+///
+/// Complexity: O(total entries across `journals`).
+pub fn executions_blocked_on<'a>(
+    journals: &'a [ExecutionJournal],
+    pid: &PromiseId,
+) -> Vec<&'a ExecutionId> {
+    journals
+        .iter()
+        .filter(|journal| !journal.entries.is_empty())
+        .filter(|journal| {
+            matches!(
+                derive_status(&journal.entries),
+                ExecutionStatus::Blocked { waiting_on, .. } if waiting_on.contains(pid)
+            )
+        })
+        .map(|journal| &journal.execution_id)
+        .collect()
+}
+
+/// What a blocked execution is waiting for, expressed as promises to resolve.
+///
+/// Returned by [`required_to_resume`] so a dispatcher knows which resolutions
+/// will unblock the execution.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ResumeRequirement {
+    /// Resolving any one of these promises is enough (`AwaitKind::Any`).
+    AnyOf(Vec<PromiseId>),
+    /// Every one of these promises must be resolved (`AwaitKind::Single`/`All`).
+    AllOf(Vec<PromiseId>),
+    /// The named signal must be delivered to this promise (`AwaitKind::Signal`).
+    Signal(String, PromiseId),
+    /// Any one of the named signals must be delivered to this promise
+    /// (`AwaitKind::AnySignal`).
+    AnySignal(Vec<String>, PromiseId),
+}
+
+/// Returns the minimal set of resolutions that would unblock `status`.
+///
+/// This is the inverse view of `can_resume`: `can_resume` answers whether a
+/// resolver set satisfies the wait, this answers what the wait needs.
+///
+/// For non-blocked statuses, and for await kinds added after this function
+/// was written, this returns `None`.
+pub fn required_to_resume(status: &ExecutionStatus) -> Option<ResumeRequirement> {
+    let (waiting_on, kind) = status.blocked_on()?;
+    Some(match kind {
+        AwaitKind::Single | AwaitKind::All => ResumeRequirement::AllOf(waiting_on.to_vec()),
+        AwaitKind::Any => ResumeRequirement::AnyOf(waiting_on.to_vec()),
+        AwaitKind::Signal { name, promise_id } => {
+            ResumeRequirement::Signal(name.clone(), promise_id.clone())
+        }
+        AwaitKind::AnySignal { names, promise_id } => {
+            ResumeRequirement::AnySignal(names.clone(), promise_id.clone())
+        }
+        _ => return None,
+    })
+}
+
+/// Coarse phase of an execution, for grouping executions in dashboards.
+///
+/// A higher-level view than [`ExecutionStatus`]: the terminal statuses
+/// collapse into [`Done`](Self::Done), and a running execution is split by
+/// whether it has done anything yet.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ExecutionPhase {
+    /// Nothing recorded beyond `ExecutionStarted`, or nothing at all.
+    Starting,
+    /// Running, with `in_flight` invokes scheduled but not completed.
+    Executing { in_flight: usize },
+    /// Blocked on an `ExecutionAwaiting`.
+    Waiting,
+    /// Cancel requested, cleanup in progress.
+    Cancelling,
+    /// Completed, failed, cancelled or continued as new.
+    Done,
+    /// A status added to [`ExecutionStatus`] after this function was
+    /// written; dashboards should show it rather than guess a phase.
+    Unknown,
+}
+
+/// Returns the [`ExecutionPhase`] of the journal `entries`.
+///
+/// Combines [`derive_status`] with a count of in-flight invokes.
+///
+/// Complexity: O(n) over `entries.len()`.
+pub fn execution_phase(entries: &[JournalEntry]) -> ExecutionPhase {
+    // An empty journal has not started yet; `derive_status` rejects it.
+    if entries.is_empty() {
+        return ExecutionPhase::Starting;
+    }
+    match derive_status(entries) {
+        ExecutionStatus::Running if entries.len() == 1 => ExecutionPhase::Starting,
+        ExecutionStatus::Running => ExecutionPhase::Executing {
+            in_flight: in_flight_invokes(entries),
+        },
+        ExecutionStatus::Blocked { .. } => ExecutionPhase::Waiting,
+        ExecutionStatus::Cancelling => ExecutionPhase::Cancelling,
+        ExecutionStatus::Completed | ExecutionStatus::Failed | ExecutionStatus::Cancelled => {
+            ExecutionPhase::Done
+        }
+        _ => ExecutionPhase::Unknown,
+    }
+}
+
+/// Number of invokes scheduled but not yet completed.
+fn in_flight_invokes(entries: &[JournalEntry]) -> usize {
+    let mut in_flight = HashSet::new();
+    for entry in entries {
+        match &entry.event {
+            EventType::InvokeScheduled { promise_id, .. } => {
+                in_flight.insert(promise_id);
+            }
+            EventType::InvokeCompleted { promise_id, .. } => {
+                in_flight.remove(promise_id);
+            }
+            _ => {}
+        }
+    }
+    in_flight.len()
+}
+
 #[cfg(test)]
 mod tests {
     use chrono::Utc;
     use invariant_types::{Codec, ExecutionError, Payload};
+    use proptest::prelude::*;
 
     use super::*;
+    use crate::testing::arb_valid_journal;
 
     fn pid(tag: u8) -> PromiseId {
         PromiseId::new([tag; 32])
@@ -160,6 +304,22 @@ mod tests {
         }
     }
 
+    #[test]
+    fn await_progress_leaves_blocked_status_unchanged() {
+        let blocked = ExecutionStatus::Blocked {
+            waiting_on: vec![pid(1), pid(2)],
+            kind: AwaitKind::All,
+        };
+        let next = derive_next_status(
+            blocked.clone(),
+            &EventType::AwaitProgress {
+                waiting_on_remaining: 1,
+                resolved_since_await: vec![pid(1)],
+            },
+        );
+        assert_eq!(next, blocked);
+    }
+
     #[test]
     fn derive_status_matches_incremental_transitions() {
         let p1 = pid(1);
@@ -182,6 +342,7 @@ mod tests {
                     function_name: "f".into(),
                     input: payload(),
                     retry_policy: None,
+                    sla: None,
                 },
             ),
             entry(
@@ -270,4 +431,230 @@ mod tests {
         assert!(!resolvers.contains(&p_random));
         assert!(!resolvers.contains(&p_time));
     }
+
+    fn blocked(waiting_on: Vec<PromiseId>, kind: AwaitKind) -> ExecutionStatus {
+        ExecutionStatus::Blocked { waiting_on, kind }
+    }
+
+    #[test]
+    fn required_to_resume_single_needs_its_promise() {
+        let status = blocked(vec![pid(1)], AwaitKind::Single);
+        assert_eq!(
+            required_to_resume(&status),
+            Some(ResumeRequirement::AllOf(vec![pid(1)]))
+        );
+    }
+
+    #[test]
+    fn required_to_resume_all_needs_every_promise() {
+        let status = blocked(vec![pid(1), pid(2)], AwaitKind::All);
+        assert_eq!(
+            required_to_resume(&status),
+            Some(ResumeRequirement::AllOf(vec![pid(1), pid(2)]))
+        );
+    }
+
+    #[test]
+    fn required_to_resume_any_needs_one_promise() {
+        let status = blocked(vec![pid(1), pid(2)], AwaitKind::Any);
+        assert_eq!(
+            required_to_resume(&status),
+            Some(ResumeRequirement::AnyOf(vec![pid(1), pid(2)]))
+        );
+    }
+
+    #[test]
+    fn required_to_resume_signal_names_signal_and_promise() {
+        let status = blocked(
+            vec![pid(3)],
+            AwaitKind::Signal {
+                name: "approve".into(),
+                promise_id: pid(3),
+            },
+        );
+        assert_eq!(
+            required_to_resume(&status),
+            Some(ResumeRequirement::Signal("approve".into(), pid(3)))
+        );
+    }
+
+    #[test]
+    fn required_to_resume_is_none_when_not_blocked() {
+        for status in [
+            ExecutionStatus::Running,
+            ExecutionStatus::Cancelling,
+            ExecutionStatus::Completed,
+        ] {
+            assert_eq!(required_to_resume(&status), None);
+        }
+    }
+
+    #[test]
+    fn executions_blocked_on_returns_only_matching_waiters() {
+        let target = pid(1);
+        let journal = |key: &str, tail: Vec<EventType>| {
+            let mut events = vec![EventType::ExecutionStarted {
+                component_digest: vec![1],
+                input: payload(),
+                parent_id: None,
+                idempotency_key: key.into(),
+            }];
+            events.extend(tail);
+            ExecutionJournal {
+                execution_id: ExecutionId::derive(&[1], key, None),
+                entries: events
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, event)| entry(i as u64, event))
+                    .collect(),
+                provenance: None,
+            }
+        };
+        let journals = vec![
+            journal("running", vec![]),
+            journal(
+                "blocked-on-target",
+                vec![EventType::ExecutionAwaiting {
+                    waiting_on: vec![pid(2), target.clone()],
+                    kind: AwaitKind::Any,
+                }],
+            ),
+            journal(
+                "blocked-elsewhere",
+                vec![EventType::ExecutionAwaiting {
+                    waiting_on: vec![pid(3)],
+                    kind: AwaitKind::Single,
+                }],
+            ),
+        ];
+
+        assert_eq!(
+            executions_blocked_on(&journals, &target),
+            vec![&journals[1].execution_id]
+        );
+        assert!(executions_blocked_on(&journals, &pid(9)).is_empty());
+    }
+
+    proptest! {
+        #[test]
+        fn derive_status_equals_incremental_fold(j in arb_valid_journal(32)) {
+            let mut status = ExecutionStatus::Running;
+            for (i, entry) in j.entries.iter().enumerate() {
+                status = derive_next_status(status, &entry.event);
+                prop_assert_eq!(&status, &derive_status(&j.entries[..=i]));
+            }
+        }
+    }
+
+    #[test]
+    fn execution_phase_follows_the_journal() {
+        let p1 = pid(1);
+        let mut entries = vec![entry(
+            0,
+            EventType::ExecutionStarted {
+                component_digest: vec![1],
+                input: payload(),
+                parent_id: None,
+                idempotency_key: "k".into(),
+            },
+        )];
+        let push = |entries: &mut Vec<JournalEntry>, event| {
+            entries.push(entry(entries.len() as u64, event));
+            execution_phase(entries)
+        };
+
+        assert_eq!(execution_phase(&[]), ExecutionPhase::Starting);
+        assert_eq!(execution_phase(&entries), ExecutionPhase::Starting);
+        assert_eq!(
+            push(
+                &mut entries,
+                EventType::InvokeScheduled {
+                    promise_id: p1.clone(),
+                    kind: invariant_types::InvokeKind::Function,
+                    function_name: "f".into(),
+                    input: payload(),
+                    retry_policy: None,
+                    sla: None,
+                },
+            ),
+            ExecutionPhase::Executing { in_flight: 1 }
+        );
+        assert_eq!(
+            push(
+                &mut entries,
+                EventType::ExecutionAwaiting {
+                    waiting_on: vec![p1.clone()],
+                    kind: AwaitKind::Single,
+                },
+            ),
+            ExecutionPhase::Waiting
+        );
+        push(
+            &mut entries,
+            EventType::InvokeStarted {
+                promise_id: p1.clone(),
+                attempt: 1,
+            },
+        );
+        push(
+            &mut entries,
+            EventType::InvokeCompleted {
+                promise_id: p1,
+                result: payload(),
+                attempt: 1,
+            },
+        );
+        assert_eq!(
+            push(&mut entries, EventType::ExecutionResumed),
+            ExecutionPhase::Executing { in_flight: 0 }
+        );
+        assert_eq!(
+            push(
+                &mut entries,
+                EventType::CancelRequested {
+                    reason: "stop".into(),
+                },
+            ),
+            ExecutionPhase::Cancelling
+        );
+        assert_eq!(
+            push(
+                &mut entries,
+                EventType::ExecutionCancelled {
+                    reason: "stop".into(),
+                },
+            ),
+            ExecutionPhase::Done
+        );
+    }
+
+    #[test]
+    fn every_terminal_status_is_done() {
+        let started = entry(
+            0,
+            EventType::ExecutionStarted {
+                component_digest: vec![1],
+                input: payload(),
+                parent_id: None,
+                idempotency_key: "k".into(),
+            },
+        );
+        for terminal in [
+            EventType::ExecutionCompleted { result: payload() },
+            EventType::ExecutionFailed {
+                error: ExecutionError::new(invariant_types::ErrorKind::Uncategorized, "boom"),
+            },
+            EventType::ExecutionCancelled {
+                reason: "stop".into(),
+            },
+            EventType::ContinuedAsNew {
+                next_execution_id: ExecutionId::derive(&[1], "k-2", None),
+                carried_state: payload(),
+            },
+        ] {
+            let name = terminal.name();
+            let entries = [started.clone(), entry(1, terminal)];
+            assert_eq!(execution_phase(&entries), ExecutionPhase::Done, "{name}");
+        }
+    }
 }
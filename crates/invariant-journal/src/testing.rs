@@ -0,0 +1,415 @@
+//! Test-support assertions and proptest strategies, enabled by the `testing`
+//! feature.
+//!
+//! Intended for `#[test]` code in this crate and downstream crates that keep
+//! their own journal fixtures.
+
+use chrono::DateTime;
+use invariant_types::{
+    Codec, ErrorKind, EventType, ExecutionError, ExecutionId, ExecutionJournal, InvokeKind,
+    JoinSetId, JournalEntry, Payload, PromiseId, SignalDeliveryId,
+};
+use proptest::prelude::*;
+
+use crate::invariants::{InvariantState, validate_journal};
+
+/// Assert that incremental and batch validation agree on `journal`.
+///
+/// [`InvariantState::check_append`] stops at the first violation while
+/// [`validate_journal`] keeps going and reports up to one violation per
+/// invariant group per entry. The two agree when:
+/// - a clean journal produces no violations on either path, and
+/// - the first violation reported incrementally is among the batch
+///   violations.
+///
+/// # Panics
+///
+/// Panics with both results if the paths disagree.
+pub fn assert_incremental_matches_batch(journal: &ExecutionJournal) {
+    let batch = validate_journal(journal);
+
+    let mut state = InvariantState::new();
+    let first = journal
+        .entries
+        .iter()
+        .find_map(|entry| state.check_append(entry).err());
+
+    match first {
+        None => assert!(
+            batch.is_empty(),
+            "incremental validation accepted the journal but batch reported {batch:#?}"
+        ),
+        Some(violation) => assert!(
+            batch.contains(&violation),
+            "first incremental violation {violation:?} missing from batch violations {batch:#?}"
+        ),
+    }
+}
+
+/// Strategy producing journals that pass [`validate_journal`].
+///
+/// Each journal starts with `ExecutionStarted`, interleaves up to `max_steps`
+/// invoke, timer, signal and join-set operations, and ends with
+/// `ExecutionCompleted`. Every step is chosen among the operations legal at
+/// that point, so shrinking keeps the journal valid.
+pub fn arb_valid_journal(max_steps: usize) -> impl Strategy<Value = ExecutionJournal> {
+    proptest::collection::vec((0u8..Step::COUNT, any::<u8>()), 0..=max_steps).prop_map(|steps| {
+        let mut model = JournalModel::default();
+        for (step, pick) in steps {
+            model.step(Step::from(step), pick as usize);
+        }
+        model.finish()
+    })
+}
+
+#[derive(Clone, Copy)]
+enum Step {
+    ScheduleInvoke,
+    StartInvoke,
+    RetryInvoke,
+    CompleteInvoke,
+    ScheduleTimer,
+    FireTimer,
+    DeliverSignal,
+    ReceiveSignal,
+    CreateJoinSet,
+    Submit,
+    Await,
+}
+
+impl Step {
+    const COUNT: u8 = 11;
+}
+
+impl From<u8> for Step {
+    fn from(n: u8) -> Self {
+        [
+            Self::ScheduleInvoke,
+            Self::StartInvoke,
+            Self::RetryInvoke,
+            Self::CompleteInvoke,
+            Self::ScheduleTimer,
+            Self::FireTimer,
+            Self::DeliverSignal,
+            Self::ReceiveSignal,
+            Self::CreateJoinSet,
+            Self::Submit,
+            Self::Await,
+        ][usize::from(n % Self::COUNT)]
+    }
+}
+
+struct ModelInvoke {
+    pid: PromiseId,
+    /// Latest started attempt; 0 before the first start.
+    attempt: u32,
+    /// Whether the latest attempt is running (started, not retried).
+    running: bool,
+    completed: bool,
+    owned: bool,
+}
+
+struct ModelJoinSet {
+    id: JoinSetId,
+    awaited: bool,
+    /// Indices into `JournalModel::invokes`.
+    members: Vec<usize>,
+    consumed: Vec<usize>,
+}
+
+/// Tracks just enough state to emit only legal events.
+struct JournalModel {
+    execution_id: ExecutionId,
+    events: Vec<EventType>,
+    next_child: u32,
+    invokes: Vec<ModelInvoke>,
+    timers: Vec<(PromiseId, bool)>,
+    /// `(name, delivery_id, consumed)`.
+    signals: Vec<(&'static str, SignalDeliveryId, bool)>,
+    join_sets: Vec<ModelJoinSet>,
+}
+
+impl Default for JournalModel {
+    fn default() -> Self {
+        Self {
+            execution_id: ExecutionId::derive(&[1], "arb", None),
+            events: vec![EventType::ExecutionStarted {
+                component_digest: vec![1],
+                input: Payload::new(vec![], Codec::Json),
+                parent_id: None,
+                idempotency_key: "arb".into(),
+            }],
+            next_child: 0,
+            invokes: Vec::new(),
+            timers: Vec::new(),
+            signals: Vec::new(),
+            join_sets: Vec::new(),
+        }
+    }
+}
+
+fn pick_from(candidates: Vec<usize>, pick: usize) -> Option<usize> {
+    (!candidates.is_empty()).then(|| candidates[pick % candidates.len()])
+}
+
+impl JournalModel {
+    fn alloc(&mut self) -> PromiseId {
+        let pid = self
+            .execution_id
+            .child(self.next_child)
+            .expect("root child depth is within bounds");
+        self.next_child += 1;
+        pid
+    }
+
+    fn step(&mut self, step: Step, pick: usize) {
+        let payload = Payload::new(vec![pick as u8], Codec::Json);
+        match step {
+            Step::ScheduleInvoke => {
+                let pid = self.alloc();
+                self.events.push(EventType::InvokeScheduled {
+                    promise_id: pid.clone(),
+                    kind: InvokeKind::Function,
+                    function_name: "f".into(),
+                    input: payload,
+                    retry_policy: None,
+                    sla: None,
+                });
+                self.invokes.push(ModelInvoke {
+                    pid,
+                    attempt: 0,
+                    running: false,
+                    completed: false,
+                    owned: false,
+                });
+            }
+            Step::StartInvoke => {
+                let idle = (0..self.invokes.len())
+                    .filter(|&i| !self.invokes[i].completed && !self.invokes[i].running)
+                    .collect();
+                if let Some(i) = pick_from(idle, pick) {
+                    let invoke = &mut self.invokes[i];
+                    invoke.attempt += 1;
+                    invoke.running = true;
+                    self.events.push(EventType::InvokeStarted {
+                        promise_id: invoke.pid.clone(),
+                        attempt: invoke.attempt,
+                    });
+                }
+            }
+            Step::RetryInvoke | Step::CompleteInvoke => {
+                let running = (0..self.invokes.len())
+                    .filter(|&i| self.invokes[i].running)
+                    .collect();
+                if let Some(i) = pick_from(running, pick) {
+                    let invoke = &mut self.invokes[i];
+                    invoke.running = false;
+                    let event = if matches!(step, Step::RetryInvoke) {
+                        EventType::InvokeRetrying {
+                            promise_id: invoke.pid.clone(),
+                            failed_attempt: invoke.attempt,
+                            error: ExecutionError::new(ErrorKind::Trap, "transient"),
+                            retry_at: DateTime::UNIX_EPOCH,
+                        }
+                    } else {
+                        invoke.completed = true;
+                        EventType::InvokeCompleted {
+                            promise_id: invoke.pid.clone(),
+                            result: payload,
+                            attempt: invoke.attempt,
+                        }
+                    };
+                    self.events.push(event);
+                }
+            }
+            Step::ScheduleTimer => {
+                let pid = self.alloc();
+                self.events.push(EventType::TimerScheduled {
+                    promise_id: pid.clone(),
+                    duration: std::time::Duration::from_secs(1),
+                    fire_at: DateTime::UNIX_EPOCH,
+                });
+                self.timers.push((pid, false));
+            }
+            Step::FireTimer => {
+                let pending = (0..self.timers.len())
+                    .filter(|&i| !self.timers[i].1)
+                    .collect();
+                if let Some(i) = pick_from(pending, pick) {
+                    self.timers[i].1 = true;
+                    self.events.push(EventType::TimerFired {
+                        promise_id: self.timers[i].0.clone(),
+                    });
+                }
+            }
+            Step::DeliverSignal => {
+                let name = ["approve", "reject"][pick % 2];
+                let delivery_id = self.signals.len() as SignalDeliveryId;
+                self.signals.push((name, delivery_id, false));
+                self.events.push(EventType::SignalDelivered {
+                    signal_name: name.into(),
+                    payload: Payload::new(vec![], Codec::Json),
+                    delivery_id,
+                    external_id: None,
+                });
+            }
+            Step::ReceiveSignal => {
+                let buffered = (0..self.signals.len())
+                    .filter(|&i| !self.signals[i].2)
+                    .collect();
+                if let Some(i) = pick_from(buffered, pick) {
+                    self.signals[i].2 = true;
+                    let (name, delivery_id, _) = self.signals[i];
+                    let promise_id = self.alloc();
+                    self.events.push(EventType::SignalReceived {
+                        promise_id,
+                        signal_name: name.into(),
+                        payload: Payload::new(vec![], Codec::Json),
+                        delivery_id,
+                    });
+                }
+            }
+            Step::CreateJoinSet => {
+                let id = JoinSetId(self.alloc());
+                self.events.push(EventType::JoinSetCreated {
+                    join_set_id: id.clone(),
+                });
+                self.join_sets.push(ModelJoinSet {
+                    id,
+                    awaited: false,
+                    members: Vec::new(),
+                    consumed: Vec::new(),
+                });
+            }
+            Step::Submit => {
+                let open = (0..self.join_sets.len())
+                    .filter(|&j| !self.join_sets[j].awaited)
+                    .collect();
+                let free = (0..self.invokes.len())
+                    .filter(|&i| !self.invokes[i].owned)
+                    .collect();
+                if let (Some(j), Some(i)) = (pick_from(open, pick), pick_from(free, pick / 3)) {
+                    self.invokes[i].owned = true;
+                    self.join_sets[j].members.push(i);
+                    self.events.push(EventType::JoinSetSubmitted {
+                        join_set_id: self.join_sets[j].id.clone(),
+                        promise_id: self.invokes[i].pid.clone(),
+                    });
+                }
+            }
+            Step::Await => {
+                let ready: Vec<(usize, usize)> = self
+                    .join_sets
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(j, set)| {
+                        set.members
+                            .iter()
+                            .filter(|&&i| self.invokes[i].completed && !set.consumed.contains(&i))
+                            .map(move |&i| (j, i))
+                    })
+                    .collect();
+                if !ready.is_empty() {
+                    let (j, i) = ready[pick % ready.len()];
+                    let set = &mut self.join_sets[j];
+                    set.awaited = true;
+                    set.consumed.push(i);
+                    self.events.push(EventType::JoinSetAwaited {
+                        join_set_id: set.id.clone(),
+                        promise_id: self.invokes[i].pid.clone(),
+                        result: payload,
+                    });
+                }
+            }
+        }
+    }
+
+    fn finish(mut self) -> ExecutionJournal {
+        self.events.push(EventType::ExecutionCompleted {
+            result: Payload::new(vec![], Codec::Json),
+        });
+        ExecutionJournal {
+            execution_id: self.execution_id,
+            entries: self
+                .events
+                .into_iter()
+                .enumerate()
+                .map(|(i, event)| JournalEntry {
+                    sequence: i as u64,
+                    timestamp: DateTime::UNIX_EPOCH,
+                    event,
+                })
+                .collect(),
+            provenance: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn payload() -> Payload {
+        Payload::new(vec![], Codec::Json)
+    }
+
+    fn journal(events: Vec<EventType>) -> ExecutionJournal {
+        ExecutionJournal {
+            execution_id: ExecutionId::derive(&[1], "k", None),
+            entries: events
+                .into_iter()
+                .enumerate()
+                .map(|(i, event)| JournalEntry {
+                    sequence: i as u64,
+                    timestamp: DateTime::UNIX_EPOCH,
+                    event,
+                })
+                .collect(),
+            provenance: None,
+        }
+    }
+
+    fn started() -> EventType {
+        EventType::ExecutionStarted {
+            component_digest: vec![1],
+            input: payload(),
+            parent_id: None,
+            idempotency_key: "k".into(),
+        }
+    }
+
+    #[test]
+    fn clean_journal_matches() {
+        assert_incremental_matches_batch(&journal(vec![
+            started(),
+            EventType::ExecutionCompleted { result: payload() },
+        ]));
+    }
+
+    #[test]
+    fn first_incremental_violation_is_in_batch() {
+        // S-4 first, then an SE-2 violation the incremental path never sees.
+        assert_incremental_matches_batch(&journal(vec![
+            started(),
+            EventType::ExecutionCompleted { result: payload() },
+            EventType::InvokeCompleted {
+                promise_id: PromiseId::new([7; 32]),
+                result: payload(),
+                attempt: 1,
+            },
+        ]));
+    }
+
+    proptest! {
+        #[test]
+        fn generated_journals_are_valid(j in arb_valid_journal(64)) {
+            prop_assert_eq!(validate_journal(&j), vec![]);
+            assert_incremental_matches_batch(&j);
+        }
+    }
+
+    #[test]
+    fn missing_start_matches() {
+        assert_incremental_matches_batch(&journal(vec![EventType::ExecutionResumed]));
+    }
+}
@@ -0,0 +1,163 @@
+//! Golden-journal regression corpus.
+//!
+//! Every `tests/corpus/<name>.journal.json` is a serialized
+//! [`ExecutionJournal`]. Its pinned analysis lives next to it in
+//! `<name>.expected.json`. Adding a fixture is a one-file drop-in: write the
+//! journal, then rebuild the expectations intentionally with
+//!
+//! ```text
+//! INVARIANT_CORPUS_REGEN=1 cargo test -p invariant-journal --test corpus
+//! ```
+//!
+//! and review the resulting diff like any other change. Regeneration is an
+//! environment variable rather than a feature so `--all-features` runs
+//! still check the expectations.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use invariant_journal::assertions::JournalAssert;
+use invariant_journal::invariants::validate_journal;
+use invariant_journal::status::derive_status;
+use invariant_types::{EventType, ExecutionJournal, ExecutionStatusKind, FunctionName};
+use serde_json::{Value, json};
+
+const JOURNAL_SUFFIX: &str = ".journal.json";
+const EXPECTED_SUFFIX: &str = ".expected.json";
+/// Set to `1` to rewrite the expectations instead of checking them.
+const REGEN_VAR: &str = "INVARIANT_CORPUS_REGEN";
+
+fn corpus_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/corpus")
+}
+
+/// Every `(name, journal path)` in the corpus, sorted by name.
+fn corpus_files() -> Vec<(String, PathBuf)> {
+    let mut files: Vec<_> = fs::read_dir(corpus_dir())
+        .expect("tests/corpus must exist")
+        .map(|entry| entry.expect("readable corpus entry").path())
+        .filter_map(|path| {
+            let file_name = path.file_name()?.to_str()?;
+            let name = file_name.strip_suffix(JOURNAL_SUFFIX)?.to_string();
+            Some((name, path))
+        })
+        .collect();
+    files.sort();
+    assert!(!files.is_empty(), "corpus is empty");
+    files
+}
+
+fn load(path: &Path) -> ExecutionJournal {
+    let text = fs::read_to_string(path)
+        .unwrap_or_else(|e| panic!("failed to read {}: {e}", path.display()));
+    serde_json::from_str(&text)
+        .unwrap_or_else(|e| panic!("failed to parse {}: {e}", path.display()))
+}
+
+/// The pinned outputs for one journal: validation verdict, derived status,
+/// per-event stats and encoded size.
+fn analyze(journal: &ExecutionJournal) -> Value {
+    let violations: Vec<String> = validate_journal(journal)
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+
+    // `derive_status` requires S-2; journals that violate it have no status.
+    let status = match journal.entries.first().map(|e| &e.event) {
+        Some(EventType::ExecutionStarted { .. }) => {
+            Some(derive_status(&journal.entries).to_string())
+        }
+        _ => None,
+    };
+
+    let mut events: BTreeMap<&str, usize> = BTreeMap::new();
+    for entry in &journal.entries {
+        *events.entry(entry.event.name()).or_default() += 1;
+    }
+
+    let json_bytes = serde_json::to_vec(journal)
+        .expect("journal serializes")
+        .len();
+
+    json!({
+        "valid": violations.is_empty(),
+        "violations": violations,
+        "status": status,
+        "entries": journal.entries.len(),
+        "events": events,
+        "encoded_bytes": { "json": json_bytes },
+    })
+}
+
+fn render(value: &Value) -> String {
+    serde_json::to_string_pretty(value).expect("expectation serializes") + "\n"
+}
+
+fn expected_path(name: &str) -> PathBuf {
+    corpus_dir().join(format!("{name}{EXPECTED_SUFFIX}"))
+}
+
+#[test]
+fn corpus_matches_pinned_expectations() {
+    let regen = std::env::var_os(REGEN_VAR).is_some_and(|v| v == "1");
+    for (name, path) in corpus_files() {
+        let actual = render(&analyze(&load(&path)));
+        let expected_path = expected_path(&name);
+        if regen {
+            fs::write(&expected_path, actual)
+                .unwrap_or_else(|e| panic!("{name}: failed to write expectations: {e}"));
+            continue;
+        }
+        let expected = fs::read_to_string(&expected_path).unwrap_or_else(|_| {
+            panic!(
+                "{name}: missing {}; run with {REGEN_VAR}=1 to create it",
+                expected_path.display()
+            )
+        });
+        similar_asserts::assert_eq!(expected: expected, actual: actual, "corpus entry `{name}`");
+    }
+}
+
+fn load_fixture(name: &str) -> ExecutionJournal {
+    load(&corpus_dir().join(format!("{name}{JOURNAL_SUFFIX}")))
+}
+
+/// Each terminal fixture ends in the status it is named for.
+#[test]
+fn terminal_fixtures_end_in_their_status() {
+    for (name, kind) in [
+        ("minimal_completed", ExecutionStatusKind::Completed),
+        ("failed", ExecutionStatusKind::Failed),
+        ("cancelled", ExecutionStatusKind::Cancelled),
+    ] {
+        JournalAssert::new(&load_fixture(name)).assert_terminal(kind);
+    }
+}
+
+/// A retried invoke is scheduled once; its retries do not count as invokes.
+#[test]
+fn retried_invoke_is_scheduled_once() {
+    let charge_card = FunctionName::new("charge_card").unwrap();
+    JournalAssert::new(&load_fixture("invoke_retry_completed"))
+        .assert_invoked_at_most(&charge_card, 1)
+        .assert_terminal(ExecutionStatusKind::Completed);
+    JournalAssert::new(&load_fixture("large_sequential"))
+        .assert_invoked_at_most(&FunctionName::new("step").unwrap(), 100)
+        .assert_no_retries_for(&FunctionName::new("step").unwrap());
+}
+
+/// The corpus as a whole must exercise every event type.
+#[test]
+fn corpus_covers_every_event_type() {
+    let seen: std::collections::BTreeSet<&'static str> = corpus_files()
+        .iter()
+        .flat_map(|(_, path)| load(path).entries)
+        .map(|entry| entry.event.name())
+        .collect();
+    let missing: Vec<_> = EventType::KNOWN_NAMES
+        .iter()
+        .filter(|name| !seen.contains(*name))
+        .collect();
+    assert!(missing.is_empty(), "no corpus journal contains {missing:?}");
+}
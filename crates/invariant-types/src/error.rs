@@ -8,4 +8,29 @@ pub enum DomainError {
     /// `max` is `u32` to match the child-sequence counter width used by `ChildSeqCounter`.
     #[error("max children of {max} exceeded")]
     MaxChildrenExceeded { max: u32 },
+
+    /// Produced when parsing a [`PromiseId`](crate::PromiseId) from its full textual form fails.
+    #[error("invalid promise id: {reason}")]
+    InvalidPromiseId { reason: String },
+
+    /// Produced when a [`FunctionName`](crate::FunctionName) or
+    /// [`SignalName`](crate::SignalName) breaks the naming rules.
+    #[error("invalid {kind} name: {reason}")]
+    InvalidName { kind: &'static str, reason: String },
+
+    /// Produced when parsing an [`ErrorKind`](crate::ErrorKind) from an
+    /// unrecognized name.
+    #[error("unknown error kind: {value:?}")]
+    InvalidErrorKind { value: String },
+
+    /// Produced when parsing an
+    /// [`ExecutionStatusKind`](crate::ExecutionStatusKind) from an
+    /// unrecognized name.
+    #[error("unknown execution status: {value:?}")]
+    InvalidStatusKind { value: String },
+
+    /// Produced when a [`PromiseId`](crate::PromiseId) below the root is
+    /// used as an [`ExecutionId`](crate::ExecutionId).
+    #[error("promise at depth {depth} is not an execution root")]
+    NotARootPromise { depth: usize },
 }
@@ -1,8 +1,9 @@
+use std::fmt;
 use std::time::Duration;
 
 use crate::payload::Payload;
 use crate::promise_id::PromiseId;
-use crate::{ExecutionError, join_set::JoinSetId};
+use crate::{ExecutionError, ExecutionId, join_set::JoinSetId};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
@@ -29,8 +30,87 @@ mod serde_duration {
     }
 }
 
+/// [`serde_duration`] for an optional field.
+mod serde_opt_duration {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::time::Duration;
+
+    pub fn serialize<S: Serializer>(d: &Option<Duration>, s: S) -> Result<S::Ok, S::Error> {
+        match d {
+            Some(d) => super::serde_duration::serialize(d, s),
+            None => s.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Option<Duration>, D::Error> {
+        #[derive(Deserialize)]
+        struct Wrapper(#[serde(with = "super::serde_duration")] Duration);
+
+        Ok(Option::<Wrapper>::deserialize(d)?.map(|Wrapper(d)| d))
+    }
+}
+
+/// Wire form of [`EventType::Unknown`]: the unrecognized tag, with its data
+/// discarded.
+///
+/// Accepts both externally tagged shapes serde produces for [`EventType`]:
+/// `"Tag"` for unit variants and `{"Tag": ...}` otherwise. Serializes back
+/// as `{"Tag": null}`. Known tags are rejected so a malformed known event is
+/// an error rather than a silent placeholder.
+mod serde_unknown {
+    use std::fmt;
+
+    use super::EventType;
+
+    use serde::de::{self, IgnoredAny, MapAccess, Visitor};
+    use serde::ser::SerializeMap;
+    use serde::{Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(name: &str, s: S) -> Result<S::Ok, S::Error> {
+        let mut map = s.serialize_map(Some(1))?;
+        map.serialize_entry(name, &())?;
+        map.end()
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<String, D::Error> {
+        struct TagVisitor;
+
+        impl<'de> Visitor<'de> for TagVisitor {
+            type Value = String;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("an externally tagged event")
+            }
+
+            fn visit_str<E: de::Error>(self, tag: &str) -> Result<Self::Value, E> {
+                unknown(tag.to_string())
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let Some((tag, IgnoredAny)) = map.next_entry::<String, IgnoredAny>()? else {
+                    return Err(de::Error::invalid_length(0, &self));
+                };
+                if map.next_key::<IgnoredAny>()?.is_some() {
+                    return Err(de::Error::invalid_length(2, &self));
+                }
+                unknown(tag)
+            }
+        }
+
+        fn unknown<E: de::Error>(tag: String) -> Result<String, E> {
+            if EventType::KNOWN_NAMES.contains(&tag.as_str()) {
+                return Err(E::custom(format_args!("malformed {tag} event")));
+            }
+            Ok(tag)
+        }
+
+        d.deserialize_any(TagVisitor)
+    }
+}
+
 /// Categorizes the type of side-effect invocation.
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
 pub enum InvokeKind {
     /// Function/task/workflow invocation.
     Function,
@@ -40,6 +120,7 @@ pub enum InvokeKind {
 
 /// Determines the wait satisfaction condition for `ExecutionAwaiting`.
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
 pub enum AwaitKind {
     /// Wait for a single promise.
     Single,
@@ -49,6 +130,13 @@ pub enum AwaitKind {
     All,
     /// Wait for a named signal.
     Signal { name: String, promise_id: PromiseId },
+    /// Wait for whichever of several named signals is delivered first.
+    ///
+    /// The `SignalReceived` resolving `promise_id` names the winning signal.
+    AnySignal {
+        names: Vec<String>,
+        promise_id: PromiseId,
+    },
 }
 
 // Retry policy for invocations.
@@ -56,14 +144,130 @@ pub enum AwaitKind {
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct RetryPolicy {}
 
+/// The five event categories of the journal schema.
+///
+/// Mirrors the `// ── Category N ──` grouping of [`EventType`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum EventCategory {
+    Lifecycle,
+    SideEffects,
+    Nondeterminism,
+    ControlFlow,
+    Concurrency,
+    /// Category of [`EventType::Unknown`]; not part of the schema.
+    Unknown,
+}
+
+/// The variant of a known [`EventType`] without its fields.
+///
+/// Lets callers name event types without comparing strings, so a misspelt
+/// name fails to compile. [`EventType::Unknown`] has no kind.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum EventKind {
+    ExecutionStarted,
+    ExecutionCompleted,
+    ExecutionFailed,
+    CancelRequested,
+    ExecutionCancelled,
+    ContinuedAsNew,
+    ComponentUpgraded,
+    InvokeScheduled,
+    InvokeStarted,
+    InvokeCompleted,
+    InvokeRetrying,
+    InvokeHeartbeat,
+    RandomGenerated,
+    TimeRecorded,
+    TimerScheduled,
+    TimerFired,
+    SignalDelivered,
+    SignalReceived,
+    SignalDeliveryExpired,
+    ExecutionAwaiting,
+    ExecutionResumed,
+    AwaitProgress,
+    JoinSetCreated,
+    JoinSetSubmitted,
+    JoinSetAwaited,
+}
+
+impl EventKind {
+    /// Every kind, in [`EventType::KNOWN_NAMES`] order.
+    pub const ALL: [EventKind; 25] = [
+        Self::ExecutionStarted,
+        Self::ExecutionCompleted,
+        Self::ExecutionFailed,
+        Self::CancelRequested,
+        Self::ExecutionCancelled,
+        Self::ContinuedAsNew,
+        Self::ComponentUpgraded,
+        Self::InvokeScheduled,
+        Self::InvokeStarted,
+        Self::InvokeCompleted,
+        Self::InvokeRetrying,
+        Self::InvokeHeartbeat,
+        Self::RandomGenerated,
+        Self::TimeRecorded,
+        Self::TimerScheduled,
+        Self::TimerFired,
+        Self::SignalDelivered,
+        Self::SignalReceived,
+        Self::SignalDeliveryExpired,
+        Self::ExecutionAwaiting,
+        Self::ExecutionResumed,
+        Self::AwaitProgress,
+        Self::JoinSetCreated,
+        Self::JoinSetSubmitted,
+        Self::JoinSetAwaited,
+    ];
+
+    /// The variant name, as returned by [`EventType::name`].
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::ExecutionStarted => "ExecutionStarted",
+            Self::ExecutionCompleted => "ExecutionCompleted",
+            Self::ExecutionFailed => "ExecutionFailed",
+            Self::CancelRequested => "CancelRequested",
+            Self::ExecutionCancelled => "ExecutionCancelled",
+            Self::ContinuedAsNew => "ContinuedAsNew",
+            Self::ComponentUpgraded => "ComponentUpgraded",
+            Self::InvokeScheduled => "InvokeScheduled",
+            Self::InvokeStarted => "InvokeStarted",
+            Self::InvokeCompleted => "InvokeCompleted",
+            Self::InvokeRetrying => "InvokeRetrying",
+            Self::InvokeHeartbeat => "InvokeHeartbeat",
+            Self::RandomGenerated => "RandomGenerated",
+            Self::TimeRecorded => "TimeRecorded",
+            Self::TimerScheduled => "TimerScheduled",
+            Self::TimerFired => "TimerFired",
+            Self::SignalDelivered => "SignalDelivered",
+            Self::SignalReceived => "SignalReceived",
+            Self::SignalDeliveryExpired => "SignalDeliveryExpired",
+            Self::ExecutionAwaiting => "ExecutionAwaiting",
+            Self::ExecutionResumed => "ExecutionResumed",
+            Self::AwaitProgress => "AwaitProgress",
+            Self::JoinSetCreated => "JoinSetCreated",
+            Self::JoinSetSubmitted => "JoinSetSubmitted",
+            Self::JoinSetAwaited => "JoinSetAwaited",
+        }
+    }
+}
+
+impl fmt::Display for EventKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
 /// Monotonic per-signal-name delivery counter.
 pub type SignalDeliveryId = u64;
 
-/// All 20 journal event types, grouped by category.
+/// All 25 journal event types, grouped by category.
 ///
 /// Each category satisfies a distinct formal correctness property.
 /// See JOURNAL_DESIGN.md for the full specification.
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
 pub enum EventType {
     // ── Category 1: Lifecycle (Soundness) ──
     /// Always the first event. Pins execution to a specific component version.
@@ -81,6 +285,20 @@ pub enum EventType {
     CancelRequested { reason: String },
     /// Cancellation finalized after cleanup (terminal). Requires preceding CancelRequested.
     ExecutionCancelled { reason: String },
+    /// Run handed off to a fresh execution that continues it (terminal).
+    /// `carried_state` is the next execution's `ExecutionStarted` input.
+    ContinuedAsNew {
+        next_execution_id: ExecutionId,
+        carried_state: Payload,
+    },
+    /// Operator-approved migration to a new component version. Does not
+    /// change status. `old_digest` is the digest in effect before it: the
+    /// started digest, or the previous upgrade's `new_digest`.
+    ComponentUpgraded {
+        old_digest: Vec<u8>,
+        new_digest: Vec<u8>,
+        approved_by: String,
+    },
 
     // ── Category 2: Side Effects (Replay Correctness) ──
     // 3-phase pattern: Scheduled → Started → Completed
@@ -91,6 +309,14 @@ pub enum EventType {
         function_name: String,
         input: Payload,
         retry_policy: Option<RetryPolicy>,
+        /// Declared bound on scheduled-to-completed time. Informational:
+        /// reported by SLA diagnostics, ignored by invariant checks.
+        #[serde(
+            default,
+            skip_serializing_if = "Option::is_none",
+            with = "serde_opt_duration"
+        )]
+        sla: Option<Duration>,
     },
     /// Invocation is in-flight. Enables timeout detection.
     InvokeStarted { promise_id: PromiseId, attempt: u32 },
@@ -107,6 +333,14 @@ pub enum EventType {
         error: ExecutionError,
         retry_at: DateTime<Utc>,
     },
+    /// Liveness report from a running attempt. `at` is the worker's clock;
+    /// `progress` is an optional application-defined snapshot. Not replayed.
+    InvokeHeartbeat {
+        promise_id: PromiseId,
+        attempt: u32,
+        at: DateTime<Utc>,
+        progress: Option<Payload>,
+    },
 
     // ── Category 3: Nondeterminism (Determinism Guarantee) ──
     // Single-phase: pure value capture, no execution to track.
@@ -136,6 +370,10 @@ pub enum EventType {
         signal_name: String,
         payload: Payload,
         delivery_id: SignalDeliveryId,
+        /// Producer-assigned message ID (e.g. from a queue), unique per
+        /// signal name. Lets ingestion adapters ack exactly once.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        external_id: Option<String>,
     },
     /// Workflow consumed signal via await_signal(). Carries promise_id for replay cache.
     SignalReceived {
@@ -144,6 +382,12 @@ pub enum EventType {
         payload: Payload,
         delivery_id: SignalDeliveryId,
     },
+    /// A buffered delivery was dropped before any `SignalReceived` consumed
+    /// it, e.g. by a `DropOldest` overflow policy. It can no longer be received.
+    SignalDeliveryExpired {
+        signal_name: String,
+        delivery_id: SignalDeliveryId,
+    },
     /// Workflow blocks on pending promises. Explicit suspend per IEEE 1849 (XES).
     ExecutionAwaiting {
         waiting_on: Vec<PromiseId>,
@@ -151,6 +395,14 @@ pub enum EventType {
     },
     /// Blocked → Running. Wait condition satisfied.
     ExecutionResumed,
+    /// Progress on a large await, emitted periodically while Blocked.
+    ///
+    /// Informational only: does not change status. `resolved_since_await`
+    /// lists members of the active await resolved since `ExecutionAwaiting`.
+    AwaitProgress {
+        waiting_on_remaining: u32,
+        resolved_since_await: Vec<PromiseId>,
+    },
 
     // ── Category 5: Concurrency (Total Ordering) ──
     /// Opens a concurrent region. Allocates a child position in the call tree.
@@ -166,9 +418,51 @@ pub enum EventType {
         promise_id: PromiseId,
         result: Payload,
     },
+
+    // ── Forward compatibility ──
+    /// An event written by a newer schema that this build does not know.
+    ///
+    /// Only produced by deserialization, never by the runtime. `name` is the
+    /// unrecognized tag; the event's data is dropped. Invariant checks past
+    /// S-1/S-2 skip it, and it does not change status.
+    #[serde(
+        untagged,
+        serialize_with = "serde_unknown::serialize",
+        deserialize_with = "serde_unknown::deserialize"
+    )]
+    Unknown { name: String },
 }
 
 impl EventType {
+    /// Tags of every variant this build understands, i.e. all but `Unknown`.
+    pub const KNOWN_NAMES: [&'static str; 25] = [
+        "ExecutionStarted",
+        "ExecutionCompleted",
+        "ExecutionFailed",
+        "CancelRequested",
+        "ExecutionCancelled",
+        "ContinuedAsNew",
+        "ComponentUpgraded",
+        "InvokeScheduled",
+        "InvokeStarted",
+        "InvokeCompleted",
+        "InvokeRetrying",
+        "InvokeHeartbeat",
+        "RandomGenerated",
+        "TimeRecorded",
+        "TimerScheduled",
+        "TimerFired",
+        "SignalDelivered",
+        "SignalReceived",
+        "SignalDeliveryExpired",
+        "ExecutionAwaiting",
+        "ExecutionResumed",
+        "AwaitProgress",
+        "JoinSetCreated",
+        "JoinSetSubmitted",
+        "JoinSetAwaited",
+    ];
+
     /// Returns the variant name as a static string for error messages and logging.
     pub fn name(&self) -> &'static str {
         match self {
@@ -177,31 +471,338 @@ impl EventType {
             Self::ExecutionFailed { .. } => "ExecutionFailed",
             Self::CancelRequested { .. } => "CancelRequested",
             Self::ExecutionCancelled { .. } => "ExecutionCancelled",
+            Self::ContinuedAsNew { .. } => "ContinuedAsNew",
+            Self::ComponentUpgraded { .. } => "ComponentUpgraded",
             Self::InvokeScheduled { .. } => "InvokeScheduled",
             Self::InvokeStarted { .. } => "InvokeStarted",
             Self::InvokeCompleted { .. } => "InvokeCompleted",
             Self::InvokeRetrying { .. } => "InvokeRetrying",
+            Self::InvokeHeartbeat { .. } => "InvokeHeartbeat",
             Self::RandomGenerated { .. } => "RandomGenerated",
             Self::TimeRecorded { .. } => "TimeRecorded",
             Self::TimerScheduled { .. } => "TimerScheduled",
             Self::TimerFired { .. } => "TimerFired",
             Self::SignalDelivered { .. } => "SignalDelivered",
             Self::SignalReceived { .. } => "SignalReceived",
+            Self::SignalDeliveryExpired { .. } => "SignalDeliveryExpired",
             Self::ExecutionAwaiting { .. } => "ExecutionAwaiting",
             Self::ExecutionResumed => "ExecutionResumed",
+            Self::AwaitProgress { .. } => "AwaitProgress",
             Self::JoinSetCreated { .. } => "JoinSetCreated",
             Self::JoinSetSubmitted { .. } => "JoinSetSubmitted",
             Self::JoinSetAwaited { .. } => "JoinSetAwaited",
+            Self::Unknown { .. } => "Unknown",
+        }
+    }
+
+    /// The [`EventKind`] of this event, or `None` for [`Self::Unknown`].
+    pub fn kind(&self) -> Option<EventKind> {
+        match self {
+            Self::ExecutionStarted { .. } => Some(EventKind::ExecutionStarted),
+            Self::ExecutionCompleted { .. } => Some(EventKind::ExecutionCompleted),
+            Self::ExecutionFailed { .. } => Some(EventKind::ExecutionFailed),
+            Self::CancelRequested { .. } => Some(EventKind::CancelRequested),
+            Self::ExecutionCancelled { .. } => Some(EventKind::ExecutionCancelled),
+            Self::ContinuedAsNew { .. } => Some(EventKind::ContinuedAsNew),
+            Self::ComponentUpgraded { .. } => Some(EventKind::ComponentUpgraded),
+            Self::InvokeScheduled { .. } => Some(EventKind::InvokeScheduled),
+            Self::InvokeStarted { .. } => Some(EventKind::InvokeStarted),
+            Self::InvokeCompleted { .. } => Some(EventKind::InvokeCompleted),
+            Self::InvokeRetrying { .. } => Some(EventKind::InvokeRetrying),
+            Self::InvokeHeartbeat { .. } => Some(EventKind::InvokeHeartbeat),
+            Self::RandomGenerated { .. } => Some(EventKind::RandomGenerated),
+            Self::TimeRecorded { .. } => Some(EventKind::TimeRecorded),
+            Self::TimerScheduled { .. } => Some(EventKind::TimerScheduled),
+            Self::TimerFired { .. } => Some(EventKind::TimerFired),
+            Self::SignalDelivered { .. } => Some(EventKind::SignalDelivered),
+            Self::SignalReceived { .. } => Some(EventKind::SignalReceived),
+            Self::SignalDeliveryExpired { .. } => Some(EventKind::SignalDeliveryExpired),
+            Self::ExecutionAwaiting { .. } => Some(EventKind::ExecutionAwaiting),
+            Self::ExecutionResumed => Some(EventKind::ExecutionResumed),
+            Self::AwaitProgress { .. } => Some(EventKind::AwaitProgress),
+            Self::JoinSetCreated { .. } => Some(EventKind::JoinSetCreated),
+            Self::JoinSetSubmitted { .. } => Some(EventKind::JoinSetSubmitted),
+            Self::JoinSetAwaited { .. } => Some(EventKind::JoinSetAwaited),
+            Self::Unknown { .. } => None,
+        }
+    }
+
+    /// The schema category this event belongs to.
+    pub fn category(&self) -> EventCategory {
+        match self {
+            Self::ExecutionStarted { .. }
+            | Self::ExecutionCompleted { .. }
+            | Self::ExecutionFailed { .. }
+            | Self::CancelRequested { .. }
+            | Self::ExecutionCancelled { .. }
+            | Self::ContinuedAsNew { .. }
+            | Self::ComponentUpgraded { .. } => EventCategory::Lifecycle,
+            Self::InvokeScheduled { .. }
+            | Self::InvokeStarted { .. }
+            | Self::InvokeCompleted { .. }
+            | Self::InvokeRetrying { .. }
+            | Self::InvokeHeartbeat { .. } => EventCategory::SideEffects,
+            Self::RandomGenerated { .. } | Self::TimeRecorded { .. } => {
+                EventCategory::Nondeterminism
+            }
+            Self::TimerScheduled { .. }
+            | Self::TimerFired { .. }
+            | Self::SignalDelivered { .. }
+            | Self::SignalReceived { .. }
+            | Self::SignalDeliveryExpired { .. }
+            | Self::ExecutionAwaiting { .. }
+            | Self::ExecutionResumed
+            | Self::AwaitProgress { .. } => EventCategory::ControlFlow,
+            Self::JoinSetCreated { .. }
+            | Self::JoinSetSubmitted { .. }
+            | Self::JoinSetAwaited { .. } => EventCategory::Concurrency,
+            Self::Unknown { .. } => EventCategory::Unknown,
+        }
+    }
+
+    /// The promise this event allocates or refers to, if any.
+    ///
+    /// For join-set membership events this is the member promise; for
+    /// `JoinSetCreated` it is the join set's own position in the call tree.
+    /// Lifecycle events, `SignalDelivered` and `ExecutionAwaiting` return `None`.
+    pub fn promise_id(&self) -> Option<&PromiseId> {
+        match self {
+            Self::InvokeScheduled { promise_id, .. }
+            | Self::InvokeStarted { promise_id, .. }
+            | Self::InvokeCompleted { promise_id, .. }
+            | Self::InvokeRetrying { promise_id, .. }
+            | Self::InvokeHeartbeat { promise_id, .. }
+            | Self::RandomGenerated { promise_id, .. }
+            | Self::TimeRecorded { promise_id, .. }
+            | Self::TimerScheduled { promise_id, .. }
+            | Self::TimerFired { promise_id }
+            | Self::SignalReceived { promise_id, .. }
+            | Self::JoinSetSubmitted { promise_id, .. }
+            | Self::JoinSetAwaited { promise_id, .. } => Some(promise_id),
+            Self::JoinSetCreated { join_set_id } => Some(&join_set_id.0),
+            _ => None,
+        }
+    }
+
+    /// The payload flowing into a step: execution or invoke input, a
+    /// delivered signal, or state carried into the next run.
+    pub fn input_payload(&self) -> Option<&Payload> {
+        match self {
+            Self::ExecutionStarted { input, .. } | Self::InvokeScheduled { input, .. } => {
+                Some(input)
+            }
+            Self::SignalDelivered { payload, .. } => Some(payload),
+            Self::ContinuedAsNew { carried_state, .. } => Some(carried_state),
+            _ => None,
+        }
+    }
+
+    /// The payload a step produced: an execution, invoke or join-set result,
+    /// or a signal as received by the workflow.
+    pub fn result_payload(&self) -> Option<&Payload> {
+        match self {
+            Self::ExecutionCompleted { result }
+            | Self::InvokeCompleted { result, .. }
+            | Self::JoinSetAwaited { result, .. } => Some(result),
+            Self::SignalReceived { payload, .. } => Some(payload),
+            _ => None,
+        }
+    }
+
+    /// Every [`Payload`] embedded in this event, including ones that are
+    /// neither input nor result, such as heartbeat progress.
+    pub fn payloads(&self) -> Vec<&Payload> {
+        match self {
+            Self::ExecutionStarted { input, .. } | Self::InvokeScheduled { input, .. } => {
+                vec![input]
+            }
+            Self::ExecutionCompleted { result }
+            | Self::InvokeCompleted { result, .. }
+            | Self::JoinSetAwaited { result, .. } => vec![result],
+            Self::SignalDelivered { payload, .. } | Self::SignalReceived { payload, .. } => {
+                vec![payload]
+            }
+            Self::ContinuedAsNew { carried_state, .. } => vec![carried_state],
+            Self::InvokeHeartbeat {
+                progress: Some(progress),
+                ..
+            } => vec![progress],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Mutable references to every [`Payload`] embedded in this event, as
+    /// [`payloads`](Self::payloads).
+    pub(crate) fn payloads_mut(&mut self) -> Vec<&mut Payload> {
+        match self {
+            Self::ExecutionStarted { input, .. } | Self::InvokeScheduled { input, .. } => {
+                vec![input]
+            }
+            Self::ExecutionCompleted { result }
+            | Self::InvokeCompleted { result, .. }
+            | Self::JoinSetAwaited { result, .. } => vec![result],
+            Self::SignalDelivered { payload, .. } | Self::SignalReceived { payload, .. } => {
+                vec![payload]
+            }
+            Self::ContinuedAsNew { carried_state, .. } => vec![carried_state],
+            Self::InvokeHeartbeat {
+                progress: Some(progress),
+                ..
+            } => vec![progress],
+            _ => Vec::new(),
         }
     }
 
-    /// Whether this event ends the execution (Completed, Failed, or Cancelled).
+    /// Whether this event ends the execution (Completed, Failed, Cancelled,
+    /// or ContinuedAsNew).
     pub fn is_terminal(&self) -> bool {
         matches!(
             self,
             Self::ExecutionCompleted { .. }
                 | Self::ExecutionFailed { .. }
                 | Self::ExecutionCancelled { .. }
+                | Self::ContinuedAsNew { .. }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn event_kinds_match_known_names() {
+        assert_eq!(
+            EventKind::ALL.map(|kind| kind.name()),
+            EventType::KNOWN_NAMES
+        );
+        assert_eq!(
+            EventType::ExecutionResumed.kind(),
+            Some(EventKind::ExecutionResumed)
+        );
+        let unknown = EventType::Unknown {
+            name: "FutureEvent".into(),
+        };
+        assert_eq!(unknown.kind(), None);
+    }
+
+    #[test]
+    fn payload_accessors_split_input_and_result() {
+        let delivered = EventType::SignalDelivered {
+            signal_name: "approve".into(),
+            payload: Payload::from_str("yes"),
+            delivery_id: 0,
+            external_id: None,
+        };
+        assert_eq!(delivered.input_payload(), Some(&Payload::from_str("yes")));
+        assert_eq!(delivered.result_payload(), None);
+
+        let completed = EventType::ExecutionCompleted {
+            result: Payload::from_str("done"),
+        };
+        assert_eq!(completed.input_payload(), None);
+        assert_eq!(completed.result_payload(), Some(&Payload::from_str("done")));
+
+        assert_eq!(EventType::ExecutionResumed.input_payload(), None);
+        assert_eq!(EventType::ExecutionResumed.result_payload(), None);
+    }
+
+    #[test]
+    fn signal_external_id_is_optional_on_the_wire() {
+        let legacy: EventType = serde_json::from_str(
+            r#"{"SignalDelivered":{"signal_name":"approve","payload":{"bytes":[],"codec":"Json"},"delivery_id":0}}"#,
         )
+        .unwrap();
+        assert!(matches!(
+            &legacy,
+            EventType::SignalDelivered {
+                external_id: None,
+                ..
+            }
+        ));
+        assert!(
+            !serde_json::to_string(&legacy)
+                .unwrap()
+                .contains("external_id")
+        );
+
+        let acked = EventType::SignalDelivered {
+            signal_name: "approve".into(),
+            payload: Payload::from_str("yes"),
+            delivery_id: 0,
+            external_id: Some("msg-7".into()),
+        };
+        let json = serde_json::to_string(&acked).unwrap();
+        assert_eq!(serde_json::from_str::<EventType>(&json).unwrap(), acked);
+    }
+
+    #[test]
+    fn invoke_sla_is_optional_on_the_wire() {
+        let legacy: EventType = serde_json::from_str(
+            r#"{"InvokeScheduled":{"promise_id":{"root":[1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1],"path":[0]},"kind":"Function","function_name":"f","input":{"bytes":[],"codec":"Json"},"retry_policy":null}}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            &legacy,
+            EventType::InvokeScheduled { sla: None, .. }
+        ));
+        assert!(!serde_json::to_string(&legacy).unwrap().contains("sla"));
+
+        let EventType::InvokeScheduled {
+            promise_id,
+            kind,
+            function_name,
+            input,
+            retry_policy,
+            ..
+        } = legacy
+        else {
+            unreachable!()
+        };
+        let declared = EventType::InvokeScheduled {
+            promise_id,
+            kind,
+            function_name,
+            input,
+            retry_policy,
+            sla: Some(Duration::from_millis(30_500)),
+        };
+        let json = serde_json::to_string(&declared).unwrap();
+        assert!(json.contains(r#""sla":[30,500000000]"#), "{json}");
+        assert_eq!(serde_json::from_str::<EventType>(&json).unwrap(), declared);
+    }
+
+    #[test]
+    fn unrecognized_tag_deserializes_as_unknown() {
+        let event: EventType =
+            serde_json::from_str(r#"{"WorkflowPaused":{"reason":"ops","until":42}}"#).unwrap();
+        assert_eq!(
+            event,
+            EventType::Unknown {
+                name: "WorkflowPaused".into()
+            }
+        );
+
+        let unit: EventType = serde_json::from_str(r#""WorkflowPinged""#).unwrap();
+        assert_eq!(unit.name(), "Unknown");
+    }
+
+    #[test]
+    fn unknown_round_trips_by_tag() {
+        let event = EventType::Unknown {
+            name: "WorkflowPaused".into(),
+        };
+        let json = serde_json::to_string(&event).unwrap();
+        assert_eq!(json, r#"{"WorkflowPaused":null}"#);
+        assert_eq!(serde_json::from_str::<EventType>(&json).unwrap(), event);
+    }
+
+    #[test]
+    fn known_tags_still_deserialize_strictly() {
+        let resumed: EventType = serde_json::from_str(r#""ExecutionResumed""#).unwrap();
+        assert_eq!(resumed, EventType::ExecutionResumed);
+
+        // A malformed known event must not fall back to `Unknown`.
+        assert!(serde_json::from_str::<EventType>(r#"{"TimerFired":{"bogus":1}}"#).is_err());
     }
 }
@@ -1,44 +1,127 @@
+use std::cell::Cell;
 use std::fmt;
+use std::str::FromStr;
 
-use serde::{Deserialize, Serialize};
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::DomainError;
+
+/// Maximum number of [`ExecutionError::cause`] links accepted when
+/// deserializing.
+///
+/// Journals are untrusted input; this bounds how deep a recorded cause chain
+/// may nest. The bound is checked before each nested cause is parsed, so a
+/// hostile chain is rejected without recursing through it.
+pub const MAX_CAUSE_DEPTH: usize = 8;
+
+/// Number of wasm backtrace frames kept when converting a wasmtime trap.
+#[cfg(feature = "wasmtime")]
+const MAX_BACKTRACE_FRAMES: usize = 4;
 
 /// Canonical category for an execution or invocation failure.
 ///
 /// This is intentionally coarse-grained: it is used for policy decisions
 /// (for example retry behavior) and for observability dimensions in logs
 /// and metrics.
+///
+/// Serialized by its canonical [`as_str`](Self::as_str) name. The variant
+/// names (`"Trap"`, `"UserError"`, ...) written by older journals are still
+/// accepted on deserialization.
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
 pub enum ErrorKind {
     /// Runtime trap or host-side execution failure.
     ///
     /// Typically retryable when the failure is transient.
+    #[serde(alias = "Trap")]
     Trap,
     /// Guest/business logic returned an application error.
     ///
     /// Usually a non-retryable, expected outcome.
+    #[serde(alias = "UserError")]
     UserError,
     /// Execution or invocation exceeded the configured time limit.
     ///
     /// Retryability depends on caller policy and idempotency guarantees.
+    #[serde(alias = "Timeout")]
     Timeout,
     /// Operation was intentionally cancelled.
     ///
     /// This represents a control-flow decision, not necessarily a fault.
+    #[serde(alias = "Cancelled")]
     Cancelled,
     /// Replay divergence (nondeterminism) was detected.
     ///
     /// Indicates a deterministic replay invariant violation.
+    #[serde(alias = "Nondeterminism")]
     Nondeterminism,
+    /// A per-execution budget (e.g. host calls that write to the journal,
+    /// capped by `invariant_engine::HostBudgets`) was used up.
+    ///
+    /// Not retryable without raising the budget.
+    #[serde(alias = "ResourceExhausted")]
+    ResourceExhausted,
     /// Catch-all bucket when no specific category applies.
+    #[serde(alias = "Uncategorized")]
     Uncategorized,
 }
 
+impl ErrorKind {
+    /// Every kind, in declaration order.
+    pub const ALL: [ErrorKind; 7] = [
+        Self::Trap,
+        Self::UserError,
+        Self::Timeout,
+        Self::Cancelled,
+        Self::Nondeterminism,
+        Self::ResourceExhausted,
+        Self::Uncategorized,
+    ];
+
+    /// Canonical lowercase name, as accepted by [`FromStr`] and used in
+    /// config files (e.g. `retry_on = ["trap", "timeout"]`).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Trap => "trap",
+            Self::UserError => "user_error",
+            Self::Timeout => "timeout",
+            Self::Cancelled => "cancelled",
+            Self::Nondeterminism => "nondeterminism",
+            Self::ResourceExhausted => "resource_exhausted",
+            Self::Uncategorized => "uncategorized",
+        }
+    }
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Parses the canonical lowercase name produced by [`Display`](fmt::Display).
+impl FromStr for ErrorKind {
+    type Err = DomainError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.as_str() == s)
+            .ok_or_else(|| DomainError::InvalidErrorKind {
+                value: s.to_string(),
+            })
+    }
+}
+
 /// Structured payload for execution failures and invoke retries.
 ///
 /// This replaces raw string errors with a stable shape that is easy to:
 /// - classify (`kind`) for retry/policy decisions,
 /// - render (`message`) for user-facing summaries,
-/// - enrich (`detail`) with optional low-level diagnostics.
+/// - enrich (`detail`) with optional low-level diagnostics,
+/// - explain (`cause`) with the underlying error it wraps.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ExecutionError {
     /// Coarse failure category used by policy and observability.
@@ -49,6 +132,16 @@ pub struct ExecutionError {
     ///
     /// Prefer concise, actionable context. Omit when no extra detail exists.
     pub detail: Option<String>,
+    /// Underlying error this one wraps, if any.
+    ///
+    /// Omitted from the serialized form when absent; deserialization rejects
+    /// chains deeper than [`MAX_CAUSE_DEPTH`].
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_cause"
+    )]
+    pub cause: Option<Box<ExecutionError>>,
 }
 
 impl ExecutionError {
@@ -60,6 +153,7 @@ impl ExecutionError {
             kind,
             message: message.into(),
             detail: None,
+            cause: None,
         }
     }
 
@@ -77,6 +171,7 @@ impl ExecutionError {
             kind,
             message: message.into(),
             detail: Some(detail.into()),
+            cause: None,
         }
     }
 
@@ -90,14 +185,299 @@ impl ExecutionError {
         self.detail = Some(detail.into());
         self
     }
+
+    /// Adds or replaces the underlying cause.
+    pub fn with_cause(mut self, cause: ExecutionError) -> Self {
+        self.cause = Some(Box::new(cause));
+        self
+    }
+
+    /// Iterates this error followed by each of its causes, outermost first.
+    pub fn chain(&self) -> impl Iterator<Item = &ExecutionError> {
+        std::iter::successors(Some(self), |err| err.cause.as_deref())
+    }
+
+    /// Returns the innermost error in the cause chain (`self` if none).
+    pub fn root_cause(&self) -> &ExecutionError {
+        self.chain().last().unwrap_or(self)
+    }
+
+    /// Kind used for policy decisions: the kind of the root cause.
+    ///
+    /// A wrapper's kind describes where the failure surfaced; the root
+    /// cause's kind describes why it happened. A `Trap` raised because a
+    /// host call timed out should be treated as a `Timeout`.
+    pub fn root_kind(&self) -> &ErrorKind {
+        &self.root_cause().kind
+    }
+}
+
+thread_local! {
+    /// Causes being deserialized on this thread, outermost included.
+    static CAUSE_DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Holds one level of [`CAUSE_DEPTH`] for as long as it lives.
+struct CauseDepthGuard;
+
+impl CauseDepthGuard {
+    fn enter() -> Option<Self> {
+        CAUSE_DEPTH.with(|depth| {
+            let next = depth.get() + 1;
+            (next <= MAX_CAUSE_DEPTH).then(|| {
+                depth.set(next);
+                Self
+            })
+        })
+    }
+}
+
+impl Drop for CauseDepthGuard {
+    fn drop(&mut self) {
+        CAUSE_DEPTH.with(|depth| depth.set(depth.get() - 1));
+    }
 }
 
+fn deserialize_cause<'de, D>(deserializer: D) -> Result<Option<Box<ExecutionError>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct CauseVisitor;
+
+    impl<'de> Visitor<'de> for CauseVisitor {
+        type Value = Option<Box<ExecutionError>>;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("an execution error or null")
+        }
+
+        fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+            Ok(None)
+        }
+
+        fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+            Ok(None)
+        }
+
+        fn visit_some<D: Deserializer<'de>>(
+            self,
+            deserializer: D,
+        ) -> Result<Self::Value, D::Error> {
+            let _depth = CauseDepthGuard::enter().ok_or_else(|| {
+                de::Error::custom(format_args!(
+                    "execution error cause chain exceeds {MAX_CAUSE_DEPTH} levels"
+                ))
+            })?;
+            ExecutionError::deserialize(deserializer).map(|cause| Some(Box::new(cause)))
+        }
+    }
+
+    deserializer.deserialize_option(CauseVisitor)
+}
+
+/// Renders the whole chain as `outer: inner: root`.
 impl fmt::Display for ExecutionError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{:?}: {}", self.kind, self.message)?;
+        write!(f, "{}: {}", self.kind.as_str(), self.message)?;
         if let Some(ref detail) = self.detail {
             write!(f, " ({})", detail)?;
         }
+        if let Some(ref cause) = self.cause {
+            write!(f, ": {cause}")?;
+        }
         Ok(())
     }
 }
+
+#[cfg(feature = "wasmtime")]
+fn trap_kind(trap: &wasmtime::Trap) -> ErrorKind {
+    match trap {
+        wasmtime::Trap::OutOfFuel | wasmtime::Trap::Interrupt => ErrorKind::Timeout,
+        _ => ErrorKind::Trap,
+    }
+}
+
+/// Converts a wasmtime error, preserving its context chain as causes.
+///
+/// Each link of the error's chain becomes one [`ExecutionError`], outermost
+/// first. The trap link keeps its trap code and the first few wasm
+/// backtrace frames in `detail`. The outermost error takes the root trap's
+/// classification: fuel exhaustion and epoch interruption both mean the
+/// guest ran past its budget and map to [`ErrorKind::Timeout`]; other traps
+/// map to [`ErrorKind::Trap`]; anything else is [`ErrorKind::Uncategorized`].
+#[cfg(feature = "wasmtime")]
+impl From<&wasmtime::Error> for ExecutionError {
+    fn from(err: &wasmtime::Error) -> Self {
+        let backtrace = err.downcast_ref::<wasmtime::WasmBacktrace>();
+        let frames = backtrace.map(|bt| {
+            bt.frames()
+                .iter()
+                .take(MAX_BACKTRACE_FRAMES)
+                .map(|frame| match frame.func_name() {
+                    Some(name) => name.to_string(),
+                    None => format!("<wasm function {}>", frame.func_index()),
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        });
+        // The backtrace is attached as a context layer, which the chain only
+        // exposes through its rendering. It is summarized in `frames` above
+        // rather than kept as a link of its own.
+        let backtrace = backtrace.map(ToString::to_string);
+
+        let mut links: Vec<ExecutionError> = err
+            .chain()
+            .filter(|link| backtrace.as_deref() != Some(link.to_string().as_str()))
+            .take(MAX_CAUSE_DEPTH + 1)
+            .map(|link| match link.downcast_ref::<wasmtime::Trap>() {
+                Some(trap) => {
+                    let detail = match frames {
+                        Some(ref frames) => format!("{trap:?} at {frames}"),
+                        None => format!("{trap:?}"),
+                    };
+                    Self::new_with_detail(trap_kind(trap), trap.to_string(), detail)
+                }
+                None => Self::new(ErrorKind::Uncategorized, link.to_string()),
+            })
+            .collect();
+
+        let mut outer = links
+            .pop()
+            .unwrap_or_else(|| Self::new(ErrorKind::Uncategorized, err.to_string()));
+        while let Some(mut link) = links.pop() {
+            link.cause = Some(Box::new(outer));
+            outer = link;
+        }
+        if let Some(trap) = err.downcast_ref::<wasmtime::Trap>() {
+            outer.kind = trap_kind(trap);
+        }
+        outer
+    }
+}
+
+/// Delegates to the borrowed conversion.
+#[cfg(feature = "wasmtime")]
+impl From<wasmtime::Error> for ExecutionError {
+    fn from(err: wasmtime::Error) -> Self {
+        Self::from(&err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timeout_in_trap() -> ExecutionError {
+        ExecutionError::new(ErrorKind::Trap, "host call failed").with_cause(ExecutionError::new(
+            ErrorKind::Timeout,
+            "http request timed out",
+        ))
+    }
+
+    #[test]
+    fn chain_walks_outermost_first() {
+        let err = timeout_in_trap();
+        let kinds: Vec<_> = err.chain().map(|e| &e.kind).collect();
+        assert_eq!(kinds, [&ErrorKind::Trap, &ErrorKind::Timeout]);
+        assert_eq!(err.root_cause().message, "http request timed out");
+    }
+
+    #[test]
+    fn root_kind_reflects_wrapped_timeout() {
+        let err = timeout_in_trap();
+        assert_eq!(err.kind, ErrorKind::Trap);
+        assert_eq!(err.root_kind(), &ErrorKind::Timeout);
+    }
+
+    #[test]
+    fn root_cause_of_unwrapped_error_is_itself() {
+        let err = ExecutionError::new(ErrorKind::UserError, "bad input");
+        assert_eq!(err.root_cause(), &err);
+    }
+
+    #[test]
+    fn display_joins_chain() {
+        let err = timeout_in_trap().with_detail("calling fetch");
+        assert_eq!(
+            err.to_string(),
+            "trap: host call failed (calling fetch): timeout: http request timed out"
+        );
+    }
+
+    #[test]
+    fn cause_is_omitted_when_absent() {
+        let err = ExecutionError::new(ErrorKind::Trap, "boom");
+        let json = serde_json::to_value(&err).unwrap();
+        assert!(json.get("cause").is_none());
+        let back: ExecutionError = serde_json::from_value(json).unwrap();
+        assert_eq!(back, err);
+    }
+
+    #[test]
+    fn cause_round_trips() {
+        let err = timeout_in_trap();
+        let json = serde_json::to_string(&err).unwrap();
+        assert_eq!(serde_json::from_str::<ExecutionError>(&json).unwrap(), err);
+    }
+
+    #[test]
+    fn deserialize_rejects_chain_deeper_than_limit() {
+        let nested = |depth: usize| {
+            let mut err = ExecutionError::new(ErrorKind::Uncategorized, "root");
+            for _ in 0..depth {
+                err = ExecutionError::new(ErrorKind::Trap, "wrap").with_cause(err);
+            }
+            serde_json::to_string(&err).unwrap()
+        };
+
+        assert!(serde_json::from_str::<ExecutionError>(&nested(MAX_CAUSE_DEPTH)).is_ok());
+        let err = serde_json::from_str::<ExecutionError>(&nested(MAX_CAUSE_DEPTH + 1)).unwrap_err();
+        assert!(err.to_string().contains("cause chain exceeds"));
+
+        // Rejected at the limit, long before serde_json's own recursion cap.
+        let err = serde_json::from_str::<ExecutionError>(&nested(100)).unwrap_err();
+        assert!(err.to_string().contains("cause chain exceeds"));
+        // A failed parse leaves no depth behind for the next one.
+        assert!(serde_json::from_str::<ExecutionError>(&nested(MAX_CAUSE_DEPTH)).is_ok());
+    }
+
+    #[test]
+    fn error_kind_name_round_trips() {
+        for kind in ErrorKind::ALL {
+            assert_eq!(kind.to_string().parse::<ErrorKind>().unwrap(), kind);
+        }
+        assert_eq!(ErrorKind::UserError.to_string(), "user_error");
+        assert_eq!(
+            "resource_exhausted".parse::<ErrorKind>().unwrap(),
+            ErrorKind::ResourceExhausted
+        );
+    }
+
+    #[test]
+    fn error_kind_serializes_by_canonical_name() {
+        for kind in ErrorKind::ALL {
+            let json = serde_json::to_value(&kind).unwrap();
+            assert_eq!(json, kind.as_str());
+            assert_eq!(serde_json::from_value::<ErrorKind>(json).unwrap(), kind);
+        }
+
+        // The form documented for config files.
+        let retry_on: Vec<ErrorKind> = serde_json::from_str(r#"["trap", "timeout"]"#).unwrap();
+        assert_eq!(retry_on, [ErrorKind::Trap, ErrorKind::Timeout]);
+
+        // Journals written before the rename still load.
+        assert_eq!(
+            serde_json::from_str::<ErrorKind>(r#""ResourceExhausted""#).unwrap(),
+            ErrorKind::ResourceExhausted
+        );
+    }
+
+    #[test]
+    fn error_kind_rejects_unknown_name() {
+        assert!(matches!(
+            "Trap".parse::<ErrorKind>(),
+            Err(DomainError::InvalidErrorKind { value }) if value == "Trap"
+        ));
+        assert!("".parse::<ErrorKind>().is_err());
+    }
+}
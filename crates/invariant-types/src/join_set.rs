@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::ops::Deref;
 
 use crate::promise_id::PromiseId;
 
@@ -15,3 +16,46 @@ impl fmt::Display for JoinSetId {
         write!(f, "js({})", self.0)
     }
 }
+
+impl Deref for JoinSetId {
+    type Target = PromiseId;
+
+    fn deref(&self) -> &PromiseId {
+        &self.0
+    }
+}
+
+impl AsRef<PromiseId> for JoinSetId {
+    fn as_ref(&self) -> &PromiseId {
+        &self.0
+    }
+}
+
+impl From<PromiseId> for JoinSetId {
+    fn from(pid: PromiseId) -> Self {
+        Self(pid)
+    }
+}
+
+impl From<JoinSetId> for PromiseId {
+    fn from(js: JoinSetId) -> Self {
+        js.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn promise_id_methods_reach_through_reference() {
+        let pid = PromiseId::new([1; 32]).child(0).unwrap().child(3).unwrap();
+        let js = JoinSetId::from(pid.clone());
+        let js_ref: &JoinSetId = &js;
+
+        assert_eq!(js_ref.depth(), 2);
+        assert_eq!(js_ref.path(), &[0, 3]);
+        assert_eq!(js.as_ref(), &pid);
+        assert_eq!(PromiseId::from(js), pid);
+    }
+}
@@ -1,7 +1,10 @@
-use crate::event::{AwaitKind, EventType};
+use crate::error::DomainError;
+use crate::event::{AwaitKind, EventKind, EventType};
 use crate::promise_id::{ExecutionId, PromiseId};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
 
 /// A single entry in the journal's append-only event log.
 ///
@@ -15,10 +18,11 @@ pub struct JournalEntry {
 }
 
 /// Derived execution status. Not stored independently — derived by
-/// folding over journal entries. Only 7 of the 20 event types change status.
+/// folding over journal entries. Only 8 of the 25 event types change status.
 ///
 /// See JOURNAL_DESIGN.md State Machine section.
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
 pub enum ExecutionStatus {
     Running,
     Blocked {
@@ -35,15 +39,139 @@ pub enum ExecutionStatus {
     Cancelled,
 }
 
+/// The variant of an [`ExecutionStatus`] without its payload.
+///
+/// Cheap to copy, hash and compare, so summaries, filters and metric labels
+/// use it where the `Blocked` details do not matter. Serializes as its
+/// lowercase name.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExecutionStatusKind {
+    Running,
+    Blocked,
+    Cancelling,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl ExecutionStatusKind {
+    /// Every kind, in declaration order.
+    pub const ALL: [ExecutionStatusKind; 6] = [
+        Self::Running,
+        Self::Blocked,
+        Self::Cancelling,
+        Self::Completed,
+        Self::Failed,
+        Self::Cancelled,
+    ];
+
+    /// Canonical lowercase name, as accepted by [`FromStr`] and used by serde.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Running => "running",
+            Self::Blocked => "blocked",
+            Self::Cancelling => "cancelling",
+            Self::Completed => "completed",
+            Self::Failed => "failed",
+            Self::Cancelled => "cancelled",
+        }
+    }
+
+    /// Whether this is the kind of a terminal status.
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, Self::Completed | Self::Failed | Self::Cancelled)
+    }
+}
+
+impl fmt::Display for ExecutionStatusKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Parses the canonical lowercase name produced by [`Display`](fmt::Display).
+impl FromStr for ExecutionStatusKind {
+    type Err = DomainError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.as_str() == s)
+            .ok_or_else(|| DomainError::InvalidStatusKind {
+                value: s.to_string(),
+            })
+    }
+}
+
 impl ExecutionStatus {
+    /// The variant of this status, dropping the `Blocked` details.
+    pub fn kind(&self) -> ExecutionStatusKind {
+        match self {
+            Self::Running => ExecutionStatusKind::Running,
+            Self::Blocked { .. } => ExecutionStatusKind::Blocked,
+            Self::Cancelling => ExecutionStatusKind::Cancelling,
+            Self::Completed => ExecutionStatusKind::Completed,
+            Self::Failed => ExecutionStatusKind::Failed,
+            Self::Cancelled => ExecutionStatusKind::Cancelled,
+        }
+    }
+
+    /// The `Blocked` details, or `None` for any other status.
+    pub fn blocked_on(&self) -> Option<(&[PromiseId], &AwaitKind)> {
+        match self {
+            Self::Blocked { waiting_on, kind } => Some((waiting_on, kind)),
+            _ => None,
+        }
+    }
+
     /// Whether the execution has reached a terminal state.
     pub fn is_terminal(&self) -> bool {
         matches!(self, Self::Completed | Self::Failed | Self::Cancelled)
     }
+
+    /// Kinds of the events that may structurally follow this status, in
+    /// [`EventKind::ALL`] order.
+    ///
+    /// `Running` admits everything except `ExecutionStarted`,
+    /// `ExecutionCancelled` and the blocked-only `ExecutionResumed` and
+    /// `AwaitProgress`; `Blocked` admits `ExecutionResumed`, `AwaitProgress` and
+    /// `CancelRequested` but no completion; `Cancelling` admits only
+    /// `ExecutionCancelled`; terminal states admit nothing. Events that never
+    /// change status (invokes, nondeterminism, timers, signals, join sets) are
+    /// allowed in both `Running` and `Blocked`.
+    ///
+    /// Intended for tests and fuzzer guidance, not for invariant checking.
+    pub fn transition_events(&self) -> Vec<EventKind> {
+        let allowed = |kind: EventKind| match self {
+            Self::Running => !matches!(
+                kind,
+                EventKind::ExecutionStarted
+                    | EventKind::ExecutionCancelled
+                    | EventKind::ExecutionResumed
+                    | EventKind::AwaitProgress
+            ),
+            Self::Blocked { .. } => !matches!(
+                kind,
+                EventKind::ExecutionStarted
+                    | EventKind::ExecutionCompleted
+                    | EventKind::ExecutionFailed
+                    | EventKind::ExecutionCancelled
+                    | EventKind::ContinuedAsNew
+                    | EventKind::ExecutionAwaiting
+            ),
+            Self::Cancelling => kind == EventKind::ExecutionCancelled,
+            Self::Completed | Self::Failed | Self::Cancelled => false,
+        };
+        EventKind::ALL
+            .into_iter()
+            .filter(|kind| allowed(*kind))
+            .collect()
+    }
 }
 
-impl std::fmt::Display for ExecutionStatus {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl fmt::Display for ExecutionStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::Running => write!(f, "Running"),
             Self::Blocked { .. } => write!(f, "Blocked"),
@@ -62,4 +190,319 @@ impl std::fmt::Display for ExecutionStatus {
 pub struct ExecutionJournal {
     pub execution_id: ExecutionId,
     pub entries: Vec<JournalEntry>,
+    /// Who wrote this journal. Absent on journals written before it existed;
+    /// never consulted by validation or replay.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<JournalProvenance>,
+}
+
+/// Software and host that produced a journal, for support triage.
+///
+/// All fields are free-form; nothing parses them.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JournalProvenance {
+    pub engine_version: String,
+    pub wasmtime_version: String,
+    pub sdk_name: String,
+    pub sdk_version: String,
+    pub hostname: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ExecutionJournal {
+    /// Entries with sequence numbers in `from_seq..=to_seq`.
+    ///
+    /// Relies on S-1 (`sequence == index`), so this is a single slice. `to_seq`
+    /// is clamped to the last entry; returns `&[]` when `from_seq > to_seq` or
+    /// `from_seq` is past the end.
+    #[inline]
+    pub fn entries_in_range(&self, from_seq: u64, to_seq: u64) -> &[JournalEntry] {
+        let len = self.entries.len() as u64;
+        if from_seq > to_seq || from_seq >= len {
+            return &[];
+        }
+        &self.entries[from_seq as usize..=to_seq.min(len - 1) as usize]
+    }
+
+    /// Timestamp of the first entry, or `None` for an empty journal.
+    #[inline]
+    pub fn first_entry_timestamp(&self) -> Option<DateTime<Utc>> {
+        self.entries.first().map(|e| e.timestamp)
+    }
+
+    /// Timestamp of the most recent entry, or `None` for an empty journal.
+    #[inline]
+    pub fn last_entry_timestamp(&self) -> Option<DateTime<Utc>> {
+        self.entries.last().map(|e| e.timestamp)
+    }
+
+    /// Entries with sequence numbers strictly greater than `seq`.
+    #[inline]
+    pub fn entries_after(&self, seq: u64) -> &[JournalEntry] {
+        let start = seq.saturating_add(1).min(self.entries.len() as u64);
+        &self.entries[start as usize..]
+    }
+
+    /// Copy of this journal with every payload's bytes emptied, for logging.
+    ///
+    /// Event structure, sequences, timestamps and payload codecs are kept so
+    /// logs show the shape of the execution without its data, and each
+    /// payload's original length is kept in
+    /// [`Payload::redacted_len`](crate::Payload::redacted_len). The provenance
+    /// hostname is cleared as well. The original journal is untouched.
+    pub fn redacted(&self) -> ExecutionJournal {
+        let mut journal = self.clone();
+        if let Some(provenance) = &mut journal.provenance {
+            provenance.hostname = String::new();
+        }
+        for entry in &mut journal.entries {
+            for payload in entry.event.payloads_mut() {
+                let bytes = std::mem::take(&mut payload.bytes);
+                payload.redacted_len = Some(bytes.len());
+            }
+        }
+        journal
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::payload::{Codec, Payload};
+
+    fn entry(sequence: u64, event: EventType) -> JournalEntry {
+        JournalEntry {
+            sequence,
+            timestamp: DateTime::UNIX_EPOCH,
+            event,
+        }
+    }
+
+    #[test]
+    fn status_kind_matches_status_and_round_trips() {
+        let waiting_on = vec![PromiseId::new([1; 32])];
+        let blocked = ExecutionStatus::Blocked {
+            waiting_on: waiting_on.clone(),
+            kind: AwaitKind::Any,
+        };
+        let statuses = [
+            ExecutionStatus::Running,
+            blocked.clone(),
+            ExecutionStatus::Cancelling,
+            ExecutionStatus::Completed,
+            ExecutionStatus::Failed,
+            ExecutionStatus::Cancelled,
+        ];
+        for (status, kind) in statuses.iter().zip(ExecutionStatusKind::ALL) {
+            assert_eq!(status.kind(), kind);
+            assert_eq!(kind.to_string(), status.to_string().to_lowercase());
+            assert_eq!(kind.as_str().parse::<ExecutionStatusKind>().unwrap(), kind);
+            let json = serde_json::to_string(&kind).unwrap();
+            assert_eq!(json, format!("\"{kind}\""));
+            assert_eq!(
+                serde_json::from_str::<ExecutionStatusKind>(&json).unwrap(),
+                kind
+            );
+        }
+        assert!(matches!(
+            "Blocked".parse::<ExecutionStatusKind>(),
+            Err(DomainError::InvalidStatusKind { .. })
+        ));
+
+        assert_eq!(
+            blocked.blocked_on(),
+            Some((waiting_on.as_slice(), &AwaitKind::Any))
+        );
+        assert_eq!(ExecutionStatus::Cancelling.blocked_on(), None);
+    }
+
+    #[test]
+    fn redacted_empties_payloads_and_keeps_structure() {
+        let secret = Payload::new(b"card=4111".to_vec(), Codec::Json);
+        let journal = ExecutionJournal {
+            execution_id: ExecutionId::derive(&[1], "k", None),
+            entries: vec![
+                entry(
+                    0,
+                    EventType::ExecutionStarted {
+                        component_digest: vec![1],
+                        input: secret.clone(),
+                        parent_id: None,
+                        idempotency_key: "k".into(),
+                    },
+                ),
+                entry(
+                    1,
+                    EventType::SignalDelivered {
+                        signal_name: "approval".into(),
+                        payload: Payload::new(vec![0xa0], Codec::Cbor),
+                        delivery_id: 0,
+                        external_id: None,
+                    },
+                ),
+                entry(2, EventType::ExecutionCompleted { result: secret }),
+            ],
+            provenance: None,
+        };
+
+        let redacted = journal.redacted();
+
+        assert_eq!(redacted.execution_id, journal.execution_id);
+        assert_eq!(redacted.entries.len(), journal.entries.len());
+        for (r, o) in redacted.entries.iter().zip(&journal.entries) {
+            assert_eq!(r.sequence, o.sequence);
+            assert_eq!(r.event.name(), o.event.name());
+        }
+        assert!(matches!(
+            &redacted.entries[1].event,
+            EventType::SignalDelivered { signal_name, payload, delivery_id: 0, .. }
+                if signal_name == "approval" && payload.bytes.is_empty() && payload.codec == Codec::Cbor
+                    && payload.redacted_len == Some(1)
+        ));
+        for (r, o) in redacted.entries.iter().zip(&journal.entries) {
+            for (r, o) in r.event.payloads().into_iter().zip(o.event.payloads()) {
+                assert!(r.bytes.is_empty());
+                assert_eq!(r.redacted_len, Some(o.byte_size()));
+            }
+        }
+        // Original untouched.
+        assert!(matches!(
+            &journal.entries[2].event,
+            EventType::ExecutionCompleted { result } if result.bytes == b"card=4111"
+        ));
+    }
+
+    fn numbered(len: u64) -> ExecutionJournal {
+        ExecutionJournal {
+            execution_id: ExecutionId::derive(&[1], "k", None),
+            entries: (0..len)
+                .map(|seq| entry(seq, EventType::ExecutionResumed))
+                .collect(),
+            provenance: None,
+        }
+    }
+
+    fn seqs(entries: &[JournalEntry]) -> Vec<u64> {
+        entries.iter().map(|e| e.sequence).collect()
+    }
+
+    fn provenance() -> JournalProvenance {
+        JournalProvenance {
+            engine_version: "0.1.0".into(),
+            wasmtime_version: "38.0.3".into(),
+            sdk_name: "invariant-sdk-rust".into(),
+            sdk_version: "0.3.1".into(),
+            hostname: "worker-17.internal".into(),
+            created_at: DateTime::UNIX_EPOCH,
+        }
+    }
+
+    #[test]
+    fn provenance_round_trips_and_is_optional() {
+        let mut journal = numbered(2);
+        let legacy = serde_json::to_string(&journal).unwrap();
+        assert!(!legacy.contains("provenance"));
+        assert_eq!(
+            serde_json::from_str::<ExecutionJournal>(&legacy).unwrap(),
+            journal
+        );
+
+        journal.provenance = Some(provenance());
+        let json = serde_json::to_string(&journal).unwrap();
+        assert_eq!(
+            serde_json::from_str::<ExecutionJournal>(&json).unwrap(),
+            journal
+        );
+    }
+
+    #[test]
+    fn redacted_scrubs_provenance_hostname() {
+        let mut journal = numbered(1);
+        journal.provenance = Some(provenance());
+
+        let redacted = journal.redacted();
+        let scrubbed = redacted.provenance.unwrap();
+        assert!(scrubbed.hostname.is_empty());
+        assert_eq!(scrubbed.sdk_version, "0.3.1");
+        assert_eq!(journal.provenance.unwrap().hostname, "worker-17.internal");
+    }
+
+    #[test]
+    fn entries_in_range_is_inclusive_and_clamped() {
+        let journal = numbered(5);
+        assert_eq!(seqs(journal.entries_in_range(1, 3)), vec![1, 2, 3]);
+        assert_eq!(seqs(journal.entries_in_range(3, 3)), vec![3]);
+        assert_eq!(seqs(journal.entries_in_range(2, 99)), vec![2, 3, 4]);
+        assert!(journal.entries_in_range(3, 2).is_empty());
+        assert!(journal.entries_in_range(5, 9).is_empty());
+        assert!(numbered(0).entries_in_range(0, 0).is_empty());
+    }
+
+    #[test]
+    fn entries_after_is_exclusive() {
+        let journal = numbered(5);
+        assert_eq!(seqs(journal.entries_after(2)), vec![3, 4]);
+        assert!(journal.entries_after(4).is_empty());
+        assert!(journal.entries_after(u64::MAX).is_empty());
+    }
+
+    #[test]
+    fn transition_events_follow_status() {
+        let running = ExecutionStatus::Running.transition_events();
+        for kind in [
+            EventKind::ExecutionAwaiting,
+            EventKind::ExecutionCompleted,
+            EventKind::ExecutionFailed,
+            EventKind::CancelRequested,
+            EventKind::InvokeScheduled,
+            EventKind::JoinSetAwaited,
+        ] {
+            assert!(running.contains(&kind), "Running should admit {kind}");
+        }
+        assert!(!running.contains(&EventKind::ExecutionResumed));
+
+        let blocked = ExecutionStatus::Blocked {
+            waiting_on: vec![],
+            kind: AwaitKind::All,
+        }
+        .transition_events();
+        for kind in [
+            EventKind::ExecutionResumed,
+            EventKind::AwaitProgress,
+            EventKind::CancelRequested,
+            EventKind::InvokeCompleted,
+        ] {
+            assert!(blocked.contains(&kind), "Blocked should admit {kind}");
+        }
+        assert!(!blocked.contains(&EventKind::ExecutionCompleted));
+        assert!(!blocked.contains(&EventKind::ExecutionAwaiting));
+
+        assert_eq!(
+            ExecutionStatus::Cancelling.transition_events(),
+            vec![EventKind::ExecutionCancelled]
+        );
+        for terminal in [
+            ExecutionStatus::Completed,
+            ExecutionStatus::Failed,
+            ExecutionStatus::Cancelled,
+        ] {
+            assert!(terminal.transition_events().is_empty());
+        }
+    }
+
+    #[test]
+    fn entry_timestamps_are_first_and_last() {
+        assert_eq!(numbered(0).first_entry_timestamp(), None);
+        assert_eq!(numbered(0).last_entry_timestamp(), None);
+
+        let mut journal = numbered(3);
+        for (i, entry) in journal.entries.iter_mut().enumerate() {
+            entry.timestamp = DateTime::UNIX_EPOCH + chrono::TimeDelta::seconds(10 * i as i64);
+        }
+        assert_eq!(journal.first_entry_timestamp(), Some(DateTime::UNIX_EPOCH));
+        assert_eq!(
+            journal.last_entry_timestamp(),
+            Some(DateTime::UNIX_EPOCH + chrono::TimeDelta::seconds(20))
+        );
+    }
 }
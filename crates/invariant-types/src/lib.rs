@@ -3,13 +3,19 @@ pub mod event;
 pub mod execution_error;
 pub mod join_set;
 pub mod journal;
+pub mod name;
 pub mod payload;
 pub mod promise_id;
 
 pub use error::DomainError;
-pub use event::{AwaitKind, EventType, InvokeKind, RetryPolicy, SignalDeliveryId};
+pub use event::{
+    AwaitKind, EventCategory, EventKind, EventType, InvokeKind, RetryPolicy, SignalDeliveryId,
+};
 pub use execution_error::{ErrorKind, ExecutionError};
 pub use join_set::JoinSetId;
-pub use journal::{ExecutionJournal, ExecutionStatus, JournalEntry};
+pub use journal::{
+    ExecutionJournal, ExecutionStatus, ExecutionStatusKind, JournalEntry, JournalProvenance,
+};
+pub use name::{FunctionName, MAX_NAME_LEN, SignalName};
 pub use payload::{Codec, Payload};
 pub use promise_id::{ExecutionId, MAX_CALL_DEPTH, PromiseId};
@@ -0,0 +1,166 @@
+//! Validated names the runtime routes on.
+//!
+//! Invokes are dispatched by [`FunctionName`] and signals are matched to
+//! waiting executions by [`SignalName`]. Both are compared exactly: equality
+//! is case-sensitive and byte-for-byte, so `"Approve"` and `"approve"` are
+//! different signals. Events keep plain `String` fields on the wire; these
+//! types guard the places where names enter the system. Query and assertion
+//! helpers take them for names a caller supplies, and return names read back
+//! from recorded events as `&str`, since the wire form was never validated.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::DomainError;
+
+/// Longest accepted name, in bytes.
+pub const MAX_NAME_LEN: usize = 256;
+
+/// Construction rules shared by every name type.
+///
+/// A name is non-empty, at most [`MAX_NAME_LEN`] bytes, and uses only ASCII
+/// letters, digits and `_ - . : /`. The restricted set keeps names portable
+/// across storage keys, URLs and WIT-style `package:interface/function` paths.
+fn validate(kind: &'static str, name: &str) -> Result<(), DomainError> {
+    let reason = if name.is_empty() {
+        "must not be empty".to_string()
+    } else if name.len() > MAX_NAME_LEN {
+        format!("{} bytes exceeds max of {MAX_NAME_LEN}", name.len())
+    } else if let Some(c) = name
+        .chars()
+        .find(|c| !(c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | ':' | '/')))
+    {
+        format!("character {c:?} is not allowed")
+    } else {
+        return Ok(());
+    };
+    Err(DomainError::InvalidName { kind, reason })
+}
+
+/// Name of a function an execution invokes.
+///
+/// Serializes as a plain string; deserialization applies the same rules as
+/// [`FunctionName::new`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct FunctionName(String);
+
+impl FunctionName {
+    /// Validate and wrap `name`. See the module docs for the rules.
+    pub fn new(name: impl Into<String>) -> Result<Self, DomainError> {
+        let name = name.into();
+        validate("function", &name)?;
+        Ok(Self(name))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Name of a signal delivered to, and awaited by, an execution.
+///
+/// Serializes as a plain string; deserialization applies the same rules as
+/// [`SignalName::new`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct SignalName(String);
+
+impl SignalName {
+    /// Validate and wrap `name`. See the module docs for the rules.
+    pub fn new(name: impl Into<String>) -> Result<Self, DomainError> {
+        let name = name.into();
+        validate("signal", &name)?;
+        Ok(Self(name))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl TryFrom<String> for FunctionName {
+    type Error = DomainError;
+
+    fn try_from(name: String) -> Result<Self, Self::Error> {
+        Self::new(name)
+    }
+}
+
+impl TryFrom<String> for SignalName {
+    type Error = DomainError;
+
+    fn try_from(name: String) -> Result<Self, Self::Error> {
+        Self::new(name)
+    }
+}
+
+impl From<FunctionName> for String {
+    fn from(name: FunctionName) -> Self {
+        name.0
+    }
+}
+
+impl From<SignalName> for String {
+    fn from(name: SignalName) -> Self {
+        name.0
+    }
+}
+
+impl fmt::Display for FunctionName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl fmt::Display for SignalName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_portable_names() {
+        for name in ["approve", "charge_card", "wasi:http/handler.handle", "v-2"] {
+            assert_eq!(FunctionName::new(name).unwrap().as_str(), name);
+            assert_eq!(SignalName::new(name).unwrap().to_string(), name);
+        }
+    }
+
+    #[test]
+    fn rejects_empty_long_and_unportable_names() {
+        let too_long = "a".repeat(MAX_NAME_LEN + 1);
+        for name in ["", too_long.as_str(), "has space", "ünïcode", "a\nb"] {
+            assert!(
+                matches!(
+                    SignalName::new(name),
+                    Err(DomainError::InvalidName { kind: "signal", .. })
+                ),
+                "{name:?} should be rejected"
+            );
+        }
+        assert!(FunctionName::new("a".repeat(MAX_NAME_LEN)).is_ok());
+    }
+
+    #[test]
+    fn equality_is_case_sensitive() {
+        assert_ne!(
+            SignalName::new("Approve").unwrap(),
+            SignalName::new("approve").unwrap()
+        );
+    }
+
+    #[test]
+    fn serde_is_a_plain_validated_string() {
+        let name = SignalName::new("approve").unwrap();
+        let json = serde_json::to_string(&name).unwrap();
+        assert_eq!(json, "\"approve\"");
+        assert_eq!(serde_json::from_str::<SignalName>(&json).unwrap(), name);
+        assert!(serde_json::from_str::<FunctionName>("\"\"").is_err());
+    }
+}
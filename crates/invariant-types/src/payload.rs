@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 /// Codec used to encode/decode payload bytes.
 /// Matches the SDK's supported serialization formats.
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
 pub enum Codec {
     Cbor,
     Json,
@@ -13,15 +14,112 @@ pub enum Codec {
 ///
 /// SDK boundary handles conversion to/from the SDK's Payload type.
 /// For Invariant types they are just bytes
-#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+///
+/// Equality compares the bytes and codec only: `redacted_len` records where
+/// the bytes went, not what they were, so two redacted payloads are equal
+/// whatever their original lengths.
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Payload {
     pub bytes: Vec<u8>,
     pub codec: Codec,
+    /// Length the bytes had before
+    /// [`ExecutionJournal::redacted`](crate::ExecutionJournal::redacted)
+    /// emptied them; `None` for a payload that was not redacted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redacted_len: Option<usize>,
 }
 
+impl PartialEq for Payload {
+    fn eq(&self, other: &Self) -> bool {
+        self.bytes == other.bytes && self.codec == other.codec
+    }
+}
+
+impl Eq for Payload {}
+
 impl Payload {
     /// Create a payload from raw bytes and their codec.
     pub fn new(bytes: Vec<u8>, codec: Codec) -> Self {
-        Self { bytes, codec }
+        Self {
+            bytes,
+            codec,
+            redacted_len: None,
+        }
+    }
+
+    /// Wrap a UTF-8 string as a JSON-codec payload.
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Self {
+        Self::new(s.as_bytes().to_vec(), Codec::Json)
+    }
+
+    /// Size of the encoded bytes.
+    pub fn byte_size(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// Whether the encoded bytes exceed `threshold_bytes`.
+    pub fn is_larger_than(&self, threshold_bytes: usize) -> bool {
+        self.bytes.len() > threshold_bytes
+    }
+
+    /// Borrow the bytes as `&str` if this is a JSON payload holding valid UTF-8.
+    ///
+    /// Returns `None` for other codecs or invalid UTF-8.
+    pub fn as_str(&self) -> Option<&str> {
+        if self.codec != Codec::Json {
+            return None;
+        }
+        std::str::from_utf8(&self.bytes).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_str_round_trips_through_as_str() {
+        let payload = Payload::from_str("approved");
+        assert_eq!(payload.codec, Codec::Json);
+        assert_eq!(payload.as_str(), Some("approved"));
+    }
+
+    #[test]
+    fn byte_size_and_threshold() {
+        let empty = Payload::new(vec![], Codec::Cbor);
+        assert_eq!(empty.byte_size(), 0);
+        assert!(!empty.is_larger_than(0));
+
+        let small = Payload::from_str("ok");
+        assert_eq!(small.byte_size(), 2);
+        assert!(small.is_larger_than(1));
+        assert!(!small.is_larger_than(2));
+
+        let large = Payload::new(vec![0; 1 << 20], Codec::Borsh);
+        assert_eq!(large.byte_size(), 1 << 20);
+        assert!(large.is_larger_than(64 * 1024));
+    }
+
+    #[test]
+    fn equality_ignores_redacted_len() {
+        let mut short = Payload::new(vec![], Codec::Json);
+        let mut long = short.clone();
+        short.redacted_len = Some(1);
+        long.redacted_len = Some(100);
+        assert_eq!(short, long);
+        assert_ne!(short, Payload::new(vec![], Codec::Cbor));
+    }
+
+    #[test]
+    fn as_str_is_none_for_binary_cbor() {
+        let payload = Payload::new(vec![0xa1, 0x61, 0x61, 0x01], Codec::Cbor);
+        assert_eq!(payload.as_str(), None);
+    }
+
+    #[test]
+    fn as_str_is_none_for_invalid_utf8_json() {
+        let payload = Payload::new(vec![0xff, 0xfe], Codec::Json);
+        assert_eq!(payload.as_str(), None);
     }
 }
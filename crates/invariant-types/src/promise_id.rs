@@ -2,6 +2,7 @@ use crate::error::DomainError;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::fmt;
+use std::str::FromStr;
 
 pub const MAX_CALL_DEPTH: usize = 64;
 
@@ -40,6 +41,19 @@ impl ExecutionId {
         ))
     }
 
+    /// Wrap a root-level `promise` received from outside, e.g. a parsed ID.
+    ///
+    /// Returns `Err(NotARootPromise)` if `promise` has a non-empty path.
+    /// Does not check that the root was derived from any particular inputs.
+    pub fn root(promise: PromiseId) -> Result<Self, DomainError> {
+        if !promise.is_root() {
+            return Err(DomainError::NotARootPromise {
+                depth: promise.depth(),
+            });
+        }
+        Ok(Self(promise))
+    }
+
     /// Create a child [`PromiseId`] by appending a sequence number.
     ///
     /// Returns `Err(MaxCallDepthExceeded)` if the resulting path would
@@ -111,6 +125,21 @@ impl PromiseId {
         Self::new(hash)
     }
 
+    /// Whether this ID's root is the [`promise_root`](Self::promise_root)
+    /// of the given inputs. The path is ignored, so any promise of an
+    /// execution verifies against that execution's inputs.
+    ///
+    /// Lets a gateway reject execution IDs that were not derived from the
+    /// inputs they claim.
+    pub fn verify_root(
+        &self,
+        component_digest: &[u8],
+        idempotency_key: &str,
+        parent_id: Option<&PromiseId>,
+    ) -> bool {
+        Self::promise_root(component_digest, idempotency_key, parent_id).root == self.root
+    }
+
     /// Create a child promise by appending a sequence number to the path.
     ///
     /// The caller provides `seq` — the local operation counter at this depth.
@@ -142,6 +171,14 @@ impl PromiseId {
             path: parent_path,
         })
     }
+
+    /// Whether `other` lies strictly below this promise in the same call tree.
+    pub fn is_ancestor_of(&self, other: &PromiseId) -> bool {
+        self.root == other.root
+            && self.path.len() < other.path.len()
+            && other.path.starts_with(&self.path)
+    }
+
     /// Whether this is a root-level promise (empty path, depth 0).
     pub fn is_root(&self) -> bool {
         self.path.is_empty()
@@ -161,14 +198,232 @@ impl PromiseId {
     pub fn path(&self) -> &[u32] {
         &self.path
     }
+
+    /// Full hex of the root: 64 characters, unique per execution.
+    pub fn root_hex(&self) -> String {
+        hex::encode(self.root)
+    }
+
+    /// Hex of the first [`DISPLAY_DEFAULT_PREFIX_BYTES`](Self::DISPLAY_DEFAULT_PREFIX_BYTES)
+    /// root bytes, as [`Display`](fmt::Display) shows it. Brief, but distinct
+    /// executions can share it.
+    pub fn short_root_hex(&self) -> String {
+        hex::encode(&self.root[..Self::DISPLAY_DEFAULT_PREFIX_BYTES])
+    }
+
+    /// Root bytes shown by the [`Display`](fmt::Display) impl.
+    pub const DISPLAY_DEFAULT_PREFIX_BYTES: usize = 4;
+
+    /// Displays the hex of the first `n` root bytes followed by the path.
+    ///
+    /// `n = 0` writes only the path (e.g. `"0.1.3"`), handy when every
+    /// promise shares one execution; `n = 32` writes the full root, matching
+    /// the [`FromStr`] form. Larger `n` is clamped to 32. Borrows `self`.
+    pub fn display_with_prefix_length(&self, n: usize) -> impl fmt::Display + '_ {
+        PrefixDisplay {
+            id: self,
+            prefix_bytes: n.min(self.root.len()),
+        }
+    }
 }
 
-impl fmt::Display for PromiseId {
+struct PrefixDisplay<'a> {
+    id: &'a PromiseId,
+    prefix_bytes: usize,
+}
+
+impl fmt::Display for PrefixDisplay<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", hex::encode(&self.root[..4]))?;
-        for seg in &self.path {
-            write!(f, ".{}", seg)?;
+        let prefix = &self.id.root[..self.prefix_bytes];
+        for byte in prefix {
+            write!(f, "{byte:02x}")?;
+        }
+        for (i, seg) in self.id.path.iter().enumerate() {
+            if i == 0 && prefix.is_empty() {
+                write!(f, "{seg}")?;
+            } else {
+                write!(f, ".{seg}")?;
+            }
         }
         Ok(())
     }
 }
+
+impl fmt::Display for PromiseId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.display_with_prefix_length(Self::DISPLAY_DEFAULT_PREFIX_BYTES)
+            .fmt(f)
+    }
+}
+
+/// Parses the full textual form `"<64 hex root>.<seg>.<seg>..."`.
+///
+/// Unlike [`Display`](fmt::Display), which abbreviates the root to 4 bytes,
+/// the parsed form requires the complete 32-byte root. The path depth is
+/// bounded by [`MAX_CALL_DEPTH`], consistent with [`PromiseId::child`].
+impl FromStr for PromiseId {
+    type Err = DomainError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split('.');
+        let root_hex = parts.next().unwrap_or_default();
+        let mut root = [0u8; 32];
+        hex::decode_to_slice(root_hex, &mut root).map_err(|e| DomainError::InvalidPromiseId {
+            reason: format!("root must be 64 hex characters: {e}"),
+        })?;
+
+        let mut path = Vec::new();
+        for seg in parts {
+            if path.len() >= MAX_CALL_DEPTH {
+                return Err(DomainError::MaxCallDepthExceeded {
+                    max: MAX_CALL_DEPTH,
+                });
+            }
+            let seg = seg
+                .parse::<u32>()
+                .map_err(|e| DomainError::InvalidPromiseId {
+                    reason: format!("invalid path segment {seg:?}: {e}"),
+                })?;
+            path.push(seg);
+        }
+        Ok(Self { root, path })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn full_form(pid: &PromiseId) -> String {
+        let mut s = hex::encode(pid.root_bytes());
+        for seg in pid.path() {
+            s.push_str(&format!(".{seg}"));
+        }
+        s
+    }
+
+    #[test]
+    fn execution_id_root_rejects_non_root_promises() {
+        let id = ExecutionId::derive(&[1], "k", None);
+        assert_eq!(ExecutionId::root(id.as_promise_id().clone()).unwrap(), id);
+
+        let nested = id.child(0).unwrap().child(3).unwrap();
+        assert!(matches!(
+            ExecutionId::root(nested),
+            Err(DomainError::NotARootPromise { depth: 2 })
+        ));
+    }
+
+    #[test]
+    fn verify_root_matches_derivation_and_rejects_tampering() {
+        let parent = PromiseId::new([3; 32]).child(1).unwrap();
+        let pid = PromiseId::promise_root(&[9, 9], "order-42", Some(&parent))
+            .child(4)
+            .unwrap();
+        assert!(pid.verify_root(&[9, 9], "order-42", Some(&parent)));
+        assert!(!pid.verify_root(&[9, 9], "order-43", Some(&parent)));
+        assert!(!pid.verify_root(&[9, 9], "order-42", None));
+    }
+
+    #[test]
+    fn root_hex_lengths() {
+        let pid = PromiseId::new([0xab; 32]).child(2).unwrap();
+        assert_eq!(pid.root_hex().len(), 64);
+        assert_eq!(pid.root_hex(), hex::encode(pid.root_bytes()));
+        assert_eq!(pid.short_root_hex(), "abababab");
+        assert!(pid.to_string().starts_with(&pid.short_root_hex()));
+    }
+
+    #[test]
+    fn display_with_prefix_length_controls_root_width() {
+        let pid = PromiseId::new([0xab; 32])
+            .child(0)
+            .unwrap()
+            .child(3)
+            .unwrap();
+
+        assert_eq!(pid.display_with_prefix_length(0).to_string(), "0.3");
+        assert_eq!(pid.display_with_prefix_length(2).to_string(), "abab.0.3");
+        assert_eq!(
+            pid.display_with_prefix_length(32).to_string(),
+            full_form(&pid)
+        );
+        assert_eq!(
+            pid.display_with_prefix_length(64).to_string(),
+            full_form(&pid)
+        );
+        assert_eq!(
+            pid.display_with_prefix_length(PromiseId::DISPLAY_DEFAULT_PREFIX_BYTES)
+                .to_string(),
+            pid.to_string()
+        );
+        assert_eq!(pid.to_string(), "abababab.0.3");
+        assert_eq!(
+            PromiseId::new([1; 32])
+                .display_with_prefix_length(0)
+                .to_string(),
+            ""
+        );
+    }
+
+    #[test]
+    fn is_ancestor_of_requires_same_root_and_strict_prefix() {
+        let root = PromiseId::new([7; 32]);
+        let child = root.child(2).unwrap();
+        let grandchild = child.child(0).unwrap();
+        let sibling = root.child(3).unwrap();
+
+        assert!(root.is_ancestor_of(&child));
+        assert!(root.is_ancestor_of(&grandchild));
+        assert!(child.is_ancestor_of(&grandchild));
+        assert!(!child.is_ancestor_of(&child));
+        assert!(!child.is_ancestor_of(&sibling));
+        assert!(!grandchild.is_ancestor_of(&child));
+        assert!(!PromiseId::new([8; 32]).is_ancestor_of(&child));
+    }
+
+    #[test]
+    fn from_str_round_trips_full_form() {
+        let pid = PromiseId::new([7; 32]).child(0).unwrap().child(3).unwrap();
+        let parsed: PromiseId = full_form(&pid).parse().unwrap();
+        assert_eq!(parsed, pid);
+    }
+
+    #[test]
+    fn from_str_rejects_path_deeper_than_max_call_depth() {
+        let mut s = hex::encode([7u8; 32]);
+        for _ in 0..=MAX_CALL_DEPTH {
+            s.push_str(".0");
+        }
+        let err = s.parse::<PromiseId>().unwrap_err();
+        assert!(matches!(
+            err,
+            DomainError::MaxCallDepthExceeded {
+                max: MAX_CALL_DEPTH
+            }
+        ));
+    }
+
+    #[test]
+    fn from_str_accepts_path_at_max_call_depth() {
+        let mut s = hex::encode([7u8; 32]);
+        for _ in 0..MAX_CALL_DEPTH {
+            s.push_str(".1");
+        }
+        let pid: PromiseId = s.parse().unwrap();
+        assert_eq!(pid.depth(), MAX_CALL_DEPTH);
+    }
+
+    #[test]
+    fn from_str_rejects_malformed_input() {
+        assert!(matches!(
+            "a1b2c3d4.0".parse::<PromiseId>(),
+            Err(DomainError::InvalidPromiseId { .. })
+        ));
+        let bad_seg = format!("{}.x", hex::encode([7u8; 32]));
+        assert!(matches!(
+            bad_seg.parse::<PromiseId>(),
+            Err(DomainError::InvalidPromiseId { .. })
+        ));
+    }
+}